@@ -23,11 +23,32 @@ aws s3 sync \
   "s3://${R2_BUCKET_NAME}/cover/" \
   --endpoint-url "${R2_ENDPOINT}" \
   --delete \
+  --exclude "*.webp" \
   --cache-control "public, max-age=31536000, immutable"
 
+# COVER_FORMAT=webp covers; set the type explicitly since not every aws-cli knows .webp.
+aws s3 sync \
+  "${SONG_DATA_PATH}/cover/" \
+  "s3://${R2_BUCKET_NAME}/cover/" \
+  --endpoint-url "${R2_ENDPOINT}" \
+  --delete \
+  --exclude "*" \
+  --include "*.webp" \
+  --cache-control "public, max-age=31536000, immutable" \
+  --content-type "image/webp"
+
 aws s3 cp \
   "${SONG_DATA_PATH}/data.json" \
   "s3://${R2_BUCKET_NAME}/data.json" \
   --endpoint-url "${R2_ENDPOINT}" \
   --cache-control "public, max-age=0, must-revalidate" \
   --content-type "application/json"
+
+if [[ -f "${SONG_DATA_PATH}/locked_songs.json" ]]; then
+  aws s3 cp \
+    "${SONG_DATA_PATH}/locked_songs.json" \
+    "s3://${R2_BUCKET_NAME}/locked_songs.json" \
+    --endpoint-url "${R2_ENDPOINT}" \
+    --cache-control "public, max-age=0, must-revalidate" \
+    --content-type "application/json"
+fi
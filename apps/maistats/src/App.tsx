@@ -1251,7 +1251,7 @@ function App() {
 
     const classifiedRows = scoreData.filter(
       (row): row is RatedScoreRow =>
-        row.rating !== null && row.version !== null,
+        row.rating !== null && row.version !== null && row.chartType !== 'UTAGE',
     );
     const newRows = classifiedRows
       .filter((row) => latestSet.has(row.version))
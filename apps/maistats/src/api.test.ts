@@ -84,6 +84,46 @@ describe('formatApiErrorMessage', () => {
     });
   });
 
+  it('parses utage sheets from data.json', async () => {
+    vi.stubGlobal(
+      'fetch',
+      vi.fn(async () =>
+        new Response(
+          JSON.stringify({
+            generatedAt: '2026-03-24T00:00:00Z',
+            songs: [
+              {
+                title: '[宴]Song U',
+                genre: '宴会場',
+                artist: 'Artist U',
+                sheets: [
+                  {
+                    type: 'utage',
+                    difficulty: 'master',
+                    level: '12+?',
+                    version: 'PRiSM',
+                    region: { jp: true, intl: true },
+                  },
+                ],
+              },
+            ],
+          }),
+          { headers: { 'content-type': 'application/json' } },
+        ),
+      ),
+    );
+
+    const metadata = await fetchAllSongMetadata('https://maimai-charts.muhwan.dev');
+    const song = metadata.get(JSON.stringify(['[宴]Song U', '宴会場', 'Artist U']));
+
+    expect(song?.sheets[0]).toMatchObject({
+      chart_type: 'UTAGE',
+      difficulty: 'MASTER',
+      level: '12+?',
+      internal_level: null,
+    });
+  });
+
   it('derives version options from data.json', async () => {
     vi.stubGlobal(
       'fetch',
@@ -2,6 +2,7 @@ import type { TranslationKey, TranslationVariables } from './app/i18n';
 import { VERSION_ORDER } from './app/constants';
 import type {
   ApiErrorResponse,
+  ChartType,
   CollectorLogsResponse,
   CollectorVersionResponse,
   PlayRecordApiResponse,
@@ -168,10 +169,11 @@ function indexSongMetadata(songs: SongInfoResponse[]): Map<string, SongInfoRespo
   return metadata;
 }
 
-const CHART_TYPE_MAP: Record<string, 'STD' | 'DX'> = {
+const CHART_TYPE_MAP: Record<string, ChartType> = {
   standard: 'STD',
   std: 'STD',
   dx: 'DX',
+  utage: 'UTAGE',
 };
 
 const DIFFICULTY_MAP: Record<string, 'BASIC' | 'ADVANCED' | 'EXPERT' | 'MASTER' | 'Re:MASTER'> = {
@@ -182,7 +184,7 @@ const DIFFICULTY_MAP: Record<string, 'BASIC' | 'ADVANCED' | 'EXPERT' | 'MASTER'
   remaster: 'Re:MASTER',
 };
 
-function parseChartType(value: string): 'STD' | 'DX' {
+function parseChartType(value: string): ChartType {
   const normalized = value.trim().toLowerCase();
   const chartType = CHART_TYPE_MAP[normalized];
   if (!chartType) {
@@ -18,7 +18,7 @@ export const DEFAULT_SONG_DATABASE_URL =
 export const DEFAULT_RECORD_COLLECTOR_URL =
   ENV_RECORD_COLLECTOR_URL || 'http://localhost:3000';
 
-export const CHART_TYPES: ChartType[] = ['STD', 'DX'];
+export const CHART_TYPES: ChartType[] = ['STD', 'DX', 'UTAGE'];
 export const DIFFICULTIES: DifficultyCategory[] = [
   'BASIC',
   'ADVANCED',
@@ -6,6 +6,8 @@ export function getChartTypeToneClass(value: ChartType | null | undefined): stri
       return 'chart-type-std';
     case 'DX':
       return 'chart-type-dx';
+    case 'UTAGE':
+      return 'chart-type-utage';
     default:
       return 'chart-type-unknown';
   }
@@ -1,4 +1,4 @@
-export type ChartType = 'STD' | 'DX';
+export type ChartType = 'STD' | 'DX' | 'UTAGE';
 
 export type DifficultyCategory =
   | 'BASIC'
@@ -6,13 +6,13 @@ pub mod intl {
     pub const MAIMAI_MOBILE_ROOT: &str = "https://maimaidx-eng.com/maimai-mobile/";
     pub const RECORD_URL: &str = "https://maimaidx-eng.com/maimai-mobile/record/";
 
-    pub fn default_mobile_headers() -> eyre::Result<HeaderMap> {
+    pub const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+
+    pub fn default_mobile_headers(user_agent: &str) -> eyre::Result<HeaderMap> {
         let mut headers = HeaderMap::new();
         headers.insert(
             reqwest::header::USER_AGENT,
-            HeaderValue::from_static(
-                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1",
-            ),
+            HeaderValue::from_str(user_agent).wrap_err("invalid User-Agent header value")?,
         );
         headers.insert(
             reqwest::header::ACCEPT,
@@ -1,10 +1,14 @@
 use eyre::{Result, WrapErr};
+use models::rating::{RatingBreakdown, RatingBucket};
 use models::{
-    ChartType, DifficultyCategory, ParsedPlayerProfile, PlayRecordApiResponse, ScoreApiResponse,
-    SongAliases, SongChartRegion, SongDetailScoreApiResponse, VersionApiResponse,
+    ChartInternalLevelPatch, ChartType, DifficultyCategory, ParsedPlayerProfile,
+    ParsedRatingTargets, PlayRecordApiResponse, RatingSnapshotApiResponse, RecapStateApiResponse,
+    ScoreApiResponse, SongAliases, SongChartRegion, SongDetailScoreApiResponse,
+    SyncStatusApiResponse, TargetRatingApiResponse, VersionApiResponse, normalize_title,
 };
 use reqwest::{Client, Url};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 use std::time::Instant;
@@ -93,6 +97,18 @@ pub struct SongCatalogSheet {
     pub region: SongChartRegion,
 }
 
+impl SongCatalogSheet {
+    /// NEW/OLD bucket of this sheet. STD and DX sheets of one song can come from different
+    /// versions (e.g. a DX chart added to an old song), so this must not be decided per title.
+    /// Sheets without a known version count as OLD.
+    pub fn rating_bucket(&self) -> RatingBucket {
+        self.version
+            .as_deref()
+            .map(RatingBucket::from_version)
+            .unwrap_or(RatingBucket::Old)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SongCatalogSong {
     pub title: String,
@@ -136,8 +152,13 @@ pub struct SongDatabaseClient {
     client: Client,
     base_url: String,
     cache: Arc<RwLock<Option<CachedSongCatalog>>>,
+    include_estimated_internal_levels: bool,
+    internal_level_patches: Arc<RwLock<HashMap<ChartPatchKey, f32>>>,
 }
 
+/// `(title, genre, artist, chart_type, diff_category)`: the title alone is not unique.
+type ChartPatchKey = (String, String, String, ChartType, DifficultyCategory);
+
 #[derive(Debug, Clone)]
 pub struct RecordCollectorClient {
     client: Client,
@@ -193,7 +214,51 @@ pub fn normalize_record_collector_url(input: &str) -> Result<String> {
     Ok(url.as_str().trim_end_matches('/').to_string())
 }
 
-fn convert_song_catalog(database: models::SongDatabase) -> Result<Vec<SongCatalogSong>> {
+/// Internal levels accepted by `upsert_chart_internal_level`.
+pub fn is_valid_internal_level(internal_level: f32) -> bool {
+    (1.0..=15.0).contains(&internal_level)
+}
+
+fn apply_internal_level_patches(
+    songs: &mut [SongCatalogSong],
+    patches: &HashMap<ChartPatchKey, f32>,
+) {
+    if patches.is_empty() {
+        return;
+    }
+    for song in songs {
+        for sheet in &mut song.sheets {
+            let key = (
+                song.title.clone(),
+                song.genre.clone(),
+                song.artist.clone(),
+                sheet.chart_type,
+                sheet.diff_category,
+            );
+            if let Some(internal_level) = patches.get(&key) {
+                sheet.internal_level = Some(*internal_level);
+            }
+        }
+    }
+}
+
+/// Drop the growth slack left by JSON deserialization before the catalog is cached for the
+/// lifetime of the process, so the sheet and alias vectors only hold what they contain.
+/// The catalog is kept for as long as the bot or collector runs, which matters on small VPS
+/// deployments where both share memory.
+fn shrink_song_catalog(songs: &mut Vec<SongCatalogSong>) {
+    for song in songs.iter_mut() {
+        song.sheets.shrink_to_fit();
+        song.aliases.en.shrink_to_fit();
+        song.aliases.ko.shrink_to_fit();
+    }
+    songs.shrink_to_fit();
+}
+
+fn convert_song_catalog(
+    database: models::SongDatabase,
+    include_estimated_internal_levels: bool,
+) -> Result<Vec<SongCatalogSong>> {
     database
         .songs
         .into_iter()
@@ -210,9 +275,12 @@ fn convert_song_catalog(database: models::SongDatabase) -> Result<Vec<SongCatalo
                         .difficulty
                         .parse::<DifficultyCategory>()
                         .map_err(|_| eyre::eyre!("parse difficulty"))?;
+                    let is_estimate =
+                        sheet.internal_level_source == Some(models::InternalLevelSource::Estimate);
                     let internal_level = sheet
                         .internal_level
                         .as_deref()
+                        .filter(|_| include_estimated_internal_levels || !is_estimate)
                         .and_then(|value| value.trim().parse::<f32>().ok());
 
                     Ok::<_, eyre::Error>(SongCatalogSheet {
@@ -245,9 +313,19 @@ impl SongDatabaseClient {
             client,
             base_url,
             cache: Arc::new(RwLock::new(None)),
+            include_estimated_internal_levels: false,
+            internal_level_patches: Arc::new(RwLock::new(HashMap::new())),
         })
     }
 
+    /// Keep band-estimated internal levels (`internalLevelSource: "estimate"`) instead of
+    /// treating those charts as having no internal level. Off by default so rating only
+    /// uses exact chart constants.
+    pub fn with_estimated_internal_levels(mut self, include: bool) -> Self {
+        self.include_estimated_internal_levels = include;
+        self
+    }
+
     pub async fn list_song_catalog(&self) -> Result<Vec<SongCatalogSong>> {
         const SONG_DATABASE_CACHE_TTL: Duration = Duration::from_secs(3600);
 
@@ -279,7 +357,9 @@ impl SongDatabaseClient {
             .json::<models::SongDatabase>()
             .await
             .wrap_err("parse song database response")?;
-        let songs = convert_song_catalog(response)?;
+        let mut songs = convert_song_catalog(response, self.include_estimated_internal_levels)?;
+        apply_internal_level_patches(&mut songs, &*self.internal_level_patches.read().await);
+        shrink_song_catalog(&mut songs);
 
         let mut cache = self.cache.write().await;
         *cache = Some(CachedSongCatalog {
@@ -290,6 +370,53 @@ impl SongDatabaseClient {
         Ok(songs)
     }
 
+    /// Override one chart's internal level in memory without waiting for a song database
+    /// rebuild. The patch is applied to the cached catalog immediately and re-applied after
+    /// every refetch until the process restarts; `data.json` itself is not modified.
+    ///
+    /// Returns `Ok(false)` when the catalog has no chart matching the patch.
+    pub async fn upsert_chart_internal_level(
+        &self,
+        patch: &ChartInternalLevelPatch,
+    ) -> Result<bool> {
+        eyre::ensure!(
+            is_valid_internal_level(patch.internal_level),
+            "internal level must be between 1.0 and 15.0, got {}",
+            patch.internal_level
+        );
+
+        let songs = self.list_song_catalog().await?;
+        let exists = songs.iter().any(|song| {
+            song.title == patch.title
+                && song.genre == patch.genre
+                && song.artist == patch.artist
+                && song.sheets.iter().any(|sheet| {
+                    sheet.chart_type == patch.chart_type
+                        && sheet.diff_category == patch.diff_category
+                })
+        });
+        if !exists {
+            return Ok(false);
+        }
+
+        let mut patches = self.internal_level_patches.write().await;
+        patches.insert(
+            (
+                patch.title.clone(),
+                patch.genre.clone(),
+                patch.artist.clone(),
+                patch.chart_type,
+                patch.diff_category,
+            ),
+            patch.internal_level,
+        );
+        if let Some(cached) = self.cache.write().await.as_mut() {
+            apply_internal_level_patches(&mut cached.songs, &patches);
+        }
+
+        Ok(true)
+    }
+
     pub fn cover_url(&self, image_name: &str) -> String {
         format!(
             "{}/cover/{}",
@@ -298,18 +425,41 @@ impl SongDatabaseClient {
         )
     }
 
+    /// Fetch a cover once so the song database host has it cached before Discord asks for it.
+    pub async fn warm_cover(&self, image_name: &str) -> Result<()> {
+        self.fetch_cover(image_name).await?;
+        Ok(())
+    }
+
+    pub async fn fetch_cover(&self, image_name: &str) -> Result<Vec<u8>> {
+        let resp = self
+            .client
+            .get(self.cover_url(image_name))
+            .send()
+            .await
+            .wrap_err("fetch cover")?;
+        if !resp.status().is_success() {
+            return Err(eyre::eyre!(
+                "Failed to fetch cover {image_name}: HTTP {}",
+                resp.status()
+            ));
+        }
+        let bytes = resp.bytes().await.wrap_err("read cover body")?;
+        Ok(bytes.to_vec())
+    }
+
     pub async fn search_song_metadata(
         &self,
         request: &SongMetadataSearchRequest,
     ) -> Result<SongMetadataSearchResponse> {
         let songs = self.list_song_catalog().await?;
+        let title = request.title.as_deref().map(normalize_title);
         let mut items = Vec::new();
 
         for song in songs {
-            let matches_song = request
-                .title
+            let matches_song = title
                 .as_deref()
-                .is_none_or(|title| song.title == title)
+                .is_none_or(|title| normalize_title(&song.title) == title)
                 && request
                     .genre
                     .as_deref()
@@ -446,6 +596,56 @@ impl RecordCollectorClient {
         self.get_with_retry("/api/player").await
     }
 
+    pub async fn get_target_rating(&self) -> Result<Option<u32>> {
+        let response: TargetRatingApiResponse = self.get_with_retry("/api/player/target").await?;
+        Ok(response.target_rating)
+    }
+
+    pub async fn set_target_rating(&self, target_rating: Option<u32>) -> Result<()> {
+        self.put_json(
+            "/api/player/target",
+            &TargetRatingApiResponse { target_rating },
+            "store target rating",
+        )
+        .await
+    }
+
+    pub async fn get_recap_state(&self) -> Result<RecapStateApiResponse> {
+        self.get_with_retry("/api/player/recap-state").await
+    }
+
+    /// Store the fields `update` carries; the collector keeps the rest.
+    pub async fn update_recap_state(&self, update: &RecapStateApiResponse) -> Result<()> {
+        self.put_json("/api/player/recap-state", update, "store recap state")
+            .await
+    }
+
+    /// Override one chart's internal level on the record collector, which recomputes the
+    /// stored ratings with it.
+    pub async fn put_chart_internal_level(&self, patch: &ChartInternalLevelPatch) -> Result<()> {
+        self.put_json(
+            "/api/admin/internal-level",
+            patch,
+            "store chart internal level",
+        )
+        .await
+    }
+
+    /// Rating changes since `since_unixtime`, oldest first, led by the rating in effect then.
+    pub async fn get_rating_history(
+        &self,
+        since_unixtime: i64,
+    ) -> Result<Vec<RatingSnapshotApiResponse>> {
+        self.get_with_retry(&format!(
+            "/api/player/rating-history?since={since_unixtime}"
+        ))
+        .await
+    }
+
+    pub async fn get_sync_status(&self) -> Result<SyncStatusApiResponse> {
+        self.get_with_retry("/api/sync/status").await
+    }
+
     pub async fn get_version(&self) -> Result<String> {
         let response: VersionApiResponse = self.get_with_retry("/api/version").await?;
         Ok(response.version)
@@ -465,6 +665,14 @@ impl RecordCollectorClient {
         self.get_with_retry("/api/scores/rated").await
     }
 
+    pub async fn get_rating_breakdown(&self) -> Result<RatingBreakdown> {
+        self.get_with_retry("/api/rating").await
+    }
+
+    pub async fn get_rating_targets(&self) -> Result<ParsedRatingTargets> {
+        self.get_with_retry("/api/rating/targets").await
+    }
+
     pub async fn get_song_detail_scores(
         &self,
         title: &str,
@@ -480,6 +688,34 @@ impl RecordCollectorClient {
         .await
     }
 
+    async fn put_json<T: Serialize + ?Sized>(
+        &self,
+        path: &str,
+        body: &T,
+        context: &'static str,
+    ) -> Result<()> {
+        let url = format!("{}{}", self.base_url, path);
+        let resp = self
+            .client
+            .put(&url)
+            .json(body)
+            .send()
+            .await
+            .wrap_err(context)?;
+
+        if resp.status().is_success() {
+            return Ok(());
+        }
+
+        let status = resp.status();
+        let body = resp.text().await.unwrap_or_default();
+        if let Ok(parsed) = serde_json::from_str::<RecordCollectorErrorResponse>(&body) {
+            return Err(ApiError::from_record_collector(status, parsed).into());
+        }
+
+        Err(ApiError::from_http_text(status, &body).into())
+    }
+
     async fn get_with_retry<T: for<'de> Deserialize<'de>>(&self, path: &str) -> Result<T> {
         let url = format!("{}{}", self.base_url, path);
         for attempt in 0..3 {
@@ -513,7 +749,14 @@ impl RecordCollectorClient {
 
 #[cfg(test)]
 mod tests {
-    use super::normalize_record_collector_url;
+    use super::{
+        CachedSongCatalog, SongCatalogSheet, SongCatalogSong, SongDatabaseClient,
+        convert_song_catalog, normalize_record_collector_url, shrink_song_catalog,
+    };
+    use models::{
+        ChartInternalLevelPatch, ChartType, DifficultyCategory, SongAliases, SongChartRegion,
+    };
+    use std::time::Instant;
 
     #[test]
     fn normalize_record_collector_url_rejects_invalid_input() {
@@ -571,4 +814,156 @@ mod tests {
 
         assert_eq!(normalized, "https://collector.example:3000/maistats");
     }
+
+    #[test]
+    fn convert_song_catalog_drops_estimated_internal_levels_unless_included() {
+        let database = serde_json::json!({
+            "generatedAt": "2026-01-01T00:00:00+00:00",
+            "songs": [{
+                "title": "Song",
+                "genre": "maimai",
+                "artist": "Artist",
+                "sheets": [
+                    {
+                        "type": "dx",
+                        "difficulty": "master",
+                        "level": "13+",
+                        "internalLevel": "13.6",
+                        "internalLevelSource": "estimate",
+                        "region": { "jp": true, "intl": true }
+                    },
+                    {
+                        "type": "dx",
+                        "difficulty": "expert",
+                        "level": "12",
+                        "internalLevel": "12.3",
+                        "internalLevelSource": "level_page",
+                        "region": { "jp": true, "intl": true }
+                    }
+                ]
+            }]
+        });
+
+        let levels = |include| {
+            let database = serde_json::from_value(database.clone()).expect("valid song database");
+            convert_song_catalog(database, include).expect("convert catalog")[0]
+                .sheets
+                .iter()
+                .map(|sheet| sheet.internal_level)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(levels(false), vec![None, Some(12.3)]);
+        assert_eq!(levels(true), vec![Some(13.6), Some(12.3)]);
+    }
+
+    #[test]
+    fn shrink_song_catalog_keeps_lookups_working() {
+        let sheet = |difficulty: &str| {
+            serde_json::json!({
+                "type": "dx",
+                "difficulty": difficulty,
+                "level": "13",
+                "internalLevel": "13.2",
+                "region": { "jp": true, "intl": true }
+            })
+        };
+        let sheets = ["basic", "advanced", "expert", "master", "remaster"].map(sheet);
+        let database = serde_json::json!({
+            "generatedAt": "2026-01-01T00:00:00+00:00",
+            "songs": [{
+                "title": "Song",
+                "genre": "maimai",
+                "artist": "Artist",
+                "aliases": { "en": ["alias one", "alias two", "alias three"] },
+                "sheets": sheets
+            }]
+        });
+        let database = serde_json::from_value(database).expect("valid song database");
+        let mut songs = convert_song_catalog(database, false).expect("convert catalog");
+        songs.reserve(64);
+        songs[0].sheets.reserve(64);
+        songs[0].aliases.en.reserve(64);
+        let before = serde_json::to_value(&songs).expect("serialize catalog");
+
+        shrink_song_catalog(&mut songs);
+
+        assert_eq!(
+            serde_json::to_value(&songs).expect("serialize catalog"),
+            before
+        );
+        assert_eq!(songs[0].sheets.len(), 5);
+        assert_eq!(
+            songs[0].aliases.en,
+            vec!["alias one", "alias two", "alias three"]
+        );
+        let master = songs
+            .iter()
+            .find(|song| song.aliases.en.iter().any(|alias| alias == "alias two"))
+            .and_then(|song| {
+                song.sheets
+                    .iter()
+                    .find(|sheet| sheet.diff_category == DifficultyCategory::Master)
+            })
+            .expect("master sheet");
+        assert_eq!(master.internal_level, Some(13.2));
+    }
+
+    #[tokio::test]
+    async fn upsert_chart_internal_level_changes_subsequent_lookup() {
+        let client = SongDatabaseClient::new("http://127.0.0.1:9".to_string()).expect("client");
+        let song = |artist: &str| SongCatalogSong {
+            title: "Link".to_string(),
+            genre: "maimai".to_string(),
+            artist: artist.to_string(),
+            image_name: None,
+            aliases: SongAliases::default(),
+            sheets: vec![SongCatalogSheet {
+                chart_type: ChartType::Dx,
+                diff_category: DifficultyCategory::Master,
+                level: "13+".to_string(),
+                version: None,
+                internal_level: None,
+                region: SongChartRegion {
+                    jp: true,
+                    intl: true,
+                },
+            }],
+        };
+        *client.cache.write().await = Some(CachedSongCatalog {
+            songs: vec![song("Artist A"), song("Artist B")],
+            fetched_at: Instant::now(),
+        });
+        let patch = |chart_type: ChartType, internal_level: f32| ChartInternalLevelPatch {
+            title: "Link".to_string(),
+            genre: "maimai".to_string(),
+            artist: "Artist A".to_string(),
+            chart_type,
+            diff_category: DifficultyCategory::Master,
+            internal_level,
+        };
+
+        assert!(
+            client
+                .upsert_chart_internal_level(&patch(ChartType::Dx, 13.7))
+                .await
+                .expect("upsert existing chart")
+        );
+        let songs = client.list_song_catalog().await.expect("cached catalog");
+        assert_eq!(songs[0].sheets[0].internal_level, Some(13.7));
+        assert_eq!(songs[1].sheets[0].internal_level, None);
+
+        assert!(
+            !client
+                .upsert_chart_internal_level(&patch(ChartType::Std, 13.7))
+                .await
+                .expect("missing chart is not an error")
+        );
+        assert!(
+            client
+                .upsert_chart_internal_level(&patch(ChartType::Dx, 16.0))
+                .await
+                .is_err()
+        );
+    }
 }
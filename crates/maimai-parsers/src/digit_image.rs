@@ -0,0 +1,65 @@
+//! Some page layouts draw numbers as one `<img>` per glyph (`.../num_7.png`, `num_dot.png`,
+//! `num_percent.png`) instead of text; these helpers read the glyphs back from file names.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DigitGlyph {
+    Digit(char),
+    Dot,
+    Percent,
+}
+
+/// `https://.../num_7.png?ver=1.50` -> `'7'`.
+pub(crate) fn digit_from_src(src: &str) -> Option<char> {
+    match glyph_from_src(src)? {
+        DigitGlyph::Digit(digit) => Some(digit),
+        DigitGlyph::Dot | DigitGlyph::Percent => None,
+    }
+}
+
+/// `num_7.png` -> `Digit('7')`, `num_dot.png` / `num_period.png` -> `Dot`,
+/// `num_percent.png` -> `Percent`; anything else is not a number glyph.
+pub(crate) fn glyph_from_src(src: &str) -> Option<DigitGlyph> {
+    let file_name = src.split(['?', '#']).next()?.rsplit('/').next()?;
+    let stem = file_name.strip_suffix(".png")?;
+    let (prefix, glyph) = stem.rsplit_once('_')?;
+    if !prefix.ends_with("num") {
+        return None;
+    }
+    match glyph {
+        "dot" | "period" => Some(DigitGlyph::Dot),
+        "percent" => Some(DigitGlyph::Percent),
+        _ => {
+            let mut chars = glyph.chars();
+            let digit = chars.next().filter(char::is_ascii_digit)?;
+            chars.next().is_none().then_some(DigitGlyph::Digit(digit))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DigitGlyph, digit_from_src, glyph_from_src};
+
+    #[test]
+    fn digit_from_src_reads_digit_filenames_only() {
+        assert_eq!(
+            digit_from_src("https://maimaidx-eng.com/maimai-mobile/img/rating/num_7.png?ver=1.50"),
+            Some('7')
+        );
+        assert_eq!(digit_from_src("img/rating_num_0.png"), Some('0'));
+        assert_eq!(digit_from_src("img/rating_base_silver.png"), None);
+        assert_eq!(digit_from_src("img/num_12.png"), None);
+        assert_eq!(digit_from_src("img/num_dot.png"), None);
+    }
+
+    #[test]
+    fn glyph_from_src_reads_achievement_punctuation() {
+        assert_eq!(glyph_from_src("img/num_dot.png"), Some(DigitGlyph::Dot));
+        assert_eq!(glyph_from_src("img/num_period.png"), Some(DigitGlyph::Dot));
+        assert_eq!(
+            glyph_from_src("img/num_percent.png"),
+            Some(DigitGlyph::Percent)
+        );
+        assert_eq!(glyph_from_src("img/num_star.png"), None);
+    }
+}
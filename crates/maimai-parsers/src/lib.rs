@@ -1,4 +1,6 @@
+mod digit_image;
 pub mod internal_level_page;
+mod mojibake;
 pub mod player_data;
 pub mod playlog_detail;
 pub mod rating_target;
@@ -13,3 +15,13 @@ pub use rating_target::parse_rating_target_music_html;
 pub use recent::parse_recent_html;
 pub use score_list::parse_scores_html;
 pub use song_detail::parse_song_detail_html;
+
+/// Parsed achievements above the in-game maximum can only come from a misparse, so they are
+/// dropped (and logged) instead of being stored as a score.
+pub(crate) fn checked_achievement_percent(percent: f32) -> Option<f32> {
+    if percent > models::rating::MAX_ACHIEVEMENT_PERCENT {
+        tracing::warn!("ignoring impossible achievement {percent}% (max 101%)");
+        return None;
+    }
+    Some(percent)
+}
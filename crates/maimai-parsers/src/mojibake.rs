@@ -0,0 +1,99 @@
+/// Characters Windows-1252 puts in `0x80..=0x9F`, indexed by `byte - 0x80`. Undefined slots
+/// decode as the matching C1 control (the Latin-1 reading), so they stay `None` here.
+const CP1252_HIGH: [Option<char>; 32] = [
+    Some('€'),
+    None,
+    Some('‚'),
+    Some('ƒ'),
+    Some('„'),
+    Some('…'),
+    Some('†'),
+    Some('‡'),
+    Some('ˆ'),
+    Some('‰'),
+    Some('Š'),
+    Some('‹'),
+    Some('Œ'),
+    None,
+    Some('Ž'),
+    None,
+    None,
+    Some('‘'),
+    Some('’'),
+    Some('“'),
+    Some('”'),
+    Some('•'),
+    Some('–'),
+    Some('—'),
+    Some('˜'),
+    Some('™'),
+    Some('š'),
+    Some('›'),
+    Some('œ'),
+    None,
+    Some('ž'),
+    Some('Ÿ'),
+];
+
+/// Undo UTF-8 that was decoded as Latin-1 / Windows-1252 somewhere upstream (`ã‚¬` for `ガ`),
+/// logging every repair. Text that does not round-trip to valid UTF-8 is returned unchanged,
+/// so genuine accented titles such as `Pokémon` are left alone.
+pub(crate) fn repair_mojibake(text: String) -> String {
+    match decode_mojibake(&text) {
+        Some(repaired) => {
+            tracing::warn!("repaired mojibake text {text:?} -> {repaired:?}");
+            repaired
+        }
+        None => text,
+    }
+}
+
+fn decode_mojibake(text: &str) -> Option<String> {
+    if text.is_ascii() {
+        return None;
+    }
+
+    let bytes = text
+        .chars()
+        .map(|c| match u32::from(c) {
+            code @ 0..=0xFF => Some(code as u8),
+            _ => CP1252_HIGH
+                .iter()
+                .position(|high| *high == Some(c))
+                .map(|idx| 0x80 + idx as u8),
+        })
+        .collect::<Option<Vec<u8>>>()?;
+    let repaired = String::from_utf8(bytes).ok()?;
+
+    let plausible = !repaired
+        .chars()
+        .any(|c| c.is_control() || c == char::REPLACEMENT_CHARACTER);
+    plausible.then_some(repaired)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::repair_mojibake;
+
+    #[test]
+    fn repair_mojibake_restores_double_encoded_titles() {
+        assert_eq!(
+            repair_mojibake("ã‚¬ãƒ©ãƒ†ã‚¢ã\u{81}®èžºæ—‹".to_string()),
+            "ガラテアの螺旋"
+        );
+        assert_eq!(repair_mojibake("LÃ¶schen".to_string()), "Löschen");
+    }
+
+    #[test]
+    fn repair_mojibake_leaves_clean_text_alone() {
+        for title in [
+            "ガラテアの螺旋",
+            "Pokémon",
+            "Ã",
+            "Session High⤴",
+            "plain ascii",
+        ] {
+            assert_eq!(repair_mojibake(title.to_string()), title);
+        }
+    }
+}
@@ -3,6 +3,8 @@ use scraper::{Html, Selector};
 
 use models::ParsedPlayerProfile;
 
+use crate::digit_image::digit_from_src;
+
 pub fn parse_player_data_html(html: &str) -> eyre::Result<ParsedPlayerProfile> {
     let document = Html::parse_document(html);
 
@@ -17,12 +19,10 @@ pub fn parse_player_data_html(html: &str) -> eyre::Result<ParsedPlayerProfile> {
         .filter(|s| !s.is_empty())
         .ok_or_else(|| eyre::eyre!("missing user name (.name_block)"))?;
 
-    let rating_text = document
+    let rating = document
         .select(&rating_selector)
         .next()
-        .map(|e| collect_text(&e))
-        .unwrap_or_default();
-    let rating = parse_u32_digits(&rating_text)
+        .and_then(|e| parse_u32_digits(&collect_text(&e)).or_else(|| parse_rating_digit_images(&e)))
         .ok_or_else(|| eyre::eyre!("missing rating (.rating_block)"))?;
 
     let counts_text = document
@@ -39,15 +39,74 @@ pub fn parse_player_data_html(html: &str) -> eyre::Result<ParsedPlayerProfile> {
             .ok_or_else(|| eyre::eyre!("missing current version play count"))?;
     let total_play_count = extract_number_after(&counts_text, "maimaiDX total play count")
         .ok_or_else(|| eyre::eyre!("missing total play count"))?;
+    let area_rank = extract_number_after(&counts_text, "area ranking");
+    let course_rank = rank_image_code(&document, "/img/course/course_rank_")
+        .and_then(course_rank_name)
+        .map(str::to_string);
+    let class_rank = rank_image_code(&document, "/img/class/class_rank_s_")
+        .and_then(class_rank_name)
+        .map(str::to_string);
 
     Ok(ParsedPlayerProfile {
         user_name,
         rating,
         current_version_play_count,
         total_play_count,
+        area_rank,
+        course_rank,
+        class_rank,
     })
 }
 
+/// The rank plates are images named `<prefix><2-digit code><hash>.png`; only the code is
+/// meaningful.
+fn rank_image_code(document: &Html, prefix: &str) -> Option<usize> {
+    let img_selector = Selector::parse("img").unwrap();
+    let src = document
+        .select(&img_selector)
+        .filter_map(|img| img.value().attr("src"))
+        .find(|src| src.contains(prefix))?;
+    let after = &src[src.find(prefix)? + prefix.len()..];
+    after.get(..2)?.parse::<usize>().ok()
+}
+
+/// Code `00` is the blank plate shown before any course is cleared.
+fn course_rank_name(code: usize) -> Option<&'static str> {
+    const COURSE_RANKS: [&str; 22] = [
+        "初段",
+        "二段",
+        "三段",
+        "四段",
+        "五段",
+        "六段",
+        "七段",
+        "八段",
+        "九段",
+        "十段",
+        "真初段",
+        "真二段",
+        "真三段",
+        "真四段",
+        "真五段",
+        "真六段",
+        "真七段",
+        "真八段",
+        "真九段",
+        "真十段",
+        "真皆伝",
+        "裏皆伝",
+    ];
+    COURSE_RANKS.get(code.checked_sub(1)?).copied()
+}
+
+fn class_rank_name(code: usize) -> Option<&'static str> {
+    const CLASS_RANKS: [&str; 26] = [
+        "B5", "B4", "B3", "B2", "B1", "A5", "A4", "A3", "A2", "A1", "S5", "S4", "S3", "S2", "S1",
+        "SS5", "SS4", "SS3", "SS2", "SS1", "SSS5", "SSS4", "SSS3", "SSS2", "SSS1", "LEGEND",
+    ];
+    CLASS_RANKS.get(code).copied()
+}
+
 fn collect_text(element: &scraper::ElementRef<'_>) -> String {
     element.text().collect::<Vec<_>>().join("")
 }
@@ -63,6 +122,19 @@ fn parse_u32_digits(text: &str) -> Option<u32> {
     digits.parse::<u32>().ok()
 }
 
+/// Some layouts draw the rating as one `<img>` per digit (`.../num_1.png`) instead of text.
+fn parse_rating_digit_images(element: &scraper::ElementRef<'_>) -> Option<u32> {
+    let img_selector = Selector::parse("img").unwrap();
+    let digits = element
+        .select(&img_selector)
+        .map(|img| img.value().attr("src").and_then(digit_from_src))
+        .collect::<Option<String>>()?;
+    if digits.is_empty() {
+        return None;
+    }
+    digits.parse::<u32>().ok()
+}
+
 fn extract_number_after(haystack: &str, needle: &str) -> Option<u32> {
     let start = haystack.find(needle)? + needle.len();
     let after = &haystack[start..];
@@ -2,6 +2,9 @@ use scraper::{Html, Selector};
 
 use models::ParsedPlaylogDetail;
 
+use crate::mojibake::repair_mojibake;
+use crate::recent::is_buddy_entry;
+
 pub fn parse_playlog_detail_html(html: &str) -> eyre::Result<ParsedPlaylogDetail> {
     let document = Html::parse_document(html);
 
@@ -23,6 +26,7 @@ pub fn parse_playlog_detail_html(html: &str) -> eyre::Result<ParsedPlaylogDetail
                 .map(|e| e.text().collect::<Vec<_>>().join(""))
                 .map(|s| s.trim().to_string())
         })
+        .map(repair_mojibake)
         .unwrap_or_default();
 
     let music_detail_idx = document
@@ -40,5 +44,6 @@ pub fn parse_playlog_detail_html(html: &str) -> eyre::Result<ParsedPlaylogDetail
     Ok(ParsedPlaylogDetail {
         title,
         music_detail_idx,
+        is_buddy_play: is_buddy_entry(&document.root_element()),
     })
 }
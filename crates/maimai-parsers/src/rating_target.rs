@@ -4,6 +4,9 @@ use models::{
     ChartType, DifficultyCategory, ParsedRatingTargetEntry, ParsedRatingTargets, ScoreRank,
 };
 
+use crate::checked_achievement_percent;
+use crate::mojibake::repair_mojibake;
+
 const SECTION_NEW: &str = "Songs for Rating(New)";
 const SECTION_OLD: &str = "Songs for Rating(Others)";
 const NEW_TARGET_COUNT: usize = 15;
@@ -73,6 +76,7 @@ fn parse_rating_entries(section_html: &str) -> eyre::Result<Vec<ParsedRatingTarg
             .next()
             .map(|e| collect_text(&e).trim().to_string())
             .filter(|s| !s.is_empty())
+            .map(repair_mojibake)
             .ok_or_else(|| eyre::eyre!("missing title (.music_name_block)"))?;
 
         let level = entry
@@ -134,12 +138,14 @@ fn parse_percent(text: &str) -> Option<f32> {
         .chars()
         .filter(|c| c.is_ascii_digit() || *c == '.')
         .collect::<String>();
-    digits.parse::<f32>().ok()
+    digits
+        .parse::<f32>()
+        .ok()
+        .and_then(checked_achievement_percent)
 }
 
 fn parse_rank_from_icon_src(src: &str) -> Option<ScoreRank> {
-    let key = icon_key(src)?;
-    key.parse::<ScoreRank>().ok()
+    ScoreRank::parse_any(&icon_key(src)?)
 }
 
 fn icon_key(src: &str) -> Option<String> {
@@ -2,6 +2,10 @@ use scraper::{ElementRef, Html, Selector};
 
 use models::{ChartType, DifficultyCategory, FcStatus, ParsedPlayRecord, ScoreRank, SyncStatus};
 
+use crate::checked_achievement_percent;
+use crate::digit_image::{DigitGlyph, glyph_from_src};
+use crate::mojibake::repair_mojibake;
+
 pub fn parse_recent_html(html: &str) -> eyre::Result<Vec<ParsedPlayRecord>> {
     let document = Html::parse_document(html);
 
@@ -20,6 +24,7 @@ pub fn parse_recent_html(html: &str) -> eyre::Result<Vec<ParsedPlayRecord>> {
     let chart_type_selector = Selector::parse("img.playlog_music_kind_icon").unwrap();
     let idx_selector = Selector::parse(r#"input[name="idx"]"#).unwrap();
     let img_selector = Selector::parse("img").unwrap();
+    let result_block_selector = Selector::parse(".playlog_result_block").unwrap();
 
     let mut out = Vec::new();
     for top in document.select(&top_selector) {
@@ -71,7 +76,10 @@ pub fn parse_recent_html(html: &str) -> eyre::Result<Vec<ParsedPlayRecord>> {
         let level = (!level.is_empty()).then_some(level);
 
         let title_raw = collect_text(&song_block);
-        let title = strip_level_from_title(&title_raw, level.as_deref().unwrap_or(""));
+        let title = repair_mojibake(strip_level_from_title(
+            &title_raw,
+            level.as_deref().unwrap_or(""),
+        ));
 
         let playlog_detail_idx = entry
             .select(&idx_selector)
@@ -86,23 +94,34 @@ pub fn parse_recent_html(html: &str) -> eyre::Result<Vec<ParsedPlayRecord>> {
             .and_then(parse_playlog_idx_components)
             .and_then(|(_, played_at_unixtime)| played_at_unixtime.parse::<i64>().ok());
 
-        let achievement_percent = entry
+        // Buddy entries render one result block per player; only the first one is ours, so
+        // the partner's rank/FC/sync icons must not leak into this record.
+        let is_buddy_play = is_buddy_entry(&entry);
+        let result_scope = if is_buddy_play {
+            entry.select(&result_block_selector).next().unwrap_or(entry)
+        } else {
+            entry
+        };
+
+        let achievement_percent = result_scope
             .select(&achievement_selector)
             .next()
-            .and_then(|e| parse_percent(&collect_text(&e)));
+            .and_then(|e| {
+                parse_percent(&collect_text(&e)).or_else(|| parse_achievement_digit_images(&e))
+            });
 
-        let achievement_new_record = entry
+        let achievement_new_record = result_scope
             .select(&achievement_new_record_selector)
             .next()
             .is_some();
 
-        let score_rank = entry
+        let score_rank = result_scope
             .select(&scorerank_selector)
             .next()
             .and_then(|e| e.value().attr("src"))
             .and_then(parse_rank_from_playlog_icon_src);
 
-        let (dx_score, dx_score_max) = entry
+        let (dx_score, dx_score_max) = result_scope
             .select(&dx_score_selector)
             .next()
             .and_then(|e| parse_dx_score_pair_from_fraction_text(&collect_text(&e)))
@@ -118,7 +137,7 @@ pub fn parse_recent_html(html: &str) -> eyre::Result<Vec<ParsedPlayRecord>> {
 
         let mut fc: Option<FcStatus> = None;
         let mut sync: Option<SyncStatus> = None;
-        for img in entry.select(&img_selector) {
+        for img in result_scope.select(&img_selector) {
             let Some(src) = img.value().attr("src") else {
                 continue;
             };
@@ -147,12 +166,29 @@ pub fn parse_recent_html(html: &str) -> eyre::Result<Vec<ParsedPlayRecord>> {
             sync,
             dx_score,
             dx_score_max,
+            is_buddy_play,
         });
     }
 
     Ok(out)
 }
 
+/// Buddy plays are marked by a `buddy` icon (or a `*buddy*` class) somewhere in the entry.
+/// No captured buddy page is checked in yet, so these markers are a best guess.
+pub(crate) fn is_buddy_entry(entry: &ElementRef<'_>) -> bool {
+    entry
+        .descendants()
+        .filter_map(ElementRef::wrap)
+        .any(|element| {
+            let value = element.value();
+            value.attr("class").is_some_and(|c| c.contains("buddy"))
+                || value
+                    .attr("src")
+                    .and_then(|src| src.rsplit('/').next())
+                    .is_some_and(|file| file.contains("buddy"))
+        })
+}
+
 fn parse_playlog_idx_components(raw: &str) -> Option<(&str, &str)> {
     let mut parts = raw.split(',');
     let playlog_detail_idx = parts.next()?.trim();
@@ -210,7 +246,31 @@ fn parse_percent(text: &str) -> Option<f32> {
         return None;
     }
     let number = trimmed.replace(['%', ' ', '\n'], "");
-    number.parse::<f32>().ok()
+    number
+        .parse::<f32>()
+        .ok()
+        .and_then(checked_achievement_percent)
+}
+
+/// Some layouts draw the achievement as images (`num_1.png`, `num_dot.png`, `num_percent.png`)
+/// instead of text; read it back from the file names.
+fn parse_achievement_digit_images(element: &ElementRef<'_>) -> Option<f32> {
+    let img_selector = Selector::parse("img").unwrap();
+    let mut number = String::new();
+    for img in element.select(&img_selector) {
+        match glyph_from_src(img.value().attr("src")?)? {
+            DigitGlyph::Digit(digit) => number.push(digit),
+            DigitGlyph::Dot => number.push('.'),
+            DigitGlyph::Percent => {}
+        }
+    }
+    if number.is_empty() {
+        return None;
+    }
+    number
+        .parse::<f32>()
+        .ok()
+        .and_then(checked_achievement_percent)
 }
 
 fn parse_dx_score_pair_from_fraction_text(text: &str) -> Option<(i32, i32)> {
@@ -264,59 +324,18 @@ fn parse_chart_type_from_icon_src(src: &str) -> Option<ChartType> {
 }
 
 fn parse_rank_from_playlog_icon_src(src: &str) -> Option<ScoreRank> {
-    let file = src.rsplit('/').next()?;
-    let file = file.split('?').next().unwrap_or(file);
-    let stem = file.strip_suffix(".png")?;
-    stem.parse::<ScoreRank>().ok()
+    ScoreRank::parse_any(src)
 }
 
+// The playlog page uses both `fc_<key>.png` and legacy `fc.png`/`fcplus.png` style names,
+// plus `fc_dummy.png` for "no FC"; `parse_any` accepts all of them.
 fn parse_fc_from_playlog_icon_src(src: &str) -> Option<FcStatus> {
-    let file = src.rsplit('/').next()?;
-    let file = file.split('?').next().unwrap_or(file);
-    let stem = file.strip_suffix(".png")?;
-    if stem == "fc_dummy" {
-        return None;
-    }
-
-    // The playlog page uses both `fc_<key>.png` and legacy `fc.png`/`fcplus.png` style names.
-    let key = if let Some(rest) = stem.strip_prefix("fc_") {
-        rest
-    } else {
-        match stem {
-            "fc" => "fc",
-            "fcplus" => "fcp",
-            "ap" => "ap",
-            "applus" => "app",
-            _ => return None,
-        }
-    };
-
-    key.parse::<FcStatus>().ok()
+    FcStatus::parse_any(src)
 }
 
+// Same for `sync_<key>.png`, legacy `sync.png`/`fsplus.png`, and `sync_dummy.png`.
 fn parse_sync_from_playlog_icon_src(src: &str) -> Option<SyncStatus> {
-    let file = src.rsplit('/').next()?;
-    let file = file.split('?').next().unwrap_or(file);
-    let stem = file.strip_suffix(".png")?;
-    if stem == "sync_dummy" {
-        return None;
-    }
-
-    // The playlog page uses both `sync_<key>.png` and legacy `sync.png` style names.
-    let key = if let Some(rest) = stem.strip_prefix("sync_") {
-        rest
-    } else {
-        match stem {
-            "sync" => "sync",
-            "fs" => "fs",
-            "fsplus" => "fsp",
-            "fdx" => "fdx",
-            "fdxplus" => "fdxp",
-            _ => return None,
-        }
-    };
-
-    key.parse::<SyncStatus>().ok()
+    SyncStatus::parse_any(src)
 }
 
 fn merge_sync(existing: Option<SyncStatus>, candidate: Option<SyncStatus>) -> Option<SyncStatus> {
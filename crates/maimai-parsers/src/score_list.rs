@@ -2,6 +2,9 @@ use scraper::{ElementRef, Html, Selector};
 
 use models::{ChartType, DifficultyCategory, FcStatus, ParsedScoreEntry, ScoreRank, SyncStatus};
 
+use crate::checked_achievement_percent;
+use crate::mojibake::repair_mojibake;
+
 pub fn parse_scores_html(html: &str, diff: u8) -> eyre::Result<Vec<ParsedScoreEntry>> {
     let document = Html::parse_document(html);
 
@@ -25,6 +28,7 @@ pub fn parse_scores_html(html: &str, diff: u8) -> eyre::Result<Vec<ParsedScoreEn
             .unwrap_or_default()
             .trim()
             .to_string();
+        let title = repair_mojibake(title);
 
         let source_idx = entry
             .select(&idx_selector)
@@ -139,7 +143,11 @@ fn parse_percent(text: &str) -> Option<f32> {
         return None;
     }
     let number = trimmed.replace(['%', ' ', '\n'], "");
-    number.parse::<f32>().ok()
+    let Ok(percent) = number.parse::<f32>() else {
+        tracing::warn!("ignoring malformed achievement {trimmed:?}");
+        return None;
+    };
+    checked_achievement_percent(percent)
 }
 
 fn parse_dx_score_pair(text: &str) -> Option<(i32, i32)> {
@@ -167,18 +175,15 @@ fn parse_dx_score_pair(text: &str) -> Option<(i32, i32)> {
 }
 
 fn parse_rank_from_icon_src(src: &str) -> Option<ScoreRank> {
-    let key = icon_key(src)?;
-    key.parse::<ScoreRank>().ok()
+    ScoreRank::parse_any(&icon_key(src)?)
 }
 
 fn parse_fc_from_icon_src(src: &str) -> Option<FcStatus> {
-    let key = icon_key(src)?;
-    key.parse::<FcStatus>().ok()
+    FcStatus::parse_any(&icon_key(src)?)
 }
 
 fn parse_sync_from_icon_src(src: &str) -> Option<SyncStatus> {
-    let key = icon_key(src)?;
-    key.parse::<SyncStatus>().ok()
+    SyncStatus::parse_any(&icon_key(src)?)
 }
 
 fn merge_sync(existing: Option<SyncStatus>, candidate: Option<SyncStatus>) -> Option<SyncStatus> {
@@ -216,3 +221,22 @@ fn parse_chart_type_from_icon_src(src: &str) -> Option<ChartType> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::parse_percent;
+
+    #[test]
+    fn parse_percent_accepts_the_full_range_up_to_101() {
+        assert_eq!(parse_percent("101.0000%"), Some(101.0));
+        assert_eq!(parse_percent("100.0000%"), Some(100.0));
+        assert_eq!(parse_percent(" 99.5000 %\n"), Some(99.5));
+    }
+
+    #[test]
+    fn parse_percent_rejects_impossible_and_malformed_values() {
+        assert_eq!(parse_percent("101.0002%"), None);
+        assert_eq!(parse_percent("1O1.0000%"), None);
+        assert_eq!(parse_percent("101.0000"), None);
+    }
+}
@@ -5,6 +5,9 @@ use models::{
     SyncStatus,
 };
 
+use crate::checked_achievement_percent;
+use crate::mojibake::repair_mojibake;
+
 pub fn parse_song_detail_html(html: &str) -> eyre::Result<ParsedSongDetail> {
     let document = Html::parse_document(html);
 
@@ -27,6 +30,7 @@ pub fn parse_song_detail_html(html: &str) -> eyre::Result<ParsedSongDetail> {
         .unwrap_or_default()
         .trim()
         .to_string();
+    let title = repair_mojibake(title);
 
     let genre = document
         .select(&genre_selector)
@@ -65,6 +69,7 @@ pub fn parse_song_detail_html(html: &str) -> eyre::Result<ParsedSongDetail> {
             .filter(|s| !s.is_empty())
             .ok_or_else(|| eyre::eyre!("missing level (.music_lv_back)"))?;
 
+        let played = section.select(&score_block_selector).next().is_some();
         let mut achievement_percent: Option<f32> = None;
         let mut dx_score: Option<i32> = None;
         let mut dx_score_max: Option<i32> = None;
@@ -138,6 +143,7 @@ pub fn parse_song_detail_html(html: &str) -> eyre::Result<ParsedSongDetail> {
             diff_category,
             level,
             chart_type: chart_type.unwrap_or(page_chart_type),
+            played,
             achievement_percent,
             rank,
             fc,
@@ -189,7 +195,10 @@ fn parse_percent(text: &str) -> Option<f32> {
         return None;
     }
     let number = trimmed.replace(['%', ' ', '\n'], "");
-    number.parse::<f32>().ok()
+    number
+        .parse::<f32>()
+        .ok()
+        .and_then(checked_achievement_percent)
 }
 
 fn parse_dx_score_pair(text: &str) -> Option<(i32, i32)> {
@@ -217,18 +226,15 @@ fn parse_dx_score_pair(text: &str) -> Option<(i32, i32)> {
 }
 
 fn parse_rank_from_icon_src(src: &str) -> Option<ScoreRank> {
-    let key = icon_key(src)?;
-    key.parse::<ScoreRank>().ok()
+    ScoreRank::parse_any(&icon_key(src)?)
 }
 
 fn parse_fc_from_icon_src(src: &str) -> Option<FcStatus> {
-    let key = icon_key(src)?;
-    key.parse::<FcStatus>().ok()
+    FcStatus::parse_any(&icon_key(src)?)
 }
 
 fn parse_sync_from_icon_src(src: &str) -> Option<SyncStatus> {
-    let key = icon_key(src)?;
-    key.parse::<SyncStatus>().ok()
+    SyncStatus::parse_any(&icon_key(src)?)
 }
 
 fn merge_sync(existing: Option<SyncStatus>, candidate: Option<SyncStatus>) -> Option<SyncStatus> {
@@ -9,6 +9,13 @@ fn fixture_path(name: &str) -> PathBuf {
         .join(name)
 }
 
+const COURSE_RANK_IMG: &str = r#"<img src="https://maimaidx-eng.com/maimai-mobile/img/course/course_rank_00T7GHJvGe.png" class="h_35 f_l"/>"#;
+const CLASS_RANK_IMG: &str = r#"<img src="https://maimaidx-eng.com/maimai-mobile/img/class/class_rank_s_10FODgtQo4.png" class="p_l_10 h_35 f_l">"#;
+
+fn player_data_html() -> String {
+    std::fs::read_to_string(fixture_path("player_data.html")).unwrap()
+}
+
 #[test]
 fn parse_player_data_fixture() {
     let html = std::fs::read_to_string(fixture_path("player_data.html")).unwrap();
@@ -18,6 +25,71 @@ fn parse_player_data_fixture() {
     assert!(parsed.rating > 0);
     assert!(parsed.current_version_play_count > 0);
     assert!(parsed.total_play_count > 0);
+    assert_eq!(parsed.area_rank, None);
+    assert_eq!(parsed.course_rank, None);
+    assert_eq!(parsed.class_rank.as_deref(), Some("S5"));
+}
+
+#[test]
+fn parse_player_data_reads_course_and_class_rank_plates() {
+    let html = player_data_html()
+        .replace("course_rank_00T7GHJvGe.png", "course_rank_12Qw8RtYu1.png")
+        .replace("class_rank_s_10FODgtQo4.png", "class_rank_s_25Lm3NpXz7.png");
+    let parsed = parse_player_data_html(&html).unwrap();
+
+    assert_eq!(parsed.course_rank.as_deref(), Some("真二段"));
+    assert_eq!(parsed.class_rank.as_deref(), Some("LEGEND"));
+
+    let html = player_data_html();
+    assert!(html.contains(COURSE_RANK_IMG) && html.contains(CLASS_RANK_IMG));
+    let html = html
+        .replace(COURSE_RANK_IMG, "")
+        .replace(CLASS_RANK_IMG, "");
+    let parsed = parse_player_data_html(&html).unwrap();
+
+    assert_eq!(parsed.course_rank, None);
+    assert_eq!(parsed.class_rank, None);
+    assert_eq!(parsed.total_play_count, 602);
+}
+
+#[test]
+fn parse_player_data_reads_area_rank_when_present() {
+    let play_counts = "maimaiDX total play count：602</div>";
+    let html = player_data_html();
+    assert!(html.contains(play_counts));
+    let html = html.replace(
+        play_counts,
+        "maimaiDX total play count：602<br>area ranking：1,234</div>",
+    );
+    let parsed = parse_player_data_html(&html).unwrap();
+
+    assert_eq!(parsed.area_rank, Some(1234));
+    assert_eq!(parsed.total_play_count, 602);
+}
+
+#[test]
+fn parse_player_data_reads_rating_digit_images() {
+    let html = player_data_html();
+    let text = parse_player_data_html(&html).unwrap();
+    let digit_images = "13394"
+        .chars()
+        .map(|digit| {
+            format!(
+                r#"<img src="https://maimaidx-eng.com/maimai-mobile/img/rating/num_{digit}.png?ver=1.50" class="h_12"/>"#
+            )
+        })
+        .collect::<String>();
+    let html = html.replace(
+        r#"<div class="rating_block">13394</div>"#,
+        &format!(r#"<div class="rating_block">{digit_images}</div>"#),
+    );
+    assert!(html.contains("rating/num_9.png"));
+    let parsed = parse_player_data_html(&html).unwrap();
+
+    assert_eq!(parsed.rating, 13394);
+    assert_eq!(parsed.rating, text.rating);
+    assert_eq!(parsed.user_name, text.user_name);
+    assert_eq!(parsed.total_play_count, text.total_play_count);
 }
 
 #[test]
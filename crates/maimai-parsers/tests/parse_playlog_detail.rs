@@ -19,6 +19,15 @@ fn parse_playlog_detail_extracts_title_and_music_detail_idx() {
     let parsed = parse_playlog_detail_html(html).unwrap();
     assert_eq!(parsed.title, "Link");
     assert_eq!(parsed.music_detail_idx, "music-detail-idx-123");
+    assert!(!parsed.is_buddy_play);
+
+    let buddy = html.replace(
+        r#"<div class="f_15 break">Link</div>"#,
+        r#"<div class="f_15 break">Link</div>
+          <img src="https://maimaidx-eng.com/maimai-mobile/img/playlog/icon_buddy.png" class="playlog_buddy_icon"/>"#,
+    );
+    let parsed = parse_playlog_detail_html(&buddy).unwrap();
+    assert!(parsed.is_buddy_play);
 }
 
 #[test]
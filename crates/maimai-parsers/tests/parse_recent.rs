@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use maimai_parsers::parse_recent_html;
-use models::{ChartType, FcStatus, SyncStatus};
+use models::{ChartType, FcStatus, ScoreRank, SyncStatus};
 
 fn fixture_path(name: &str) -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -70,3 +70,60 @@ fn parse_recent_record_fixture() {
     assert!(entries.iter().any(|e| e.fc == Some(FcStatus::Fc)));
     assert!(entries.iter().any(|e| e.sync == Some(SyncStatus::Fs)));
 }
+
+/// No buddy playlog has been captured yet, so this marks the first entry of the captured
+/// record page as a buddy play and appends a partner result block, using the markers
+/// `is_buddy_entry` assumes.
+#[test]
+fn parse_recent_buddy_entry_keeps_own_result_block() {
+    let html = std::fs::read_to_string(fixture_path("record.html")).unwrap();
+    let solo = parse_recent_html(&html).unwrap();
+
+    let kind_icon = r#"class="playlog_music_kind_icon"/>"#;
+    let own_block_end = "</form>\n\t\t\t\t\t<div class=\"clearfix\"></div>\n\t\t\t\t</div>";
+    let partner_block = r#"<div class="playlog_result_block m_t_5 f_l">
+        <div class="playlog_achievement_txt t_r">100<span class="f_20">.6123%</span></div>
+        <img src="https://maimaidx-eng.com/maimai-mobile/img/playlog/sssp.png?ver=1.60" class="playlog_scorerank"/>
+        <div class="white p_r_5 f_15 f_r">1,650 / 1,689</div>
+        <img src="https://maimaidx-eng.com/maimai-mobile/img/playlog/fc_ap.png?ver=1.60" class="h_35 m_5 f_l"/>
+        <img src="https://maimaidx-eng.com/maimai-mobile/img/playlog/sync_fdxp.png?ver=1.60" class="h_35 m_5 f_l"/>
+    </div>"#;
+    assert!(html.contains(kind_icon) && html.contains(own_block_end));
+    let html = html
+        .replacen(
+            kind_icon,
+            &format!(
+                r#"{kind_icon}<img src="https://maimaidx-eng.com/maimai-mobile/img/playlog/icon_buddy.png" class="playlog_buddy_icon"/>"#
+            ),
+            1,
+        )
+        .replacen(own_block_end, &format!("{own_block_end}{partner_block}"), 1);
+    let entries = parse_recent_html(&html).unwrap();
+
+    assert_eq!(entries.len(), solo.len());
+    let (buddy, expected) = (&entries[0], &solo[0]);
+    assert!(buddy.is_buddy_play);
+    assert_eq!(buddy.title, expected.title);
+    assert_eq!(buddy.achievement_percent, Some(94.2744));
+    assert_eq!(buddy.score_rank, Some(ScoreRank::Aaa));
+    assert_eq!(buddy.dx_score, expected.dx_score);
+    assert_eq!(buddy.fc, None);
+    assert_eq!(buddy.sync, None);
+    assert_eq!(buddy.playlog_detail_idx, expected.playlog_detail_idx);
+    assert!(entries[1..].iter().all(|entry| !entry.is_buddy_play));
+}
+
+#[test]
+fn parse_recent_reads_achievement_drawn_as_digit_images() {
+    let html = std::fs::read_to_string(fixture_path("achievement_images.html")).unwrap();
+    let entries = parse_recent_html(&html).unwrap();
+
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].title, "tape/stop/night");
+    assert_eq!(entries[0].achievement_percent, Some(100.6123));
+    assert_eq!(entries[0].score_rank, Some(ScoreRank::SssPlus));
+
+    let unknown_glyph = html.replacen("num_dot.png", "num_star.png", 1);
+    let entries = parse_recent_html(&unknown_glyph).unwrap();
+    assert_eq!(entries[0].achievement_percent, None);
+}
@@ -83,6 +83,22 @@ fn parse_scores_diff4_fixture() {
     run_fixture_test(4, "diff4_remaster.html");
 }
 
+#[test]
+fn parse_scores_keeps_unplayed_placeholder_rows() {
+    let html = std::fs::read_to_string(fixture_path("diff3_master.html")).unwrap();
+    let entries = parse_scores_html(&html, 3).unwrap();
+
+    let (played, unplayed): (Vec<_>, Vec<_>) = entries.iter().partition(|e| e.is_played());
+    assert!(!played.is_empty());
+    assert!(!unplayed.is_empty());
+    assert!(played.iter().all(|e| e.achievement_percent.is_some()));
+    assert!(
+        unplayed
+            .iter()
+            .all(|e| e.rank.is_none() && e.dx_score.is_none() && !e.title.trim().is_empty())
+    );
+}
+
 #[test]
 fn parse_scores_intl_version0_diff0_fixture() {
     let html = std::fs::read_to_string(fixture_path("version0_maimai_diff0.html")).unwrap();
@@ -118,3 +134,30 @@ fn parse_scores_universe_fixture_technicians_high_is_std() {
     );
     assert_eq!(technicians_high[0].chart_type, ChartType::Std);
 }
+
+#[test]
+fn parse_scores_drops_achievement_above_101_percent() {
+    let html = std::fs::read_to_string(fixture_path("diff3_master.html")).unwrap();
+    let original = parse_scores_html(&html, 3).unwrap();
+    let target_index = original
+        .iter()
+        .position(|e| e.achievement_percent == Some(100.6868))
+        .expect("fixture has a 100.6868% entry");
+
+    let malformed = html.replacen(">100.6868%", ">1006.868%", 1);
+    let entries = parse_scores_html(&malformed, 3).unwrap();
+
+    assert_eq!(entries.len(), original.len());
+    assert_eq!(entries[target_index].title, original[target_index].title);
+    assert_eq!(entries[target_index].achievement_percent, None);
+    assert_eq!(
+        entries[target_index].dx_score,
+        original[target_index].dx_score
+    );
+    assert!(
+        entries
+            .iter()
+            .filter_map(|e| e.achievement_percent)
+            .all(|p| p <= 101.0)
+    );
+}
@@ -177,3 +177,43 @@ fn parse_song_detail_missing_title_fixture() {
 
     assert_eq!(parsed.title, "");
 }
+
+#[test]
+fn parse_song_detail_marks_difficulties_without_score_block_unplayed() {
+    // example1 with a BASIC section the way the page renders a never-played difficulty: the
+    // level box and nothing else.
+    const UNPLAYED_BASIC: &str = r#"<div id="basic" class="music_basic_score_back w_450 m_15 p_3 f_0">
+			<img src="https://maimaidx-eng.com/maimai-mobile/img/diff_basic.png" class="h_20 f_l">
+			<img src="https://maimaidx-eng.com/maimai-mobile/img/music_dx.png" class="music_kind_icon f_r"/>
+			<div class="clearfix"></div>
+			<div class="music_lv_back m_3 m_b_0 f_l t_c f_14">4</div>
+			<div class="t_r f_r">
+			</div>
+			<div class="clearfix"></div>
+		</div>
+		"#;
+    let html = std::fs::read_to_string(fixture_path("example1.html")).unwrap();
+    let expert = r#"<div id="expert""#;
+    assert!(html.contains(expert));
+    let html = html.replacen(expert, &format!("{UNPLAYED_BASIC}{expert}"), 1);
+    let parsed = parse_song_detail_html(&html).unwrap();
+
+    let played = parsed
+        .difficulties
+        .iter()
+        .map(|d| (d.diff_category, d.played))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        played,
+        vec![
+            (DifficultyCategory::Basic, false),
+            (DifficultyCategory::Expert, true),
+            (DifficultyCategory::Master, true),
+        ]
+    );
+    let basic = &parsed.difficulties[0];
+    assert_eq!(basic.level, "4");
+    assert_eq!(basic.achievement_percent, None);
+    assert_eq!(basic.play_count, None);
+    assert_eq!(parsed.difficulties[1].achievement_percent, Some(98.4787));
+}
@@ -67,3 +67,63 @@ pub struct PlayRecordApiResponse {
     pub credit_id: Option<i32>,
     pub achievement_new_record: Option<i32>,
 }
+
+/// Body of `GET`/`PUT /api/player/target`. `None` means no goal is set.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TargetRatingApiResponse {
+    pub target_rating: Option<u32>,
+}
+
+/// Body of `GET`/`PUT /api/player/recap-state`: what the Discord bot's recap DMs already
+/// covered, kept by the collector so a bot restart neither re-sends a recap nor forgets an
+/// open session. `PUT` only overwrites the fields it carries.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecapStateApiResponse {
+    /// Play day the last recap DM covered.
+    #[serde(default)]
+    pub recapped_day: Option<String>,
+    #[serde(default)]
+    pub session: Option<RecapSessionState>,
+}
+
+/// Session-end detection for one play day.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecapSessionState {
+    pub day: String,
+    /// Newest `played_at_unixtime` seen that day.
+    pub latest_play: Option<i64>,
+    /// When `latest_play` last moved, in unix seconds.
+    pub last_activity_unixtime: Option<i64>,
+    pub recapped: bool,
+}
+
+/// Body of `PUT /api/admin/internal-level`. Title, genre and artist together
+/// identify the song, so same-title songs are patched independently.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChartInternalLevelPatch {
+    pub title: String,
+    pub genre: String,
+    pub artist: String,
+    pub chart_type: ChartType,
+    pub diff_category: DifficultyCategory,
+    pub internal_level: f32,
+}
+
+/// One row of `GET /api/player/rating-history`: the rating as of `recorded_at`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RatingSnapshotApiResponse {
+    pub recorded_at: i64,
+    pub rating: u32,
+    pub total_play_count: u32,
+}
+
+/// Body of `GET /api/sync/status`: parser health and freshness of the background poll.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SyncStatusApiResponse {
+    /// Poll cycles in a row that failed inside an HTML parser; reset by a clean cycle.
+    pub parse_error_count: u32,
+    pub last_parse_error: Option<String>,
+    /// Unix time of the last poll cycle that checked maimai DX NET without failing.
+    #[serde(default)]
+    pub last_synced_at_unixtime: Option<i64>,
+}
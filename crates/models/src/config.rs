@@ -8,4 +8,6 @@ pub struct AppConfig {
     pub cookie_path: PathBuf,
     pub discord_bot_token: Option<String>,
     pub discord_user_id: Option<String>,
+    /// User-Agent strings for maimai DX NET; empty means the built-in iPhone Safari one.
+    pub user_agents: Vec<String>,
 }
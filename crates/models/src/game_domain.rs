@@ -1,4 +1,5 @@
 use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as DeError};
+use std::cmp::Ordering;
 use std::fmt;
 use std::str::FromStr;
 use strum::{Display, EnumIter, IntoEnumIterator};
@@ -56,6 +57,7 @@ fn parse_chart_type(value: &str) -> Option<ChartType> {
     match normalize_ascii_token(stem).as_str() {
         "std" | "standard" | "musicstandard" => Some(ChartType::Std),
         "dx" | "deluxe" | "musicdx" => Some(ChartType::Dx),
+        "utage" => Some(ChartType::Utage),
         _ => None,
     }
 }
@@ -258,6 +260,9 @@ pub enum ChartType {
     Std = 0,
     #[serde(rename = "DX")]
     Dx = 1,
+    /// 宴会場 (utage) charts. Never part of rating.
+    #[serde(rename = "UTAGE")]
+    Utage = 2,
 }
 
 impl ChartType {
@@ -269,6 +274,7 @@ impl ChartType {
         match self {
             Self::Std => "STD",
             Self::Dx => "DX",
+            Self::Utage => "UTAGE",
         }
     }
 
@@ -276,6 +282,7 @@ impl ChartType {
         match self {
             Self::Std => "std",
             Self::Dx => "dx",
+            Self::Utage => "utage",
         }
     }
 }
@@ -381,6 +388,17 @@ impl<'de> Deserialize<'de> for DifficultyCategory {
     }
 }
 
+/// In-game chart order: STD before DX, then BASIC through Re:MASTER. Sort in Rust with this
+/// instead of `ORDER BY diff_category`, which sorts the stored names alphabetically.
+pub fn cmp_chart_order(
+    a: (ChartType, DifficultyCategory),
+    b: (ChartType, DifficultyCategory),
+) -> Ordering {
+    a.0.as_u8()
+        .cmp(&b.0.as_u8())
+        .then_with(|| a.1.as_u8().cmp(&b.1.as_u8()))
+}
+
 impl fmt::Display for DifficultyCategory {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(self.as_str())
@@ -428,6 +446,18 @@ impl MaimaiVersion {
         !matches!(self, Self::CirclePlus)
     }
 
+    /// Whether charts added in this version rate in the NEW bucket: the latest INTL version and
+    /// the one before it. JP-only versions past it are not out on INTL yet, so they are excluded.
+    pub fn is_current_bucket(self) -> bool {
+        let Some(latest) = Self::iter()
+            .rev()
+            .find(|version| version.is_available_in_intl())
+        else {
+            return false;
+        };
+        self <= latest && self.as_index() + 1 >= latest.as_index()
+    }
+
     pub const fn as_str(self) -> &'static str {
         match self {
             Self::Maimai => "maimai",
@@ -463,6 +493,45 @@ impl MaimaiVersion {
     pub fn from_index(index: u8) -> Option<Self> {
         Self::iter().find(|version| version.as_index() == index)
     }
+
+    /// First release code of this version in SEGA's `maimai_songs.json` `version` field.
+    pub const fn version_id(self) -> i64 {
+        match self {
+            Self::Maimai => 10000,
+            Self::MaimaiPlus => 11000,
+            Self::Green => 12000,
+            Self::GreenPlus => 13000,
+            Self::Orange => 14000,
+            Self::OrangePlus => 15000,
+            Self::Pink => 16000,
+            Self::PinkPlus => 17000,
+            Self::Murasaki => 18000,
+            Self::MurasakiPlus => 18500,
+            Self::Milk => 19000,
+            Self::MilkPlus => 19500,
+            Self::Finale => 19900,
+            Self::Deluxe => 20000,
+            Self::DeluxePlus => 20500,
+            Self::Splash => 21000,
+            Self::SplashPlus => 21500,
+            Self::Universe => 22000,
+            Self::UniversePlus => 22500,
+            Self::Festival => 23000,
+            Self::FestivalPlus => 23500,
+            Self::Buddies => 24000,
+            Self::BuddiesPlus => 24500,
+            Self::Prism => 25000,
+            Self::PrismPlus => 25500,
+            Self::Circle => 26000,
+            Self::CirclePlus => 26500,
+        }
+    }
+
+    /// The version a SEGA release code (e.g. `24001`) belongs to; the last two digits number
+    /// updates within the version and are ignored.
+    pub fn from_version_id(id: i64) -> Option<Self> {
+        Self::iter().find(|version| version.version_id() / 100 == id / 100)
+    }
 }
 
 impl FromStr for MaimaiVersion {
@@ -576,7 +645,7 @@ mod song_genre_tests {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Display)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Display, EnumIter)]
 pub enum ScoreRank {
     #[serde(rename = "SSS+")]
     SssPlus,
@@ -609,6 +678,19 @@ pub enum ScoreRank {
 }
 
 impl ScoreRank {
+    /// Accepts display labels (`SSS+`), icon keys (`sssp`), and icon file names or URLs
+    /// (`music_icon_sssp.png`).
+    pub fn parse_any(value: &str) -> Option<Self> {
+        parse_score_rank(value)
+    }
+
+    /// Rank the game awards for an achievement, for rows where the rank icon is missing.
+    pub fn from_achievement(percent: f32) -> Self {
+        // Compare in ten-thousandths so `96.9999` never rounds up across a cutoff.
+        let achievement_x10000 = (f64::from(percent) * 10000.0).round() as i64;
+        crate::rating::rank_for_achievement_x10000(achievement_x10000)
+    }
+
     pub const fn as_str(self) -> &'static str {
         match self {
             Self::SssPlus => "SSS+",
@@ -627,13 +709,27 @@ impl ScoreRank {
             Self::D => "D",
         }
     }
+
+    /// Colored square for the achievement tier, shown next to the rank text so chart
+    /// lists scan faster.
+    pub const fn tier_emoji(self) -> &'static str {
+        match self {
+            Self::SssPlus => "🟦",
+            Self::Sss => "🟨",
+            Self::SsPlus | Self::Ss => "🟧",
+            Self::SPlus | Self::S => "🟩",
+            Self::Aaa | Self::Aa | Self::A => "🟥",
+            Self::Bbb | Self::Bb | Self::B | Self::C => "🟫",
+            Self::D => "⬛",
+        }
+    }
 }
 
 impl FromStr for ScoreRank {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parse_score_rank(s).ok_or(())
+        Self::parse_any(s).ok_or(())
     }
 }
 
@@ -652,7 +748,7 @@ impl<'de> Deserialize<'de> for ScoreRank {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Display)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Display, EnumIter)]
 pub enum FcStatus {
     #[serde(rename = "AP+")]
     ApPlus,
@@ -665,6 +761,12 @@ pub enum FcStatus {
 }
 
 impl FcStatus {
+    /// Accepts display labels (`AP+`), icon keys (`app`), playlog stems (`fc_app`),
+    /// and icon file names or URLs (`music_icon_app.png`).
+    pub fn parse_any(value: &str) -> Option<Self> {
+        parse_fc_status(value)
+    }
+
     pub const fn as_str(self) -> &'static str {
         match self {
             Self::ApPlus => "AP+",
@@ -679,7 +781,7 @@ impl FromStr for FcStatus {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parse_fc_status(s).ok_or(())
+        Self::parse_any(s).ok_or(())
     }
 }
 
@@ -698,7 +800,7 @@ impl<'de> Deserialize<'de> for FcStatus {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Display)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Display, EnumIter)]
 pub enum SyncStatus {
     #[serde(rename = "FDX+")]
     FdxPlus,
@@ -713,6 +815,12 @@ pub enum SyncStatus {
 }
 
 impl SyncStatus {
+    /// Accepts display labels (`FDX+`), icon keys (`fdxp`), playlog stems (`sync_fdxp`),
+    /// and icon file names or URLs (`music_icon_fdxp.png`).
+    pub fn parse_any(value: &str) -> Option<Self> {
+        parse_sync_status(value)
+    }
+
     pub const fn as_str(self) -> &'static str {
         match self {
             Self::FdxPlus => "FDX+",
@@ -738,7 +846,7 @@ impl FromStr for SyncStatus {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parse_sync_status(s).ok_or(())
+        Self::parse_any(s).ok_or(())
     }
 }
 
@@ -761,9 +869,73 @@ impl<'de> Deserialize<'de> for SyncStatus {
 mod tests {
     use super::{
         ChartType, DifficultyCategory, FcStatus, MaimaiVersion, ScoreRank, SongGenre, SyncStatus,
+        cmp_chart_order,
     };
     use strum::IntoEnumIterator;
 
+    #[test]
+    fn cmp_chart_order_follows_game_convention() {
+        let mut charts = vec![
+            (ChartType::Dx, DifficultyCategory::Basic),
+            (ChartType::Std, DifficultyCategory::ReMaster),
+            (ChartType::Std, DifficultyCategory::Advanced),
+            (ChartType::Dx, DifficultyCategory::Master),
+            (ChartType::Std, DifficultyCategory::Basic),
+            (ChartType::Dx, DifficultyCategory::Expert),
+        ];
+
+        charts.sort_by(|a, b| cmp_chart_order(*a, *b));
+
+        assert_eq!(
+            charts,
+            vec![
+                (ChartType::Std, DifficultyCategory::Basic),
+                (ChartType::Std, DifficultyCategory::Advanced),
+                (ChartType::Std, DifficultyCategory::ReMaster),
+                (ChartType::Dx, DifficultyCategory::Basic),
+                (ChartType::Dx, DifficultyCategory::Expert),
+                (ChartType::Dx, DifficultyCategory::Master),
+            ]
+        );
+    }
+
+    #[test]
+    fn score_rank_from_achievement_matches_official_cutoffs() {
+        let cases = [
+            (101.0, ScoreRank::SssPlus),
+            (100.5, ScoreRank::SssPlus),
+            (100.4999, ScoreRank::Sss),
+            (100.0, ScoreRank::Sss),
+            (99.9999, ScoreRank::SsPlus),
+            (99.5, ScoreRank::SsPlus),
+            (99.4999, ScoreRank::Ss),
+            (99.0, ScoreRank::Ss),
+            (98.9999, ScoreRank::SPlus),
+            (98.0, ScoreRank::SPlus),
+            (97.9999, ScoreRank::S),
+            (97.0, ScoreRank::S),
+            (96.9999, ScoreRank::Aaa),
+            (94.0, ScoreRank::Aaa),
+            (93.9999, ScoreRank::Aa),
+            (90.0, ScoreRank::Aa),
+            (89.9999, ScoreRank::A),
+            (80.0, ScoreRank::A),
+            (79.9999, ScoreRank::Bbb),
+            (75.0, ScoreRank::Bbb),
+            (74.9999, ScoreRank::Bb),
+            (70.0, ScoreRank::Bb),
+            (69.9999, ScoreRank::B),
+            (60.0, ScoreRank::B),
+            (59.9999, ScoreRank::C),
+            (50.0, ScoreRank::C),
+            (49.9999, ScoreRank::D),
+            (0.0, ScoreRank::D),
+        ];
+        for (percent, expected) in cases {
+            assert_eq!(ScoreRank::from_achievement(percent), expected, "{percent}");
+        }
+    }
+
     #[test]
     fn release_order_indices_are_stable() {
         for (expected_index, version) in MaimaiVersion::iter().enumerate() {
@@ -785,6 +957,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn version_id_roundtrip() {
+        for version in MaimaiVersion::iter() {
+            assert_eq!(
+                MaimaiVersion::from_version_id(version.version_id()),
+                Some(version)
+            );
+            assert_eq!(
+                MaimaiVersion::from_version_id(version.version_id() + 1)
+                    .map(MaimaiVersion::as_str)
+                    .and_then(|name| name.parse::<MaimaiVersion>().ok()),
+                Some(version)
+            );
+        }
+        assert_eq!(
+            MaimaiVersion::from_version_id(24001),
+            Some(MaimaiVersion::Buddies)
+        );
+        assert_eq!(MaimaiVersion::from_version_id(99000), None);
+    }
+
+    #[test]
+    fn current_bucket_is_the_latest_two_intl_versions() {
+        let current = MaimaiVersion::iter()
+            .filter(|version| version.is_current_bucket())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            current,
+            vec![MaimaiVersion::PrismPlus, MaimaiVersion::Circle]
+        );
+    }
+
     #[test]
     fn intl_availability_skips_future_jp_only_versions() {
         assert!(MaimaiVersion::Circle.is_available_in_intl());
@@ -795,6 +999,7 @@ mod tests {
     fn chart_and_difficulty_display_are_canonical() {
         assert_eq!(ChartType::Std.to_string(), "STD");
         assert_eq!(ChartType::Dx.to_string(), "DX");
+        assert_eq!(ChartType::Utage.to_string(), "UTAGE");
         assert_eq!(DifficultyCategory::Basic.to_string(), "BASIC");
         assert_eq!(DifficultyCategory::Advanced.to_string(), "ADVANCED");
         assert_eq!(DifficultyCategory::Expert.to_string(), "EXPERT");
@@ -813,6 +1018,7 @@ mod tests {
             Some(ChartType::Std)
         );
         assert_eq!("deluxe".parse::<ChartType>().ok(), Some(ChartType::Dx));
+        assert_eq!("utage".parse::<ChartType>().ok(), Some(ChartType::Utage));
         assert_eq!(
             "diff_remaster.png".parse::<DifficultyCategory>().ok(),
             Some(DifficultyCategory::ReMaster)
@@ -859,4 +1065,98 @@ mod tests {
         assert_eq!("FS+".parse::<SyncStatus>().ok(), Some(SyncStatus::FsPlus));
         assert_eq!("FDX+".parse::<SyncStatus>().ok(), Some(SyncStatus::FdxPlus));
     }
+
+    #[test]
+    fn score_rank_round_trips_through_every_spelling() {
+        for rank in ScoreRank::iter() {
+            assert_eq!(ScoreRank::parse_any(rank.as_str()), Some(rank));
+            let json = serde_json::to_string(&rank).expect("serialize rank");
+            assert_eq!(json, format!("\"{}\"", rank.as_str()));
+            assert_eq!(serde_json::from_str::<ScoreRank>(&json).ok(), Some(rank));
+
+            let icon_key = rank.as_str().to_ascii_lowercase().replace('+', "p");
+            assert_eq!(ScoreRank::parse_any(&icon_key), Some(rank));
+            assert_eq!(
+                ScoreRank::parse_any(&format!(
+                    "https://example.com/img/music_icon_{icon_key}.png?ver=1"
+                )),
+                Some(rank)
+            );
+            assert_eq!(
+                ScoreRank::parse_any(&format!("playlog/{icon_key}.png")),
+                Some(rank)
+            );
+        }
+        assert_eq!(ScoreRank::parse_any("music_icon_back.png"), None);
+    }
+
+    #[test]
+    fn score_rank_tier_emoji_groups_adjacent_ranks() {
+        let tiers = ScoreRank::iter()
+            .map(|rank| (rank.as_str(), rank.tier_emoji()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tiers,
+            vec![
+                ("SSS+", "🟦"),
+                ("SSS", "🟨"),
+                ("SS+", "🟧"),
+                ("SS", "🟧"),
+                ("S+", "🟩"),
+                ("S", "🟩"),
+                ("AAA", "🟥"),
+                ("AA", "🟥"),
+                ("A", "🟥"),
+                ("BBB", "🟫"),
+                ("BB", "🟫"),
+                ("B", "🟫"),
+                ("C", "🟫"),
+                ("D", "⬛"),
+            ]
+        );
+    }
+
+    #[test]
+    fn fc_status_round_trips_through_every_spelling() {
+        for status in FcStatus::iter() {
+            assert_eq!(FcStatus::parse_any(status.as_str()), Some(status));
+            let json = serde_json::to_string(&status).expect("serialize fc status");
+            assert_eq!(serde_json::from_str::<FcStatus>(&json).ok(), Some(status));
+
+            let icon_key = status.as_str().to_ascii_lowercase().replace('+', "p");
+            assert_eq!(FcStatus::parse_any(&icon_key), Some(status));
+            assert_eq!(
+                FcStatus::parse_any(&format!("/img/music_icon_{icon_key}.png")),
+                Some(status)
+            );
+            assert_eq!(
+                FcStatus::parse_any(&format!("/img/playlog/fc_{icon_key}.png")),
+                Some(status)
+            );
+        }
+        assert_eq!(FcStatus::parse_any("music_icon_back.png"), None);
+        assert_eq!(FcStatus::parse_any("/img/playlog/fc_dummy.png"), None);
+    }
+
+    #[test]
+    fn sync_status_round_trips_through_every_spelling() {
+        for status in SyncStatus::iter() {
+            assert_eq!(SyncStatus::parse_any(status.as_str()), Some(status));
+            let json = serde_json::to_string(&status).expect("serialize sync status");
+            assert_eq!(serde_json::from_str::<SyncStatus>(&json).ok(), Some(status));
+
+            let icon_key = status.as_str().to_ascii_lowercase().replace('+', "p");
+            assert_eq!(SyncStatus::parse_any(&icon_key), Some(status));
+            assert_eq!(
+                SyncStatus::parse_any(&format!("/img/music_icon_{icon_key}.png")),
+                Some(status)
+            );
+            assert_eq!(
+                SyncStatus::parse_any(&format!("/img/playlog/sync_{icon_key}.png")),
+                Some(status)
+            );
+        }
+        assert_eq!(SyncStatus::parse_any("music_icon_back.png"), None);
+        assert_eq!(SyncStatus::parse_any("/img/playlog/sync_dummy.png"), None);
+    }
 }
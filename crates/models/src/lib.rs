@@ -2,21 +2,27 @@ pub mod api_models;
 pub mod config;
 pub mod game_domain;
 pub mod parser_models;
+pub mod rating;
 pub mod song_catalog;
 pub mod storage_models;
 pub mod versioning;
 
-pub use api_models::{PlayRecordApiResponse, ScoreApiResponse, SongDetailScoreApiResponse};
+pub use api_models::{
+    ChartInternalLevelPatch, PlayRecordApiResponse, RatingSnapshotApiResponse, RecapSessionState,
+    RecapStateApiResponse, ScoreApiResponse, SongDetailScoreApiResponse, SyncStatusApiResponse,
+    TargetRatingApiResponse,
+};
 pub use game_domain::{
     ChartType, DifficultyCategory, FcStatus, MaimaiVersion, ScoreRank, SongGenre, SyncStatus,
+    cmp_chart_order,
 };
 pub use parser_models::{
     ParsedPlayRecord, ParsedPlayerProfile, ParsedPlaylogDetail, ParsedRatingTargetEntry,
     ParsedRatingTargets, ParsedScoreEntry, ParsedSongChartDetail, ParsedSongDetail,
 };
 pub use song_catalog::{
-    SongAliases, SongCatalog, SongCatalogChart, SongCatalogSong, SongChartRegion, SongDatabase,
-    SongInternalLevelIndex,
+    InternalLevelSource, SongAliases, SongCatalog, SongCatalogChart, SongCatalogSong,
+    SongChartRegion, SongDatabase, SongInternalLevelIndex, UtageChartInfo, normalize_title,
 };
 pub use storage_models::{StoredPlayRecord, StoredScoreEntry};
 pub use versioning::{VersionApiResponse, is_minor_or_more_outdated};
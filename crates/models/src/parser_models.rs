@@ -22,6 +22,12 @@ pub struct ParsedScoreEntry {
 }
 
 impl ParsedScoreEntry {
+    /// Score lists also render charts the player never played, with a blank achievement.
+    /// Those rows are kept by the parser (they double as the song index) but never stored.
+    pub fn is_played(&self) -> bool {
+        self.achievement_percent.is_some()
+    }
+
     pub fn format_recent_sync_log_fields(&self) -> String {
         format!(
             "title='{}' genre='{}' artist='{}' chart_type={} diff_category={} last_played_at='{}' play_count={} achievement_x10000={} rank={} fc={} sync={} dx_score={}/{}",
@@ -62,12 +68,15 @@ pub struct ParsedPlayRecord {
     pub sync: Option<SyncStatus>,
     pub dx_score: Option<i32>,
     pub dx_score_max: Option<i32>,
+    /// Buddy (2P) chart play; result fields are taken from the player's own result block.
+    #[serde(default)]
+    pub is_buddy_play: bool,
 }
 
 impl ParsedPlayRecord {
     pub fn format_recent_sync_log_fields(&self) -> String {
         format!(
-            "played_at_unixtime={} played_at='{}' credit_id={} track={} title='{}' genre='{}' artist='{}' chart_type={} diff_category={} achievement_x10000={} new_record={} rank={} fc={} sync={} dx_score={}/{}",
+            "played_at_unixtime={} played_at='{}' credit_id={} track={} title='{}' genre='{}' artist='{}' chart_type={} diff_category={} achievement_x10000={} new_record={} rank={} fc={} sync={} dx_score={}/{} buddy={}",
             display_opt_i64(self.played_at_unixtime),
             display_opt_str(self.played_at.as_deref()),
             display_opt_u32(self.credit_id),
@@ -84,6 +93,7 @@ impl ParsedPlayRecord {
             display_opt_sync(self.sync),
             display_opt_i32(self.dx_score),
             display_opt_i32(self.dx_score_max),
+            self.is_buddy_play,
         )
     }
 }
@@ -101,6 +111,8 @@ pub struct ParsedSongDetail {
 pub struct ParsedPlaylogDetail {
     pub title: String,
     pub music_detail_idx: String,
+    #[serde(default)]
+    pub is_buddy_play: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -108,6 +120,10 @@ pub struct ParsedSongChartDetail {
     pub diff_category: DifficultyCategory,
     pub level: String,
     pub chart_type: ChartType,
+    /// The page shows a score block for this difficulty. `false` means never played, as opposed
+    /// to played with an achievement that failed to parse.
+    #[serde(default)]
+    pub played: bool,
     pub achievement_percent: Option<f32>,
     pub rank: Option<ScoreRank>,
     pub fc: Option<FcStatus>,
@@ -124,6 +140,15 @@ pub struct ParsedPlayerProfile {
     pub rating: u32,
     pub current_version_play_count: u32,
     pub total_play_count: u32,
+    /// Versus-mode area ranking; only shown on the page once the player has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub area_rank: Option<u32>,
+    /// Dan course rank, e.g. `真二段`; `None` until a course has been cleared.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub course_rank: Option<String>,
+    /// Versus-mode class, e.g. `S5` or `LEGEND`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class_rank: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
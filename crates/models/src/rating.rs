@@ -0,0 +1,521 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ChartType, DifficultyCategory, FcStatus, MaimaiVersion, ScoreRank};
+
+/// Number of charts from the current versions that count towards rating.
+pub const NEW_RATING_SET_SIZE: usize = 15;
+/// Number of charts from all older versions that count towards rating.
+pub const OLD_RATING_SET_SIZE: usize = 35;
+
+/// Highest achievement the game can award (every note critical perfect plus the break bonus).
+pub const MAX_ACHIEVEMENT_PERCENT: f32 = 101.0;
+
+const ACHIEVEMENT_CAP: f64 = 100.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum RatingBucket {
+    New,
+    Old,
+}
+
+impl RatingBucket {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::New => "NEW",
+            Self::Old => "OLD",
+        }
+    }
+
+    pub fn from_version(version: &str) -> Self {
+        if is_new_version(version) {
+            Self::New
+        } else {
+            Self::Old
+        }
+    }
+}
+
+impl FromStr for RatingBucket {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "NEW" => Ok(Self::New),
+            "OLD" => Ok(Self::Old),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Whether charts added in `version` belong to the NEW rating bucket.
+pub fn is_new_version(version: &str) -> bool {
+    version
+        .parse::<MaimaiVersion>()
+        .is_ok_and(MaimaiVersion::is_current_bucket)
+}
+
+pub fn is_ap_like(fc: Option<&FcStatus>) -> bool {
+    matches!(fc, Some(&FcStatus::Ap) | Some(&FcStatus::ApPlus))
+}
+
+/// One step of the rating coefficient table. `rank` is set where the step is also a rank
+/// cutoff; the other steps sit just below one (e.g. 99.9999%) or under C.
+struct CoefficientStep {
+    min_achievement_x10000: i64,
+    coefficient: f64,
+    rank: Option<ScoreRank>,
+}
+
+impl CoefficientStep {
+    fn min_achievement_percent(&self) -> f64 {
+        self.min_achievement_x10000 as f64 / 10000.0
+    }
+}
+
+const fn step(
+    min_achievement_x10000: i64,
+    coefficient: f64,
+    rank: Option<ScoreRank>,
+) -> CoefficientStep {
+    CoefficientStep {
+        min_achievement_x10000,
+        coefficient,
+        rank,
+    }
+}
+
+/// Highest step first; achievements under the last step are worth nothing.
+const COEFFICIENT_STEPS: [CoefficientStep; 22] = [
+    step(1_005_000, 22.4, Some(ScoreRank::SssPlus)),
+    step(1_004_999, 22.2, None),
+    step(1_000_000, 21.6, Some(ScoreRank::Sss)),
+    step(999_999, 21.4, None),
+    step(995_000, 21.1, Some(ScoreRank::SsPlus)),
+    step(990_000, 20.8, Some(ScoreRank::Ss)),
+    step(989_999, 20.6, None),
+    step(980_000, 20.3, Some(ScoreRank::SPlus)),
+    step(970_000, 20.0, Some(ScoreRank::S)),
+    step(969_999, 17.6, None),
+    step(940_000, 16.8, Some(ScoreRank::Aaa)),
+    step(900_000, 15.2, Some(ScoreRank::Aa)),
+    step(800_000, 13.6, Some(ScoreRank::A)),
+    step(799_999, 12.8, None),
+    step(750_000, 12.0, Some(ScoreRank::Bbb)),
+    step(700_000, 11.2, Some(ScoreRank::Bb)),
+    step(600_000, 9.6, Some(ScoreRank::B)),
+    step(500_000, 8.0, Some(ScoreRank::C)),
+    step(400_000, 6.4, None),
+    step(300_000, 4.8, None),
+    step(200_000, 3.2, None),
+    step(100_000, 1.6, None),
+];
+
+pub fn coefficient_for_achievement(achievement_percent: f64) -> f64 {
+    let a = achievement_percent.min(ACHIEVEMENT_CAP);
+    COEFFICIENT_STEPS
+        .iter()
+        .find(|step| a >= step.min_achievement_percent())
+        .map_or(0.0, |step| step.coefficient)
+}
+
+/// Rank cutoffs from SSS+ down to C, with their lowest achievement in ten-thousandths.
+fn rank_cutoffs() -> impl DoubleEndedIterator<Item = (i64, ScoreRank)> {
+    COEFFICIENT_STEPS
+        .iter()
+        .filter_map(|step| Some((step.min_achievement_x10000, step.rank?)))
+}
+
+/// Backs [`ScoreRank::from_achievement`], so ranks and coefficients share one table.
+pub(crate) fn rank_for_achievement_x10000(achievement_x10000: i64) -> ScoreRank {
+    rank_cutoffs()
+        .find(|(min_x10000, _)| achievement_x10000 >= *min_x10000)
+        .map_or(ScoreRank::D, |(_, rank)| rank)
+}
+
+pub fn chart_rating_points(internal_level: f64, achievement_percent: f64, ap_bonus: bool) -> u32 {
+    let coef = coefficient_for_achievement(achievement_percent);
+    let ach = achievement_percent.min(ACHIEVEMENT_CAP);
+    let base = ((coef * internal_level * ach) / 100.0).floor();
+    let base = if base.is_finite() && base > 0.0 {
+        base as u32
+    } else {
+        0
+    };
+    if ap_bonus {
+        base.saturating_add(1)
+    } else {
+        base
+    }
+}
+
+/// Reaching the next rank cutoff above a chart's current achievement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RankGain {
+    pub rank: ScoreRank,
+    pub target_achievement_percent: f64,
+    /// Chart rating points added by reaching `target_achievement_percent`.
+    pub points_gained: u32,
+}
+
+/// `None` once the chart is at SSS+, where achievement stops adding rating.
+pub fn rating_gain_to_next_rank(
+    internal_level: f64,
+    current_achievement_percent: f64,
+    ap_bonus: bool,
+) -> Option<RankGain> {
+    let (target_x10000, rank) = rank_cutoffs()
+        .rev()
+        .find(|(min_x10000, _)| *min_x10000 as f64 / 10000.0 > current_achievement_percent)?;
+    let target_achievement_percent = target_x10000 as f64 / 10000.0;
+    let current_points = chart_rating_points(internal_level, current_achievement_percent, ap_bonus);
+    let target_points = chart_rating_points(internal_level, target_achievement_percent, ap_bonus);
+    Some(RankGain {
+        rank,
+        target_achievement_percent,
+        points_gained: target_points.saturating_sub(current_points),
+    })
+}
+
+/// DX-score percentages (of the chart's max) needed for each star, from one to five.
+const DX_SCORE_STAR_THRESHOLDS: [i64; 5] = [85, 90, 93, 95, 97];
+
+/// Number of DX-score stars (0-5). A zero or negative `dx_score_max` yields no stars.
+pub fn dx_score_stars(dx_score: i32, dx_score_max: i32) -> u8 {
+    if dx_score_max <= 0 {
+        return 0;
+    }
+    let scaled = i64::from(dx_score) * 100;
+    DX_SCORE_STAR_THRESHOLDS
+        .iter()
+        .take_while(|threshold| scaled >= **threshold * i64::from(dx_score_max))
+        .count() as u8
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RatedChart {
+    pub title: String,
+    pub chart_type: ChartType,
+    pub diff_category: DifficultyCategory,
+    pub level: String,
+    pub internal_level: f32,
+    pub achievement_percent: f64,
+    pub fc: Option<FcStatus>,
+    pub bucket: RatingBucket,
+    pub rating_points: u32,
+    /// Song identity from the score row; empty when the chart was rated without one.
+    #[serde(default)]
+    pub artist: String,
+    #[serde(default)]
+    pub genre: String,
+}
+
+impl RatedChart {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        title: String,
+        chart_type: ChartType,
+        diff_category: DifficultyCategory,
+        level: String,
+        internal_level: f32,
+        achievement_percent: f64,
+        fc: Option<FcStatus>,
+        bucket: RatingBucket,
+    ) -> Self {
+        let rating_points = chart_rating_points(
+            f64::from(internal_level),
+            achievement_percent,
+            is_ap_like(fc.as_ref()),
+        );
+        Self {
+            title,
+            chart_type,
+            diff_category,
+            level,
+            internal_level,
+            achievement_percent,
+            fc,
+            bucket,
+            rating_points,
+            artist: String::new(),
+            genre: String::new(),
+        }
+    }
+
+    pub fn with_song_identity(mut self, artist: String, genre: String) -> Self {
+        self.artist = artist;
+        self.genre = genre;
+        self
+    }
+}
+
+/// The charts that make up a player's rating: the best NEW 15 and OLD 35.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RatingSelection {
+    pub new: Vec<RatedChart>,
+    pub old: Vec<RatedChart>,
+}
+
+impl RatingSelection {
+    pub fn new_sum(&self) -> u32 {
+        self.new.iter().map(|chart| chart.rating_points).sum()
+    }
+
+    pub fn old_sum(&self) -> u32 {
+        self.old.iter().map(|chart| chart.rating_points).sum()
+    }
+
+    pub fn total(&self) -> u32 {
+        self.new_sum() + self.old_sum()
+    }
+}
+
+/// A computed rating as served to clients, so every consumer shows the same numbers.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RatingBreakdown {
+    pub total: u32,
+    pub new_sum: u32,
+    pub old_sum: u32,
+    pub new: Vec<RatedChart>,
+    pub old: Vec<RatedChart>,
+    /// Played charts that could not be rated because song data has no internal level.
+    pub missing: usize,
+}
+
+impl RatingBreakdown {
+    pub fn from_selection(selection: RatingSelection, missing: usize) -> Self {
+        Self {
+            total: selection.total(),
+            new_sum: selection.new_sum(),
+            old_sum: selection.old_sum(),
+            new: selection.new,
+            old: selection.old,
+            missing,
+        }
+    }
+}
+
+/// Pick the highest-rated NEW 15 / OLD 35 charts, ordered by rating points then achievement.
+/// Remaining ties fall back to title, chart type and difficulty so the cutoff does not depend
+/// on input order.
+pub fn select_rating_set(charts: impl IntoIterator<Item = RatedChart>) -> RatingSelection {
+    let (mut new, mut old): (Vec<_>, Vec<_>) = charts
+        .into_iter()
+        .partition(|chart| chart.bucket == RatingBucket::New);
+
+    for (charts, size) in [
+        (&mut new, NEW_RATING_SET_SIZE),
+        (&mut old, OLD_RATING_SET_SIZE),
+    ] {
+        charts.sort_by(|a, b| {
+            b.rating_points
+                .cmp(&a.rating_points)
+                .then_with(|| b.achievement_percent.total_cmp(&a.achievement_percent))
+                .then_with(|| a.title.cmp(&b.title))
+                .then_with(|| a.chart_type.cmp(&b.chart_type))
+                .then_with(|| a.diff_category.cmp(&b.diff_category))
+        });
+        charts.truncate(size);
+    }
+
+    RatingSelection { new, old }
+}
+
+#[cfg(test)]
+mod tests {
+    use strum::IntoEnumIterator;
+
+    use super::{
+        COEFFICIENT_STEPS, NEW_RATING_SET_SIZE, OLD_RATING_SET_SIZE, RatedChart, RatingBreakdown,
+        RatingBucket, chart_rating_points, coefficient_for_achievement, dx_score_stars,
+        is_new_version, rank_cutoffs, rating_gain_to_next_rank, select_rating_set,
+    };
+    use crate::{ChartType, DifficultyCategory, FcStatus, ScoreRank};
+
+    fn chart(title: &str, internal_level: f32, bucket: RatingBucket) -> RatedChart {
+        RatedChart::new(
+            title.to_string(),
+            ChartType::Dx,
+            DifficultyCategory::Master,
+            "13".to_string(),
+            internal_level,
+            100.5,
+            None,
+            bucket,
+        )
+    }
+
+    #[test]
+    fn coefficient_table_key_breakpoints() {
+        assert_eq!(coefficient_for_achievement(0.0), 0.0);
+        assert_eq!(coefficient_for_achievement(79.9999), 12.8);
+        assert_eq!(coefficient_for_achievement(97.0), 20.0);
+        assert_eq!(coefficient_for_achievement(100.4999), 22.2);
+        assert_eq!(coefficient_for_achievement(101.0), 22.4);
+    }
+
+    #[test]
+    fn coefficient_steps_descend_and_every_rank_has_a_cutoff() {
+        for pair in COEFFICIENT_STEPS.windows(2) {
+            assert!(pair[0].min_achievement_x10000 > pair[1].min_achievement_x10000);
+            assert!(pair[0].coefficient > pair[1].coefficient);
+        }
+        let ranks: Vec<ScoreRank> = rank_cutoffs().map(|(_, rank)| rank).collect();
+        let expected: Vec<ScoreRank> = ScoreRank::iter()
+            .filter(|rank| *rank != ScoreRank::D)
+            .collect();
+        assert_eq!(ranks, expected);
+    }
+
+    #[test]
+    fn chart_rating_points_caps_achievement_and_adds_ap_bonus() {
+        assert_eq!(chart_rating_points(13.7, 100.5, false), 308);
+        assert_eq!(chart_rating_points(13.7, 101.0, false), 308);
+        assert_eq!(chart_rating_points(13.7, 100.5, true), 309);
+    }
+
+    #[test]
+    fn rating_gain_to_next_rank_targets_the_next_cutoff() {
+        let gain = rating_gain_to_next_rank(13.7, 99.7, false).expect("below SSS+");
+        assert_eq!(gain.rank, ScoreRank::Sss);
+        assert_eq!(gain.target_achievement_percent, 100.0);
+        assert_eq!(
+            gain.points_gained,
+            chart_rating_points(13.7, 100.0, false) - chart_rating_points(13.7, 99.7, false)
+        );
+
+        // Sitting exactly on a cutoff aims for the one above it.
+        let gain = rating_gain_to_next_rank(13.7, 100.0, false).expect("below SSS+");
+        assert_eq!(gain.rank, ScoreRank::SssPlus);
+
+        assert_eq!(rating_gain_to_next_rank(13.7, 100.5, false), None);
+        assert_eq!(rating_gain_to_next_rank(13.7, 100.8, true), None);
+    }
+
+    #[test]
+    fn rated_chart_applies_ap_bonus_from_fc() {
+        let ap = RatedChart::new(
+            "AP".to_string(),
+            ChartType::Std,
+            DifficultyCategory::Expert,
+            "13+".to_string(),
+            13.7,
+            100.5,
+            Some(FcStatus::ApPlus),
+            RatingBucket::Old,
+        );
+        assert_eq!(ap.rating_points, 309);
+    }
+
+    #[test]
+    fn dx_score_stars_follow_official_thresholds() {
+        // 1000 max DX score: stars start at 850 / 900 / 930 / 950 / 970.
+        assert_eq!(dx_score_stars(849, 1000), 0);
+        assert_eq!(dx_score_stars(850, 1000), 1);
+        assert_eq!(dx_score_stars(929, 1000), 2);
+        assert_eq!(dx_score_stars(930, 1000), 3);
+        assert_eq!(dx_score_stars(969, 1000), 4);
+        assert_eq!(dx_score_stars(970, 1000), 5);
+        assert_eq!(dx_score_stars(1000, 1000), 5);
+        // 95% of 2067 is 1963.65, so 1963 falls just short of the fourth star.
+        assert_eq!(dx_score_stars(1963, 2067), 3);
+        assert_eq!(dx_score_stars(1964, 2067), 4);
+        assert_eq!(dx_score_stars(0, 0), 0);
+        assert_eq!(dx_score_stars(500, 0), 0);
+    }
+
+    #[test]
+    fn is_new_version_only_matches_current_bucket() {
+        assert!(is_new_version("PRiSM PLUS"));
+        assert!(is_new_version("CiRCLE"));
+        assert!(!is_new_version("PRiSM"));
+        assert!(!is_new_version("maimai"));
+        assert!(!is_new_version("unknown"));
+    }
+
+    #[test]
+    fn select_rating_set_keeps_best_charts_per_bucket() {
+        let charts = (0..20)
+            .map(|i| {
+                chart(
+                    &format!("new {i}"),
+                    12.0 + i as f32 * 0.1,
+                    RatingBucket::New,
+                )
+            })
+            .chain((0..40).map(|i| {
+                chart(
+                    &format!("old {i}"),
+                    10.0 + i as f32 * 0.1,
+                    RatingBucket::Old,
+                )
+            }))
+            .collect::<Vec<_>>();
+
+        let selection = select_rating_set(charts);
+
+        assert_eq!(selection.new.len(), NEW_RATING_SET_SIZE);
+        assert_eq!(selection.old.len(), OLD_RATING_SET_SIZE);
+        assert_eq!(selection.new[0].title, "new 19");
+        assert_eq!(selection.new[14].title, "new 5");
+        assert_eq!(selection.old[0].title, "old 39");
+        assert!(
+            selection
+                .new
+                .windows(2)
+                .all(|pair| pair[0].rating_points >= pair[1].rating_points)
+        );
+        assert_eq!(selection.total(), selection.new_sum() + selection.old_sum());
+    }
+
+    #[test]
+    fn select_rating_set_breaks_ties_at_the_cutoff_by_title() {
+        let filler = (0..NEW_RATING_SET_SIZE - 2)
+            .map(|i| chart(&format!("high {i}"), 14.0, RatingBucket::New))
+            .collect::<Vec<_>>();
+        let tied = ["Tie C", "Tie A", "Tie B"].map(|title| chart(title, 12.0, RatingBucket::New));
+
+        let forward = select_rating_set(filler.iter().chain(&tied).cloned());
+        let reversed = select_rating_set(filler.iter().chain(tied.iter().rev()).cloned());
+
+        let tail = |selection: &super::RatingSelection| {
+            selection.new[NEW_RATING_SET_SIZE - 2..]
+                .iter()
+                .map(|chart| chart.title.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(tail(&forward), vec!["Tie A", "Tie B"]);
+        assert_eq!(forward, reversed);
+    }
+
+    #[test]
+    fn rating_breakdown_matches_a_manual_sum() {
+        let charts = vec![
+            chart("New A", 13.7, RatingBucket::New),
+            chart("New B", 12.0, RatingBucket::New),
+            chart("Old A", 14.2, RatingBucket::Old),
+        ];
+
+        let breakdown = RatingBreakdown::from_selection(select_rating_set(charts), 4);
+
+        // floor(22.4 * level * 100.5 / 100) for each chart.
+        let new_sum =
+            chart_rating_points(13.7, 100.5, false) + chart_rating_points(12.0, 100.5, false);
+        let old_sum = chart_rating_points(14.2, 100.5, false);
+        assert_eq!((new_sum, old_sum), (308 + 270, 319));
+        assert_eq!(breakdown.new_sum, new_sum);
+        assert_eq!(breakdown.old_sum, old_sum);
+        assert_eq!(breakdown.total, new_sum + old_sum);
+        assert_eq!(
+            breakdown
+                .new
+                .iter()
+                .map(|chart| chart.title.as_str())
+                .collect::<Vec<_>>(),
+            vec!["New A", "New B"]
+        );
+        assert_eq!(breakdown.missing, 4);
+    }
+}
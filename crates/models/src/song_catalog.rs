@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use unicode_normalization::UnicodeNormalization;
 
 use crate::{ChartType, DifficultyCategory, SongGenre};
 
@@ -22,6 +23,13 @@ pub struct SongCatalogSong {
     pub artist: String,
     #[serde(rename = "imageName", skip_serializing_if = "Option::is_none")]
     pub image_name: Option<String>,
+    /// `YYYY-MM-DD` from the official `release` field; `None` when upstream has no date.
+    #[serde(
+        rename = "releaseDate",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub release_date: Option<String>,
     #[serde(default, skip_serializing_if = "SongAliases::is_empty")]
     pub aliases: SongAliases,
     pub sheets: Vec<SongCatalogChart>,
@@ -51,7 +59,40 @@ pub struct SongCatalogChart {
     pub version_name: Option<String>,
     #[serde(rename = "internalLevel", skip_serializing_if = "Option::is_none")]
     pub internal_level: Option<String>,
+    #[serde(
+        rename = "internalLevelSource",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub internal_level_source: Option<InternalLevelSource>,
     pub region: SongChartRegion,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub utage: Option<UtageChartInfo>,
+}
+
+/// Metadata only 宴会場 (utage) charts carry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UtageChartInfo {
+    /// The single character shown in the title brackets, e.g. `協` for `[協]Love You`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kanji: Option<String>,
+    #[serde(rename = "utageType", skip_serializing_if = "Option::is_none")]
+    pub utage_type: Option<String>,
+}
+
+/// Where a chart's `internalLevel` came from, so consumers can reason about trust.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InternalLevelSource {
+    /// Inferred from the INTL maimai DX NET level search pages.
+    LevelPage,
+    /// Taken verbatim from `manual_override.json`.
+    ManualOverride,
+    /// Gap-filled from `internal_level_overrides.json` when no other source had a value.
+    Fallback,
+    /// Conservative band default derived from the displayed level (`13+` -> 13.6);
+    /// only assigned when no source had a value. Not an exact chart constant.
+    Estimate,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -90,7 +131,7 @@ impl SongInternalLevelIndex {
         diff_category: DifficultyCategory,
     ) -> Option<f32> {
         let key = SongChartLookupKey {
-            title: normalize_identity_component(title),
+            title: normalize_title(title),
             genre: normalize_genre_identity_component(genre),
             artist: normalize_identity_component(artist),
             chart_type,
@@ -114,6 +155,16 @@ impl SongInternalLevelIndex {
     }
 }
 
+/// Canonical form for matching song titles across SEGA pages, the song database and user input;
+/// never display or store it. The title is NFKC-normalized, so full-width ASCII and the
+/// ideographic space fold to half-width and half-width katakana to full-width, then all
+/// whitespace is removed. Case and other symbols are kept: distinct songs share titles that
+/// differ only in case (`Link` / `link`), and plenty of titles are mostly punctuation (`+♂`,
+/// `≠彡"/了→`). Case-insensitive lookups lowercase the result themselves.
+pub fn normalize_title(title: &str) -> String {
+    title.nfkc().filter(|ch| !ch.is_whitespace()).collect()
+}
+
 fn normalize_identity_component(s: &str) -> String {
     s.trim().to_string()
 }
@@ -130,7 +181,7 @@ fn populate_internal_level_map(
     songs: &[SongCatalogSong],
 ) {
     for song in songs {
-        let title = normalize_identity_component(&song.title);
+        let title = normalize_title(&song.title);
         let genre = normalize_genre_identity_component(song.genre.as_str());
         let artist = normalize_identity_component(&song.artist);
 
@@ -138,6 +189,10 @@ fn populate_internal_level_map(
             let Ok(chart_type) = sheet.chart_type.parse::<ChartType>() else {
                 continue;
             };
+            // Utage charts stay in the catalog but never feed rating.
+            if chart_type == ChartType::Utage {
+                continue;
+            }
 
             let Some(internal_str) = &sheet.internal_level else {
                 continue;
@@ -176,10 +231,12 @@ mod tests {
             level: "13+".to_string(),
             version_name: None,
             internal_level: Some("13.7".to_string()),
+            internal_level_source: None,
             region: SongChartRegion {
                 jp: true,
                 intl: true,
             },
+            utage: None,
         }
     }
 
@@ -191,6 +248,7 @@ mod tests {
                 genre: SongGenre::Maimai,
                 artist: " Artist ".to_string(),
                 image_name: None,
+                release_date: None,
                 aliases: SongAliases::default(),
                 sheets: vec![chart()],
             }],
@@ -208,6 +266,59 @@ mod tests {
         );
     }
 
+    #[test]
+    fn normalize_title_folds_width_and_whitespace_but_keeps_case_and_symbols() {
+        assert_eq!(
+            normalize_title("  Bad Apple!!  feat.nomico "),
+            "BadApple!!feat.nomico"
+        );
+        assert_eq!(normalize_title("Ｌｉｎｋ"), "Link");
+        assert_eq!(normalize_title("夜明けまであと３秒"), "夜明けまであと3秒");
+        assert_eq!(normalize_title("ガラテア\u{3000}の螺旋"), "ガラテアの螺旋");
+        assert_eq!(normalize_title("link"), "link");
+        assert_eq!(normalize_title("+♂"), "+♂");
+    }
+
+    #[test]
+    fn normalize_title_applies_nfkc_compatibility_folding() {
+        assert_eq!(
+            normalize_title("夜明けまであと３秒"),
+            normalize_title("夜明けまであと3秒")
+        );
+        assert_eq!(normalize_title("Ｌink"), normalize_title("Link"));
+        assert_eq!(normalize_title("ｶﾞﾗﾃｱの螺旋"), "ガラテアの螺旋");
+        // Decomposed dakuten composes onto its base kana.
+        assert_eq!(normalize_title("か\u{3099}らてあ"), "がらてあ");
+        assert_eq!(normalize_title("≠彡\"/了→"), "≠彡\"/了→");
+    }
+
+    #[test]
+    fn internal_level_index_matches_width_and_spacing_variants() {
+        let index = SongInternalLevelIndex::from_catalog(SongCatalog {
+            songs: vec![SongCatalogSong {
+                title: "Bad Apple!! feat.nomico".to_string(),
+                genre: SongGenre::Maimai,
+                artist: "Artist".to_string(),
+                image_name: None,
+                release_date: None,
+                aliases: SongAliases::default(),
+                sheets: vec![chart()],
+            }],
+        });
+
+        // A full-width `！` and a double space used to miss the trim-only key.
+        assert_eq!(
+            index.internal_level(
+                "Bad Apple！!  feat.nomico",
+                "maimai",
+                "Artist",
+                ChartType::Std,
+                DifficultyCategory::Master
+            ),
+            Some(13.7)
+        );
+    }
+
     #[test]
     fn internal_level_index_keeps_case_distinct() {
         let index = SongInternalLevelIndex::from_catalog(SongCatalog {
@@ -217,6 +328,7 @@ mod tests {
                     genre: SongGenre::Maimai,
                     artist: "".to_string(),
                     image_name: None,
+                    release_date: None,
                     aliases: SongAliases::default(),
                     sheets: vec![chart()],
                 },
@@ -225,6 +337,7 @@ mod tests {
                     genre: SongGenre::Maimai,
                     artist: "".to_string(),
                     image_name: None,
+                    release_date: None,
                     aliases: SongAliases::default(),
                     sheets: vec![SongCatalogChart {
                         internal_level: Some("14.0".to_string()),
@@ -255,4 +368,38 @@ mod tests {
             Some(14.0)
         );
     }
+
+    #[test]
+    fn internal_level_index_skips_utage_charts() {
+        let index = SongInternalLevelIndex::from_catalog(SongCatalog {
+            songs: vec![SongCatalogSong {
+                title: "[協]Love You".to_string(),
+                genre: SongGenre::Utage,
+                artist: "".to_string(),
+                image_name: None,
+                release_date: None,
+                aliases: SongAliases::default(),
+                sheets: vec![SongCatalogChart {
+                    chart_type: "utage".to_string(),
+                    internal_level: Some("12.0".to_string()),
+                    utage: Some(UtageChartInfo {
+                        kanji: Some("協".to_string()),
+                        utage_type: None,
+                    }),
+                    ..chart()
+                }],
+            }],
+        });
+
+        assert_eq!(
+            index.internal_level(
+                "[協]Love You",
+                "宴会場",
+                "",
+                ChartType::Utage,
+                DifficultyCategory::Master
+            ),
+            None
+        );
+    }
 }
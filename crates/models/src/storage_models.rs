@@ -15,6 +15,10 @@ pub struct StoredScoreEntry {
     pub dx_score_max: Option<i32>,
     pub last_played_at: Option<String>,
     pub play_count: Option<i64>,
+    /// Not every query selects the level, so it defaults to `None` when absent.
+    #[sqlx(default)]
+    #[serde(default)]
+    pub level: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -35,4 +39,7 @@ pub struct StoredPlayRecord {
     pub dx_score_max: Option<i32>,
     pub credit_id: Option<i32>,
     pub achievement_new_record: Option<i32>,
+    /// Dumps written before buddy plays were tracked have no such field.
+    #[serde(default)]
+    pub is_buddy_play: bool,
 }
@@ -0,0 +1,156 @@
+//! Scripted HTTP/1.1 server for tests that need a real socket: connection reuse, retries on
+//! transient statuses, slow responses and fallbacks between hosts.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// A request as seen by the script.
+#[derive(Debug, Clone)]
+pub struct Request {
+    /// Zero-based position among every request the server received.
+    pub index: usize,
+    pub path: String,
+}
+
+/// What the server answers to one request.
+#[derive(Debug, Clone)]
+pub struct Reply {
+    status_line: String,
+    content_type: Option<String>,
+    body: Vec<u8>,
+    delay: Duration,
+}
+
+impl Reply {
+    pub fn ok(body: impl Into<Vec<u8>>) -> Self {
+        Self::status("200 OK").with_body(body)
+    }
+
+    /// An empty reply with `status_line`, e.g. `"503 Service Unavailable"`.
+    pub fn status(status_line: &str) -> Self {
+        Self {
+            status_line: status_line.to_string(),
+            content_type: None,
+            body: Vec::new(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub fn with_body(mut self, body: impl Into<Vec<u8>>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    pub fn with_content_type(mut self, content_type: &str) -> Self {
+        self.content_type = Some(content_type.to_string());
+        self
+    }
+
+    /// Wait this long after reading the request before answering.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut head = format!(
+            "HTTP/1.1 {}\r\ncontent-length: {}\r\n",
+            self.status_line,
+            self.body.len()
+        );
+        if let Some(content_type) = &self.content_type {
+            head.push_str(&format!("content-type: {content_type}\r\n"));
+        }
+        head.push_str("\r\n");
+        let mut bytes = head.into_bytes();
+        bytes.extend_from_slice(&self.body);
+        bytes
+    }
+}
+
+/// Keep-alive server on an ephemeral localhost port, answering every request with the
+/// script's reply. Stops with the test runtime.
+pub struct TestServer {
+    base_url: String,
+    connections: Arc<AtomicUsize>,
+    paths: Arc<Mutex<Vec<String>>>,
+}
+
+impl TestServer {
+    pub async fn spawn<F>(script: F) -> Self
+    where
+        F: Fn(&Request) -> Reply + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let addr = listener.local_addr().expect("local addr");
+        let connections = Arc::new(AtomicUsize::new(0));
+        let paths = Arc::new(Mutex::new(Vec::new()));
+        let script = Arc::new(script);
+
+        let connection_counter = connections.clone();
+        let seen_paths = paths.clone();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                connection_counter.fetch_add(1, Ordering::SeqCst);
+                let seen_paths = seen_paths.clone();
+                let script = script.clone();
+                tokio::spawn(async move {
+                    let mut buf = [0_u8; 4096];
+                    loop {
+                        let mut request = Vec::new();
+                        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                            match socket.read(&mut buf).await {
+                                Ok(0) | Err(_) => return,
+                                Ok(n) => request.extend_from_slice(&buf[..n]),
+                            }
+                        }
+                        let path = String::from_utf8_lossy(&request)
+                            .split_whitespace()
+                            .nth(1)
+                            .unwrap_or_default()
+                            .to_string();
+                        let index = {
+                            let mut seen = seen_paths.lock().expect("paths lock");
+                            seen.push(path.clone());
+                            seen.len() - 1
+                        };
+                        let reply = script(&Request { index, path });
+                        tokio::time::sleep(reply.delay).await;
+                        if socket.write_all(&reply.to_bytes()).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        Self {
+            base_url: format!("http://{addr}"),
+            connections,
+            paths,
+        }
+    }
+
+    /// `path` must start with `/`.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{path}", self.base_url)
+    }
+
+    /// Accepted TCP connections so far.
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::SeqCst)
+    }
+
+    /// Paths of every request received so far, in order.
+    pub fn paths(&self) -> Vec<String> {
+        self.paths.lock().expect("paths lock").clone()
+    }
+
+    pub fn requests(&self) -> usize {
+        self.paths.lock().expect("paths lock").len()
+    }
+}
@@ -0,0 +1,4 @@
+-- Set once the player was DMed about the current parse failure streak, so a bot
+-- restart does not alert them again; cleared when the streak ends.
+ALTER TABLE discord_user_record_collectors
+  ADD COLUMN parse_failure_alerted INTEGER NOT NULL DEFAULT 0;
@@ -4,6 +4,7 @@ fn chart_type_query_token(chart_type: ChartType) -> &'static str {
     match chart_type {
         ChartType::Std => "ST",
         ChartType::Dx => "DX",
+        ChartType::Utage => "UTAGE",
     }
 }
 
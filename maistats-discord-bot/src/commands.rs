@@ -1,15 +1,21 @@
 use std::collections::HashMap;
+use std::ops::Range;
 use std::sync::{Mutex, OnceLock};
 use std::time::{Duration, Instant};
 
 use eyre::WrapErr;
-use models::SongAliases;
+use models::{
+    ChartInternalLevelPatch, ChartType, DifficultyCategory, PlayRecordApiResponse, SongAliases,
+    SongDetailScoreApiResponse, cmp_chart_order, normalize_title,
+};
 use poise::CreateReply;
+use poise::futures_util::StreamExt;
 use poise::serenity_prelude as serenity;
 use time::{Duration as TimeDuration, OffsetDateTime, UtcOffset};
 use tracing::warn;
 
 use models::is_minor_or_more_outdated;
+use models::rating::{chart_rating_points, dx_score_stars, is_ap_like};
 
 use crate::BotData;
 use crate::chart_links::{linked_chart_label, linked_short_difficulty};
@@ -29,11 +35,20 @@ const CHANGELOG: &[(&str, &str)] = &[(
 )];
 use crate::db;
 use crate::embeds::{
-    RecentRecordView, build_mai_recent_embeds, build_mai_today_embed, embed_base,
-    embed_maintenance, format_level_with_internal,
+    MAX_EMBEDS_PER_MESSAGE, RecentRecordView, build_mai_recent_embeds, build_mai_today_embed,
+    embed_base, embed_maintenance, format_level_with_internal, format_table,
 };
-use crate::emoji::{format_fc, format_rank, format_sync};
+use crate::emoji::{format_fc, format_rank, format_sync, tier_prefix};
+use crate::plates;
+use crate::play_day::{jst_offset, play_day_window};
 use crate::plot;
+use crate::profile;
+use crate::random;
+use crate::rating_audit;
+use crate::rating_compare;
+use crate::rating_history;
+use crate::score_compare;
+use crate::sync_alert;
 use crate::updown;
 
 type Context<'a> = poise::Context<'a, BotData, Box<dyn std::error::Error + Send + Sync>>;
@@ -41,6 +56,8 @@ type Error = Box<dyn std::error::Error + Send + Sync>;
 const VERSION_WARNING_INTERVAL_SECONDS: i64 = 24 * 60 * 60;
 const VERSION_STATUS_CACHE_TTL: Duration = Duration::from_secs(300);
 const PLAYLOG_HISTORY_LIMIT: usize = 10_000;
+/// Enough history to cover the latest credit plus some slack for missing track numbers.
+const PROFILE_RECENT_LIMIT: usize = 50;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum RecordCollectorVersionIssue {
@@ -146,7 +163,7 @@ pub(crate) async fn how_to_use(ctx: Context<'_>) -> Result<(), Error> {
                 "maistats helps you collect and manage your personal maimai records over time.\n\n\
                 Open `https://maistats.muhwan.dev` to see how to set up your own record collector.\n\
                 Once your collector is ready, connect it to this bot with `/register <url>`.\n\n\
-                After registering, you can use commands like `/mai-score`, `/mai-recent`, `/mai-song-info`, `/mai-today`, and `/mai-updown` with your own data.",
+                After registering, you can use commands like `/mai-score`, `/mai-recent`, `/mai-song-info`, `/mai-jacket`, `/mai-today`, `/mai-profile`, `/mai-set-target`, `/mai-sync-status`, `/mai-rating-history`, `/mai-rating`, `/mai-rating-audit`, `/mai-compare-version`, `/mai-suggest`, `/mai-compare`, `/mai-rating-target-chart`, `/mai-plates`, `/mai-random`, and `/mai-updown` with your own data.",
             ),
         ),
     )
@@ -243,6 +260,7 @@ pub(crate) async fn register(
 pub(crate) async fn mai_score(
     ctx: Context<'_>,
     #[description = "Song title or alias to search for"] search: String,
+    #[description = "Show every chart as one aligned table"] compact: Option<bool>,
 ) -> Result<(), Error> {
     ctx.defer().await?;
 
@@ -251,6 +269,7 @@ pub(crate) async fn mai_score(
     };
     let record_collector_client = collector_context.client;
     let pending_warning = collector_context.pending_warning;
+    let compact = compact.unwrap_or(false);
 
     let requested_title = search.trim();
     if requested_title.is_empty() {
@@ -292,16 +311,30 @@ pub(crate) async fn mai_score(
 
     let resolved_song = matched_songs.into_iter().next().expect("checked non-empty");
 
-    let detailed_scores = match record_collector_client
-        .get_song_detail_scores(
-            &resolved_song.title,
-            &resolved_song.genre,
-            &resolved_song.artist,
-        )
+    // The candidate may fold several catalog spellings; scores are stored under each one.
+    let catalog = ctx
+        .data()
+        .song_database_client
+        .list_song_catalog()
         .await
-    {
+        .wrap_err("load song catalog")?;
+    let mut variant_results = Vec::new();
+    for (title, genre, artist) in song_variant_keys(&catalog, &resolved_song) {
+        variant_results.push(
+            record_collector_client
+                .get_song_detail_scores(&title, &genre, &artist)
+                .await,
+        );
+    }
+
+    let detailed_scores = match merge_variant_scores(variant_results) {
         Ok(mut v) => {
-            v.sort_by_key(|s| (s.chart_type.as_u8(), s.diff_category));
+            v.sort_by(|a, b| {
+                cmp_chart_order(
+                    (a.chart_type, a.diff_category),
+                    (b.chart_type, b.diff_category),
+                )
+            });
             v
         }
         Err(e) => {
@@ -354,14 +387,19 @@ pub(crate) async fn mai_score(
     let mut has_rows = false;
     let mut first_image_name = None::<String>;
     let mut desc_blocks: Vec<String> = Vec::new();
+    let mut table_rows = vec![
+        ["Type", "Diff", "Level", "Achievement", "Rank", "FC", "Sync"]
+            .map(str::to_string)
+            .to_vec(),
+    ];
 
     for score in &detailed_scores {
         has_rows = true;
         let metadata = fetch_song_metadata(
             &ctx.data().song_database_client,
             &score.title,
-            &resolved_song.genre,
-            &resolved_song.artist,
+            &score.genre,
+            &score.artist,
             score.chart_type,
             score.diff_category,
         )
@@ -377,6 +415,24 @@ pub(crate) async fn mai_score(
             .unwrap_or("N/A");
         let level =
             format_level_with_internal(level, metadata.as_ref().and_then(|m| m.internal_level));
+
+        if first_image_name.is_none() {
+            first_image_name = metadata.and_then(|m| m.image_name);
+        }
+
+        if compact {
+            table_rows.push(vec![
+                score.chart_type.to_string(),
+                score.diff_category.as_str().to_string(),
+                level,
+                format!("{achievement_percent:.4}%"),
+                score.rank.map_or("-", |rank| rank.as_str()).to_string(),
+                score.fc.map_or("-", |fc| fc.as_str()).to_string(),
+                score.sync.map_or("-", |sync| sync.as_str()).to_string(),
+            ]);
+            continue;
+        }
+
         let rank = format_rank(&ctx.data().status_emojis, score.rank, "N/A");
         let fc = format_fc(&ctx.data().status_emojis, score.fc, "-");
         let sync = format_sync(&ctx.data().status_emojis, score.sync, "-");
@@ -391,13 +447,13 @@ pub(crate) async fn mai_score(
             .collect::<Vec<_>>()
             .join(" • ");
 
-        if first_image_name.is_none() {
-            first_image_name = metadata.and_then(|m| m.image_name);
-        }
-
         let chart_line =
             linked_chart_label(&score.title, score.chart_type, score.diff_category, &level);
-        let score_line = format!("{achievement_percent:.4}% • {rank} • {fc} • {sync}");
+        let tier = tier_prefix(score.rank);
+        let mut score_line = format!("{tier}{achievement_percent:.4}% • {rank} • {fc} • {sync}");
+        if let (Some(dx_score), Some(dx_score_max)) = (score.dx_score, score.dx_score_max) {
+            score_line.push_str(&format!(" • ★×{}", dx_score_stars(dx_score, dx_score_max)));
+        }
 
         let block = if detail_suffix.is_empty() {
             format!("**{chart_line}**\n{score_line}")
@@ -407,6 +463,15 @@ pub(crate) async fn mai_score(
         desc_blocks.push(block);
     }
 
+    if compact {
+        desc_blocks.push(format!("```\n{}\n```", format_table(&table_rows)));
+    }
+
+    let remaster_hints = missing_remaster_hints(&resolved_song);
+    if !remaster_hints.is_empty() {
+        desc_blocks.push(remaster_hints.join("\n"));
+    }
+
     let mut embed = embed_base(embed_title).description(desc_blocks.join("\n\n"));
     if let Some(ref image_name) = first_image_name {
         embed = embed.thumbnail(ctx.data().song_database_client.cover_url(image_name));
@@ -468,8 +533,12 @@ pub(crate) async fn mai_song_info(
 
     let mut embeds = Vec::new();
     for mut song in matched_songs {
-        song.sheets
-            .sort_by_key(|sheet| (sheet.chart_type.as_u8(), sheet.diff_category.as_u8()));
+        song.sheets.sort_by(|a, b| {
+            cmp_chart_order(
+                (a.chart_type, a.diff_category),
+                (b.chart_type, b.diff_category),
+            )
+        });
         let mut embed = build_song_info_embed(&song);
         if let Some(image_name) = song.image_name.as_deref() {
             embed = embed.thumbnail(ctx.data().song_database_client.cover_url(image_name));
@@ -486,20 +555,103 @@ pub(crate) async fn mai_song_info(
     Ok(())
 }
 
+/// Show a song's cover art at full size, with its artist and version
+#[poise::command(slash_command, rename = "mai-jacket")]
+pub(crate) async fn mai_jacket(
+    ctx: Context<'_>,
+    #[description = "Song title or alias to search for"] title: String,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let requested_title = title.trim();
+    if requested_title.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .embed(embed_base("No song found").description("Please provide a title.")),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let matched_songs = search_song_catalog(&ctx.data().song_database_client, requested_title)
+        .await
+        .wrap_err("search song catalog")?;
+
+    if matched_songs.is_empty() {
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .embed(embed_base("No song found").description("No matching title or alias.")),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    if matched_songs.len() > 1 {
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .embed(build_duplicate_song_candidates_embed(&matched_songs)),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let song = matched_songs.into_iter().next().expect("checked non-empty");
+    let Some(image_name) = song.image_name.as_deref() else {
+        ctx.send(CreateReply::default().ephemeral(true).embed(
+            embed_base(&song.title).description("No cover image is available for this song."),
+        ))
+        .await?;
+        return Ok(());
+    };
+
+    let mut description = song_version_lines(&song);
+    description.push(format!("Artist: {}", song.artist));
+    let embed = embed_base(&song.title).description(description.join("\n"));
+
+    // Attached rather than linked so Discord does not need to reach the song database host.
+    let reply = match ctx
+        .data()
+        .song_database_client
+        .fetch_cover(image_name)
+        .await
+    {
+        Ok(bytes) => {
+            use poise::serenity_prelude::builder::CreateAttachment;
+            CreateReply::default()
+                .embed(embed.image(format!("attachment://{image_name}")))
+                .attachment(CreateAttachment::bytes(bytes, image_name))
+        }
+        Err(err) => {
+            warn!("mai-jacket cover download failed for {image_name}: {err:?}");
+            CreateReply::default()
+                .embed(embed.image(ctx.data().song_database_client.cover_url(image_name)))
+        }
+    };
+    ctx.send(reply).await?;
+
+    Ok(())
+}
+
+/// `Version (STD): ...` / `Version (DX): ...` for each chart type the song has.
+fn song_version_lines(song: &SongCatalogSong) -> Vec<String> {
+    [models::ChartType::Std, models::ChartType::Dx]
+        .into_iter()
+        .filter_map(|chart_type| {
+            song.sheets
+                .iter()
+                .find(|sheet| sheet.chart_type == chart_type)
+                .and_then(|sheet| sheet.version.as_deref())
+                .map(|version| format!("Version ({chart_type}): {version}"))
+        })
+        .collect()
+}
+
 fn build_song_info_embed(song: &SongCatalogSong) -> serenity::CreateEmbed {
     let region_unreleased_line = build_region_unreleased_line(&song.sheets);
 
-    let std_version = song
-        .sheets
-        .iter()
-        .find(|sheet| sheet.chart_type == models::ChartType::Std)
-        .and_then(|sheet| sheet.version.as_deref());
-    let dx_version = song
-        .sheets
-        .iter()
-        .find(|sheet| sheet.chart_type == models::ChartType::Dx)
-        .and_then(|sheet| sheet.version.as_deref());
-
     let format_levels =
         |chart_type: models::ChartType| -> Option<String> {
             let ordered_difficulties = [
@@ -539,13 +691,7 @@ fn build_song_info_embed(song: &SongCatalogSong) -> serenity::CreateEmbed {
         blocks.push(region_line);
     }
 
-    let mut version_lines = Vec::new();
-    if let Some(version) = std_version {
-        version_lines.push(format!("Version (STD): {version}"));
-    }
-    if let Some(version) = dx_version {
-        version_lines.push(format!("Version (DX): {version}"));
-    }
+    let version_lines = song_version_lines(song);
     if !version_lines.is_empty() {
         blocks.push(version_lines.join("\n"));
     }
@@ -568,7 +714,10 @@ fn build_song_info_embed(song: &SongCatalogSong) -> serenity::CreateEmbed {
 
 /// Get most recent credit records
 #[poise::command(slash_command, rename = "mai-recent")]
-pub(crate) async fn mai_recent(ctx: Context<'_>) -> Result<(), Error> {
+pub(crate) async fn mai_recent(
+    ctx: Context<'_>,
+    #[description = "Show only tracks that set a new record"] new_records_only: Option<bool>,
+) -> Result<(), Error> {
     ctx.defer().await?;
 
     let Some(collector_context) = registered_record_collector_client(ctx).await? else {
@@ -582,6 +731,7 @@ pub(crate) async fn mai_recent(ctx: Context<'_>) -> Result<(), Error> {
         .get_recent(50)
         .await
         .wrap_err("fetch recent plays")?;
+    let play_records = dedup_recent_plays(play_records);
 
     if play_records.is_empty() {
         ctx.send(CreateReply::default().embed(embed_base("No recent records found")))
@@ -594,10 +744,99 @@ pub(crate) async fn mai_recent(ctx: Context<'_>) -> Result<(), Error> {
         .iter()
         .map(|r| r.track.map(|t| t as i64))
         .collect();
-    let take = latest_credit_len(&tracks);
+    let credits = credit_ranges(&tracks);
+    let new_records_only = new_records_only.unwrap_or(false);
+
+    let mut page = 0;
+    let embeds = build_recent_credit_embeds(
+        ctx,
+        &display_name,
+        &play_records[credits[page].clone()],
+        new_records_only,
+    )
+    .await;
+    let reply = ctx
+        .send(CreateReply {
+            embeds,
+            components: (credits.len() > 1).then(|| recent_credit_buttons(page, credits.len())),
+            ..Default::default()
+        })
+        .await?;
+    send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+    if credits.len() <= 1 {
+        return Ok(());
+    }
+
+    let mut message = reply.message().await?.into_owned();
+    let mut interactions = serenity::ComponentInteractionCollector::new(ctx.serenity_context())
+        .message_id(message.id)
+        .author_id(ctx.author().id)
+        .timeout(RECENT_PAGINATION_TIMEOUT)
+        .stream();
+    while let Some(interaction) = interactions.next().await {
+        page = match interaction.data.custom_id.as_str() {
+            RECENT_OLDER_CREDIT_ID => (page + 1).min(credits.len() - 1),
+            RECENT_NEWER_CREDIT_ID => page.saturating_sub(1),
+            _ => continue,
+        };
+        let embeds = build_recent_credit_embeds(
+            ctx,
+            &display_name,
+            &play_records[credits[page].clone()],
+            new_records_only,
+        )
+        .await;
+        interaction
+            .create_response(
+                ctx,
+                serenity::CreateInteractionResponse::UpdateMessage(
+                    serenity::CreateInteractionResponseMessage::new()
+                        .embeds(embeds)
+                        .components(recent_credit_buttons(page, credits.len())),
+                ),
+            )
+            .await?;
+    }
+
+    message
+        .edit(ctx, serenity::EditMessage::new().components(Vec::new()))
+        .await?;
+
+    Ok(())
+}
+
+const RECENT_OLDER_CREDIT_ID: &str = "mai-recent-older-credit";
+const RECENT_NEWER_CREDIT_ID: &str = "mai-recent-newer-credit";
+const RECENT_PAGINATION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// `page` 0 is the latest credit; "Prev" walks back to older ones.
+fn recent_credit_buttons(page: usize, credit_count: usize) -> Vec<serenity::CreateActionRow> {
+    vec![serenity::CreateActionRow::Buttons(vec![
+        serenity::CreateButton::new(RECENT_OLDER_CREDIT_ID)
+            .label("◀ Prev credit")
+            .style(serenity::ButtonStyle::Secondary)
+            .disabled(page + 1 >= credit_count),
+        serenity::CreateButton::new(RECENT_NEWER_CREDIT_ID)
+            .label("Next credit ▶")
+            .style(serenity::ButtonStyle::Secondary)
+            .disabled(page == 0),
+    ])]
+}
 
-    let mut recent = play_records.into_iter().take(take).collect::<Vec<_>>();
-    recent.reverse();
+/// Embeds for one credit, given its rows newest first.
+async fn build_recent_credit_embeds(
+    ctx: Context<'_>,
+    display_name: &str,
+    credit: &[PlayRecordApiResponse],
+    new_records_only: bool,
+) -> Vec<serenity::CreateEmbed> {
+    let mut recent = credit.iter().rev().cloned().collect::<Vec<_>>();
+    if new_records_only {
+        recent = keep_new_records(recent);
+        if recent.is_empty() {
+            return vec![embed_base("No new records were set this credit")];
+        }
+    }
 
     let mut records = Vec::with_capacity(recent.len());
     for record in recent {
@@ -646,31 +885,79 @@ pub(crate) async fn mai_recent(ctx: Context<'_>) -> Result<(), Error> {
         });
     }
 
-    let embeds = build_mai_recent_embeds(
-        &display_name,
+    build_mai_recent_embeds(
+        display_name,
         &records,
         None,
         &ctx.data().status_emojis,
         &ctx.data().song_database_client,
-    );
+        ctx.data().display_offset,
+        MAX_EMBEDS_PER_MESSAGE,
+    )
+}
 
-    ctx.send(CreateReply {
-        embeds,
-        ..Default::default()
-    })
-    .await?;
-    send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+fn keep_new_records(records: Vec<PlayRecordApiResponse>) -> Vec<PlayRecordApiResponse> {
+    records
+        .into_iter()
+        .filter(|record| record.achievement_new_record.unwrap_or(0) != 0)
+        .collect()
+}
 
-    Ok(())
+/// Drop repeated rows for the same play (same `played_at` and track), keeping the first.
+/// Overlapping poll pages can return a play twice, which would otherwise render twice and
+/// split its credit at a duplicated TRACK 01.
+fn dedup_recent_plays(records: Vec<PlayRecordApiResponse>) -> Vec<PlayRecordApiResponse> {
+    let mut seen = std::collections::HashSet::new();
+    records
+        .into_iter()
+        .filter(|record| seen.insert((record.played_at_unixtime, record.track)))
+        .collect()
 }
 
-fn latest_credit_len(tracks: &[Option<i64>]) -> usize {
+pub(crate) fn latest_credit_len(tracks: &[Option<i64>]) -> usize {
     match tracks.iter().position(|t| *t == Some(1)) {
         Some(idx) => idx + 1,
         None => tracks.len().min(4),
     }
 }
 
+/// Split a newest-first playlog window into credits, latest first, each ending at its
+/// TRACK 01 row. A trailing credit cut off by the window is split like [`latest_credit_len`].
+pub(crate) fn credit_ranges(tracks: &[Option<i64>]) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    while start < tracks.len() {
+        let end = start + latest_credit_len(&tracks[start..]);
+        ranges.push(start..end);
+        start = end;
+    }
+    ranges
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TodayCounts {
+    pub(crate) credits: i64,
+    pub(crate) tracks: i64,
+    pub(crate) new_records: i64,
+}
+
+impl TodayCounts {
+    pub(crate) fn from_plays(plays: &[PlayRecordApiResponse]) -> Self {
+        Self {
+            credits: plays
+                .iter()
+                .filter_map(|p| p.credit_id)
+                .collect::<std::collections::HashSet<_>>()
+                .len() as i64,
+            tracks: plays.len() as i64,
+            new_records: plays
+                .iter()
+                .filter(|p| p.achievement_new_record.unwrap_or(0) != 0)
+                .count() as i64,
+        }
+    }
+}
+
 /// Show today's play summary (day boundary: 04:00 JST)
 #[poise::command(slash_command, rename = "mai-today")]
 pub(crate) async fn mai_today(ctx: Context<'_>) -> Result<(), Error> {
@@ -682,46 +969,16 @@ pub(crate) async fn mai_today(ctx: Context<'_>) -> Result<(), Error> {
     let record_collector_client = collector_context.client;
     let pending_warning = collector_context.pending_warning;
 
-    let offset = UtcOffset::from_hms(9, 0, 0).unwrap_or(UtcOffset::UTC);
+    let offset = jst_offset();
     let now_jst = OffsetDateTime::now_utc().to_offset(offset);
-
-    let day_date = if now_jst.hour() < 4 {
-        (now_jst - TimeDuration::days(1)).date()
-    } else {
-        now_jst.date()
-    };
-    let end_date = day_date + TimeDuration::days(1);
-
-    let today_str = format!(
-        "{:04}-{:02}-{:02}",
-        day_date.year(),
-        u8::from(day_date.month()),
-        day_date.day()
-    );
+    let window = play_day_window(now_jst);
+    let today_str = window.day.clone();
 
     let plays = record_collector_client.get_today(&today_str).await?;
-
-    let tracks = plays.len() as i64;
-    let credits = plays
-        .iter()
-        .filter_map(|p| p.credit_id)
-        .collect::<std::collections::HashSet<_>>()
-        .len() as i64;
-    let new_records = plays
-        .iter()
-        .filter(|p| p.achievement_new_record.unwrap_or(0) != 0)
-        .count() as i64;
-
-    let start = format!("{} 04:00", today_str);
-    let end = format!(
-        "{:04}-{:02}-{:02} 04:00",
-        end_date.year(),
-        u8::from(end_date.month()),
-        end_date.day()
-    );
+    let counts = TodayCounts::from_plays(&plays);
 
     let display_name = load_player_display_name(&record_collector_client).await;
-    let embed = build_mai_today_embed(&display_name, &start, &end, credits, tracks, new_records);
+    let embed = build_mai_today_embed(&display_name, &window, &counts);
 
     let mut reply = CreateReply::default().embed(embed);
     match build_mai_today_plot(
@@ -749,148 +1006,1008 @@ pub(crate) async fn mai_today(ctx: Context<'_>) -> Result<(), Error> {
     Ok(())
 }
 
-/// Build a scatter plot PNG of today's plays across the full 1.0-15.0 level range.
-/// Returns `Ok(None)` if there is nothing to plot (no plays, or none could be
-/// resolved against the song catalog).
-async fn build_mai_today_plot(
-    ctx: Context<'_>,
-    record_collector_client: &RecordCollectorClient,
-    plays: &[models::PlayRecordApiResponse],
-    today_str: &str,
-    now_jst: OffsetDateTime,
-    jst: UtcOffset,
-) -> Result<Option<Vec<u8>>, Error> {
-    if plays.is_empty() {
-        return Ok(None);
-    }
+/// Compare SEGA's displayed rating with the rating computed from our internal levels
+#[poise::command(slash_command, rename = "mai-rating-audit")]
+pub(crate) async fn mai_rating_audit(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let Some(collector_context) = registered_record_collector_client(ctx).await? else {
+        return Ok(());
+    };
+    let record_collector_client = collector_context.client;
+    let pending_warning = collector_context.pending_warning;
 
+    let player_profile = record_collector_client
+        .get_player_profile()
+        .await
+        .wrap_err("fetch player profile")?;
+    let targets = record_collector_client
+        .get_rating_targets()
+        .await
+        .wrap_err("fetch rating targets")?;
+    let rated_scores = record_collector_client
+        .get_all_rated_scores()
+        .await
+        .wrap_err("fetch rated scores")?;
     let catalog = ctx
         .data()
         .song_database_client
         .list_song_catalog()
         .await
-        .wrap_err("fetch song catalog")?;
+        .wrap_err("load song catalog")?;
 
-    let level_map = plot::build_level_map(&catalog);
-    let recent_playlogs = record_collector_client
-        .get_recent(PLAYLOG_HISTORY_LIMIT)
-        .await
-        .wrap_err("fetch recent playlogs")?;
-    let previous_achievement_by_played_at =
-        previous_new_record_achievements_by_played_at(plays, &recent_playlogs);
+    let target_rating = fetch_target_rating(&record_collector_client).await;
 
-    let points = plot::build_plot_points(
-        plays.iter().map(|p| plot::PlotInputRecord {
-            achievement_x10000: p.achievement_x10000,
-            title: &p.title,
-            genre: p.genre.as_deref(),
-            artist: p.artist.as_deref(),
-            chart_type: p.chart_type,
-            diff_category: p.diff_category,
-            played_at: p.played_at.as_deref(),
-            is_new_record: p.achievement_new_record.unwrap_or(0) != 0,
-            previous_achievement_x10000: previous_achievement_by_played_at
-                .get(&p.played_at_unixtime)
-                .copied(),
-        }),
-        &level_map,
-        now_jst,
-        jst,
-        &plot::PlotFilter {
-            day_range: None,
-            level_range_tenths: None,
-        },
+    let audit = rating_audit::build_rating_audit(&targets, &catalog, &rated_scores);
+    let mut embed = rating_audit::build_rating_audit_embed(
+        &player_profile.user_name,
+        player_profile.rating,
+        &audit,
     );
+    if let Some(target_rating) = target_rating {
+        embed = embed.field(
+            "Goal",
+            profile::format_target_progress(player_profile.rating, target_rating),
+            false,
+        );
+    }
 
-    if points.is_empty() {
-        return Ok(None);
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+
+    Ok(())
+}
+
+/// Show the NEW 15 / OLD 35 charts behind your rating, as computed by your record collector
+#[poise::command(slash_command, rename = "mai-rating")]
+pub(crate) async fn mai_rating(
+    ctx: Context<'_>,
+    #[description = "Also split the rating by song genre"] by_genre: Option<bool>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let Some(collector_context) = registered_record_collector_client(ctx).await? else {
+        return Ok(());
+    };
+    let record_collector_client = collector_context.client;
+    let pending_warning = collector_context.pending_warning;
+
+    let player_profile = record_collector_client
+        .get_player_profile()
+        .await
+        .wrap_err("fetch player profile")?;
+    let breakdown = record_collector_client
+        .get_rating_breakdown()
+        .await
+        .wrap_err("fetch rating breakdown")?;
+
+    let mut embed =
+        rating_compare::build_rating_breakdown_embed(&player_profile.user_name, &breakdown);
+    if by_genre.unwrap_or(false) {
+        embed = embed.field(
+            "By genre",
+            rating_compare::format_genre_breakdown(&rating_compare::rating_points_by_genre(
+                &breakdown,
+            )),
+            false,
+        );
     }
 
-    let x_min = plot::compute_x_min(&points);
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    send_pending_record_collector_update_warning(ctx, pending_warning).await?;
 
-    let total = points.len();
-    let title = format!(
-        "{}  —  {} play{}",
-        today_str,
-        total,
+    Ok(())
+}
+
+/// Suggest rating-set charts where the next rank adds the most rating
+#[poise::command(slash_command, rename = "mai-suggest")]
+pub(crate) async fn mai_suggest(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let Some(collector_context) = registered_record_collector_client(ctx).await? else {
+        return Ok(());
+    };
+    let record_collector_client = collector_context.client;
+    let pending_warning = collector_context.pending_warning;
+
+    let player_profile = record_collector_client
+        .get_player_profile()
+        .await
+        .wrap_err("fetch player profile")?;
+    let rated_scores = record_collector_client
+        .get_all_rated_scores()
+        .await
+        .wrap_err("fetch rated scores")?;
+    let catalog = ctx
+        .data()
+        .song_database_client
+        .list_song_catalog()
+        .await
+        .wrap_err("load song catalog")?;
+
+    let suggestions = rating_compare::build_rank_suggestions(&rated_scores, &catalog);
+    let embed =
+        rating_compare::build_rank_suggestions_embed(&player_profile.user_name, &suggestions);
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+
+    Ok(())
+}
+
+/// Show NEW-only and OLD-only rating, and the ceiling if every played chart were SSS+
+#[poise::command(slash_command, rename = "mai-compare-version")]
+pub(crate) async fn mai_compare_version(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let Some(collector_context) = registered_record_collector_client(ctx).await? else {
+        return Ok(());
+    };
+    let record_collector_client = collector_context.client;
+    let pending_warning = collector_context.pending_warning;
+
+    let player_profile = record_collector_client
+        .get_player_profile()
+        .await
+        .wrap_err("fetch player profile")?;
+    let rated_scores = record_collector_client
+        .get_all_rated_scores()
+        .await
+        .wrap_err("fetch rated scores")?;
+    let catalog = ctx
+        .data()
+        .song_database_client
+        .list_song_catalog()
+        .await
+        .wrap_err("load song catalog")?;
+
+    let comparison = rating_compare::build_rating_comparison(&rated_scores, &catalog);
+    let embed =
+        rating_compare::build_rating_comparison_embed(&player_profile.user_name, &comparison);
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+
+    Ok(())
+}
+
+/// Show how far along a version plate (e.g. all PRiSM charts AP) you are
+#[poise::command(slash_command, rename = "mai-plates")]
+pub(crate) async fn mai_plates(
+    ctx: Context<'_>,
+    #[description = "Version name (e.g. PRiSM, CiRCLE)"] version: String,
+    #[description = "Plate requirement"] target: plates::PlateTarget,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let Ok(version) = version.parse::<models::MaimaiVersion>() else {
+        ctx.send(CreateReply::default().ephemeral(true).embed(
+            embed_base("Unknown version").description(format!(
+                "`{}` is not a maimai version name.",
+                version.trim()
+            )),
+        ))
+        .await?;
+        return Ok(());
+    };
+
+    let Some(collector_context) = registered_record_collector_client(ctx).await? else {
+        return Ok(());
+    };
+    let record_collector_client = collector_context.client;
+    let pending_warning = collector_context.pending_warning;
+
+    let player_profile = record_collector_client
+        .get_player_profile()
+        .await
+        .wrap_err("fetch player profile")?;
+    let scores = record_collector_client
+        .get_all_rated_scores()
+        .await
+        .wrap_err("fetch scores")?;
+    let catalog = ctx
+        .data()
+        .song_database_client
+        .list_song_catalog()
+        .await
+        .wrap_err("load song catalog")?;
+
+    let progress = plates::build_plate_progress(&scores, &catalog, version, target);
+    let embed =
+        plates::build_plate_progress_embed(&player_profile.user_name, version, target, &progress);
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+
+    Ok(())
+}
+
+/// Show what SS+ / SSS / SSS+ on one chart would add to your rating
+#[poise::command(slash_command, rename = "mai-rating-target-chart")]
+pub(crate) async fn mai_rating_target_chart(
+    ctx: Context<'_>,
+    #[description = "Song title or alias"] title: String,
+    #[description = "Chart type (STD or DX)"] chart_type: String,
+    #[description = "Difficulty (BASIC, ADVANCED, EXPERT, MASTER, Re:MASTER)"] difficulty: String,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let (Ok(chart_type), Ok(diff_category)) = (
+        chart_type.parse::<ChartType>(),
+        difficulty.parse::<DifficultyCategory>(),
+    ) else {
+        ctx.send(
+            CreateReply::default().embed(embed_base("Invalid chart").description(format!(
+                "Unknown chart type '{chart_type}' or difficulty '{difficulty}'."
+            ))),
+        )
+        .await?;
+        return Ok(());
+    };
+
+    let Some(collector_context) = registered_record_collector_client(ctx).await? else {
+        return Ok(());
+    };
+    let record_collector_client = collector_context.client;
+    let pending_warning = collector_context.pending_warning;
+
+    let matched_songs = search_song_catalog(&ctx.data().song_database_client, title.trim())
+        .await
+        .wrap_err("search song catalog")?;
+    if matched_songs.len() != 1 {
+        let embed = if matched_songs.is_empty() {
+            embed_base("No song found").description("No matching title or alias.")
+        } else {
+            build_duplicate_song_candidates_embed(&matched_songs)
+        };
+        ctx.send(CreateReply::default().embed(embed)).await?;
+        send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+        return Ok(());
+    }
+    let song = matched_songs.into_iter().next().expect("checked len");
+    let Some(sheet) = song
+        .sheets
+        .iter()
+        .find(|sheet| sheet.chart_type == chart_type && sheet.diff_category == diff_category)
+    else {
+        ctx.send(
+            CreateReply::default().embed(embed_base("No such chart").description(format!(
+                "{} has no {chart_type} {diff_category} chart.",
+                song.title
+            ))),
+        )
+        .await?;
+        send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+        return Ok(());
+    };
+
+    let rated_scores = record_collector_client
+        .get_all_rated_scores()
+        .await
+        .wrap_err("fetch rated scores")?;
+    let catalog = ctx
+        .data()
+        .song_database_client
+        .list_song_catalog()
+        .await
+        .wrap_err("load song catalog")?;
+
+    let embed = match rating_compare::build_chart_target(&rated_scores, &catalog, &song, sheet) {
+        Some(target) => {
+            let mut embed = rating_compare::build_chart_target_embed(&song, sheet, &target);
+            if let Some(image_name) = song.image_name.as_deref() {
+                embed = embed.thumbnail(ctx.data().song_database_client.cover_url(image_name));
+            }
+            embed
+        }
+        None => embed_base("Internal level unknown").description(format!(
+            "{} {chart_type} {diff_category} has no known internal level, so its rating cannot be computed.",
+            song.title
+        )),
+    };
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+
+    Ok(())
+}
+
+/// Summarize rating, play counts and the latest credit in a single embed
+#[poise::command(slash_command, rename = "mai-profile")]
+pub(crate) async fn mai_profile(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let Some(collector_context) = registered_record_collector_client(ctx).await? else {
+        return Ok(());
+    };
+    let record_collector_client = collector_context.client;
+    let pending_warning = collector_context.pending_warning;
+
+    let player_profile = record_collector_client
+        .get_player_profile()
+        .await
+        .wrap_err("fetch player profile")?;
+    let recent = record_collector_client
+        .get_recent(PROFILE_RECENT_LIMIT)
+        .await
+        .wrap_err("fetch recent plays")?;
+    let breakdown = record_collector_client
+        .get_rating_breakdown()
+        .await
+        .wrap_err("fetch rating breakdown")?;
+    let catalog = ctx
+        .data()
+        .song_database_client
+        .list_song_catalog()
+        .await
+        .wrap_err("load song catalog")?;
+
+    let target_rating = fetch_target_rating(&record_collector_client).await;
+    let last_synced_at_unixtime = match record_collector_client.get_sync_status().await {
+        Ok(status) => status.last_synced_at_unixtime,
+        Err(err) => {
+            warn!("failed to fetch sync status: {err:#}");
+            None
+        }
+    };
+
+    let latest_credit = profile::summarize_latest_credit(&recent);
+    let top_chart = profile::top_rated_chart(&breakdown, &catalog);
+    let mut embed = profile::build_profile_embed(
+        &player_profile,
+        latest_credit.as_ref(),
+        top_chart.as_ref(),
+        last_synced_at_unixtime,
+        ctx.data().display_offset,
+    );
+    if let Some(target_rating) = target_rating {
+        embed = embed.field(
+            "Goal",
+            profile::format_target_progress(player_profile.rating, target_rating),
+            false,
+        );
+    }
+    if let Some(image_name) = top_chart
+        .as_ref()
+        .and_then(|chart| chart.image_name.as_deref())
+    {
+        embed = embed.thumbnail(ctx.data().song_database_client.cover_url(image_name));
+    }
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+
+    Ok(())
+}
+
+/// Store (or clear) a rating goal shown by `/mai-profile` and `/mai-rating-audit`
+#[poise::command(slash_command, rename = "mai-set-target")]
+pub(crate) async fn mai_set_target(
+    ctx: Context<'_>,
+    #[description = "Target rating (omit to clear the goal)"]
+    #[min = 1]
+    #[max = 20000]
+    rating: Option<u32>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let Some(collector_context) = registered_record_collector_client(ctx).await? else {
+        return Ok(());
+    };
+    let record_collector_client = collector_context.client;
+    let pending_warning = collector_context.pending_warning;
+
+    record_collector_client
+        .set_target_rating(rating)
+        .await
+        .wrap_err("store target rating")?;
+
+    let embed = match rating {
+        Some(target_rating) => {
+            let player_profile = record_collector_client
+                .get_player_profile()
+                .await
+                .wrap_err("fetch player profile")?;
+            embed_base("Rating goal set").description(profile::format_target_progress(
+                player_profile.rating,
+                target_rating,
+            ))
+        }
+        None => embed_base("Rating goal cleared"),
+    };
+
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+
+    Ok(())
+}
+
+/// Show whether the record collector's background poll is failing to parse SEGA's pages
+#[poise::command(slash_command, rename = "mai-sync-status")]
+pub(crate) async fn mai_sync_status(ctx: Context<'_>) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let Some(collector_context) = registered_record_collector_client(ctx).await? else {
+        return Ok(());
+    };
+    let record_collector_client = collector_context.client;
+    let pending_warning = collector_context.pending_warning;
+
+    let status = record_collector_client
+        .get_sync_status()
+        .await
+        .wrap_err("fetch sync status")?;
+
+    ctx.send(CreateReply::default().embed(sync_alert::build_sync_status_embed(&status)))
+        .await?;
+    send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+
+    Ok(())
+}
+
+/// Compare your scores against another player's `db export` JSON
+#[poise::command(slash_command, rename = "mai-compare")]
+pub(crate) async fn mai_compare(
+    ctx: Context<'_>,
+    #[description = "JSON from `maistats-record-collector db export`"] file: serenity::Attachment,
+    #[description = "Name to show for the other player"] name: Option<String>,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    if file.size > score_compare::MAX_SCORE_DUMP_BYTES {
+        ctx.send(CreateReply::default().ephemeral(true).embed(
+            embed_base("File too large").description(format!(
+                "Score exports up to {} MiB are supported.",
+                score_compare::MAX_SCORE_DUMP_BYTES / 1024 / 1024
+            )),
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let Some(collector_context) = registered_record_collector_client(ctx).await? else {
+        return Ok(());
+    };
+    let record_collector_client = collector_context.client;
+    let pending_warning = collector_context.pending_warning;
+
+    let bytes = file.download().await.wrap_err("download score export")?;
+    let theirs = match score_compare::parse_score_dump(&bytes) {
+        Ok(theirs) => theirs,
+        Err(err) => {
+            ctx.send(CreateReply::default().ephemeral(true).embed(
+                embed_base("Unreadable score export").description(format!(
+                    "`{}` is not a `db export` JSON file: {err:#}",
+                    file.filename
+                )),
+            ))
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let display_name = load_player_display_name(&record_collector_client).await;
+    let mine = record_collector_client
+        .get_all_rated_scores()
+        .await
+        .wrap_err("fetch scores")?;
+
+    let other_name = name
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "Them".to_string());
+    let comparison = score_compare::compare_scores(&mine, &theirs);
+    let embed =
+        score_compare::build_score_comparison_embed(&display_name, &other_name, &comparison);
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+
+    Ok(())
+}
+
+/// Show how the player's rating moved over the last 7 or 30 days
+#[poise::command(slash_command, rename = "mai-rating-history")]
+pub(crate) async fn mai_rating_history(
+    ctx: Context<'_>,
+    #[description = "Window to look back over (default 7 days)"] window: Option<
+        rating_history::HistoryWindow,
+    >,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let Some(collector_context) = registered_record_collector_client(ctx).await? else {
+        return Ok(());
+    };
+    let record_collector_client = collector_context.client;
+    let pending_warning = collector_context.pending_warning;
+
+    let window = window.unwrap_or(rating_history::HistoryWindow::Week);
+    let since = (OffsetDateTime::now_utc() - TimeDuration::days(window.days())).unix_timestamp();
+    let player_profile = match record_collector_client.get_player_profile().await {
+        Ok(player_profile) => Some(player_profile),
+        Err(err) => {
+            warn!("failed to load player profile: {err:#}");
+            None
+        }
+    };
+    let display_name = player_profile
+        .as_ref()
+        .map_or_else(|| "Player".to_string(), |profile| profile.user_name.clone());
+    let history = record_collector_client
+        .get_rating_history(since)
+        .await
+        .wrap_err("fetch rating history")?;
+
+    let summary = rating_history::summarize_rating_history(
+        &history,
+        since,
+        player_profile
+            .as_ref()
+            .map(|profile| profile.total_play_count),
+    );
+    let embed = rating_history::build_rating_history_embed(&display_name, window, summary.as_ref());
+    ctx.send(CreateReply::default().embed(embed)).await?;
+    send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+
+    Ok(())
+}
+
+/// Collectors older than the goal endpoint answer 404; show no goal rather than failing.
+async fn fetch_target_rating(client: &RecordCollectorClient) -> Option<u32> {
+    match client.get_target_rating().await {
+        Ok(target_rating) => target_rating,
+        Err(err) => {
+            warn!("failed to fetch target rating: {err:#}");
+            None
+        }
+    }
+}
+
+/// Build a scatter plot PNG of today's plays across the full 1.0-15.0 level range.
+/// Returns `Ok(None)` if there is nothing to plot (no plays, or none could be
+/// resolved against the song catalog).
+async fn build_mai_today_plot(
+    ctx: Context<'_>,
+    record_collector_client: &RecordCollectorClient,
+    plays: &[models::PlayRecordApiResponse],
+    today_str: &str,
+    now_jst: OffsetDateTime,
+    jst: UtcOffset,
+) -> Result<Option<Vec<u8>>, Error> {
+    if plays.is_empty() {
+        return Ok(None);
+    }
+
+    let catalog = ctx
+        .data()
+        .song_database_client
+        .list_song_catalog()
+        .await
+        .wrap_err("fetch song catalog")?;
+
+    let level_map = plot::build_level_map(&catalog);
+    let recent_playlogs = record_collector_client
+        .get_recent(PLAYLOG_HISTORY_LIMIT)
+        .await
+        .wrap_err("fetch recent playlogs")?;
+    let previous_achievement_by_played_at =
+        previous_new_record_achievements_by_played_at(plays, &recent_playlogs);
+
+    let points = plot::build_plot_points(
+        plays.iter().map(|p| plot::PlotInputRecord {
+            achievement_x10000: p.achievement_x10000,
+            title: &p.title,
+            genre: p.genre.as_deref(),
+            artist: p.artist.as_deref(),
+            chart_type: p.chart_type,
+            diff_category: p.diff_category,
+            played_at: p.played_at.as_deref(),
+            is_new_record: p.achievement_new_record.unwrap_or(0) != 0,
+            previous_achievement_x10000: previous_achievement_by_played_at
+                .get(&p.played_at_unixtime)
+                .copied(),
+        }),
+        &level_map,
+        now_jst,
+        jst,
+        &plot::PlotFilter {
+            day_range: None,
+            level_range_tenths: None,
+        },
+    );
+
+    if points.is_empty() {
+        return Ok(None);
+    }
+
+    let x_min = plot::compute_x_min(&points);
+
+    let total = points.len();
+    let title = format!(
+        "{}  —  {} play{}",
+        today_str,
+        total,
         if total == 1 { "" } else { "s" }
     );
 
-    let png = plot::generate_scatter_plot(&points, x_min, Some(&title))
-        .await
-        .wrap_err("generate scatter plot")?;
-    Ok(Some(png))
-}
+    let png = plot::generate_scatter_plot(&points, x_min, Some(&title))
+        .await
+        .wrap_err("generate scatter plot")?;
+    Ok(Some(png))
+}
+
+fn previous_new_record_achievements_by_played_at(
+    plays: &[models::PlayRecordApiResponse],
+    playlog_history: &[models::PlayRecordApiResponse],
+) -> HashMap<i64, i64> {
+    plays
+        .iter()
+        .filter(|play| play.achievement_new_record.unwrap_or(0) != 0)
+        .filter_map(|play| {
+            previous_best_achievement_x10000(play, playlog_history)
+                .map(|previous| (play.played_at_unixtime, previous))
+        })
+        .collect()
+}
+
+fn previous_best_achievement_x10000(
+    play: &models::PlayRecordApiResponse,
+    playlog_history: &[models::PlayRecordApiResponse],
+) -> Option<i64> {
+    playlog_history
+        .iter()
+        .filter(|candidate| {
+            candidate.played_at_unixtime < play.played_at_unixtime
+                && same_playlog_chart_identity(candidate, play)
+        })
+        .filter_map(|candidate| candidate.achievement_x10000)
+        .max()
+}
+
+fn same_playlog_chart_identity(
+    left: &models::PlayRecordApiResponse,
+    right: &models::PlayRecordApiResponse,
+) -> bool {
+    left.title == right.title
+        && left.genre == right.genre
+        && left.artist == right.artist
+        && left.chart_type == right.chart_type
+        && left.diff_category == right.diff_category
+}
+
+/// Draw a setlist of random charts with no song repeated
+#[poise::command(slash_command, rename = "mai-random")]
+pub(crate) async fn mai_random(
+    ctx: Context<'_>,
+    #[description = "Number of charts (default 4, one credit)"]
+    #[min = 1]
+    #[max = 10]
+    count: Option<u32>,
+    #[description = "Level (for example 13+)"] level: Option<String>,
+    #[description = "Minimum internal level (for example 13.0)"] min_internal_level: Option<f64>,
+    #[description = "Maximum internal level (for example 13.9)"] max_internal_level: Option<f64>,
+    #[description = "Seed shown under an earlier setlist, to draw the same one again"] seed: Option<
+        u32,
+    >,
+) -> Result<(), Error> {
+    ctx.defer().await?;
+
+    let count = count
+        .map(|count| count as usize)
+        .unwrap_or(random::DEFAULT_SETLIST_SIZE)
+        .clamp(1, random::MAX_SETLIST_SIZE);
+    let filter = random::RandomChartFilter {
+        level: level.filter(|level| !level.trim().is_empty()),
+        min_internal_level: min_internal_level.map(|value| value as f32),
+        max_internal_level: max_internal_level.map(|value| value as f32),
+    };
+
+    let catalog = ctx
+        .data()
+        .song_database_client
+        .list_song_catalog()
+        .await
+        .wrap_err("load song catalog")?;
+    let candidates = random::random_chart_candidates(&catalog, &filter);
+    let (seed, mut rng) = random::setlist_rng(seed);
+    let setlist = random::pick_setlist(candidates, count, &mut rng);
+
+    let reply = if setlist.is_empty() {
+        CreateReply::default().embed(
+            embed_base("No charts found")
+                .description("No INTL charts match the given level filters."),
+        )
+    } else {
+        let mut embed = random::build_setlist_embed(&setlist);
+        if let Some(image_name) = setlist[0].image_name.as_deref() {
+            embed = embed.thumbnail(ctx.data().song_database_client.cover_url(image_name));
+        }
+        let mut footer = format!("Seed: {seed}");
+        if setlist.len() < count {
+            footer.push_str(&format!(
+                " • Only {} distinct songs match these filters.",
+                setlist.len()
+            ));
+        }
+        CreateReply::default().embed(embed.footer(serenity::CreateEmbedFooter::new(footer)))
+    };
+
+    ctx.send(reply).await?;
+    Ok(())
+}
+
+/// Start a mai-updown random session in a thread
+#[poise::command(slash_command, rename = "mai-updown", guild_only)]
+pub(crate) async fn mai_updown(
+    ctx: Context<'_>,
+    #[description = "Starting internal level (for example 13.0)"] internal_level: f64,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let Some(collector_context) = registered_record_collector_client(ctx).await? else {
+        return Ok(());
+    };
+    let record_collector_client = collector_context.client;
+    let pending_warning = collector_context.pending_warning;
+
+    let reply = match updown::parse_level_tenths(internal_level) {
+        Ok(start_level_tenths) => {
+            match updown::start_session(ctx, record_collector_client, start_level_tenths).await {
+                Ok(()) => CreateReply::default()
+                    .ephemeral(true)
+                    .content("mai-updown session started."),
+                Err(err) => build_mai_updown_start_error_reply(&err),
+            }
+        }
+        Err(err) => CreateReply::default()
+            .ephemeral(true)
+            .embed(embed_base("Invalid internal level").description(err.to_string())),
+    };
+
+    ctx.send(reply).await?;
+
+    send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+
+    Ok(())
+}
+
+/// Patch one chart's internal level in the bot's and your collector's song catalog (developer only)
+#[poise::command(slash_command, rename = "mai-dev-internal-level")]
+pub(crate) async fn mai_dev_internal_level(
+    ctx: Context<'_>,
+    #[description = "Exact song title"] title: String,
+    #[description = "Chart type (STD or DX)"] chart_type: String,
+    #[description = "Difficulty (BASIC, ADVANCED, EXPERT, MASTER, Re:MASTER)"] difficulty: String,
+    #[description = "Internal level (for example 13.7)"] internal_level: f64,
+    #[description = "Exact artist, when several songs share the title"] artist: Option<String>,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    if ctx.author().id != ctx.data().dev_user_id {
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content("This command is restricted to the bot developer."),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let title = title.trim();
+    let artist = artist.as_deref().map(str::trim);
+    let parsed = chart_type
+        .parse::<ChartType>()
+        .map_err(|_| format!("Unknown chart type '{chart_type}'."))
+        .and_then(|chart_type| {
+            difficulty
+                .parse::<DifficultyCategory>()
+                .map(|diff_category| (chart_type, diff_category))
+                .map_err(|_| format!("Unknown difficulty '{difficulty}'."))
+        });
+    let (chart_type, diff_category) = match parsed {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            ctx.send(
+                CreateReply::default()
+                    .ephemeral(true)
+                    .embed(embed_base("Invalid chart").description(message)),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let songs = ctx
+        .data()
+        .song_database_client
+        .list_song_catalog()
+        .await
+        .wrap_err("load song catalog")?;
+    let candidates = songs
+        .into_iter()
+        .filter(|song| song.title == title && artist.is_none_or(|artist| song.artist == artist))
+        .collect::<Vec<_>>();
+    let song = match candidates.as_slice() {
+        [song] => song,
+        [] => {
+            ctx.send(CreateReply::default().ephemeral(true).embed(
+                embed_base("Song not found").description(format!(
+                        "No song titled '{title}'{} in the song database.",
+                        artist.map(|artist| format!(" by '{artist}'")).unwrap_or_default()
+                    )),
+            ))
+            .await?;
+            return Ok(());
+        }
+        _ => {
+            ctx.send(
+                CreateReply::default()
+                    .ephemeral(true)
+                    .embed(build_duplicate_song_candidates_embed(&candidates)),
+            )
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let patch = ChartInternalLevelPatch {
+        title: song.title.clone(),
+        genre: song.genre.clone(),
+        artist: song.artist.clone(),
+        chart_type,
+        diff_category,
+        internal_level: internal_level as f32,
+    };
+    let reply =
+        match ctx
+            .data()
+            .song_database_client
+            .upsert_chart_internal_level(&patch)
+            .await
+        {
+            Ok(true) => {
+                let collector_status = patch_record_collector_internal_level(ctx, &patch).await?;
+                CreateReply::default().ephemeral(true).embed(
+                    embed_base("Internal level patched").description(format!(
+                        "{title} / {} [{chart_type} {diff_category}] → {internal_level:.1}\n\
+                    {collector_status}\n\
+                    Applies until restart; fix the song database for a permanent change.",
+                        patch.artist
+                    )),
+                )
+            }
+            Ok(false) => CreateReply::default().ephemeral(true).embed(
+                embed_base("Patch failed").description(format!(
+                    "{title} has no {chart_type} {diff_category} chart."
+                )),
+            ),
+            Err(err) => CreateReply::default()
+                .ephemeral(true)
+                .embed(embed_base("Patch failed").description(format!("{err:#}"))),
+        };
 
-fn previous_new_record_achievements_by_played_at(
-    plays: &[models::PlayRecordApiResponse],
-    playlog_history: &[models::PlayRecordApiResponse],
-) -> HashMap<i64, i64> {
-    plays
-        .iter()
-        .filter(|play| play.achievement_new_record.unwrap_or(0) != 0)
-        .filter_map(|play| {
-            previous_best_achievement_x10000(play, playlog_history)
-                .map(|previous| (play.played_at_unixtime, previous))
-        })
-        .collect()
-}
+    ctx.send(reply).await?;
 
-fn previous_best_achievement_x10000(
-    play: &models::PlayRecordApiResponse,
-    playlog_history: &[models::PlayRecordApiResponse],
-) -> Option<i64> {
-    playlog_history
-        .iter()
-        .filter(|candidate| {
-            candidate.played_at_unixtime < play.played_at_unixtime
-                && same_playlog_chart_identity(candidate, play)
-        })
-        .filter_map(|candidate| candidate.achievement_x10000)
-        .max()
+    Ok(())
 }
 
-fn same_playlog_chart_identity(
-    left: &models::PlayRecordApiResponse,
-    right: &models::PlayRecordApiResponse,
-) -> bool {
-    left.title == right.title
-        && left.genre == right.genre
-        && left.artist == right.artist
-        && left.chart_type == right.chart_type
-        && left.diff_category == right.diff_category
+/// Forward an internal level patch to the caller's registered record collector, which owns
+/// the stored ratings behind `/mai-rating`. Returns a one-line status for the reply.
+async fn patch_record_collector_internal_level(
+    ctx: Context<'_>,
+    patch: &ChartInternalLevelPatch,
+) -> Result<String, Error> {
+    let Some(registration) = db::get_registration(&ctx.data().db_pool, ctx.author().id)
+        .await
+        .wrap_err("load user registration")?
+    else {
+        return Ok(
+            "No record collector registered; only the bot's catalog was patched.".to_string(),
+        );
+    };
+
+    let result = match RecordCollectorClient::new(registration.record_collector_server_url) {
+        Ok(client) => client.put_chart_internal_level(patch).await,
+        Err(err) => Err(err),
+    };
+    Ok(match result {
+        Ok(()) => "Record collector patched; stored ratings were recomputed.".to_string(),
+        Err(err) => format!("Record collector patch failed: {err:#}"),
+    })
 }
 
-/// Start a mai-updown random session in a thread
-#[poise::command(slash_command, rename = "mai-updown", guild_only)]
-pub(crate) async fn mai_updown(
+/// Dump everything known about one chart's rating (developer only)
+#[poise::command(slash_command, rename = "mai-debug-chart")]
+pub(crate) async fn mai_debug_chart(
     ctx: Context<'_>,
-    #[description = "Starting internal level (for example 13.0)"] internal_level: f64,
+    #[description = "Song title or alias"] title: String,
+    #[description = "Chart type (STD or DX)"] chart_type: String,
+    #[description = "Difficulty (BASIC, ADVANCED, EXPERT, MASTER, Re:MASTER)"] difficulty: String,
 ) -> Result<(), Error> {
     ctx.defer_ephemeral().await?;
 
+    if ctx.author().id != ctx.data().dev_user_id {
+        ctx.send(
+            CreateReply::default()
+                .ephemeral(true)
+                .content("This command is restricted to the bot developer."),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let (Ok(chart_type), Ok(diff_category)) = (
+        chart_type.parse::<ChartType>(),
+        difficulty.parse::<DifficultyCategory>(),
+    ) else {
+        ctx.send(CreateReply::default().ephemeral(true).embed(
+            embed_base("Invalid chart").description(format!(
+                "Unknown chart type '{chart_type}' or difficulty '{difficulty}'."
+            )),
+        ))
+        .await?;
+        return Ok(());
+    };
+
     let Some(collector_context) = registered_record_collector_client(ctx).await? else {
         return Ok(());
     };
     let record_collector_client = collector_context.client;
     let pending_warning = collector_context.pending_warning;
 
-    let reply = match updown::parse_level_tenths(internal_level) {
-        Ok(start_level_tenths) => {
-            match updown::start_session(ctx, record_collector_client, start_level_tenths).await {
-                Ok(()) => CreateReply::default()
-                    .ephemeral(true)
-                    .content("mai-updown session started."),
-                Err(err) => build_mai_updown_start_error_reply(&err),
-            }
-        }
-        Err(err) => CreateReply::default()
-            .ephemeral(true)
-            .embed(embed_base("Invalid internal level").description(err.to_string())),
+    let matched_songs = search_song_catalog(&ctx.data().song_database_client, title.trim())
+        .await
+        .wrap_err("search song catalog")?;
+    if matched_songs.len() != 1 {
+        let embed = if matched_songs.is_empty() {
+            embed_base("No song found").description("No matching title or alias.")
+        } else {
+            build_duplicate_song_candidates_embed(&matched_songs)
+        };
+        ctx.send(CreateReply::default().ephemeral(true).embed(embed))
+            .await?;
+        send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+        return Ok(());
+    }
+    let song = matched_songs.into_iter().next().expect("checked len");
+    let Some(sheet) = song
+        .sheets
+        .iter()
+        .find(|sheet| sheet.chart_type == chart_type && sheet.diff_category == diff_category)
+    else {
+        ctx.send(CreateReply::default().ephemeral(true).embed(
+            embed_base("No such chart").description(format!(
+                "{} has no {chart_type} {diff_category} chart.",
+                song.title
+            )),
+        ))
+        .await?;
+        send_pending_record_collector_update_warning(ctx, pending_warning).await?;
+        return Ok(());
     };
 
-    ctx.send(reply).await?;
+    let rated_scores = record_collector_client
+        .get_all_rated_scores()
+        .await
+        .wrap_err("fetch rated scores")?;
+    let score = rated_scores.iter().find(|score| {
+        score.title == song.title
+            && score.artist == song.artist
+            && score.chart_type == chart_type
+            && score.diff_category == diff_category
+    });
+    let targets = record_collector_client
+        .get_rating_targets()
+        .await
+        .wrap_err("fetch rating targets")?;
+
+    let mut embed = rating_audit::build_chart_debug_embed(&song, sheet, score, &targets);
+    if let Some(image_name) = song.image_name.as_deref() {
+        embed = embed.thumbnail(ctx.data().song_database_client.cover_url(image_name));
+    }
 
+    ctx.send(CreateReply::default().ephemeral(true).embed(embed))
+        .await?;
     send_pending_record_collector_update_warning(ctx, pending_warning).await?;
 
     Ok(())
@@ -1076,6 +2193,28 @@ async fn send_registration_validation_error(
     Ok(())
 }
 
+/// Framework-wide check: reject a command still on its configured cooldown for this user.
+pub(crate) async fn check_command_cooldown(ctx: Context<'_>) -> Result<bool, Error> {
+    let command = &ctx.command().qualified_name;
+    let Err(remaining) =
+        ctx.data()
+            .command_cooldowns
+            .try_use(command, ctx.author().id, std::time::Instant::now())
+    else {
+        return Ok(true);
+    };
+    ctx.send(
+        CreateReply::default()
+            .ephemeral(true)
+            .embed(embed_base("On cooldown").description(format!(
+                "`/{command}` is on cooldown, try again in {}s.",
+                remaining.as_secs_f64().ceil() as u64
+            ))),
+    )
+    .await?;
+    Ok(false)
+}
+
 async fn registered_record_collector_client(
     ctx: Context<'_>,
 ) -> Result<Option<RegisteredRecordCollectorContext>, Error> {
@@ -1280,9 +2419,12 @@ async fn send_no_records_found_reply(
     song: &SongCatalogSong,
 ) -> Result<(), Error> {
     let mut info_song = song.clone();
-    info_song
-        .sheets
-        .sort_by_key(|sheet| (sheet.chart_type.as_u8(), sheet.diff_category.as_u8()));
+    info_song.sheets.sort_by(|a, b| {
+        cmp_chart_order(
+            (a.chart_type, a.diff_category),
+            (b.chart_type, b.diff_category),
+        )
+    });
     let mut info_embed = build_song_info_embed(&info_song);
     if let Some(image_name) = info_song.image_name.as_deref() {
         info_embed = info_embed.thumbnail(ctx.data().song_database_client.cover_url(image_name));
@@ -1299,6 +2441,29 @@ async fn send_no_records_found_reply(
     Ok(())
 }
 
+/// Greyed lines for chart types whose song data has no Re:MASTER at all, so a missing row
+/// reads as "doesn't exist" rather than "not played yet".
+fn missing_remaster_hints(song: &SongCatalogSong) -> Vec<String> {
+    let mut chart_types = song
+        .sheets
+        .iter()
+        .map(|sheet| sheet.chart_type)
+        .collect::<Vec<_>>();
+    chart_types.sort_by_key(|chart_type| chart_type.as_u8());
+    chart_types.dedup();
+
+    chart_types
+        .into_iter()
+        .filter(|chart_type| {
+            !song.sheets.iter().any(|sheet| {
+                sheet.chart_type == *chart_type
+                    && sheet.diff_category == DifficultyCategory::ReMaster
+            })
+        })
+        .map(|chart_type| format!("-# [{chart_type}] Re:MASTER — not available"))
+        .collect()
+}
+
 async fn search_song_catalog(
     song_database_client: &SongDatabaseClient,
     query: &str,
@@ -1341,24 +2506,20 @@ fn format_song_candidate_details(genre: &str, artist: &str, aliases: &SongAliase
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 enum SongSearchMatchKind {
     Exact,
-    CaseInsensitiveExact,
-    WhitespaceInsensitiveExact,
+    /// Equal after [`search_key`].
+    NormalizedExact,
     Contains,
 }
 
 fn find_song_candidates(mut songs: Vec<SongCatalogSong>, query: &str) -> Vec<SongCatalogSong> {
     let mut exact_matches = Vec::new();
-    let mut case_insensitive_matches = Vec::new();
-    let mut whitespace_insensitive_matches = Vec::new();
+    let mut normalized_matches = Vec::new();
     let mut contains_matches = Vec::new();
 
     for song in songs.drain(..) {
         match song_match_kind(&song, query) {
             Some(SongSearchMatchKind::Exact) => exact_matches.push(song),
-            Some(SongSearchMatchKind::CaseInsensitiveExact) => case_insensitive_matches.push(song),
-            Some(SongSearchMatchKind::WhitespaceInsensitiveExact) => {
-                whitespace_insensitive_matches.push(song);
-            }
+            Some(SongSearchMatchKind::NormalizedExact) => normalized_matches.push(song),
             Some(SongSearchMatchKind::Contains) => contains_matches.push(song),
             None => {}
         }
@@ -1366,8 +2527,7 @@ fn find_song_candidates(mut songs: Vec<SongCatalogSong>, query: &str) -> Vec<Son
 
     for matches in [
         &mut exact_matches,
-        &mut case_insensitive_matches,
-        &mut whitespace_insensitive_matches,
+        &mut normalized_matches,
         &mut contains_matches,
     ] {
         if matches.is_empty() {
@@ -1376,12 +2536,112 @@ fn find_song_candidates(mut songs: Vec<SongCatalogSong>, query: &str) -> Vec<Son
 
         matches
             .sort_by(|a, b| (&a.title, &a.genre, &a.artist).cmp(&(&b.title, &b.genre, &b.artist)));
-        return std::mem::take(matches);
+        return dedupe_song_candidates(std::mem::take(matches));
     }
 
     Vec::new()
 }
 
+/// Collapse candidates whose title/artist only differ by whitespace or full-width ASCII
+/// (equal [`normalize_title`]), merging their sheets into one entry. The display title prefers the
+/// variant that is already in normalized (half-width, trimmed) form.
+fn dedupe_song_candidates(candidates: Vec<SongCatalogSong>) -> Vec<SongCatalogSong> {
+    let mut deduped: Vec<(String, String, SongCatalogSong)> = Vec::new();
+
+    for mut candidate in candidates {
+        let title_key = normalize_title(&candidate.title);
+        let artist_key = normalize_title(&candidate.artist);
+        let Some((_, _, existing)) = deduped
+            .iter_mut()
+            .find(|(title, artist, _)| *title == title_key && *artist == artist_key)
+        else {
+            deduped.push((title_key, artist_key, candidate));
+            continue;
+        };
+
+        if is_folded_title(&candidate.title) && !is_folded_title(&existing.title) {
+            std::mem::swap(existing, &mut candidate);
+        }
+        for sheet in candidate.sheets {
+            if !existing.sheets.iter().any(|kept| {
+                kept.chart_type == sheet.chart_type && kept.diff_category == sheet.diff_category
+            }) {
+                existing.sheets.push(sheet);
+            }
+        }
+        if existing.image_name.is_none() {
+            existing.image_name = candidate.image_name;
+        }
+    }
+
+    deduped.into_iter().map(|(_, _, song)| song).collect()
+}
+
+/// `(title, genre, artist)` of every catalog entry [`dedupe_song_candidates`] folds into
+/// `song`, which is itself one of them.
+fn song_variant_keys(
+    catalog: &[SongCatalogSong],
+    song: &SongCatalogSong,
+) -> Vec<(String, String, String)> {
+    let title_key = normalize_title(&song.title);
+    let artist_key = normalize_title(&song.artist);
+    let mut keys = vec![(song.title.clone(), song.genre.clone(), song.artist.clone())];
+    for variant in catalog {
+        let key = (
+            variant.title.clone(),
+            variant.genre.clone(),
+            variant.artist.clone(),
+        );
+        if normalize_title(&variant.title) == title_key
+            && normalize_title(&variant.artist) == artist_key
+            && !keys.contains(&key)
+        {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
+/// Scores of every folded variant together. A variant without records (`NOT_FOUND`) is
+/// skipped; the error is only returned when no variant has any, or for other failures.
+fn merge_variant_scores(
+    results: Vec<eyre::Result<Vec<SongDetailScoreApiResponse>>>,
+) -> eyre::Result<Vec<SongDetailScoreApiResponse>> {
+    let mut merged = None::<Vec<SongDetailScoreApiResponse>>;
+    let mut not_found = None;
+    for result in results {
+        match result {
+            Ok(scores) => merged.get_or_insert_with(Vec::new).extend(scores),
+            Err(err)
+                if err
+                    .downcast_ref::<ApiError>()
+                    .is_some_and(|api_error| api_error.code() == "NOT_FOUND") =>
+            {
+                not_found.get_or_insert(err);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    match (merged, not_found) {
+        (Some(scores), _) => Ok(scores),
+        (None, Some(err)) => Err(err),
+        (None, None) => Ok(Vec::new()),
+    }
+}
+
+/// Already in display form: trimmed, single-spaced, and free of full-width ASCII.
+fn is_folded_title(title: &str) -> bool {
+    !title
+        .chars()
+        .any(|ch| matches!(ch, '\u{3000}' | '\u{FF01}'..='\u{FF5E}'))
+        && title.split_whitespace().collect::<Vec<_>>().join(" ") == title
+}
+
+/// Case-insensitive [`normalize_title`] for matching user input.
+fn search_key(value: &str) -> String {
+    normalize_title(value).to_lowercase()
+}
+
 fn song_match_kind(song: &SongCatalogSong, query: &str) -> Option<SongSearchMatchKind> {
     let trimmed_query = query.trim();
     if trimmed_query.is_empty() {
@@ -1396,31 +2656,22 @@ fn song_match_kind(song: &SongCatalogSong, query: &str) -> Option<SongSearchMatc
         return Some(SongSearchMatchKind::Exact);
     }
 
-    let normalized_query = normalize_search_value(trimmed_query);
-    if search_values
-        .iter()
-        .any(|value| normalize_search_value(value) == normalized_query)
-    {
-        return Some(SongSearchMatchKind::CaseInsensitiveExact);
-    }
-
-    let collapsed_query = collapse_search_value(trimmed_query);
+    let query_key = search_key(trimmed_query);
     if search_values
         .iter()
-        .any(|value| collapse_search_value(value) == collapsed_query)
+        .any(|value| search_key(value) == query_key)
     {
-        return Some(SongSearchMatchKind::WhitespaceInsensitiveExact);
+        return Some(SongSearchMatchKind::NormalizedExact);
     }
 
-    if normalized_query.len() < 2 && collapsed_query.len() < 2 {
+    if query_key.chars().count() < 2 {
         return None;
     }
 
-    if search_values.iter().any(|value| {
-        let normalized_value = normalize_search_value(value);
-        let collapsed_value = collapse_search_value(value);
-        normalized_value.contains(&normalized_query) || collapsed_value.contains(&collapsed_query)
-    }) {
+    if search_values
+        .iter()
+        .any(|value| search_key(value).contains(&query_key))
+    {
         return Some(SongSearchMatchKind::Contains);
     }
 
@@ -1434,18 +2685,6 @@ fn song_search_values(song: &SongCatalogSong) -> Vec<&str> {
         .collect()
 }
 
-fn normalize_search_value(value: &str) -> String {
-    value.trim().to_lowercase()
-}
-
-fn collapse_search_value(value: &str) -> String {
-    value
-        .chars()
-        .filter(|ch| !ch.is_whitespace())
-        .flat_map(|ch| ch.to_lowercase())
-        .collect()
-}
-
 fn format_song_alias_summary(aliases: &SongAliases) -> Option<String> {
     let mut values = Vec::new();
     for alias in aliases.en.iter().chain(aliases.ko.iter()) {
@@ -1483,91 +2722,19 @@ fn build_region_unreleased_line(sheets: &[SongCatalogSheet]) -> Option<String> {
     }
 }
 
-fn is_ap_like(fc: Option<&models::FcStatus>) -> bool {
-    matches!(
-        fc,
-        Some(&models::FcStatus::Ap) | Some(&models::FcStatus::ApPlus)
-    )
-}
-
-fn coefficient_for_achievement(achievement_percent: f64) -> f64 {
-    const ACHIEVEMENT_CAP: f64 = 100.5;
-    let a = achievement_percent.min(ACHIEVEMENT_CAP);
-
-    if a >= 100.5 {
-        22.4
-    } else if a >= 100.4999 {
-        22.2
-    } else if a >= 100.0 {
-        21.6
-    } else if a >= 99.9999 {
-        21.4
-    } else if a >= 99.5 {
-        21.1
-    } else if a >= 99.0 {
-        20.8
-    } else if a >= 98.9999 {
-        20.6
-    } else if a >= 98.0 {
-        20.3
-    } else if a >= 97.0 {
-        20.0
-    } else if a >= 96.9999 {
-        17.6
-    } else if a >= 94.0 {
-        16.8
-    } else if a >= 90.0 {
-        15.2
-    } else if a >= 80.0 {
-        13.6
-    } else if a >= 79.9999 {
-        12.8
-    } else if a >= 75.0 {
-        12.0
-    } else if a >= 70.0 {
-        11.2
-    } else if a >= 60.0 {
-        9.6
-    } else if a >= 50.0 {
-        8.0
-    } else if a >= 40.0 {
-        6.4
-    } else if a >= 30.0 {
-        4.8
-    } else if a >= 20.0 {
-        3.2
-    } else if a >= 10.0 {
-        1.6
-    } else {
-        0.0
-    }
-}
-
-fn chart_rating_points(internal_level: f64, achievement_percent: f64, ap_bonus: bool) -> u32 {
-    const ACHIEVEMENT_CAP: f64 = 100.5;
-    let coef = coefficient_for_achievement(achievement_percent);
-    let ach = achievement_percent.min(ACHIEVEMENT_CAP);
-    let base = ((coef * internal_level * ach) / 100.0).floor();
-    let base = if base.is_finite() && base > 0.0 {
-        base as u32
-    } else {
-        0
-    };
-    if ap_bonus {
-        base.saturating_add(1)
-    } else {
-        base
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::{
-        find_song_candidates, format_song_alias_summary, format_song_candidate_details,
-        latest_credit_len, previous_new_record_achievements_by_played_at,
+        credit_ranges, dedup_recent_plays, find_song_candidates, format_song_alias_summary,
+        format_song_candidate_details, keep_new_records, latest_credit_len, merge_variant_scores,
+        missing_remaster_hints, previous_new_record_achievements_by_played_at, song_variant_keys,
+        song_version_lines,
+    };
+    use maimai_client::{SongCatalogSheet, SongCatalogSong};
+    use models::{
+        ChartType, DifficultyCategory, PlayRecordApiResponse, SongAliases, SongChartRegion,
+        SongDetailScoreApiResponse,
     };
-    use maimai_client::SongCatalogSong;
-    use models::{ChartType, DifficultyCategory, PlayRecordApiResponse, SongAliases};
 
     #[test]
     fn latest_credit_len_uses_first_track_one_boundary() {
@@ -1575,12 +2742,68 @@ mod tests {
         assert_eq!(latest_credit_len(&[Some(2), Some(1), Some(4)]), 2);
     }
 
+    #[test]
+    fn credit_ranges_walk_the_window_credit_by_credit() {
+        let tracks = [
+            Some(2),
+            Some(1),
+            Some(4),
+            Some(3),
+            Some(2),
+            Some(1),
+            Some(3),
+            Some(2),
+        ];
+        assert_eq!(credit_ranges(&tracks), vec![0..2, 2..6, 6..8]);
+        assert_eq!(credit_ranges(&[]), Vec::<std::ops::Range<usize>>::new());
+    }
+
+    #[test]
+    fn dedup_recent_plays_shows_a_repeated_row_once() {
+        let mut track_two = test_playlog(200, "Song B", Some("maimai"), Some(1_000_000), false);
+        track_two.track = Some(2);
+        let mut track_one = test_playlog(100, "Song A", Some("maimai"), Some(990_000), true);
+        track_one.track = Some(1);
+        let records = vec![track_two, track_one.clone(), track_one];
+
+        let deduped = dedup_recent_plays(records);
+        let tracks = deduped
+            .iter()
+            .map(|r| r.track.map(i64::from))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            deduped.iter().map(|r| r.title.as_str()).collect::<Vec<_>>(),
+            vec!["Song B", "Song A"]
+        );
+        assert_eq!(credit_ranges(&tracks), vec![0..2]);
+    }
+
     #[test]
     fn latest_credit_len_falls_back_to_four_when_track_one_is_missing() {
         assert_eq!(latest_credit_len(&[Some(4), Some(3), Some(2), Some(5)]), 4);
         assert_eq!(latest_credit_len(&[Some(4), Some(3)]), 2);
     }
 
+    #[test]
+    fn keep_new_records_drops_tracks_without_the_flag() {
+        let mut unflagged = test_playlog(4, "Unknown", None, Some(990_000), false);
+        unflagged.achievement_new_record = None;
+        let credit = vec![
+            test_playlog(1, "First", None, Some(1_005_000), true),
+            test_playlog(2, "Second", None, Some(980_000), false),
+            test_playlog(3, "Third", None, Some(1_000_500), true),
+            unflagged,
+        ];
+
+        let titles = keep_new_records(credit.clone())
+            .into_iter()
+            .map(|record| record.title)
+            .collect::<Vec<_>>();
+        assert_eq!(titles, vec!["First", "Third"]);
+        assert!(keep_new_records(vec![credit[1].clone()]).is_empty());
+    }
+
     #[test]
     fn format_song_alias_summary_deduplicates_and_limits_values() {
         let aliases = SongAliases {
@@ -1650,6 +2873,92 @@ mod tests {
         assert_eq!(matches[1].title, "Beta Song");
     }
 
+    #[test]
+    fn find_song_candidates_collapses_near_duplicate_titles() {
+        let mut padded = test_song("Night of Nights ", "first");
+        padded.sheets = vec![test_sheet(ChartType::Std)];
+        let mut full_width = test_song("Ｎｉｇｈｔ　of Nights", "nights alias");
+        full_width.sheets = vec![test_sheet(ChartType::Dx)];
+        let canonical = test_song("Night of Nights", "third");
+
+        let matches = find_song_candidates(vec![padded, full_width, canonical], "night");
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].title, "Night of Nights");
+        let mut chart_types = matches[0]
+            .sheets
+            .iter()
+            .map(|sheet| sheet.chart_type)
+            .collect::<Vec<_>>();
+        chart_types.sort();
+        assert_eq!(chart_types, vec![ChartType::Std, ChartType::Dx]);
+    }
+
+    #[test]
+    fn mai_score_keeps_the_scores_of_every_folded_variant() {
+        let mut padded = test_song("Night of Nights ", "first");
+        padded.sheets = vec![test_sheet(ChartType::Std)];
+        let mut canonical = test_song("Night of Nights", "second");
+        canonical.sheets = vec![test_sheet(ChartType::Dx)];
+        let catalog = vec![padded.clone(), canonical.clone(), test_song("Other", "x")];
+
+        let merged = find_song_candidates(catalog.clone(), "night");
+        assert_eq!(merged.len(), 1);
+        let keys = song_variant_keys(&catalog, &merged[0]);
+        assert_eq!(keys.len(), 2);
+
+        let score = |title: &str, chart_type: ChartType| SongDetailScoreApiResponse {
+            title: title.to_string(),
+            genre: canonical.genre.clone(),
+            artist: canonical.artist.clone(),
+            chart_type,
+            diff_category: DifficultyCategory::Master,
+            achievement_x10000: Some(1_000_000),
+            rank: None,
+            fc: None,
+            sync: None,
+            dx_score: None,
+            dx_score_max: None,
+            last_played_at: None,
+            play_count: None,
+        };
+        let results = keys
+            .iter()
+            .map(|(title, _, _)| {
+                let chart_type = if title.ends_with(' ') {
+                    ChartType::Std
+                } else {
+                    ChartType::Dx
+                };
+                Ok(vec![score(title, chart_type)])
+            })
+            .collect();
+
+        let scores = merge_variant_scores(results).expect("merged scores");
+        let mut chart_types = scores
+            .iter()
+            .map(|score| score.chart_type)
+            .collect::<Vec<_>>();
+        chart_types.sort();
+        assert_eq!(chart_types, vec![ChartType::Std, ChartType::Dx]);
+    }
+
+    #[test]
+    fn find_song_candidates_folds_full_width_queries() {
+        // Candidate dedupe already folded full-width text, but the query itself was only
+        // lowercased, so this typed title found nothing.
+        let matches = find_song_candidates(
+            vec![
+                test_song("Night of Nights", "alias"),
+                test_song("Nightwalker", "night"),
+            ],
+            "Ｎｉｇｈｔ　ｏｆ　Ｎｉｇｈｔｓ",
+        );
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].title, "Night of Nights");
+    }
+
     #[test]
     fn find_song_candidates_matches_alias_case_insensitively() {
         let matches = find_song_candidates(vec![test_song("Real Title", "My Alias")], "my alias");
@@ -1688,6 +2997,46 @@ mod tests {
         assert!(result.is_empty());
     }
 
+    #[test]
+    fn missing_remaster_hints_only_flag_chart_types_without_remaster() {
+        let mut song = test_song("Song", "alias");
+        let mut std_remaster = test_sheet(ChartType::Std);
+        std_remaster.diff_category = DifficultyCategory::ReMaster;
+        song.sheets = vec![
+            test_sheet(ChartType::Dx),
+            test_sheet(ChartType::Std),
+            std_remaster,
+        ];
+
+        assert_eq!(
+            missing_remaster_hints(&song),
+            vec!["-# [DX] Re:MASTER — not available".to_string()]
+        );
+
+        song.sheets.clear();
+        assert!(missing_remaster_hints(&song).is_empty());
+    }
+
+    #[test]
+    fn song_version_lines_lists_std_before_dx() {
+        let mut song = test_song("Link", "link");
+        assert!(song_version_lines(&song).is_empty());
+
+        let mut dx = test_sheet(ChartType::Dx);
+        dx.version = Some("maimaiでらっくす".to_string());
+        let mut std = test_sheet(ChartType::Std);
+        std.version = Some("maimai PLUS".to_string());
+        song.sheets = vec![dx, std];
+
+        assert_eq!(
+            song_version_lines(&song),
+            vec![
+                "Version (STD): maimai PLUS".to_string(),
+                "Version (DX): maimaiでらっくす".to_string(),
+            ]
+        );
+    }
+
     fn test_song(title: &str, alias: &str) -> SongCatalogSong {
         SongCatalogSong {
             title: title.to_string(),
@@ -1702,6 +3051,20 @@ mod tests {
         }
     }
 
+    fn test_sheet(chart_type: ChartType) -> SongCatalogSheet {
+        SongCatalogSheet {
+            chart_type,
+            diff_category: DifficultyCategory::Master,
+            level: "13".to_string(),
+            version: None,
+            internal_level: None,
+            region: SongChartRegion {
+                jp: true,
+                intl: true,
+            },
+        }
+    }
+
     fn test_playlog(
         played_at_unixtime: i64,
         title: &str,
@@ -7,6 +7,19 @@ pub struct DiscordConfig {
     pub song_database_url: String,
     pub database_url: String,
     pub data_dir: String,
+    pub warm_cover_cache: bool,
+    pub include_estimated_internal_levels: bool,
+    /// Offset from UTC used when showing played-at times; SEGA reports JST (+540).
+    pub display_timezone_offset_minutes: i32,
+    /// JST time of the daily recap DM; `None` unless `DAILY_RECAP_DM` is enabled.
+    pub daily_recap_time: Option<time::Time>,
+    /// Idle time after which a session recap DM goes out; `None` unless `SESSION_RECAP_DM`
+    /// is enabled.
+    pub session_recap_idle: Option<time::Duration>,
+    /// Per-command cooldowns: polling commands by default, adjusted by `COMMAND_COOLDOWNS`.
+    pub command_cooldowns: std::collections::HashMap<String, std::time::Duration>,
+    /// Days whose best play is below this achievement (percent) get no recap DM.
+    pub dm_min_achievement: f64,
 }
 
 impl DiscordConfig {
@@ -21,12 +34,81 @@ impl DiscordConfig {
         let database_url = std::env::var("DISCORD_BOT_DATABASE_URL")
             .unwrap_or_else(|_| format!("sqlite:{data_dir}/maistats-discord-bot.sqlite3"));
 
+        let warm_cover_cache = env_flag("WARM_COVER_CACHE");
+        let include_estimated_internal_levels = env_flag("INCLUDE_ESTIMATED_INTERNAL_LEVELS");
+        let display_timezone_offset_minutes = std::env::var("DISPLAY_TIMEZONE_OFFSET_MINUTES")
+            .unwrap_or_else(|_| "540".to_string())
+            .trim()
+            .parse::<i32>()
+            .wrap_err("DISPLAY_TIMEZONE_OFFSET_MINUTES must be a valid i32")?;
+        eyre::ensure!(
+            (-720..=840).contains(&display_timezone_offset_minutes),
+            "DISPLAY_TIMEZONE_OFFSET_MINUTES must be between -720 and 840"
+        );
+
+        let daily_recap_time = if env_flag("DAILY_RECAP_DM") {
+            let raw = std::env::var("DAILY_RECAP_TIME").unwrap_or_else(|_| "23:59".to_string());
+            Some(
+                parse_hh_mm(&raw)
+                    .ok_or_else(|| eyre::eyre!("DAILY_RECAP_TIME must be HH:MM, got '{raw}'"))?,
+            )
+        } else {
+            None
+        };
+
+        let session_recap_idle = if env_flag("SESSION_RECAP_DM") {
+            let minutes = std::env::var("SESSION_IDLE_MINUTES")
+                .unwrap_or_else(|_| "30".to_string())
+                .trim()
+                .parse::<u32>()
+                .wrap_err("SESSION_IDLE_MINUTES must be a valid u32")?;
+            eyre::ensure!(minutes > 0, "SESSION_IDLE_MINUTES must be at least 1");
+            Some(time::Duration::minutes(i64::from(minutes)))
+        } else {
+            None
+        };
+
+        let command_cooldowns = crate::cooldown::parse_command_cooldowns(
+            &std::env::var("COMMAND_COOLDOWNS").unwrap_or_default(),
+        )?;
+
+        let dm_min_achievement = std::env::var("DM_MIN_ACHIEVEMENT")
+            .unwrap_or_else(|_| "0".to_string())
+            .trim()
+            .parse::<f64>()
+            .wrap_err("DM_MIN_ACHIEVEMENT must be a number")?;
+        eyre::ensure!(
+            (0.0..=101.0).contains(&dm_min_achievement),
+            "DM_MIN_ACHIEVEMENT must be between 0 and 101"
+        );
+
         Ok(Self {
             bot_token,
             dev_user_id,
             song_database_url,
             database_url,
             data_dir,
+            warm_cover_cache,
+            include_estimated_internal_levels,
+            display_timezone_offset_minutes,
+            daily_recap_time,
+            session_recap_idle,
+            command_cooldowns,
+            dm_min_achievement,
         })
     }
 }
+
+fn env_flag(name: &str) -> bool {
+    std::env::var(name).ok().is_some_and(|value| {
+        matches!(
+            value.trim().to_ascii_lowercase().as_str(),
+            "1" | "true" | "yes" | "on"
+        )
+    })
+}
+
+fn parse_hh_mm(value: &str) -> Option<time::Time> {
+    let (hour, minute) = value.trim().split_once(':')?;
+    time::Time::from_hms(hour.parse().ok()?, minute.parse().ok()?, 0).ok()
+}
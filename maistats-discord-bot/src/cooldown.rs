@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use poise::serenity_prelude as serenity;
+
+/// Commands that ask the record collector to poll before answering. Every poll fetches at
+/// least the player data page from maimai DX NET, so these get a cooldown unless
+/// `COMMAND_COOLDOWNS` overrides it.
+const POLLING_COMMANDS: [&str; 17] = [
+    "mai-score",
+    "mai-recent",
+    "mai-today",
+    "mai-rating-audit",
+    "mai-rating",
+    "mai-suggest",
+    "mai-compare-version",
+    "mai-plates",
+    "mai-rating-target-chart",
+    "mai-profile",
+    "mai-set-target",
+    "mai-sync-status",
+    "mai-compare",
+    "mai-rating-history",
+    "mai-updown",
+    "mai-debug-chart",
+    "mai-plot",
+];
+
+const DEFAULT_POLLING_COOLDOWN: Duration = Duration::from_secs(5);
+
+/// Per-user, per-command cooldowns.
+#[derive(Debug, Default)]
+pub(crate) struct CommandCooldowns {
+    durations: HashMap<String, Duration>,
+    last_used: Mutex<HashMap<(String, serenity::UserId), Instant>>,
+}
+
+impl CommandCooldowns {
+    pub(crate) fn new(durations: HashMap<String, Duration>) -> Self {
+        Self {
+            durations,
+            last_used: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Records the invocation and returns `Ok` when `command` is off cooldown for `user_id`,
+    /// otherwise the time left. Rejected invocations do not restart the window.
+    pub(crate) fn try_use(
+        &self,
+        command: &str,
+        user_id: serenity::UserId,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let Some(duration) = self.durations.get(command).copied() else {
+            return Ok(());
+        };
+        let mut last_used = self.last_used.lock().expect("cooldown lock poisoned");
+        // Drop windows that have run out so the map only holds users still on cooldown.
+        last_used.retain(|(command, _), previous| {
+            self.durations
+                .get(command)
+                .is_some_and(|duration| now.saturating_duration_since(*previous) < *duration)
+        });
+        let key = (command.to_string(), user_id);
+        if let Some(previous) = last_used.get(&key) {
+            let elapsed = now.saturating_duration_since(*previous);
+            if elapsed < duration {
+                return Err(duration - elapsed);
+            }
+        }
+        last_used.insert(key, now);
+        Ok(())
+    }
+}
+
+/// `mai-recent=10,mai-today=30` (seconds) into per-command durations, on top of the
+/// polling command defaults. `=0` turns a command's cooldown off.
+pub(crate) fn parse_command_cooldowns(raw: &str) -> eyre::Result<HashMap<String, Duration>> {
+    let mut durations: HashMap<String, Duration> = POLLING_COMMANDS
+        .iter()
+        .map(|command| (command.to_string(), DEFAULT_POLLING_COOLDOWN))
+        .collect();
+    for entry in raw
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+    {
+        let Some((command, seconds)) = entry.split_once('=') else {
+            eyre::bail!("COMMAND_COOLDOWNS entry '{entry}' must be <command>=<seconds>");
+        };
+        let seconds = seconds
+            .trim()
+            .parse::<u64>()
+            .map_err(|_| eyre::eyre!("COMMAND_COOLDOWNS entry '{entry}' must use whole seconds"))?;
+        let command = command.trim().trim_start_matches('/').to_string();
+        if seconds == 0 {
+            durations.remove(&command);
+        } else {
+            durations.insert(command, Duration::from_secs(seconds));
+        }
+    }
+    Ok(durations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommandCooldowns, DEFAULT_POLLING_COOLDOWN, parse_command_cooldowns};
+    use poise::serenity_prelude::UserId;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn second_invocation_inside_the_window_is_rejected() {
+        let cooldowns = CommandCooldowns::new(
+            parse_command_cooldowns("mai-recent=10, /mai-today=30").expect("valid config"),
+        );
+        let user = UserId::new(1);
+        let start = Instant::now();
+
+        assert_eq!(cooldowns.try_use("mai-recent", user, start), Ok(()));
+        assert_eq!(
+            cooldowns.try_use("mai-recent", user, start + Duration::from_secs(4)),
+            Err(Duration::from_secs(6))
+        );
+        // Other users and other commands keep their own windows.
+        assert_eq!(
+            cooldowns.try_use("mai-recent", UserId::new(2), start),
+            Ok(())
+        );
+        assert_eq!(cooldowns.try_use("mai-today", user, start), Ok(()));
+        // Commands without a cooldown are never limited.
+        assert_eq!(cooldowns.try_use("mai-song-info", user, start), Ok(()));
+        assert_eq!(cooldowns.try_use("mai-song-info", user, start), Ok(()));
+
+        assert_eq!(
+            cooldowns.try_use("mai-recent", user, start + Duration::from_secs(10)),
+            Ok(())
+        );
+        assert!(parse_command_cooldowns("mai-recent").is_err());
+        assert!(parse_command_cooldowns("mai-recent=soon").is_err());
+    }
+
+    #[test]
+    fn polling_commands_default_to_a_cooldown_that_config_can_turn_off() {
+        let defaults = parse_command_cooldowns("").expect("valid config");
+        assert_eq!(defaults.get("mai-rating"), Some(&DEFAULT_POLLING_COOLDOWN));
+        assert_eq!(defaults.get("mai-song-info"), None);
+
+        let overridden = parse_command_cooldowns("mai-rating=0").expect("valid config");
+        assert_eq!(overridden.get("mai-rating"), None);
+        assert_eq!(overridden.get("mai-today"), Some(&DEFAULT_POLLING_COOLDOWN));
+    }
+
+    #[test]
+    fn expired_windows_are_pruned() {
+        let cooldowns =
+            CommandCooldowns::new(parse_command_cooldowns("mai-recent=10").expect("valid config"));
+        let start = Instant::now();
+        for id in 1..=100 {
+            assert_eq!(
+                cooldowns.try_use("mai-recent", UserId::new(id), start),
+                Ok(())
+            );
+        }
+
+        let later = start + Duration::from_secs(11);
+        assert_eq!(
+            cooldowns.try_use("mai-recent", UserId::new(1), later),
+            Ok(())
+        );
+        assert_eq!(cooldowns.last_used.lock().expect("lock").len(), 1);
+    }
+}
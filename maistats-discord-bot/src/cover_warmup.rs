@@ -0,0 +1,162 @@
+use std::collections::BTreeSet;
+
+use maimai_client::{RecordCollectorClient, SongCatalogSong, SongDatabaseClient};
+use models::ParsedRatingTargets;
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+
+use crate::db;
+
+const COVER_WARMUP_CONCURRENCY: usize = 4;
+
+/// Fetch the covers of every registered player's NEW 15 / OLD 35 charts once at startup,
+/// so the first embed thumbnail for those charts does not wait on a cold cover cache.
+pub(crate) async fn warm_rating_set_covers(
+    db_pool: db::SqlitePool,
+    song_database_client: SongDatabaseClient,
+) {
+    let registrations = match db::list_registrations(&db_pool).await {
+        Ok(registrations) => registrations,
+        Err(err) => {
+            warn!("cover warmup: failed to list registrations: {err:#}");
+            return;
+        }
+    };
+    let catalog = match song_database_client.list_song_catalog().await {
+        Ok(catalog) => catalog,
+        Err(err) => {
+            warn!("cover warmup: failed to load song catalog: {err:#}");
+            return;
+        }
+    };
+
+    let mut image_names = BTreeSet::new();
+    for registration in registrations {
+        let url = registration.record_collector_server_url;
+        let targets = match RecordCollectorClient::new(url.clone()) {
+            Ok(client) => client.get_rating_targets().await,
+            Err(err) => Err(err),
+        };
+        match targets {
+            Ok(targets) => image_names.extend(rating_target_image_names(&targets, &catalog)),
+            Err(err) => warn!("cover warmup: failed to load rating targets from {url}: {err:#}"),
+        }
+    }
+
+    let total = image_names.len();
+    let image_names = image_names.into_iter().collect::<Vec<_>>();
+    let mut warmed = 0;
+    let mut failed = 0;
+    for chunk in image_names.chunks(COVER_WARMUP_CONCURRENCY) {
+        let mut tasks = JoinSet::new();
+        for image_name in chunk {
+            let client = song_database_client.clone();
+            let image_name = image_name.clone();
+            tasks.spawn(async move {
+                let result = client.warm_cover(&image_name).await;
+                (image_name, result)
+            });
+        }
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((_, Ok(()))) => warmed += 1,
+                Ok((image_name, Err(err))) => {
+                    warn!("cover warmup: {image_name} failed: {err:#}");
+                    failed += 1;
+                }
+                Err(err) => {
+                    warn!("cover warmup: task panicked: {err}");
+                    failed += 1;
+                }
+            }
+        }
+    }
+
+    info!("cover warmup: warmed {warmed} of {total} rating set covers ({failed} failed)");
+}
+
+fn rating_target_image_names(
+    targets: &ParsedRatingTargets,
+    catalog: &[SongCatalogSong],
+) -> BTreeSet<String> {
+    targets
+        .current_targets
+        .iter()
+        .chain(targets.legacy_targets.iter())
+        .flat_map(|target| {
+            catalog.iter().filter(move |song| {
+                song.title == target.title
+                    && song.sheets.iter().any(|sheet| {
+                        sheet.chart_type == target.chart_type
+                            && sheet.diff_category == target.diff_category
+                    })
+            })
+        })
+        .filter_map(|song| song.image_name.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rating_target_image_names;
+    use maimai_client::{SongCatalogSheet, SongCatalogSong};
+    use models::{
+        ChartType, DifficultyCategory, ParsedRatingTargetEntry, ParsedRatingTargets, SongAliases,
+        SongChartRegion,
+    };
+
+    fn song(title: &str, image_name: &str, chart_type: ChartType) -> SongCatalogSong {
+        SongCatalogSong {
+            title: title.to_string(),
+            genre: "maimai".to_string(),
+            artist: "Artist".to_string(),
+            image_name: Some(image_name.to_string()),
+            aliases: SongAliases::default(),
+            sheets: vec![SongCatalogSheet {
+                chart_type,
+                diff_category: DifficultyCategory::Master,
+                level: "13".to_string(),
+                version: None,
+                internal_level: Some(13.0),
+                region: SongChartRegion {
+                    jp: true,
+                    intl: true,
+                },
+            }],
+        }
+    }
+
+    fn target(title: &str, chart_type: ChartType) -> ParsedRatingTargetEntry {
+        ParsedRatingTargetEntry {
+            title: title.to_string(),
+            chart_type,
+            diff_category: DifficultyCategory::Master,
+            level: "13".to_string(),
+            achievement_percent: Some(100.0),
+            rank: None,
+        }
+    }
+
+    #[test]
+    fn rating_target_image_names_only_includes_matching_charts() {
+        let catalog = vec![
+            song("New Song", "new.png", ChartType::Dx),
+            song("Old Song", "old.png", ChartType::Std),
+            song("Unrated Song", "unrated.png", ChartType::Dx),
+        ];
+        let targets = ParsedRatingTargets {
+            current_targets: vec![target("New Song", ChartType::Dx)],
+            legacy_targets: vec![
+                target("Old Song", ChartType::Std),
+                target("Old Song", ChartType::Dx),
+            ],
+        };
+
+        let names = rating_target_image_names(&targets, &catalog);
+
+        assert_eq!(
+            names.into_iter().collect::<Vec<_>>(),
+            vec!["new.png".to_string(), "old.png".to_string()]
+        );
+    }
+}
@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use eyre::WrapErr;
+use maimai_client::RecordCollectorClient;
+use models::{
+    ChartType, DifficultyCategory, ParsedPlayerProfile, PlayRecordApiResponse,
+    RecapStateApiResponse,
+};
+use poise::serenity_prelude as serenity;
+use serenity::builder::CreateEmbed;
+use time::{Duration as TimeDuration, OffsetDateTime, Time};
+use tracing::{info, warn};
+
+use crate::commands::TodayCounts;
+use crate::db;
+use crate::dm;
+use crate::embeds::{build_mai_today_embed, with_rank_plate_fields};
+use crate::play_day::{PlayDayWindow, jst_offset, play_day_window};
+
+/// The best-achievement play of the day.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BestPlay {
+    pub(crate) title: String,
+    pub(crate) chart_type: ChartType,
+    pub(crate) diff_category: Option<DifficultyCategory>,
+    pub(crate) achievement_percent: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DailyRecap {
+    pub(crate) counts: TodayCounts,
+    pub(crate) best_play: Option<BestPlay>,
+    pub(crate) rating: u32,
+    /// Change since the previous recap; unknown for the first recap after a restart.
+    pub(crate) rating_change: Option<i64>,
+}
+
+/// `None` when nothing was played, or when no play reached `min_achievement_percent`, so idle
+/// and warmup-only days do not produce a DM.
+pub(crate) fn build_daily_recap(
+    plays: &[PlayRecordApiResponse],
+    rating: u32,
+    previous_rating: Option<u32>,
+    min_achievement_percent: f64,
+) -> Option<DailyRecap> {
+    let min_achievement_x10000 = (min_achievement_percent * 10000.0).round() as i64;
+    let has_qualifying_play = plays
+        .iter()
+        .any(|play| play.achievement_x10000.unwrap_or(0) >= min_achievement_x10000);
+    if !has_qualifying_play {
+        return None;
+    }
+
+    let best_play = plays
+        .iter()
+        .filter_map(|play| Some((play, play.achievement_x10000?)))
+        .max_by_key(|(_, achievement_x10000)| *achievement_x10000)
+        .map(|(play, achievement_x10000)| BestPlay {
+            title: play.title.clone(),
+            chart_type: play.chart_type,
+            diff_category: play.diff_category,
+            achievement_percent: achievement_x10000 as f64 / 10000.0,
+        });
+
+    Some(DailyRecap {
+        counts: TodayCounts::from_plays(plays),
+        best_play,
+        rating,
+        rating_change: previous_rating.map(|previous| i64::from(rating) - i64::from(previous)),
+    })
+}
+
+pub(crate) fn build_daily_recap_embed(
+    profile: &ParsedPlayerProfile,
+    window: &PlayDayWindow,
+    recap: &DailyRecap,
+) -> CreateEmbed {
+    let display_name = &profile.user_name;
+    let rating = match recap.rating_change {
+        Some(change) => format!("{} ({change:+})", recap.rating),
+        None => recap.rating.to_string(),
+    };
+    let embed = build_mai_today_embed(display_name, window, &recap.counts)
+        .title(format!("{display_name}'s daily recap"))
+        .field("Rating", rating, true);
+    let mut embed = with_rank_plate_fields(embed, profile);
+    if let Some(best) = &recap.best_play {
+        let diff = best
+            .diff_category
+            .map(|diff| diff.to_string())
+            .unwrap_or_else(|| "Unknown".to_string());
+        embed = embed.field(
+            "Best play",
+            format!(
+                "[{}] {diff} {} — {:.4}%",
+                best.chart_type, best.title, best.achievement_percent
+            ),
+            false,
+        );
+    }
+    embed
+}
+
+/// Whether a recap DM already covered `day`. The daily and session recaps both record it in
+/// the player's collector, so one day is never summarized twice, even across bot restarts.
+pub(crate) fn is_recapped(state: &RecapStateApiResponse, day: &str) -> bool {
+    state.recapped_day.as_deref() == Some(day)
+}
+
+pub(crate) async fn mark_recapped(client: &RecordCollectorClient, day: &str) -> eyre::Result<()> {
+    client
+        .update_recap_state(&RecapStateApiResponse {
+            recapped_day: Some(day.to_string()),
+            ..RecapStateApiResponse::default()
+        })
+        .await
+}
+
+/// Next `recap_time` (JST) strictly after `now`.
+pub(crate) fn next_recap_at(now: OffsetDateTime, recap_time: Time) -> OffsetDateTime {
+    let now_jst = now.to_offset(jst_offset());
+    let today = now_jst.replace_time(recap_time);
+    if today > now_jst {
+        today
+    } else {
+        today + TimeDuration::days(1)
+    }
+}
+
+/// DM every registered player a recap of their play day at `recap_time` JST, forever.
+pub(crate) async fn run_daily_recap(
+    db_pool: db::SqlitePool,
+    http: Arc<serenity::Http>,
+    recap_time: Time,
+    min_achievement_percent: f64,
+) {
+    let mut last_ratings = HashMap::new();
+    loop {
+        let next = next_recap_at(OffsetDateTime::now_utc(), recap_time);
+        let wait = next - OffsetDateTime::now_utc();
+        tokio::time::sleep(wait.unsigned_abs()).await;
+
+        let window = play_day_window(next);
+        let registrations = match db::list_registrations(&db_pool).await {
+            Ok(registrations) => registrations,
+            Err(err) => {
+                warn!("daily recap: failed to list registrations: {err:#}");
+                continue;
+            }
+        };
+
+        let mut sent = 0;
+        for registration in registrations {
+            let user_id = registration.discord_user_id;
+            let previous_rating = last_ratings.get(&user_id).copied();
+            match send_daily_recap(
+                &http,
+                &registration,
+                &window,
+                previous_rating,
+                min_achievement_percent,
+            )
+            .await
+            {
+                Ok(Some((rating, sent_dm))) => {
+                    last_ratings.insert(user_id, rating);
+                    if sent_dm {
+                        sent += 1;
+                    }
+                }
+                Ok(None) => {}
+                Err(err) => warn!("daily recap failed for {user_id}: {err:#}"),
+            }
+        }
+        info!("daily recap for {}: sent {sent} DM(s)", window.day);
+    }
+}
+
+/// Returns the player's current rating and whether a DM was sent, or `None` when the day was
+/// already recapped.
+async fn send_daily_recap(
+    http: &serenity::Http,
+    registration: &db::Registration,
+    window: &PlayDayWindow,
+    previous_rating: Option<u32>,
+    min_achievement_percent: f64,
+) -> eyre::Result<Option<(u32, bool)>> {
+    let client = RecordCollectorClient::new(registration.record_collector_server_url.clone())?;
+    let recap_state = client
+        .get_recap_state()
+        .await
+        .wrap_err("fetch recap state")?;
+    if is_recapped(&recap_state, &window.day) {
+        return Ok(None);
+    }
+    let plays = client
+        .get_today(&window.day)
+        .await
+        .wrap_err("fetch today's plays")?;
+    let profile = client
+        .get_player_profile()
+        .await
+        .wrap_err("fetch player profile")?;
+
+    let Some(recap) = build_daily_recap(
+        &plays,
+        profile.rating,
+        previous_rating,
+        min_achievement_percent,
+    ) else {
+        return Ok(Some((profile.rating, false)));
+    };
+    let embed = build_daily_recap_embed(&profile, window, &recap);
+    dm::send_daily_recap_dm(http, registration.discord_user_id, embed).await?;
+    mark_recapped(&client, &window.day).await?;
+    Ok(Some((profile.rating, true)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BestPlay, build_daily_recap, build_daily_recap_embed, next_recap_at};
+    use crate::play_day::{jst_offset, play_day_window};
+    use crate::plot::parse_jst_played_at;
+    use models::{ChartType, DifficultyCategory, ParsedPlayerProfile, PlayRecordApiResponse};
+    use time::{OffsetDateTime, Time};
+
+    fn jst(raw: &str) -> OffsetDateTime {
+        parse_jst_played_at(raw, jst_offset()).expect("valid timestamp")
+    }
+
+    fn play(
+        played_at_unixtime: i64,
+        credit_id: i32,
+        achievement_x10000: i64,
+        new_record: bool,
+    ) -> PlayRecordApiResponse {
+        PlayRecordApiResponse {
+            played_at_unixtime,
+            played_at: None,
+            track: None,
+            title: format!("Song {played_at_unixtime}"),
+            genre: None,
+            artist: None,
+            chart_type: ChartType::Dx,
+            diff_category: Some(DifficultyCategory::Master),
+            achievement_x10000: Some(achievement_x10000),
+            score_rank: None,
+            fc: None,
+            sync: None,
+            dx_score: None,
+            dx_score_max: None,
+            credit_id: Some(credit_id),
+            achievement_new_record: Some(i32::from(new_record)),
+        }
+    }
+
+    #[test]
+    fn build_daily_recap_assembles_counts_best_play_and_rating_change() {
+        let plays = vec![
+            play(1, 10, 990_000, false),
+            play(2, 10, 1_005_123, true),
+            play(3, 11, 1_001_000, true),
+        ];
+
+        let recap = build_daily_recap(&plays, 13_050, Some(13_000), 0.0).expect("recap");
+
+        assert_eq!(recap.counts.credits, 2);
+        assert_eq!(recap.counts.tracks, 3);
+        assert_eq!(recap.counts.new_records, 2);
+        assert_eq!(recap.rating_change, Some(50));
+        assert_eq!(
+            recap.best_play,
+            Some(BestPlay {
+                title: "Song 2".to_string(),
+                chart_type: ChartType::Dx,
+                diff_category: Some(DifficultyCategory::Master),
+                achievement_percent: 100.5123,
+            })
+        );
+
+        assert_eq!(
+            build_daily_recap(&plays, 13_050, None, 0.0).and_then(|recap| recap.rating_change),
+            None
+        );
+        assert_eq!(build_daily_recap(&[], 13_050, Some(13_000), 0.0), None);
+    }
+
+    #[test]
+    fn build_daily_recap_skips_days_without_a_play_above_the_threshold() {
+        let warmups = vec![play(1, 10, 970_000, true), play(2, 10, 985_500, true)];
+
+        assert_eq!(build_daily_recap(&warmups, 13_050, None, 99.0), None);
+        assert!(build_daily_recap(&warmups, 13_050, None, 98.55).is_some());
+
+        let mut with_main_play = warmups.clone();
+        with_main_play.push(play(3, 11, 1_003_000, false));
+        let recap = build_daily_recap(&with_main_play, 13_050, None, 99.0).expect("recap");
+        assert_eq!(recap.counts.tracks, 3);
+    }
+
+    #[test]
+    fn build_daily_recap_embed_shows_rank_plates() {
+        let profile = ParsedPlayerProfile {
+            user_name: "PLAYER".to_string(),
+            rating: 13_050,
+            current_version_play_count: 10,
+            total_play_count: 100,
+            area_rank: None,
+            course_rank: Some("真二段".to_string()),
+            class_rank: None,
+        };
+        let recap =
+            build_daily_recap(&[play(1, 10, 1_005_123, true)], 13_050, None, 0.0).expect("recap");
+
+        let embed =
+            build_daily_recap_embed(&profile, &play_day_window(jst("2026/03/01 12:00")), &recap);
+        let fields = serde_json::to_value(embed).expect("serialize")["fields"].clone();
+        let field_names = fields
+            .as_array()
+            .expect("fields")
+            .iter()
+            .map(|field| field["name"].as_str().expect("name").to_string())
+            .collect::<Vec<_>>();
+
+        assert!(field_names.contains(&"Course".to_string()));
+        assert!(!field_names.contains(&"Class".to_string()));
+    }
+
+    #[test]
+    fn next_recap_at_rolls_over_to_tomorrow_once_passed() {
+        let recap_time = Time::from_hms(23, 59, 0).expect("valid time");
+
+        assert_eq!(
+            next_recap_at(jst("2026/03/01 12:00"), recap_time),
+            jst("2026/03/01 23:59")
+        );
+        assert_eq!(
+            next_recap_at(jst("2026/03/01 23:59"), recap_time),
+            jst("2026/03/02 23:59")
+        );
+        // 2026-03-01 15:30 UTC is already past midnight in JST.
+        assert_eq!(
+            next_recap_at(
+                jst("2026/03/02 00:30").to_offset(time::UtcOffset::UTC),
+                recap_time
+            ),
+            jst("2026/03/02 23:59")
+        );
+    }
+}
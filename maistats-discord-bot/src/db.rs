@@ -96,6 +96,62 @@ WHERE discord_user_id = ?1
     }))
 }
 
+pub(crate) async fn list_registrations(pool: &SqlitePool) -> eyre::Result<Vec<Registration>> {
+    let rows = sqlx::query_as::<_, (String, String)>(
+        r#"
+SELECT discord_user_id, record_collector_server_url
+FROM discord_user_record_collectors
+ORDER BY discord_user_id
+"#,
+    )
+    .fetch_all(pool)
+    .await
+    .wrap_err("list registrations")?;
+
+    rows.into_iter()
+        .map(|(discord_user_id, record_collector_server_url)| {
+            let parsed_id = discord_user_id
+                .parse::<u64>()
+                .wrap_err("parse discord_user_id from database")?;
+            Ok(Registration {
+                discord_user_id: serenity::UserId::new(parsed_id),
+                record_collector_server_url,
+            })
+        })
+        .collect()
+}
+
+/// `false` also for unregistered users.
+pub(crate) async fn get_parse_failure_alerted(
+    pool: &SqlitePool,
+    discord_user_id: serenity::UserId,
+) -> eyre::Result<bool> {
+    let alerted = sqlx::query_scalar::<_, bool>(
+        "SELECT parse_failure_alerted FROM discord_user_record_collectors WHERE discord_user_id = ?1",
+    )
+    .bind(discord_user_id.to_string())
+    .fetch_optional(pool)
+    .await
+    .wrap_err("fetch parse failure alerted flag")?;
+    Ok(alerted.unwrap_or(false))
+}
+
+pub(crate) async fn set_parse_failure_alerted(
+    pool: &SqlitePool,
+    discord_user_id: serenity::UserId,
+    alerted: bool,
+) -> eyre::Result<()> {
+    sqlx::query(
+        "UPDATE discord_user_record_collectors SET parse_failure_alerted = ?1 WHERE discord_user_id = ?2",
+    )
+    .bind(alerted)
+    .bind(discord_user_id.to_string())
+    .execute(pool)
+    .await
+    .wrap_err("update parse failure alerted flag")?;
+    Ok(())
+}
+
 pub(crate) async fn count_registrations(pool: &SqlitePool) -> eyre::Result<i64> {
     sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM discord_user_record_collectors")
         .fetch_one(pool)
@@ -289,6 +345,34 @@ mod tests {
         upsert_registration(&pool, other_user_id, "https://second.example", 300).await?;
         assert_eq!(count_registrations(&pool).await?, 2);
 
+        let registrations = list_registrations(&pool).await?;
+        assert_eq!(registrations.len(), 2);
+        assert_eq!(registrations[0].discord_user_id, user_id);
+        assert_eq!(
+            registrations[1].record_collector_server_url,
+            "https://second.example"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn parse_failure_alerted_flag_survives_reconnects() -> eyre::Result<()> {
+        let pool = connect("sqlite::memory:").await?;
+        migrate(&pool).await?;
+        let user_id = serenity::UserId::new(42);
+
+        upsert_registration(&pool, user_id, "http://localhost:3000", 100).await?;
+        assert!(!get_parse_failure_alerted(&pool, user_id).await?);
+
+        set_parse_failure_alerted(&pool, user_id, true).await?;
+        assert!(get_parse_failure_alerted(&pool, user_id).await?);
+        upsert_registration(&pool, user_id, "http://localhost:3000", 200).await?;
+        assert!(get_parse_failure_alerted(&pool, user_id).await?);
+
+        set_parse_failure_alerted(&pool, user_id, false).await?;
+        assert!(!get_parse_failure_alerted(&pool, user_id).await?);
+        assert!(!get_parse_failure_alerted(&pool, serenity::UserId::new(7)).await?);
         Ok(())
     }
 
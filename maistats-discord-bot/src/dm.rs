@@ -1,4 +1,5 @@
 use eyre::Result;
+use models::ParsedPlayerProfile;
 use poise::serenity_prelude as serenity;
 use serenity::builder::CreateMessage;
 
@@ -16,10 +17,32 @@ async fn send_dm(
     Ok(())
 }
 
+pub(crate) async fn send_daily_recap_dm(
+    http: &serenity::Http,
+    user_id: serenity::UserId,
+    embed: serenity::CreateEmbed,
+) -> Result<()> {
+    send_dm(http, user_id, embed).await
+}
+
+pub(crate) async fn send_parse_failure_alert_dm(
+    http: &serenity::Http,
+    user_id: serenity::UserId,
+    embed: serenity::CreateEmbed,
+) -> Result<()> {
+    send_dm(http, user_id, embed).await
+}
+
 pub(crate) async fn send_developer_startup_dm(
     http: &serenity::Http,
     user_id: serenity::UserId,
     registered_url_count: i64,
+    developer_profile: Option<&ParsedPlayerProfile>,
 ) -> Result<()> {
-    send_dm(http, user_id, embed_startup_summary(registered_url_count)).await
+    send_dm(
+        http,
+        user_id,
+        embed_startup_summary(registered_url_count, developer_profile),
+    )
+    .await
 }
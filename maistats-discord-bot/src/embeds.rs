@@ -1,13 +1,19 @@
-use models::{ChartType, DifficultyCategory, FcStatus, ScoreRank, SyncStatus};
+use models::{ChartType, DifficultyCategory, FcStatus, ParsedPlayerProfile, ScoreRank, SyncStatus};
 use poise::serenity_prelude as serenity;
 use serenity::builder::{CreateEmbed, CreateEmbedFooter};
+use time::{OffsetDateTime, UtcOffset};
 
 use crate::chart_links::linked_chart_label;
-use crate::emoji::{MaimaiStatusEmojis, format_fc, format_rank, format_sync};
+use crate::commands::TodayCounts;
+use crate::emoji::{MaimaiStatusEmojis, format_fc, format_rank, format_sync, tier_prefix};
+use crate::play_day::PlayDayWindow;
+use crate::plot::parse_jst_played_at;
 use maimai_client::SongDatabaseClient;
 
 const EMBED_COLOR: u32 = 0x51BCF3;
 const EMBED_COLOR_MAINTENANCE: u32 = 0xFFA500;
+/// Discord rejects messages with more embeds than this.
+pub(crate) const MAX_EMBEDS_PER_MESSAGE: usize = 10;
 
 pub(crate) fn embed_base(title: &str) -> CreateEmbed {
     let mut e = CreateEmbed::new();
@@ -25,10 +31,35 @@ pub(crate) fn embed_maintenance() -> CreateEmbed {
         .color(EMBED_COLOR_MAINTENANCE)
 }
 
-pub(crate) fn embed_startup_summary(registered_url_count: i64) -> CreateEmbed {
-    embed_base("maistats-discord-bot ready").description(format!(
+/// `developer_profile` is the developer's own player, when they registered a collector.
+pub(crate) fn embed_startup_summary(
+    registered_url_count: i64,
+    developer_profile: Option<&ParsedPlayerProfile>,
+) -> CreateEmbed {
+    let embed = embed_base("maistats-discord-bot ready").description(format!(
         "Startup complete.\n**Registered URLs**: {registered_url_count}"
-    ))
+    ));
+    match developer_profile {
+        Some(profile) => with_rank_plate_fields(
+            embed.field("Rating", profile.rating.to_string(), true),
+            profile,
+        ),
+        None => embed,
+    }
+}
+
+/// Course and class rank plates, skipped when the playerData page showed none.
+pub(crate) fn with_rank_plate_fields(
+    mut embed: CreateEmbed,
+    profile: &ParsedPlayerProfile,
+) -> CreateEmbed {
+    if let Some(course_rank) = &profile.course_rank {
+        embed = embed.field("Course", course_rank, true);
+    }
+    if let Some(class_rank) = &profile.class_rank {
+        embed = embed.field("Class", class_rank, true);
+    }
+    embed
 }
 
 #[derive(Debug, Clone)]
@@ -65,6 +96,34 @@ pub(crate) fn format_level_with_internal(level: &str, internal_level: Option<f32
     }
 }
 
+/// Left-align every column to its widest cell, separated by two spaces, for use inside a
+/// code block. Widths count characters, so the columns should stay ASCII.
+pub(crate) fn format_table(rows: &[Vec<String>]) -> String {
+    let column_count = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let widths = (0..column_count)
+        .map(|column| {
+            rows.iter()
+                .filter_map(|row| row.get(column))
+                .map(|cell| cell.chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect::<Vec<_>>();
+
+    rows.iter()
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 fn format_recent_title(record: &RecentRecordView) -> String {
     record.title.clone()
 }
@@ -90,16 +149,67 @@ fn format_recent_detail_lines(
     let rank = format_rank(status_emojis, record.rank, "-");
     let fc = format_fc(status_emojis, record.fc, "-");
     let sync = format_sync(status_emojis, record.sync, "-");
+    let tier = tier_prefix(record.rank);
 
-    format!("{achievement} • {rank} • {fc} • {sync}")
+    format!("{tier}{achievement} • {rank} • {fc} • {sync}")
 }
 
-fn format_recent_footer(record: &RecentRecordView) -> CreateEmbedFooter {
+/// SEGA reports played-at times in JST.
+const JST_OFFSET_SECONDS: i32 = 9 * 3600;
+
+fn timezone_label(offset: UtcOffset) -> String {
+    match offset.whole_seconds() {
+        JST_OFFSET_SECONDS => "JST".to_string(),
+        0 => "UTC".to_string(),
+        _ => {
+            let (hours, minutes, _) = offset.as_hms();
+            let sign = if offset.is_negative() { '-' } else { '+' };
+            format!("UTC{sign}{:02}:{:02}", hours.abs(), minutes.abs())
+        }
+    }
+}
+
+/// Convert a raw SEGA `YYYY/MM/DD HH:MM` (JST) timestamp to `display_offset`, labelled with
+/// the zone. Unparseable values are shown as-is.
+pub(crate) fn format_played_at(raw: &str, display_offset: UtcOffset) -> String {
+    let jst = UtcOffset::from_whole_seconds(JST_OFFSET_SECONDS).unwrap_or(UtcOffset::UTC);
+    let Some(played_at) = parse_jst_played_at(raw, jst) else {
+        return raw.to_string();
+    };
+    format_local_time(played_at, display_offset)
+}
+
+/// A unix timestamp from the record collector in `display_offset`, labelled with the zone.
+pub(crate) fn format_unix_time(unixtime: i64, display_offset: UtcOffset) -> String {
+    match OffsetDateTime::from_unix_timestamp(unixtime) {
+        Ok(at) => format_local_time(at, display_offset),
+        Err(_) => unixtime.to_string(),
+    }
+}
+
+fn format_local_time(at: OffsetDateTime, display_offset: UtcOffset) -> String {
+    let local = at.to_offset(display_offset);
+    format!(
+        "{:04}/{:02}/{:02} {:02}:{:02} {}",
+        local.year(),
+        u8::from(local.month()),
+        local.day(),
+        local.hour(),
+        local.minute(),
+        timezone_label(display_offset)
+    )
+}
+
+fn format_recent_footer(record: &RecentRecordView, display_offset: UtcOffset) -> CreateEmbedFooter {
     let rating = record
         .rating_points
         .map(|v| v.to_string())
         .unwrap_or_else(|| "-".to_string());
-    let played_at = record.played_at.as_deref().unwrap_or("-");
+    let played_at = record
+        .played_at
+        .as_deref()
+        .map(|raw| format_played_at(raw, display_offset))
+        .unwrap_or_else(|| "-".to_string());
 
     CreateEmbedFooter::new(format!("Rating: {rating} • Played: {played_at}"))
 }
@@ -111,12 +221,17 @@ fn format_percent_f64(value: Option<f64>) -> String {
     }
 }
 
+/// One embed per track, plus the optional credit summary, never more than `max_embeds` in
+/// total. Tracks that do not fit are listed together in a final "more tracks" embed. The
+/// credit's rating points go on the credit summary, or on the last embed without one.
 pub(crate) fn build_mai_recent_embeds(
     display_name: &str,
     records: &[RecentRecordView],
     optional_fields: Option<&RecentOptionalFields>,
     status_emojis: &MaimaiStatusEmojis,
     song_database_client: &SongDatabaseClient,
+    display_offset: UtcOffset,
+    max_embeds: usize,
 ) -> Vec<CreateEmbed> {
     let mut embeds = Vec::new();
 
@@ -135,13 +250,25 @@ pub(crate) fn build_mai_recent_embeds(
             summary = summary.field("Play count", v, true);
         }
         if let Some(v) = started_at {
-            summary = summary.field("Credit started at", v, false);
+            summary = summary.field(
+                "Credit started at",
+                format_played_at(v, display_offset),
+                false,
+            );
         }
 
         embeds.push(summary);
     }
 
-    embeds.extend(records.iter().map(|record| {
+    let has_credit_summary = !embeds.is_empty();
+    let track_slots = max_embeds.saturating_sub(embeds.len()).max(1);
+    let (shown, overflow) = if records.len() > track_slots {
+        records.split_at(track_slots - 1)
+    } else {
+        (records, &[][..])
+    };
+
+    embeds.extend(shown.iter().map(|record| {
         let mut desc = format!(
             "**{}**\n{}",
             format_recent_chart_line(record),
@@ -153,29 +280,245 @@ pub(crate) fn build_mai_recent_embeds(
 
         let mut embed = embed_base(&format_recent_title(record))
             .description(desc)
-            .footer(format_recent_footer(record));
+            .footer(format_recent_footer(record, display_offset));
         if let Some(image_name) = record.image_name.as_deref() {
             embed = embed.thumbnail(song_database_client.cover_url(image_name));
         }
         embed
     }));
 
+    if !overflow.is_empty() {
+        let lines = overflow
+            .iter()
+            .map(|record| {
+                format!(
+                    "**{}**\n{}",
+                    format_recent_chart_line(record),
+                    format_recent_detail_lines(record, status_emojis)
+                )
+            })
+            .collect::<Vec<_>>();
+        embeds.push(
+            embed_base(&format!("{} more track(s)", overflow.len())).description(lines.join("\n")),
+        );
+    }
+
+    let rating_target = if has_credit_summary {
+        embeds.first_mut()
+    } else {
+        embeds.last_mut()
+    };
+    if let Some(embed) = rating_target {
+        *embed = add_recent_rating_field(std::mem::take(embed), records);
+    }
+
     embeds
 }
 
+/// Sum of rating points across the credit's tracks, and how many tracks had no computable
+/// rating. Returns `None` when no track has a known internal level and achievement.
+fn recent_rating_totals(records: &[RecentRecordView]) -> Option<(u32, usize)> {
+    let known = records
+        .iter()
+        .filter_map(|record| record.rating_points)
+        .collect::<Vec<_>>();
+    if known.is_empty() {
+        return None;
+    }
+    Some((known.iter().sum(), records.len() - known.len()))
+}
+
+fn add_recent_rating_field(embed: CreateEmbed, records: &[RecentRecordView]) -> CreateEmbed {
+    let Some((total, unknown)) = recent_rating_totals(records) else {
+        return embed;
+    };
+    let mut value = format!("{total} pts");
+    if unknown > 0 {
+        value.push_str(&format!(" ({unknown} play(s) unknown)"));
+    }
+    embed.field("Credit rating", value, false)
+}
+
 pub(crate) fn build_mai_today_embed(
     display_name: &str,
-    start: &str,
-    end: &str,
-    credits: i64,
-    tracks: i64,
-    new_records: i64,
+    window: &PlayDayWindow,
+    counts: &TodayCounts,
 ) -> CreateEmbed {
     let mut e = embed_base(&format!("{}'s today", display_name));
     e = e
-        .field("Window", format!("{} ~ {}", start, end), false)
-        .field("Credits", credits.to_string(), true)
-        .field("Tracks", tracks.to_string(), true)
-        .field("New records", new_records.to_string(), true);
+        .field(
+            "Window",
+            format!("{} ~ {}", window.start, window.end),
+            false,
+        )
+        .field("Credits", counts.credits.to_string(), true)
+        .field("Tracks", counts.tracks.to_string(), true)
+        .field("New records", counts.new_records.to_string(), true);
     e
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        MAX_EMBEDS_PER_MESSAGE, RecentOptionalFields, RecentRecordView, build_mai_recent_embeds,
+        format_played_at, format_table, format_unix_time, recent_rating_totals,
+    };
+    use crate::emoji::MaimaiStatusEmojis;
+    use maimai_client::SongDatabaseClient;
+    use models::ChartType;
+    use time::UtcOffset;
+
+    fn record(rating_points: Option<u32>) -> RecentRecordView {
+        RecentRecordView {
+            track: None,
+            played_at: None,
+            title: "Song".to_string(),
+            chart_type: ChartType::Dx,
+            diff_category: None,
+            image_name: None,
+            level: None,
+            internal_level: None,
+            rating_points,
+            achievement_percent: None,
+            achievement_new_record: false,
+            rank: None,
+            fc: None,
+            sync: None,
+        }
+    }
+
+    #[test]
+    fn recent_rating_totals_sums_known_tracks_and_counts_unknown() {
+        let records = vec![record(Some(300)), record(None), record(Some(250))];
+        assert_eq!(recent_rating_totals(&records), Some((550, 1)));
+    }
+
+    #[test]
+    fn recent_rating_totals_is_none_without_computable_tracks() {
+        assert_eq!(recent_rating_totals(&[record(None), record(None)]), None);
+        assert_eq!(recent_rating_totals(&[]), None);
+    }
+
+    #[test]
+    fn build_mai_recent_embeds_folds_overflow_tracks_under_the_cap() {
+        let records = (0..12).map(|_| record(Some(300))).collect::<Vec<_>>();
+        let song_database_client =
+            SongDatabaseClient::new("http://localhost".to_string()).expect("client");
+        let summary = RecentOptionalFields {
+            rating: Some("15000".to_string()),
+            play_count: None,
+        };
+
+        let embeds = build_mai_recent_embeds(
+            "Player",
+            &records,
+            Some(&summary),
+            &MaimaiStatusEmojis::default(),
+            &song_database_client,
+            UtcOffset::UTC,
+            MAX_EMBEDS_PER_MESSAGE,
+        );
+        let titles = embeds
+            .iter()
+            .map(|embed| {
+                serde_json::to_value(embed).expect("serialize")["title"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(embeds.len(), MAX_EMBEDS_PER_MESSAGE);
+        assert_eq!(titles[0], "Player's latest credit");
+        assert_eq!(titles[9], "4 more track(s)");
+        let summary_fields = serde_json::to_value(&embeds[0]).expect("serialize")["fields"].clone();
+        assert!(
+            summary_fields
+                .as_array()
+                .expect("fields")
+                .iter()
+                .any(|field| field["name"] == "Credit rating" && field["value"] == "3600 pts")
+        );
+
+        let short = build_mai_recent_embeds(
+            "Player",
+            &records[..4],
+            None,
+            &MaimaiStatusEmojis::default(),
+            &song_database_client,
+            UtcOffset::UTC,
+            MAX_EMBEDS_PER_MESSAGE,
+        );
+        assert_eq!(short.len(), 4);
+        let last_fields = serde_json::to_value(&short[3]).expect("serialize")["fields"].clone();
+        assert_eq!(last_fields[0]["name"], "Credit rating");
+        assert_eq!(last_fields[0]["value"], "1200 pts");
+    }
+
+    #[test]
+    fn format_table_aligns_columns_across_cell_widths() {
+        let rows = [
+            vec!["Type", "Diff", "Level", "Achievement"],
+            vec!["STD", "BASIC", "3", "100.5000%"],
+            vec!["DX", "Re:MASTER", "14+ (14.9)", "97.0012%"],
+        ]
+        .map(|row| row.into_iter().map(str::to_string).collect::<Vec<_>>());
+
+        let table = format_table(&rows);
+        let lines = table.lines().collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            vec![
+                "Type  Diff       Level       Achievement",
+                "STD   BASIC      3           100.5000%",
+                "DX    Re:MASTER  14+ (14.9)  97.0012%",
+            ]
+        );
+        for column in ["Diff", "Level", "Achievement"] {
+            let start = lines[0].find(column).expect("header column");
+            assert!(
+                lines[1..].iter().all(
+                    |line| line.as_bytes()[start - 1] == b' ' && line.as_bytes()[start] != b' '
+                )
+            );
+        }
+    }
+
+    #[test]
+    fn format_played_at_converts_jst_to_display_offset() {
+        let jst = UtcOffset::from_hms(9, 0, 0).expect("valid offset");
+        let india = UtcOffset::from_hms(5, 30, 0).expect("valid offset");
+        let pacific = UtcOffset::from_hms(-8, 0, 0).expect("valid offset");
+
+        assert_eq!(
+            format_played_at("2024/03/01 08:30", UtcOffset::UTC),
+            "2024/02/29 23:30 UTC"
+        );
+        assert_eq!(
+            format_played_at("2024/03/01 08:30", jst),
+            "2024/03/01 08:30 JST"
+        );
+        assert_eq!(
+            format_played_at("2024/03/01 08:30", india),
+            "2024/03/01 05:00 UTC+05:30"
+        );
+        assert_eq!(
+            format_played_at("2024/03/01 08:30", pacific),
+            "2024/02/29 15:30 UTC-08:00"
+        );
+        assert_eq!(format_played_at("unknown", jst), "unknown");
+    }
+
+    #[test]
+    fn format_unix_time_uses_display_offset() {
+        let jst = UtcOffset::from_hms(9, 0, 0).expect("valid offset");
+
+        // 2024-02-29 23:30:00 UTC.
+        assert_eq!(
+            format_unix_time(1_709_249_400, UtcOffset::UTC),
+            "2024/02/29 23:30 UTC"
+        );
+        assert_eq!(format_unix_time(1_709_249_400, jst), "2024/03/01 08:30 JST");
+    }
+}
@@ -301,6 +301,12 @@ pub(crate) fn format_rank(
     }
 }
 
+/// `"🟦 "` style prefix for an achievement, or nothing for unplayed charts.
+pub(crate) fn tier_prefix(rank: Option<ScoreRank>) -> String {
+    rank.map(|rank| format!("{} ", rank.tier_emoji()))
+        .unwrap_or_default()
+}
+
 pub(crate) fn format_fc(
     emojis: &MaimaiStatusEmojis,
     fc: Option<FcStatus>,
@@ -8,11 +8,24 @@ use tracing::{info, warn};
 mod chart_links;
 mod commands;
 mod config;
+mod cooldown;
+mod cover_warmup;
+mod daily_recap;
 mod db;
 mod dm;
 mod embeds;
 mod emoji;
+mod plates;
+mod play_day;
 mod plot;
+mod profile;
+mod random;
+mod rating_audit;
+mod rating_compare;
+mod rating_history;
+mod score_compare;
+mod session_recap;
+mod sync_alert;
 mod updown;
 
 use config::DiscordConfig;
@@ -26,8 +39,10 @@ pub(crate) struct BotData {
     pub(crate) discord_http: std::sync::Arc<serenity::Http>,
     pub(crate) song_database_client: SongDatabaseClient,
     pub(crate) status_emojis: MaimaiStatusEmojis,
+    pub(crate) display_offset: time::UtcOffset,
     pub(crate) version_warning_cache: Arc<Mutex<HashMap<String, i64>>>,
     pub(crate) updown_in_flight: updown::UpdownInFlightLocks,
+    pub(crate) command_cooldowns: Arc<cooldown::CommandCooldowns>,
 }
 
 #[tokio::main]
@@ -57,7 +72,15 @@ async fn main() -> eyre::Result<()> {
     let db_pool = db::connect(&config.database_url).await?;
     db::migrate(&db_pool).await?;
 
-    let song_database_client = SongDatabaseClient::new(config.song_database_url.clone())?;
+    let song_database_client = SongDatabaseClient::new(config.song_database_url.clone())?
+        .with_estimated_internal_levels(config.include_estimated_internal_levels);
+    let warm_cover_cache = config.warm_cover_cache;
+    let daily_recap_time = config.daily_recap_time;
+    let session_recap_idle = config.session_recap_idle;
+    let dm_min_achievement = config.dm_min_achievement;
+    let display_offset =
+        time::UtcOffset::from_whole_seconds(config.display_timezone_offset_minutes * 60)
+            .wrap_err("build display timezone offset")?;
 
     let bot_data = BotData {
         db_pool,
@@ -65,8 +88,12 @@ async fn main() -> eyre::Result<()> {
         discord_http,
         song_database_client,
         status_emojis: MaimaiStatusEmojis::default(),
+        display_offset,
         version_warning_cache: Arc::new(Mutex::new(HashMap::new())),
         updown_in_flight: updown::new_in_flight_locks(),
+        command_cooldowns: Arc::new(cooldown::CommandCooldowns::new(
+            config.command_cooldowns.clone(),
+        )),
     };
 
     let framework = poise::Framework::builder()
@@ -77,11 +104,27 @@ async fn main() -> eyre::Result<()> {
                 commands::register(),
                 commands::mai_score(),
                 commands::mai_song_info(),
+                commands::mai_jacket(),
                 commands::mai_recent(),
                 commands::mai_today(),
+                commands::mai_profile(),
+                commands::mai_set_target(),
+                commands::mai_sync_status(),
+                commands::mai_rating_history(),
+                commands::mai_rating(),
+                commands::mai_rating_audit(),
+                commands::mai_compare_version(),
+                commands::mai_suggest(),
+                commands::mai_compare(),
+                commands::mai_rating_target_chart(),
+                commands::mai_plates(),
+                commands::mai_random(),
                 commands::mai_updown(),
                 commands::mai_plot(),
+                commands::mai_dev_internal_level(),
+                commands::mai_debug_chart(),
             ],
+            command_check: Some(|ctx| Box::pin(commands::check_command_cooldown(ctx))),
             event_handler: |ctx, event, _framework, data| {
                 Box::pin(updown::handle_event(ctx, event, data))
             },
@@ -106,6 +149,8 @@ async fn main() -> eyre::Result<()> {
                                 )
                                 .await;
                         }
+                        // A cooldown rejection has already been answered.
+                        poise::FrameworkError::CommandCheckFailed { error: None, .. } => {}
                         poise::FrameworkError::ArgumentParse { error, ctx, .. } => {
                             tracing::error!("Argument parse error: {:?}", error);
                             let _ = ctx
@@ -146,16 +191,53 @@ async fn main() -> eyre::Result<()> {
                     };
 
                 let registration_count = db::count_registrations(&bot_data.db_pool).await?;
+                let developer_profile =
+                    fetch_developer_profile(&bot_data.db_pool, bot_data.dev_user_id).await;
                 if let Err(e) = dm::send_developer_startup_dm(
                     &bot_data.discord_http,
                     bot_data.dev_user_id,
                     registration_count,
+                    developer_profile.as_ref(),
                 )
                 .await
                 {
                     warn!("Developer startup DM failed: {e}");
                 }
 
+                if warm_cover_cache {
+                    tokio::spawn(cover_warmup::warm_rating_set_covers(
+                        bot_data.db_pool.clone(),
+                        bot_data.song_database_client.clone(),
+                    ));
+                }
+
+                tokio::spawn(sync_alert::run_parse_failure_alerts(
+                    bot_data.db_pool.clone(),
+                    bot_data.discord_http.clone(),
+                ));
+
+                if let Some(recap_time) = daily_recap_time {
+                    info!("Daily recap DM enabled at {recap_time} JST");
+                    tokio::spawn(daily_recap::run_daily_recap(
+                        bot_data.db_pool.clone(),
+                        bot_data.discord_http.clone(),
+                        recap_time,
+                        dm_min_achievement,
+                    ));
+                }
+                if let Some(idle_after) = session_recap_idle {
+                    info!(
+                        "Session recap DM enabled after {} idle minute(s)",
+                        idle_after.whole_minutes()
+                    );
+                    tokio::spawn(session_recap::run_session_recap(
+                        bot_data.db_pool.clone(),
+                        bot_data.discord_http.clone(),
+                        idle_after,
+                        dm_min_achievement,
+                    ));
+                }
+
                 Ok(BotData {
                     status_emojis,
                     ..bot_data
@@ -177,3 +259,28 @@ async fn main() -> eyre::Result<()> {
 
     Ok(())
 }
+
+/// The developer's own player for the startup DM; `None` when they have no registered
+/// collector or it cannot be reached, so a missing profile never blocks startup.
+async fn fetch_developer_profile(
+    pool: &db::SqlitePool,
+    dev_user_id: serenity::UserId,
+) -> Option<models::ParsedPlayerProfile> {
+    let result = async {
+        let Some(registration) = db::get_registration(pool, dev_user_id).await? else {
+            return Ok(None);
+        };
+        let client =
+            maimai_client::RecordCollectorClient::new(registration.record_collector_server_url)?;
+        let profile = client
+            .get_player_profile()
+            .await
+            .wrap_err("fetch player profile")?;
+        eyre::Ok(Some(profile))
+    }
+    .await;
+    result.unwrap_or_else(|e| {
+        warn!("Developer profile for the startup DM unavailable: {e:?}");
+        None
+    })
+}
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+
+use maimai_client::SongCatalogSong;
+use models::{
+    ChartType, DifficultyCategory, FcStatus, MaimaiVersion, ScoreApiResponse, normalize_title,
+};
+use poise::serenity_prelude as serenity;
+use serenity::builder::CreateEmbed;
+
+use crate::embeds::embed_base;
+use crate::rating_compare::find_sheet;
+
+/// Plates only count BASIC through MASTER; Re:MASTER is never required.
+const PLATE_DIFFICULTIES: [DifficultyCategory; 4] = [
+    DifficultyCategory::Basic,
+    DifficultyCategory::Advanced,
+    DifficultyCategory::Expert,
+    DifficultyCategory::Master,
+];
+
+/// Achievement needed to clear a chart (rank A).
+const CLEAR_ACHIEVEMENT_X10000: i64 = 800_000;
+const SSS_PLUS_ACHIEVEMENT_X10000: i64 = 1_005_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub(crate) enum PlateTarget {
+    #[name = "Clear"]
+    Clear,
+    #[name = "FC"]
+    Fc,
+    #[name = "FC+"]
+    FcPlus,
+    #[name = "AP"]
+    Ap,
+    #[name = "AP+"]
+    ApPlus,
+    #[name = "SSS+"]
+    SssPlus,
+}
+
+impl PlateTarget {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Clear => "cleared",
+            Self::Fc => "FC",
+            Self::FcPlus => "FC+",
+            Self::Ap => "AP",
+            Self::ApPlus => "AP+",
+            Self::SssPlus => "SSS+",
+        }
+    }
+
+    fn is_met_by(self, score: &ScoreApiResponse) -> bool {
+        let achievement_x10000 = score.achievement_x10000.unwrap_or(0);
+        match self {
+            Self::Clear => achievement_x10000 >= CLEAR_ACHIEVEMENT_X10000,
+            Self::Fc => score.fc.is_some(),
+            Self::FcPlus => matches!(
+                score.fc,
+                Some(FcStatus::FcPlus | FcStatus::Ap | FcStatus::ApPlus)
+            ),
+            Self::Ap => matches!(score.fc, Some(FcStatus::Ap | FcStatus::ApPlus)),
+            Self::ApPlus => score.fc == Some(FcStatus::ApPlus),
+            Self::SssPlus => achievement_x10000 >= SSS_PLUS_ACHIEVEMENT_X10000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PlateRow {
+    pub(crate) diff_category: DifficultyCategory,
+    pub(crate) total: usize,
+    pub(crate) cleared: usize,
+    pub(crate) achieved: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PlateProgress {
+    pub(crate) rows: Vec<PlateRow>,
+    /// Played charts that could not be matched to a catalog chart with a known version.
+    pub(crate) unclassified: usize,
+}
+
+/// Count, per difficulty, the INTL charts added in `version` and how many of them the player
+/// has cleared and met `target` on.
+pub(crate) fn build_plate_progress(
+    scores: &[ScoreApiResponse],
+    catalog: &[SongCatalogSong],
+    version: MaimaiVersion,
+    target: PlateTarget,
+) -> PlateProgress {
+    let scores_by_chart = scores
+        .iter()
+        .map(|score| {
+            (
+                (
+                    normalize_title(&score.title),
+                    score.artist.as_str(),
+                    score.chart_type,
+                    score.diff_category,
+                ),
+                score,
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut rows = PLATE_DIFFICULTIES
+        .iter()
+        .map(|diff_category| PlateRow {
+            diff_category: *diff_category,
+            total: 0,
+            cleared: 0,
+            achieved: 0,
+        })
+        .collect::<Vec<_>>();
+    for song in catalog {
+        for sheet in song.sheets.iter().filter(|sheet| {
+            sheet.region.intl
+                && sheet.chart_type != ChartType::Utage
+                && sheet.version.as_deref() == Some(version.as_str())
+        }) {
+            let Some(row) = rows
+                .iter_mut()
+                .find(|row| row.diff_category == sheet.diff_category)
+            else {
+                continue;
+            };
+            row.total += 1;
+            let key = (
+                normalize_title(&song.title),
+                song.artist.as_str(),
+                sheet.chart_type,
+                sheet.diff_category,
+            );
+            if let Some(score) = scores_by_chart.get(&key) {
+                row.cleared += usize::from(PlateTarget::Clear.is_met_by(score));
+                row.achieved += usize::from(target.is_met_by(score));
+            }
+        }
+    }
+
+    let unclassified = scores
+        .iter()
+        .filter(|score| find_sheet(catalog, score).is_none_or(|sheet| sheet.version.is_none()))
+        .count();
+
+    PlateProgress { rows, unclassified }
+}
+
+pub(crate) fn build_plate_progress_embed(
+    display_name: &str,
+    version: MaimaiVersion,
+    target: PlateTarget,
+    progress: &PlateProgress,
+) -> CreateEmbed {
+    let lines = progress
+        .rows
+        .iter()
+        .map(|row| {
+            let mut line = format!(
+                "**{}** cleared {}/{}",
+                row.diff_category, row.cleared, row.total
+            );
+            if target != PlateTarget::Clear {
+                line.push_str(&format!(
+                    ", {} {}/{}",
+                    target.label(),
+                    row.achieved,
+                    row.total
+                ));
+            }
+            line
+        })
+        .collect::<Vec<_>>();
+
+    let mut embed = embed_base(&format!(
+        "{display_name}'s {} plate progress ({})",
+        version.as_str(),
+        target.label()
+    ))
+    .description(lines.join("\n"));
+    if progress.unclassified > 0 {
+        embed = embed.footer(serenity::CreateEmbedFooter::new(format!(
+            "{} played chart(s) could not be classified (missing song metadata)",
+            progress.unclassified
+        )));
+    }
+    embed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlateRow, PlateTarget, build_plate_progress};
+    use maimai_client::{SongCatalogSheet, SongCatalogSong};
+    use models::{
+        ChartType, DifficultyCategory, FcStatus, MaimaiVersion, ScoreApiResponse, SongAliases,
+        SongChartRegion,
+    };
+
+    fn song(title: &str, version: Option<&str>) -> SongCatalogSong {
+        SongCatalogSong {
+            title: title.to_string(),
+            genre: "maimai".to_string(),
+            artist: "Artist".to_string(),
+            image_name: None,
+            aliases: SongAliases::default(),
+            sheets: [DifficultyCategory::Expert, DifficultyCategory::Master]
+                .into_iter()
+                .map(|diff_category| SongCatalogSheet {
+                    chart_type: ChartType::Dx,
+                    diff_category,
+                    level: "13".to_string(),
+                    version: version.map(str::to_string),
+                    internal_level: Some(13.0),
+                    region: SongChartRegion {
+                        jp: true,
+                        intl: true,
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    fn score(title: &str, achievement_x10000: i64, fc: Option<FcStatus>) -> ScoreApiResponse {
+        ScoreApiResponse {
+            title: title.to_string(),
+            genre: "maimai".to_string(),
+            artist: "Artist".to_string(),
+            chart_type: ChartType::Dx,
+            diff_category: DifficultyCategory::Master,
+            achievement_x10000: Some(achievement_x10000),
+            rank: None,
+            fc,
+            sync: None,
+            dx_score: None,
+            dx_score_max: None,
+            last_played_at: None,
+            play_count: None,
+        }
+    }
+
+    #[test]
+    fn build_plate_progress_counts_version_charts_and_unclassified_scores() {
+        let catalog = vec![
+            song("Prism A", Some("PRiSM")),
+            song("Prism B", Some("PRiSM")),
+            song("Circle", Some("CiRCLE")),
+            song("No Version", None),
+        ];
+        let scores = vec![
+            score("Prism A", 1_005_000, Some(FcStatus::Ap)),
+            score("Prism B", 790_000, Some(FcStatus::Fc)),
+            score("Circle", 1_010_000, Some(FcStatus::ApPlus)),
+            score("No Version", 1_000_000, None),
+            score("Deleted Song", 1_000_000, None),
+        ];
+
+        let progress =
+            build_plate_progress(&scores, &catalog, MaimaiVersion::Prism, PlateTarget::Ap);
+
+        let master = progress
+            .rows
+            .iter()
+            .find(|row| row.diff_category == DifficultyCategory::Master)
+            .expect("MASTER row");
+        assert_eq!(
+            *master,
+            PlateRow {
+                diff_category: DifficultyCategory::Master,
+                total: 2,
+                cleared: 1,
+                achieved: 1,
+            }
+        );
+        let expert = progress
+            .rows
+            .iter()
+            .find(|row| row.diff_category == DifficultyCategory::Expert)
+            .expect("EXPERT row");
+        assert_eq!((expert.total, expert.cleared), (2, 0));
+        assert_eq!(progress.rows.len(), 4);
+        assert_eq!(progress.unclassified, 2);
+    }
+}
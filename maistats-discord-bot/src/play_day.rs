@@ -0,0 +1,59 @@
+use time::{Duration as TimeDuration, OffsetDateTime, UtcOffset};
+
+pub(crate) fn jst_offset() -> UtcOffset {
+    UtcOffset::from_hms(9, 0, 0).unwrap_or(UtcOffset::UTC)
+}
+
+/// A maimai play day: `day` is the `YYYY-MM-DD` key `/api/today` expects, and the window
+/// runs from 04:00 JST that day to 04:00 JST the next.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PlayDayWindow {
+    pub(crate) day: String,
+    pub(crate) start: String,
+    pub(crate) end: String,
+}
+
+pub(crate) fn play_day_window(now_jst: OffsetDateTime) -> PlayDayWindow {
+    let day_date = if now_jst.hour() < 4 {
+        (now_jst - TimeDuration::days(1)).date()
+    } else {
+        now_jst.date()
+    };
+    let end_date = day_date + TimeDuration::days(1);
+    let format_date = |date: time::Date| {
+        format!(
+            "{:04}-{:02}-{:02}",
+            date.year(),
+            u8::from(date.month()),
+            date.day()
+        )
+    };
+
+    PlayDayWindow {
+        day: format_date(day_date),
+        start: format!("{} 04:00", format_date(day_date)),
+        end: format!("{} 04:00", format_date(end_date)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PlayDayWindow, jst_offset, play_day_window};
+    use crate::plot::parse_jst_played_at;
+
+    #[test]
+    fn play_day_window_starts_at_four_am_jst() {
+        let before_cutoff = parse_jst_played_at("2026/03/02 03:59", jst_offset()).expect("time");
+        let after_cutoff = parse_jst_played_at("2026/03/02 04:00", jst_offset()).expect("time");
+
+        assert_eq!(
+            play_day_window(before_cutoff),
+            PlayDayWindow {
+                day: "2026-03-01".to_string(),
+                start: "2026-03-01 04:00".to_string(),
+                end: "2026-03-02 04:00".to_string(),
+            }
+        );
+        assert_eq!(play_day_window(after_cutoff).day, "2026-03-02");
+    }
+}
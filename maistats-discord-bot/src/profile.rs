@@ -0,0 +1,268 @@
+use maimai_client::SongCatalogSong;
+use models::rating::RatingBreakdown;
+use models::{ChartType, DifficultyCategory, ParsedPlayerProfile, PlayRecordApiResponse};
+use poise::serenity_prelude as serenity;
+use serenity::builder::CreateEmbed;
+use time::UtcOffset;
+
+use crate::commands::latest_credit_len;
+use crate::embeds::{embed_base, format_played_at, format_unix_time, with_rank_plate_fields};
+use crate::rating_compare::find_song;
+
+/// Aggregate of the most recent credit in `/api/recent` (newest first).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct LatestCreditSummary {
+    pub(crate) tracks: usize,
+    pub(crate) new_records: usize,
+    pub(crate) best_achievement_percent: Option<f64>,
+    pub(crate) last_played_at: Option<String>,
+}
+
+/// The highest-scoring chart in the current NEW 15 / OLD 35, used for the profile thumbnail.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TopRatedChart {
+    pub(crate) title: String,
+    pub(crate) chart_type: ChartType,
+    pub(crate) diff_category: DifficultyCategory,
+    pub(crate) rating_points: u32,
+    pub(crate) image_name: Option<String>,
+}
+
+pub(crate) fn summarize_latest_credit(
+    recent: &[PlayRecordApiResponse],
+) -> Option<LatestCreditSummary> {
+    if recent.is_empty() {
+        return None;
+    }
+
+    let tracks = recent
+        .iter()
+        .map(|record| record.track.map(i64::from))
+        .collect::<Vec<_>>();
+    let credit = &recent[..latest_credit_len(&tracks)];
+
+    Some(LatestCreditSummary {
+        tracks: credit.len(),
+        new_records: credit
+            .iter()
+            .filter(|record| record.achievement_new_record.unwrap_or(0) != 0)
+            .count(),
+        best_achievement_percent: credit
+            .iter()
+            .filter_map(|record| record.achievement_x10000)
+            .max()
+            .map(|x| x as f64 / 10000.0),
+        last_played_at: recent[0].played_at.clone(),
+    })
+}
+
+/// Read from the collector's stored NEW 15 / OLD 35, so the profile never waits on SEGA.
+pub(crate) fn top_rated_chart(
+    breakdown: &RatingBreakdown,
+    catalog: &[SongCatalogSong],
+) -> Option<TopRatedChart> {
+    let chart = breakdown
+        .new
+        .iter()
+        .chain(&breakdown.old)
+        .max_by_key(|chart| chart.rating_points)?;
+    Some(TopRatedChart {
+        title: chart.title.clone(),
+        chart_type: chart.chart_type,
+        diff_category: chart.diff_category,
+        rating_points: chart.rating_points,
+        image_name: find_song(catalog, &chart.title, &chart.artist)
+            .and_then(|song| song.image_name.clone()),
+    })
+}
+
+/// "12800 / 13000, 200 to go", or a celebration once the goal is met.
+pub(crate) fn format_target_progress(rating: u32, target_rating: u32) -> String {
+    match target_rating.checked_sub(rating) {
+        Some(remaining) if remaining > 0 => {
+            format!("{rating} / {target_rating}, {remaining} to go")
+        }
+        _ => format!("{rating} / {target_rating} 🎉 Goal reached!"),
+    }
+}
+
+pub(crate) fn build_profile_embed(
+    profile: &ParsedPlayerProfile,
+    latest_credit: Option<&LatestCreditSummary>,
+    top_chart: Option<&TopRatedChart>,
+    last_synced_at_unixtime: Option<i64>,
+    display_offset: UtcOffset,
+) -> CreateEmbed {
+    let mut embed = embed_base(&format!("{}'s profile", profile.user_name))
+        .field("Rating", profile.rating.to_string(), true)
+        .field(
+            "Plays (version)",
+            profile.current_version_play_count.to_string(),
+            true,
+        )
+        .field("Plays (total)", profile.total_play_count.to_string(), true);
+    if let Some(area_rank) = profile.area_rank {
+        embed = embed.field("Area rank", format!("#{area_rank}"), true);
+    }
+    embed = with_rank_plate_fields(embed, profile);
+
+    match latest_credit {
+        Some(credit) => {
+            let best = credit
+                .best_achievement_percent
+                .map(|value| format!("{value:.4}%"))
+                .unwrap_or_else(|| "N/A".to_string());
+            embed = embed
+                .field("Last credit", format!("{} tracks", credit.tracks), true)
+                .field("New records", credit.new_records.to_string(), true)
+                .field("Best", best, true);
+            let last_played = credit
+                .last_played_at
+                .as_deref()
+                .map(|raw| format_played_at(raw, display_offset))
+                .unwrap_or_else(|| "-".to_string());
+            embed = embed.field("Last played", last_played, false);
+        }
+        None => {
+            embed = embed.field("Last credit", "No recent plays", false);
+        }
+    }
+
+    if let Some(chart) = top_chart {
+        embed = embed.field(
+            "Top rated chart",
+            format!(
+                "[{}] {} {} — {} pts",
+                chart.chart_type, chart.diff_category, chart.title, chart.rating_points
+            ),
+            false,
+        );
+    }
+
+    let freshness = last_synced_at_unixtime
+        .map(|unixtime| format!("Synced {}", format_unix_time(unixtime, display_offset)))
+        .unwrap_or_else(|| "Not synced yet".to_string());
+    embed.field("Data freshness", freshness, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_target_progress, summarize_latest_credit, top_rated_chart};
+    use maimai_client::{SongCatalogSheet, SongCatalogSong};
+    use models::rating::{RatedChart, RatingBreakdown, RatingBucket, RatingSelection};
+    use models::{
+        ChartType, DifficultyCategory, PlayRecordApiResponse, SongAliases, SongChartRegion,
+    };
+
+    fn play(track: i32, achievement_x10000: i64, new_record: bool) -> PlayRecordApiResponse {
+        PlayRecordApiResponse {
+            played_at_unixtime: i64::from(track),
+            played_at: Some(format!("2026/01/01 12:0{track}")),
+            track: Some(track),
+            title: "Song".to_string(),
+            genre: None,
+            artist: None,
+            chart_type: ChartType::Dx,
+            diff_category: Some(DifficultyCategory::Master),
+            achievement_x10000: Some(achievement_x10000),
+            score_rank: None,
+            fc: None,
+            sync: None,
+            dx_score: None,
+            dx_score_max: None,
+            credit_id: None,
+            achievement_new_record: Some(i32::from(new_record)),
+        }
+    }
+
+    fn song(title: &str, artist: &str) -> SongCatalogSong {
+        SongCatalogSong {
+            title: title.to_string(),
+            genre: "maimai".to_string(),
+            artist: artist.to_string(),
+            image_name: Some(format!("{title} by {artist}.png")),
+            aliases: SongAliases::default(),
+            sheets: vec![SongCatalogSheet {
+                chart_type: ChartType::Dx,
+                diff_category: DifficultyCategory::Master,
+                level: "13".to_string(),
+                version: None,
+                internal_level: Some(13.0),
+                region: SongChartRegion {
+                    jp: true,
+                    intl: true,
+                },
+            }],
+        }
+    }
+
+    fn chart(title: &str, artist: &str, internal_level: f32, bucket: RatingBucket) -> RatedChart {
+        RatedChart::new(
+            title.to_string(),
+            ChartType::Dx,
+            DifficultyCategory::Master,
+            "13".to_string(),
+            internal_level,
+            100.5,
+            None,
+            bucket,
+        )
+        .with_song_identity(artist.to_string(), "maimai".to_string())
+    }
+
+    #[test]
+    fn summarize_latest_credit_stops_at_track_one() {
+        let recent = vec![
+            play(2, 1_005_000, true),
+            play(1, 990_000, false),
+            play(4, 1_010_000, true),
+        ];
+
+        let summary = summarize_latest_credit(&recent).expect("summary");
+
+        assert_eq!(summary.tracks, 2);
+        assert_eq!(summary.new_records, 1);
+        assert_eq!(summary.best_achievement_percent, Some(100.5));
+        assert_eq!(summary.last_played_at.as_deref(), Some("2026/01/01 12:02"));
+        assert_eq!(summarize_latest_credit(&[]), None);
+    }
+
+    #[test]
+    fn top_rated_chart_picks_highest_points_and_matches_the_cover_by_artist() {
+        let catalog = vec![
+            song("Low", "Artist"),
+            song("High", "Artist A"),
+            song("High", "Artist B"),
+        ];
+        let breakdown = RatingBreakdown::from_selection(
+            RatingSelection {
+                new: vec![chart("Low", "Artist", 12.0, RatingBucket::New)],
+                old: vec![chart("High", "Artist B", 14.2, RatingBucket::Old)],
+            },
+            0,
+        );
+
+        let top = top_rated_chart(&breakdown, &catalog).expect("top chart");
+
+        assert_eq!(top.title, "High");
+        assert_eq!(top.image_name.as_deref(), Some("High by Artist B.png"));
+        assert_eq!(top.rating_points, 319);
+        assert_eq!(top_rated_chart(&RatingBreakdown::default(), &catalog), None);
+    }
+
+    #[test]
+    fn format_target_progress_counts_down_and_celebrates() {
+        assert_eq!(
+            format_target_progress(12_800, 13_000),
+            "12800 / 13000, 200 to go"
+        );
+        assert_eq!(
+            format_target_progress(13_000, 13_000),
+            "13000 / 13000 🎉 Goal reached!"
+        );
+        assert_eq!(
+            format_target_progress(13_050, 13_000),
+            "13050 / 13000 🎉 Goal reached!"
+        );
+    }
+}
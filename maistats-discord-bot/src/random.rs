@@ -0,0 +1,285 @@
+use std::collections::HashSet;
+
+use maimai_client::SongCatalogSong;
+use models::{ChartType, DifficultyCategory};
+use poise::serenity_prelude as serenity;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serenity::builder::CreateEmbed;
+
+use crate::chart_links::linked_chart_label;
+use crate::embeds::{embed_base, format_level_with_internal};
+
+/// One credit's worth of tracks.
+pub(crate) const DEFAULT_SETLIST_SIZE: usize = 4;
+pub(crate) const MAX_SETLIST_SIZE: usize = 10;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct RandomChartFilter {
+    /// Display level such as `13+`.
+    pub(crate) level: Option<String>,
+    pub(crate) min_internal_level: Option<f32>,
+    pub(crate) max_internal_level: Option<f32>,
+}
+
+impl RandomChartFilter {
+    fn matches(&self, level: &str, internal_level: Option<f32>) -> bool {
+        if self
+            .level
+            .as_deref()
+            .is_some_and(|wanted| !wanted.trim().eq_ignore_ascii_case(level))
+        {
+            return false;
+        }
+        let has_range = self.min_internal_level.is_some() || self.max_internal_level.is_some();
+        if !has_range {
+            return true;
+        }
+        let Some(internal_level) = internal_level else {
+            return false;
+        };
+        self.min_internal_level
+            .is_none_or(|min| internal_level >= min)
+            && self
+                .max_internal_level
+                .is_none_or(|max| internal_level <= max)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RandomChart {
+    pub(crate) title: String,
+    pub(crate) chart_type: ChartType,
+    pub(crate) diff_category: DifficultyCategory,
+    pub(crate) level: String,
+    pub(crate) internal_level: Option<f32>,
+    pub(crate) image_name: Option<String>,
+}
+
+/// Every INTL chart in the catalog that passes `filter`. Utage charts are left out.
+pub(crate) fn random_chart_candidates(
+    catalog: &[SongCatalogSong],
+    filter: &RandomChartFilter,
+) -> Vec<RandomChart> {
+    catalog
+        .iter()
+        .flat_map(|song| {
+            song.sheets
+                .iter()
+                .filter(|sheet| sheet.region.intl && sheet.chart_type != ChartType::Utage)
+                .filter(|sheet| filter.matches(&sheet.level, sheet.internal_level))
+                .map(|sheet| RandomChart {
+                    title: song.title.clone(),
+                    chart_type: sheet.chart_type,
+                    diff_category: sheet.diff_category,
+                    level: sheet.level.clone(),
+                    internal_level: sheet.internal_level,
+                    image_name: song.image_name.clone(),
+                })
+        })
+        .collect()
+}
+
+/// The seed to draw with: the requested one, or a fresh one that is shown with the setlist so
+/// it can be drawn again. Returned alongside the rng built from it.
+pub(crate) fn setlist_rng(seed: Option<u32>) -> (u32, StdRng) {
+    let seed = seed.unwrap_or_else(rand::random);
+    (seed, StdRng::seed_from_u64(u64::from(seed)))
+}
+
+/// Draw up to `count` charts with no song repeated. Returns fewer only when the candidates
+/// do not contain `count` distinct songs.
+///
+/// Candidates are put in a fixed order before the (uniform) shuffle, so a seeded `rng` gives
+/// the same setlist however the song catalog happens to be ordered.
+pub(crate) fn pick_setlist(
+    mut candidates: Vec<RandomChart>,
+    count: usize,
+    rng: &mut impl Rng,
+) -> Vec<RandomChart> {
+    candidates.sort_by(|a, b| {
+        (&a.title, a.chart_type, a.diff_category).cmp(&(&b.title, b.chart_type, b.diff_category))
+    });
+    candidates.shuffle(rng);
+    let mut seen_titles = HashSet::new();
+    candidates
+        .into_iter()
+        .filter(|chart| seen_titles.insert(chart.title.clone()))
+        .take(count)
+        .collect()
+}
+
+pub(crate) fn build_setlist_embed(setlist: &[RandomChart]) -> CreateEmbed {
+    let lines = setlist
+        .iter()
+        .enumerate()
+        .map(|(idx, chart)| {
+            let level = format_level_with_internal(&chart.level, chart.internal_level);
+            format!(
+                "**{}.** {}",
+                idx + 1,
+                linked_chart_label(&chart.title, chart.chart_type, chart.diff_category, &level)
+            )
+        })
+        .collect::<Vec<_>>();
+    embed_base(&format!("Random setlist ({} tracks)", setlist.len())).description(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RandomChartFilter, pick_setlist, random_chart_candidates, setlist_rng};
+    use maimai_client::{SongCatalogSheet, SongCatalogSong};
+    use models::{ChartType, DifficultyCategory, SongAliases, SongChartRegion};
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::collections::{HashMap, HashSet};
+
+    fn song(title: &str, sheets: &[(DifficultyCategory, &str, f32)]) -> SongCatalogSong {
+        SongCatalogSong {
+            title: title.to_string(),
+            genre: "maimai".to_string(),
+            artist: "Artist".to_string(),
+            image_name: None,
+            aliases: SongAliases::default(),
+            sheets: sheets
+                .iter()
+                .map(|(diff_category, level, internal_level)| SongCatalogSheet {
+                    chart_type: ChartType::Dx,
+                    diff_category: *diff_category,
+                    level: level.to_string(),
+                    version: None,
+                    internal_level: Some(*internal_level),
+                    region: SongChartRegion {
+                        jp: true,
+                        intl: true,
+                    },
+                })
+                .collect(),
+        }
+    }
+
+    fn catalog() -> Vec<SongCatalogSong> {
+        (0..6)
+            .map(|idx| {
+                song(
+                    &format!("Song {idx}"),
+                    &[
+                        (DifficultyCategory::Expert, "13", 13.0),
+                        (DifficultyCategory::Master, "13+", 13.7),
+                    ],
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pick_setlist_returns_distinct_songs_when_enough_exist() {
+        let mut catalog = catalog();
+        let mut utage = song("[宴]Song", &[(DifficultyCategory::Master, "13", 13.0)]);
+        utage.sheets[0].chart_type = ChartType::Utage;
+        catalog.push(utage);
+        let candidates = random_chart_candidates(&catalog, &RandomChartFilter::default());
+        assert_eq!(candidates.len(), 12);
+
+        for seed in 0..20 {
+            let setlist = pick_setlist(candidates.clone(), 4, &mut StdRng::seed_from_u64(seed));
+            let titles = setlist
+                .iter()
+                .map(|chart| chart.title.as_str())
+                .collect::<HashSet<_>>();
+            assert_eq!(setlist.len(), 4);
+            assert_eq!(titles.len(), 4);
+        }
+
+        let setlist = pick_setlist(candidates, 10, &mut StdRng::seed_from_u64(0));
+        assert_eq!(setlist.len(), 6);
+    }
+
+    #[test]
+    fn pick_setlist_draws_each_chart_about_equally_often() {
+        let candidates = random_chart_candidates(&catalog(), &RandomChartFilter::default());
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut counts = HashMap::new();
+        let draws = 12_000;
+        for _ in 0..draws {
+            let chart = pick_setlist(candidates.clone(), 1, &mut rng).remove(0);
+            *counts
+                .entry((chart.title, chart.diff_category))
+                .or_insert(0usize) += 1;
+        }
+
+        let expected = draws / candidates.len();
+        assert_eq!(counts.len(), candidates.len());
+        for (chart, count) in counts {
+            assert!(
+                count.abs_diff(expected) < expected / 10,
+                "{chart:?} drawn {count} times, expected about {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn pick_setlist_is_reproducible_regardless_of_catalog_order() {
+        let mut reversed_catalog = catalog();
+        reversed_catalog.reverse();
+        let forward = random_chart_candidates(&catalog(), &RandomChartFilter::default());
+        let reversed = random_chart_candidates(&reversed_catalog, &RandomChartFilter::default());
+
+        for seed in 0..10 {
+            assert_eq!(
+                pick_setlist(forward.clone(), 4, &mut StdRng::seed_from_u64(seed)),
+                pick_setlist(reversed.clone(), 4, &mut StdRng::seed_from_u64(seed))
+            );
+        }
+    }
+
+    #[test]
+    fn setlist_rng_repeats_the_same_setlist_for_the_same_seed() {
+        let candidates = random_chart_candidates(&catalog(), &RandomChartFilter::default());
+        let draw = |seed| {
+            let (used_seed, mut rng) = setlist_rng(seed);
+            (used_seed, pick_setlist(candidates.clone(), 4, &mut rng))
+        };
+
+        let (seed, first) = draw(Some(20261016));
+        assert_eq!(seed, 20261016);
+        assert_eq!(draw(Some(20261016)).1, first);
+
+        // An unseeded draw reports the seed it used, which replays it.
+        let (seed, unseeded) = draw(None);
+        assert_eq!(draw(Some(seed)).1, unseeded);
+    }
+
+    #[test]
+    fn random_chart_candidates_respect_level_and_internal_range() {
+        let by_level = random_chart_candidates(
+            &catalog(),
+            &RandomChartFilter {
+                level: Some("13+".to_string()),
+                ..RandomChartFilter::default()
+            },
+        );
+        assert_eq!(by_level.len(), 6);
+        assert!(
+            by_level
+                .iter()
+                .all(|chart| chart.diff_category == DifficultyCategory::Master)
+        );
+
+        let by_range = random_chart_candidates(
+            &catalog(),
+            &RandomChartFilter {
+                min_internal_level: Some(12.5),
+                max_internal_level: Some(13.2),
+                ..RandomChartFilter::default()
+            },
+        );
+        assert_eq!(by_range.len(), 6);
+        assert!(
+            by_range
+                .iter()
+                .all(|chart| chart.diff_category == DifficultyCategory::Expert)
+        );
+    }
+}
@@ -0,0 +1,425 @@
+use maimai_client::{SongCatalogSheet, SongCatalogSong};
+use models::rating::{RatingBucket, chart_rating_points, coefficient_for_achievement, is_ap_like};
+use models::{ChartType, DifficultyCategory, ParsedRatingTargetEntry, ParsedRatingTargets};
+use models::{FcStatus, ScoreApiResponse};
+use poise::serenity_prelude as serenity;
+use serenity::builder::CreateEmbed;
+
+use crate::embeds::embed_base;
+
+/// Maximum number of excluded charts listed in the audit embed.
+const EXCLUDED_LIST_LIMIT: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ExclusionReason {
+    MissingInternalLevel,
+    MissingAchievement,
+}
+
+impl ExclusionReason {
+    fn label(self) -> &'static str {
+        match self {
+            Self::MissingInternalLevel => "no internal level",
+            Self::MissingAchievement => "no achievement",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ExcludedChart {
+    pub(crate) title: String,
+    pub(crate) chart_type: ChartType,
+    pub(crate) diff_category: DifficultyCategory,
+    pub(crate) level: String,
+    pub(crate) reason: ExclusionReason,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RatingAudit {
+    pub(crate) computed_total: u32,
+    pub(crate) included: usize,
+    pub(crate) excluded: Vec<ExcludedChart>,
+}
+
+/// Recompute SEGA's rating set (NEW + OLD targets) from our catalog internal levels,
+/// recording which charts could not be rated and why.
+pub(crate) fn build_rating_audit(
+    targets: &ParsedRatingTargets,
+    catalog: &[SongCatalogSong],
+    rated_scores: &[ScoreApiResponse],
+) -> RatingAudit {
+    let mut audit = RatingAudit {
+        computed_total: 0,
+        included: 0,
+        excluded: Vec::new(),
+    };
+
+    for target in targets
+        .current_targets
+        .iter()
+        .chain(targets.legacy_targets.iter())
+    {
+        let exclude = |reason| ExcludedChart {
+            title: target.title.clone(),
+            chart_type: target.chart_type,
+            diff_category: target.diff_category,
+            level: target.level.clone(),
+            reason,
+        };
+        let Some(achievement_percent) = target.achievement_percent else {
+            audit
+                .excluded
+                .push(exclude(ExclusionReason::MissingAchievement));
+            continue;
+        };
+        let Some(internal_level) = target_internal_level(target, catalog) else {
+            audit
+                .excluded
+                .push(exclude(ExclusionReason::MissingInternalLevel));
+            continue;
+        };
+
+        let achievement_percent = (achievement_percent as f64 * 10000.0).round() / 10000.0;
+        let points = chart_rating_points(
+            internal_level as f64,
+            achievement_percent,
+            is_ap_like(target_fc(target, rated_scores)),
+        );
+        audit.computed_total += points;
+        audit.included += 1;
+    }
+
+    audit
+}
+
+fn target_internal_level(
+    target: &ParsedRatingTargetEntry,
+    catalog: &[SongCatalogSong],
+) -> Option<f32> {
+    catalog
+        .iter()
+        .filter(|song| song.title == target.title)
+        .flat_map(|song| song.sheets.iter())
+        .find(|sheet| {
+            sheet.chart_type == target.chart_type && sheet.diff_category == target.diff_category
+        })
+        .and_then(|sheet| sheet.internal_level)
+}
+
+fn target_fc<'a>(
+    target: &ParsedRatingTargetEntry,
+    rated_scores: &'a [ScoreApiResponse],
+) -> Option<&'a FcStatus> {
+    rated_scores
+        .iter()
+        .find(|score| {
+            score.title == target.title
+                && score.chart_type == target.chart_type
+                && score.diff_category == target.diff_category
+        })
+        .and_then(|score| score.fc.as_ref())
+}
+
+pub(crate) fn build_rating_audit_embed(
+    display_name: &str,
+    sega_rating: u32,
+    audit: &RatingAudit,
+) -> CreateEmbed {
+    let difference = i64::from(audit.computed_total) - i64::from(sega_rating);
+    let mut embed = embed_base(&format!("{display_name}'s rating audit"))
+        .field("SEGA rating", sega_rating.to_string(), true)
+        .field("Computed", audit.computed_total.to_string(), true)
+        .field("Difference", format!("{difference:+}"), true)
+        .field(
+            "Charts",
+            format!(
+                "{} rated • {} excluded",
+                audit.included,
+                audit.excluded.len()
+            ),
+            false,
+        );
+
+    if !audit.excluded.is_empty() {
+        let mut lines = audit
+            .excluded
+            .iter()
+            .take(EXCLUDED_LIST_LIMIT)
+            .map(|chart| {
+                format!(
+                    "[{}] {} {} {} — {}",
+                    chart.chart_type,
+                    chart.diff_category,
+                    chart.level,
+                    chart.title,
+                    chart.reason.label()
+                )
+            })
+            .collect::<Vec<_>>();
+        if audit.excluded.len() > EXCLUDED_LIST_LIMIT {
+            lines.push(format!(
+                "…and {} more",
+                audit.excluded.len() - EXCLUDED_LIST_LIMIT
+            ));
+        }
+        embed = embed.field("Excluded charts", lines.join("\n"), false);
+    }
+
+    embed
+}
+
+/// Where a chart sits in SEGA's current rating target lists, as `(list, 1-based position)`.
+pub(crate) fn rating_list_position(
+    targets: &ParsedRatingTargets,
+    title: &str,
+    chart_type: ChartType,
+    diff_category: DifficultyCategory,
+) -> Option<(&'static str, usize)> {
+    let position = |entries: &[ParsedRatingTargetEntry]| {
+        entries.iter().position(|entry| {
+            entry.title == title
+                && entry.chart_type == chart_type
+                && entry.diff_category == diff_category
+        })
+    };
+    position(&targets.current_targets)
+        .map(|idx| ("NEW 15", idx + 1))
+        .or_else(|| position(&targets.legacy_targets).map(|idx| ("OLD 35", idx + 1)))
+}
+
+/// The rating formula inputs and result for one chart, e.g.
+/// `floor(22.4 × 13.7 × 100.5000 / 100) + 1 (AP) = 309`.
+pub(crate) fn describe_chart_rating(
+    sheet: &SongCatalogSheet,
+    score: Option<&ScoreApiResponse>,
+) -> String {
+    let Some(internal_level) = sheet.internal_level else {
+        return "No internal level in song data".to_string();
+    };
+    let Some(achievement_x10000) = score.and_then(|score| score.achievement_x10000) else {
+        return "No achievement recorded".to_string();
+    };
+
+    let achievement_percent = achievement_x10000 as f64 / 10000.0;
+    let ap_bonus = is_ap_like(score.and_then(|score| score.fc.as_ref()));
+    let points = chart_rating_points(f64::from(internal_level), achievement_percent, ap_bonus);
+    format!(
+        "floor({:.1} × {internal_level:.1} × {:.4} / 100){} = {points}",
+        coefficient_for_achievement(achievement_percent),
+        achievement_percent.min(100.5),
+        if ap_bonus { " + 1 (AP)" } else { "" },
+    )
+}
+
+pub(crate) fn build_chart_debug_embed(
+    song: &SongCatalogSong,
+    sheet: &SongCatalogSheet,
+    score: Option<&ScoreApiResponse>,
+    targets: &ParsedRatingTargets,
+) -> CreateEmbed {
+    let bucket = match sheet.rating_bucket() {
+        RatingBucket::New => "NEW",
+        RatingBucket::Old => "OLD",
+    };
+    let song_data = [
+        format!("Level: {}", sheet.level),
+        format!(
+            "Internal level: {}",
+            sheet
+                .internal_level
+                .map(|level| format!("{level:.1}"))
+                .unwrap_or_else(|| "-".to_string())
+        ),
+        format!("Version: {}", sheet.version.as_deref().unwrap_or("-")),
+        format!("Bucket: {bucket}"),
+        format!("Image: {}", song.image_name.as_deref().unwrap_or("-")),
+    ];
+    let score_row = match score {
+        Some(score) => [
+            format!(
+                "Achievement: {}",
+                score
+                    .achievement_x10000
+                    .map(|value| format!("{:.4}%", value as f64 / 10000.0))
+                    .unwrap_or_else(|| "-".to_string())
+            ),
+            format!(
+                "Rank: {}",
+                display_opt(score.rank.map(|rank| rank.as_str()))
+            ),
+            format!("FC: {}", display_opt(score.fc.map(|fc| fc.as_str()))),
+            format!(
+                "Sync: {}",
+                display_opt(score.sync.map(|sync| sync.as_str()))
+            ),
+            format!(
+                "DX score: {}/{}",
+                display_opt(score.dx_score.map(|v| v.to_string()).as_deref()),
+                display_opt(score.dx_score_max.map(|v| v.to_string()).as_deref())
+            ),
+            format!(
+                "Last played: {}",
+                display_opt(score.last_played_at.as_deref())
+            ),
+            format!(
+                "Plays: {}",
+                display_opt(score.play_count.map(|v| v.to_string()).as_deref())
+            ),
+        ]
+        .join("\n"),
+        None => "No scores row".to_string(),
+    };
+    let rating_lists =
+        rating_list_position(targets, &song.title, sheet.chart_type, sheet.diff_category)
+            .map(|(list, position)| format!("{list} #{position}"))
+            .unwrap_or_else(|| "Not in current rating lists".to_string());
+
+    embed_base(&format!(
+        "[{}] {} {}",
+        sheet.chart_type, sheet.diff_category, song.title
+    ))
+    .field("Song data", song_data.join("\n"), false)
+    .field("Scores row", score_row, false)
+    .field("Rating", describe_chart_rating(sheet, score), false)
+    .field("Rating lists", rating_lists, false)
+}
+
+fn display_opt(value: Option<&str>) -> &str {
+    value.unwrap_or("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExclusionReason, build_rating_audit, describe_chart_rating, rating_list_position};
+    use maimai_client::{SongCatalogSheet, SongCatalogSong};
+    use models::{
+        ChartType, DifficultyCategory, FcStatus, ParsedRatingTargetEntry, ParsedRatingTargets,
+        ScoreApiResponse, SongAliases, SongChartRegion,
+    };
+
+    fn song(title: &str, internal_level: Option<f32>) -> SongCatalogSong {
+        SongCatalogSong {
+            title: title.to_string(),
+            genre: "maimai".to_string(),
+            artist: "Artist".to_string(),
+            image_name: None,
+            aliases: SongAliases::default(),
+            sheets: vec![SongCatalogSheet {
+                chart_type: ChartType::Dx,
+                diff_category: DifficultyCategory::Master,
+                level: "13+".to_string(),
+                version: None,
+                internal_level,
+                region: SongChartRegion {
+                    jp: true,
+                    intl: true,
+                },
+            }],
+        }
+    }
+
+    fn target(title: &str, achievement_percent: Option<f32>) -> ParsedRatingTargetEntry {
+        ParsedRatingTargetEntry {
+            title: title.to_string(),
+            chart_type: ChartType::Dx,
+            diff_category: DifficultyCategory::Master,
+            level: "13+".to_string(),
+            achievement_percent,
+            rank: None,
+        }
+    }
+
+    fn score(title: &str, fc: Option<FcStatus>) -> ScoreApiResponse {
+        ScoreApiResponse {
+            title: title.to_string(),
+            genre: "maimai".to_string(),
+            artist: "Artist".to_string(),
+            chart_type: ChartType::Dx,
+            diff_category: DifficultyCategory::Master,
+            achievement_x10000: Some(1_005_000),
+            rank: None,
+            fc,
+            sync: None,
+            dx_score: None,
+            dx_score_max: None,
+            last_played_at: None,
+            play_count: None,
+        }
+    }
+
+    #[test]
+    fn build_rating_audit_sums_rated_charts_and_lists_exclusions() {
+        let catalog = vec![
+            song("Known", Some(13.7)),
+            song("AP Song", Some(13.7)),
+            song("Unknown", None),
+        ];
+        let targets = ParsedRatingTargets {
+            current_targets: vec![target("Known", Some(100.5)), target("Unknown", Some(100.5))],
+            legacy_targets: vec![target("AP Song", Some(100.5)), target("Unplayed", None)],
+        };
+        let rated_scores = vec![score("AP Song", Some(FcStatus::Ap))];
+
+        let audit = build_rating_audit(&targets, &catalog, &rated_scores);
+
+        // floor(22.4 * 13.7 * 100.5 / 100) = 308, plus 1 for AP.
+        assert_eq!(audit.computed_total, 308 + 309);
+        assert_eq!(audit.included, 2);
+        assert_eq!(
+            audit
+                .excluded
+                .iter()
+                .map(|chart| (chart.title.as_str(), chart.reason))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Unknown", ExclusionReason::MissingInternalLevel),
+                ("Unplayed", ExclusionReason::MissingAchievement),
+            ]
+        );
+    }
+
+    #[test]
+    fn describe_chart_rating_shows_inputs_and_ap_bonus() {
+        let catalog = song("AP Song", Some(13.7));
+        let sheet = &catalog.sheets[0];
+
+        assert_eq!(
+            describe_chart_rating(sheet, Some(&score("AP Song", Some(FcStatus::Ap)))),
+            "floor(22.4 × 13.7 × 100.5000 / 100) + 1 (AP) = 309"
+        );
+        assert_eq!(
+            describe_chart_rating(sheet, None),
+            "No achievement recorded"
+        );
+        assert_eq!(
+            describe_chart_rating(&song("Unknown", None).sheets[0], None),
+            "No internal level in song data"
+        );
+    }
+
+    #[test]
+    fn rating_list_position_reports_list_and_rank() {
+        let targets = ParsedRatingTargets {
+            current_targets: vec![target("First", Some(100.0)), target("Second", Some(99.0))],
+            legacy_targets: vec![target("Old", Some(100.0))],
+        };
+
+        assert_eq!(
+            rating_list_position(
+                &targets,
+                "Second",
+                ChartType::Dx,
+                DifficultyCategory::Master
+            ),
+            Some(("NEW 15", 2))
+        );
+        assert_eq!(
+            rating_list_position(&targets, "Old", ChartType::Dx, DifficultyCategory::Master),
+            Some(("OLD 35", 1))
+        );
+        assert_eq!(
+            rating_list_position(&targets, "Old", ChartType::Std, DifficultyCategory::Master),
+            None
+        );
+    }
+}
@@ -0,0 +1,658 @@
+use std::collections::HashMap;
+
+use maimai_client::{SongCatalogSheet, SongCatalogSong};
+use models::rating::{
+    NEW_RATING_SET_SIZE, OLD_RATING_SET_SIZE, RankGain, RatedChart, RatingBreakdown, RatingBucket,
+    RatingSelection, is_ap_like, rating_gain_to_next_rank, select_rating_set,
+};
+use models::{ChartType, DifficultyCategory, ScoreApiResponse, ScoreRank, normalize_title};
+use poise::serenity_prelude as serenity;
+use serenity::builder::CreateEmbed;
+
+use crate::embeds::embed_base;
+
+/// Bucket for rated charts that came without a genre.
+const UNKNOWN_GENRE: &str = "Unknown";
+/// SSS+ achievement; anything above it rates the same.
+const SSS_PLUS_ACHIEVEMENT_PERCENT: f64 = 100.5;
+/// Discord's limit on an embed field value.
+const MAX_FIELD_CHARS: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RatingComparison {
+    /// NEW 15 / OLD 35 from the player's current scores.
+    pub(crate) current: RatingSelection,
+    /// NEW 15 / OLD 35 if every played chart were raised to SSS+.
+    pub(crate) potential: RatingSelection,
+}
+
+/// Rate every played chart twice: at its recorded achievement and at SSS+. Charts without an
+/// internal level in the catalog are skipped in both.
+pub(crate) fn build_rating_comparison(
+    rated_scores: &[ScoreApiResponse],
+    catalog: &[SongCatalogSong],
+) -> RatingComparison {
+    RatingComparison {
+        current: select_rating_set(rated_charts(rated_scores, catalog, |achievement| {
+            achievement
+        })),
+        potential: select_rating_set(rated_charts(rated_scores, catalog, |achievement| {
+            achievement.max(SSS_PLUS_ACHIEVEMENT_PERCENT)
+        })),
+    }
+}
+
+/// Every played chart with a known internal level, rated at `achievement(recorded)`.
+fn rated_charts(
+    rated_scores: &[ScoreApiResponse],
+    catalog: &[SongCatalogSong],
+    achievement: impl Fn(f64) -> f64,
+) -> Vec<RatedChart> {
+    rated_scores
+        .iter()
+        .filter_map(|score| {
+            let achievement_x10000 = score.achievement_x10000?;
+            let sheet = find_sheet(catalog, score)?;
+            let internal_level = sheet.internal_level?;
+            Some(
+                RatedChart::new(
+                    score.title.clone(),
+                    score.chart_type,
+                    score.diff_category,
+                    sheet.level.clone(),
+                    internal_level,
+                    achievement(achievement_x10000 as f64 / 10000.0),
+                    score.fc,
+                    sheet.rating_bucket(),
+                )
+                .with_song_identity(score.artist.clone(), score.genre.clone()),
+            )
+        })
+        .collect()
+}
+
+/// Achievements the chart advisor evaluates: SS+, SSS, and SSS+.
+const CHART_TARGET_ACHIEVEMENTS: [f64; 3] = [99.5, 100.0, SSS_PLUS_ACHIEVEMENT_PERCENT];
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ChartTargetCheckpoint {
+    pub(crate) rank: ScoreRank,
+    pub(crate) achievement_percent: f64,
+    /// The chart's own rating points at this achievement.
+    pub(crate) chart_points: u32,
+    /// How much the player's rating would rise.
+    pub(crate) rating_gain: u32,
+    /// Whether the chart would sit in the NEW 15 / OLD 35.
+    pub(crate) counts_toward_rating: bool,
+    /// The player's best on this chart is already at or above this achievement.
+    pub(crate) already_reached: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ChartTarget {
+    pub(crate) internal_level: f32,
+    pub(crate) bucket: RatingBucket,
+    pub(crate) current_achievement_percent: Option<f64>,
+    pub(crate) checkpoints: Vec<ChartTargetCheckpoint>,
+}
+
+/// What reaching SS+ / SSS / SSS+ on one chart would add to the player's rating, found by
+/// re-running the NEW 15 / OLD 35 selection with that chart raised. `None` when the chart has
+/// no known internal level.
+pub(crate) fn build_chart_target(
+    rated_scores: &[ScoreApiResponse],
+    catalog: &[SongCatalogSong],
+    song: &SongCatalogSong,
+    sheet: &SongCatalogSheet,
+) -> Option<ChartTarget> {
+    let internal_level = sheet.internal_level?;
+    let is_target = |title: &str, chart_type: ChartType, diff_category: DifficultyCategory| {
+        title == song.title
+            && chart_type == sheet.chart_type
+            && diff_category == sheet.diff_category
+    };
+
+    let current = rated_charts(rated_scores, catalog, |achievement| achievement);
+    let current_total = select_rating_set(current.clone()).total();
+    let existing = rated_scores.iter().find(|score| {
+        score.artist == song.artist
+            && is_target(&score.title, score.chart_type, score.diff_category)
+    });
+    let current_achievement_percent = existing
+        .and_then(|score| score.achievement_x10000)
+        .map(|achievement_x10000| achievement_x10000 as f64 / 10000.0);
+    let others = current
+        .into_iter()
+        .filter(|chart| !is_target(&chart.title, chart.chart_type, chart.diff_category))
+        .collect::<Vec<_>>();
+
+    let checkpoints = CHART_TARGET_ACHIEVEMENTS
+        .into_iter()
+        .map(|target_percent| {
+            let already_reached =
+                current_achievement_percent.is_some_and(|current| current >= target_percent);
+            let (achievement_percent, fc) = match (already_reached, existing) {
+                (true, Some(score)) => (
+                    current_achievement_percent.unwrap_or(target_percent),
+                    score.fc,
+                ),
+                _ => (target_percent, None),
+            };
+            let chart = RatedChart::new(
+                song.title.clone(),
+                sheet.chart_type,
+                sheet.diff_category,
+                sheet.level.clone(),
+                internal_level,
+                achievement_percent,
+                fc,
+                sheet.rating_bucket(),
+            )
+            .with_song_identity(song.artist.clone(), song.genre.clone());
+            let chart_points = chart.rating_points;
+            let selection = select_rating_set(others.iter().cloned().chain([chart]));
+            let counts_toward_rating = selection
+                .new
+                .iter()
+                .chain(&selection.old)
+                .any(|chart| is_target(&chart.title, chart.chart_type, chart.diff_category));
+            ChartTargetCheckpoint {
+                rank: ScoreRank::from_achievement(target_percent as f32),
+                achievement_percent: target_percent,
+                chart_points,
+                rating_gain: selection.total().saturating_sub(current_total),
+                counts_toward_rating,
+                already_reached,
+            }
+        })
+        .collect();
+
+    Some(ChartTarget {
+        internal_level,
+        bucket: sheet.rating_bucket(),
+        current_achievement_percent,
+        checkpoints,
+    })
+}
+
+/// Most charts `/mai-suggest` lists.
+pub(crate) const MAX_RANK_SUGGESTIONS: usize = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RankSuggestion {
+    pub(crate) chart: RatedChart,
+    pub(crate) gain: RankGain,
+}
+
+impl RankSuggestion {
+    /// Rating points per percent of achievement still to gain.
+    fn value(&self) -> f64 {
+        let gap = self.gain.target_achievement_percent - self.chart.achievement_percent;
+        f64::from(self.gain.points_gained) / gap.max(0.0001)
+    }
+}
+
+/// Charts in the current NEW 15 / OLD 35 whose next rank cutoff adds the most rating per
+/// percent of achievement. Charts at SSS+ or gaining nothing are left out; charts without
+/// an internal level never enter the rating set.
+pub(crate) fn build_rank_suggestions(
+    rated_scores: &[ScoreApiResponse],
+    catalog: &[SongCatalogSong],
+) -> Vec<RankSuggestion> {
+    let selection = select_rating_set(rated_charts(rated_scores, catalog, |achievement| {
+        achievement
+    }));
+    let mut suggestions = selection
+        .new
+        .into_iter()
+        .chain(selection.old)
+        .filter_map(|chart| {
+            let gain = rating_gain_to_next_rank(
+                f64::from(chart.internal_level),
+                chart.achievement_percent,
+                is_ap_like(chart.fc.as_ref()),
+            )?;
+            (gain.points_gained > 0).then_some(RankSuggestion { chart, gain })
+        })
+        .collect::<Vec<_>>();
+    suggestions.sort_by(|a, b| {
+        b.value()
+            .total_cmp(&a.value())
+            .then_with(|| b.gain.points_gained.cmp(&a.gain.points_gained))
+            .then_with(|| a.chart.title.cmp(&b.chart.title))
+    });
+    suggestions.truncate(MAX_RANK_SUGGESTIONS);
+    suggestions
+}
+
+/// Title match, disambiguated by artist when several songs share a title; `None` when they
+/// do and none is by `artist`, so another song's data never stands in.
+pub(crate) fn find_song<'a>(
+    catalog: &'a [SongCatalogSong],
+    title: &str,
+    artist: &str,
+) -> Option<&'a SongCatalogSong> {
+    let title = normalize_title(title);
+    let songs = catalog
+        .iter()
+        .filter(|song| normalize_title(&song.title) == title)
+        .collect::<Vec<_>>();
+    match songs.as_slice() {
+        [only] => Some(*only),
+        _ => songs.into_iter().find(|song| song.artist == artist),
+    }
+}
+
+pub(crate) fn find_sheet<'a>(
+    catalog: &'a [SongCatalogSong],
+    score: &ScoreApiResponse,
+) -> Option<&'a SongCatalogSheet> {
+    let song = find_song(catalog, &score.title, &score.artist)?;
+    song.sheets.iter().find(|sheet| {
+        sheet.chart_type == score.chart_type && sheet.diff_category == score.diff_category
+    })
+}
+
+pub(crate) fn build_rating_comparison_embed(
+    display_name: &str,
+    comparison: &RatingComparison,
+) -> CreateEmbed {
+    let row = |current: u32, potential: u32| {
+        format!(
+            "{current} → {potential} (+{})",
+            potential.saturating_sub(current)
+        )
+    };
+    let current = &comparison.current;
+    let potential = &comparison.potential;
+
+    embed_base(&format!("{display_name}'s rating by version"))
+        .description("Current rating vs. every played chart at SSS+.")
+        .field("Total", row(current.total(), potential.total()), false)
+        .field(
+            format!("NEW {} only", current.new.len()),
+            row(current.new_sum(), potential.new_sum()),
+            true,
+        )
+        .field(
+            format!("OLD {} only", current.old.len()),
+            row(current.old_sum(), potential.old_sum()),
+            true,
+        )
+}
+
+fn format_rated_chart_lines(charts: &[RatedChart]) -> String {
+    if charts.is_empty() {
+        return "None".to_string();
+    }
+    let mut value = String::new();
+    for (idx, chart) in charts.iter().enumerate() {
+        let line = format!(
+            "{}. **{}** [{}] {} ({:.1}) — {:.4}%: {} pt\n",
+            idx + 1,
+            chart.title,
+            chart.chart_type,
+            chart.diff_category,
+            chart.internal_level,
+            chart.achievement_percent,
+            chart.rating_points
+        );
+        if value.len() + line.len() > MAX_FIELD_CHARS {
+            break;
+        }
+        value.push_str(&line);
+    }
+    value
+}
+
+/// Renders the collector's breakdown as served; the bot does not re-select charts here.
+pub(crate) fn build_rating_breakdown_embed(
+    display_name: &str,
+    breakdown: &RatingBreakdown,
+) -> CreateEmbed {
+    let embed = embed_base(&format!("{display_name}'s rating"))
+        .field("Total", breakdown.total.to_string(), false)
+        .field(
+            format!("NEW {}", breakdown.new.len()),
+            breakdown.new_sum.to_string(),
+            true,
+        )
+        .field(
+            format!("OLD {}", breakdown.old.len()),
+            breakdown.old_sum.to_string(),
+            true,
+        )
+        .field(
+            "NEW charts",
+            format_rated_chart_lines(&breakdown.new),
+            false,
+        )
+        .field(
+            "OLD charts",
+            format_rated_chart_lines(&breakdown.old),
+            false,
+        );
+    if breakdown.missing == 0 {
+        return embed;
+    }
+    embed.footer(serenity::CreateEmbedFooter::new(format!(
+        "{} played chart(s) have no internal level in song data and were left out.",
+        breakdown.missing
+    )))
+}
+
+/// The NEW 15 / OLD 35 points split by the genre stored with each chart, most points first.
+pub(crate) fn rating_points_by_genre(breakdown: &RatingBreakdown) -> Vec<(String, u32)> {
+    let mut points_by_genre = HashMap::<&str, u32>::new();
+    for chart in breakdown.new.iter().chain(&breakdown.old) {
+        let genre = match chart.genre.trim() {
+            "" => UNKNOWN_GENRE,
+            genre => genre,
+        };
+        *points_by_genre.entry(genre).or_default() += chart.rating_points;
+    }
+    let mut points_by_genre = points_by_genre
+        .into_iter()
+        .map(|(genre, points)| (genre.to_string(), points))
+        .collect::<Vec<_>>();
+    points_by_genre.sort_by(|(a_genre, a_points), (b_genre, b_points)| {
+        b_points.cmp(a_points).then_with(|| a_genre.cmp(b_genre))
+    });
+    points_by_genre
+}
+
+pub(crate) fn format_genre_breakdown(points_by_genre: &[(String, u32)]) -> String {
+    if points_by_genre.is_empty() {
+        return "No rated charts".to_string();
+    }
+    points_by_genre
+        .iter()
+        .map(|(genre, points)| format!("{genre}: {points}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub(crate) fn build_chart_target_embed(
+    song: &SongCatalogSong,
+    sheet: &SongCatalogSheet,
+    target: &ChartTarget,
+) -> CreateEmbed {
+    let (bucket, set_size) = match target.bucket {
+        RatingBucket::New => ("NEW", NEW_RATING_SET_SIZE),
+        RatingBucket::Old => ("OLD", OLD_RATING_SET_SIZE),
+    };
+    let current = target
+        .current_achievement_percent
+        .map(|percent| format!("{percent:.4}%"))
+        .unwrap_or_else(|| "Unplayed".to_string());
+    let lines = target
+        .checkpoints
+        .iter()
+        .map(|checkpoint| {
+            let outcome = if checkpoint.already_reached {
+                "already reached".to_string()
+            } else if checkpoint.counts_toward_rating {
+                format!("+{} rating, in {bucket} {set_size}", checkpoint.rating_gain)
+            } else {
+                format!("+0 rating, outside {bucket} {set_size}")
+            };
+            format!(
+                "**{}** ({:.1}%): {} pt — {outcome}",
+                checkpoint.rank.as_str(),
+                checkpoint.achievement_percent,
+                checkpoint.chart_points
+            )
+        })
+        .collect::<Vec<_>>();
+
+    embed_base(&song.title).description(format!(
+        "{} {} {} ({:.1}, {bucket})\nCurrent: {current}\n\n{}",
+        sheet.chart_type,
+        sheet.diff_category,
+        sheet.level,
+        target.internal_level,
+        lines.join("\n")
+    ))
+}
+
+pub(crate) fn build_rank_suggestions_embed(
+    display_name: &str,
+    suggestions: &[RankSuggestion],
+) -> CreateEmbed {
+    let embed = embed_base(&format!("{display_name}'s charts to push"));
+    if suggestions.is_empty() {
+        return embed.description("Every chart in your rating set is already at SSS+.");
+    }
+    let lines = suggestions
+        .iter()
+        .enumerate()
+        .map(|(idx, suggestion)| {
+            let chart = &suggestion.chart;
+            let bucket = match chart.bucket {
+                RatingBucket::New => "NEW",
+                RatingBucket::Old => "OLD",
+            };
+            format!(
+                "{}. **{}** [{}] {} {} ({:.1}, {bucket}) — {:.4}% → {} ({:.1}%): +{} pt",
+                idx + 1,
+                chart.title,
+                chart.chart_type,
+                chart.diff_category,
+                chart.level,
+                chart.internal_level,
+                chart.achievement_percent,
+                suggestion.gain.rank.as_str(),
+                suggestion.gain.target_achievement_percent,
+                suggestion.gain.points_gained
+            )
+        })
+        .collect::<Vec<_>>();
+    embed
+        .description(lines.join("\n"))
+        .footer(serenity::CreateEmbedFooter::new(
+            "Ranked by rating points per percent of achievement to the next rank.",
+        ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        build_chart_target, build_rank_suggestions, build_rating_comparison, find_sheet,
+        rating_points_by_genre,
+    };
+    use maimai_client::{SongCatalogSheet, SongCatalogSong};
+    use models::rating::RatingBreakdown;
+    use models::{ChartType, DifficultyCategory, ScoreApiResponse, SongAliases, SongChartRegion};
+
+    fn song(title: &str, version: &str, internal_level: Option<f32>) -> SongCatalogSong {
+        SongCatalogSong {
+            title: title.to_string(),
+            genre: "maimai".to_string(),
+            artist: "Artist".to_string(),
+            image_name: None,
+            aliases: SongAliases::default(),
+            sheets: vec![SongCatalogSheet {
+                chart_type: ChartType::Dx,
+                diff_category: DifficultyCategory::Master,
+                level: "13".to_string(),
+                version: Some(version.to_string()),
+                internal_level,
+                region: SongChartRegion {
+                    jp: true,
+                    intl: true,
+                },
+            }],
+        }
+    }
+
+    fn score(title: &str, achievement_x10000: i64) -> ScoreApiResponse {
+        ScoreApiResponse {
+            title: title.to_string(),
+            genre: "maimai".to_string(),
+            artist: "Artist".to_string(),
+            chart_type: ChartType::Dx,
+            diff_category: DifficultyCategory::Master,
+            achievement_x10000: Some(achievement_x10000),
+            rank: None,
+            fc: None,
+            sync: None,
+            dx_score: None,
+            dx_score_max: None,
+            last_played_at: None,
+            play_count: None,
+        }
+    }
+
+    #[test]
+    fn rating_points_by_genre_sums_the_stored_genres() {
+        let catalog = vec![
+            song("New Song", "CiRCLE", Some(13.0)),
+            song("Old Song", "FESTiVAL", Some(13.0)),
+            song("Other Old", "FESTiVAL", Some(12.0)),
+        ];
+        let mut touhou = score("Old Song", 990_000);
+        touhou.genre = "東方Project".to_string();
+        let mut unknown = score("Other Old", 1_000_000);
+        unknown.genre = String::new();
+        let scores = vec![score("New Song", 1_005_000), touhou, unknown];
+        let current = build_rating_comparison(&scores, &catalog).current;
+        let breakdown = RatingBreakdown::from_selection(current, 0);
+
+        // 292 (New Song), 267 (Old Song), floor(21.6 * 12.0 * 100.0 / 100) = 259 (Other Old).
+        assert_eq!(
+            rating_points_by_genre(&breakdown),
+            vec![
+                ("maimai".to_string(), 292),
+                ("東方Project".to_string(), 267),
+                ("Unknown".to_string(), 259),
+            ]
+        );
+    }
+
+    #[test]
+    fn build_rating_comparison_splits_buckets_and_raises_charts_to_sss_plus() {
+        let catalog = vec![
+            song("New Song", "CiRCLE", Some(13.0)),
+            song("Old Song", "FESTiVAL", Some(13.0)),
+            song("Unknown Level", "FESTiVAL", None),
+        ];
+        let scores = vec![
+            score("New Song", 1_005_000),
+            score("Old Song", 990_000),
+            score("Unknown Level", 1_000_000),
+        ];
+
+        let comparison = build_rating_comparison(&scores, &catalog);
+
+        // floor(22.4 * 13.0 * 100.5 / 100) = 292; floor(20.8 * 13.0 * 99.0 / 100) = 267.
+        assert_eq!(comparison.current.new_sum(), 292);
+        assert_eq!(comparison.current.old_sum(), 267);
+        assert_eq!(comparison.current.total(), 559);
+        assert_eq!(comparison.potential.new_sum(), 292);
+        assert_eq!(comparison.potential.old_sum(), 292);
+        assert_eq!(comparison.potential.total(), 584);
+        assert_eq!(
+            comparison.current.new.len() + comparison.current.old.len(),
+            2
+        );
+    }
+
+    #[test]
+    fn build_chart_target_simulates_rating_at_each_checkpoint() {
+        // Fifteen NEW charts already at 292 points fill the NEW 15.
+        let mut catalog = (0..15)
+            .map(|idx| song(&format!("Filler {idx}"), "CiRCLE", Some(13.0)))
+            .collect::<Vec<_>>();
+        let mut scores = (0..15)
+            .map(|idx| score(&format!("Filler {idx}"), 1_005_000))
+            .collect::<Vec<_>>();
+        catalog.push(song("Target", "CiRCLE", Some(13.4)));
+        scores.push(score("Target", 990_000));
+        let target_song = catalog.last().expect("target song");
+        let target_sheet = &target_song.sheets[0];
+
+        let target = build_chart_target(&scores, &catalog, target_song, target_sheet)
+            .expect("target has an internal level");
+
+        assert_eq!(target.current_achievement_percent, Some(99.0));
+        let summary = target
+            .checkpoints
+            .iter()
+            .map(|checkpoint| {
+                (
+                    checkpoint.chart_points,
+                    checkpoint.rating_gain,
+                    checkpoint.counts_toward_rating,
+                )
+            })
+            .collect::<Vec<_>>();
+        // floor(21.1 * 13.4 * 99.5 / 100) = 281, floor(21.6 * 13.4) = 289,
+        // floor(22.4 * 13.4 * 100.5 / 100) = 301 which displaces one 292 filler.
+        assert_eq!(
+            summary,
+            vec![(281, 0, false), (289, 0, false), (301, 9, true)]
+        );
+
+        let unknown = song("Unknown", "CiRCLE", None);
+        assert!(build_chart_target(&scores, &catalog, &unknown, &unknown.sheets[0]).is_none());
+    }
+
+    #[test]
+    fn build_rank_suggestions_prefers_cheap_gains_and_skips_maxed_charts() {
+        let catalog = vec![
+            song("Near SSS", "FESTiVAL", Some(13.0)),
+            song("Far SSS", "FESTiVAL", Some(13.0)),
+            song("Maxed", "FESTiVAL", Some(13.0)),
+            song("Unknown Level", "FESTiVAL", None),
+        ];
+        let scores = vec![
+            score("Near SSS", 999_900),
+            score("Far SSS", 995_000),
+            score("Maxed", 1_005_000),
+            score("Unknown Level", 990_000),
+        ];
+
+        let suggestions = build_rank_suggestions(&scores, &catalog);
+
+        assert_eq!(
+            suggestions
+                .iter()
+                .map(|suggestion| (
+                    suggestion.chart.title.as_str(),
+                    suggestion.gain.rank.as_str(),
+                    suggestion.gain.points_gained,
+                ))
+                .collect::<Vec<_>>(),
+            // floor(21.6 * 13.0) - floor(21.1 * 13.0 * 99.99 / 100) = 280 - 274 over 0.01%;
+            // floor(21.6 * 13.0) - floor(21.1 * 13.0 * 99.5 / 100) = 280 - 272 over 0.5%.
+            vec![("Near SSS", "SSS", 6), ("Far SSS", "SSS", 8)]
+        );
+    }
+
+    #[test]
+    fn find_sheet_requires_the_artist_among_same_title_songs() {
+        let by = |artist: &str, internal_level: f32| SongCatalogSong {
+            artist: artist.to_string(),
+            ..song("Link", "CiRCLE", Some(internal_level))
+        };
+        let catalog = vec![by("Artist A", 12.3), by("Artist B", 12.6)];
+        let level = |artist: &str| {
+            find_sheet(
+                &catalog,
+                &ScoreApiResponse {
+                    artist: artist.to_string(),
+                    ..score("Link", 1_000_000)
+                },
+            )
+            .and_then(|sheet| sheet.internal_level)
+        };
+
+        assert_eq!(level("Artist A"), Some(12.3));
+        assert_eq!(level("Artist B"), Some(12.6));
+        assert_eq!(level("Unknown"), None);
+
+        // A unique title still matches whatever the stored artist spelling.
+        let unique = vec![by("Artist A", 12.3)];
+        assert!(find_sheet(&unique, &score("Link", 1_000_000)).is_some());
+    }
+}
@@ -0,0 +1,150 @@
+use models::RatingSnapshotApiResponse;
+use poise::serenity_prelude as serenity;
+use serenity::builder::CreateEmbed;
+
+use crate::embeds::embed_base;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub(crate) enum HistoryWindow {
+    #[name = "7 days"]
+    Week,
+    #[name = "30 days"]
+    Month,
+}
+
+impl HistoryWindow {
+    pub(crate) fn days(self) -> i64 {
+        match self {
+            Self::Week => 7,
+            Self::Month => 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RatingHistorySummary {
+    pub(crate) start_rating: u32,
+    pub(crate) end_rating: u32,
+    /// Rating changes recorded inside the window.
+    pub(crate) changes: usize,
+    /// Plays since the baseline snapshot, up to the current profile when it is known.
+    pub(crate) plays: u32,
+}
+
+impl RatingHistorySummary {
+    pub(crate) fn delta(&self) -> i64 {
+        i64::from(self.end_rating) - i64::from(self.start_rating)
+    }
+}
+
+/// `history` is oldest first and may lead with the snapshot in effect before `since`, which
+/// serves as the baseline. `None` when nothing has been recorded yet.
+///
+/// Snapshots are only written when the rating changes, so plays are counted up to
+/// `current_play_count` (the live profile) rather than the last snapshot; without it the last
+/// snapshot is the best known count.
+pub(crate) fn summarize_rating_history(
+    history: &[RatingSnapshotApiResponse],
+    since: i64,
+    current_play_count: Option<u32>,
+) -> Option<RatingHistorySummary> {
+    let first = history.first()?;
+    let last = history.last()?;
+    let baseline = history
+        .iter()
+        .rev()
+        .find(|snapshot| snapshot.recorded_at <= since)
+        .unwrap_or(first);
+    let current_play_count = current_play_count.unwrap_or(last.total_play_count);
+    Some(RatingHistorySummary {
+        start_rating: first.rating,
+        end_rating: last.rating,
+        changes: history
+            .iter()
+            .skip(1)
+            .filter(|snapshot| snapshot.recorded_at >= since)
+            .count(),
+        plays: current_play_count.saturating_sub(baseline.total_play_count),
+    })
+}
+
+pub(crate) fn build_rating_history_embed(
+    display_name: &str,
+    window: HistoryWindow,
+    summary: Option<&RatingHistorySummary>,
+) -> CreateEmbed {
+    let embed = embed_base(&format!(
+        "{display_name}'s rating over the last {} days",
+        window.days()
+    ));
+    let Some(summary) = summary else {
+        return embed.description("No rating history has been recorded yet.");
+    };
+    embed
+        .field("Start", summary.start_rating.to_string(), true)
+        .field("Now", summary.end_rating.to_string(), true)
+        .field("Change", format!("{:+}", summary.delta()), true)
+        .field("Rating updates", summary.changes.to_string(), true)
+        .field("Plays", summary.plays.to_string(), true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RatingHistorySummary, summarize_rating_history};
+    use models::RatingSnapshotApiResponse;
+
+    fn snapshot(recorded_at: i64, rating: u32, total_play_count: u32) -> RatingSnapshotApiResponse {
+        RatingSnapshotApiResponse {
+            recorded_at,
+            rating,
+            total_play_count,
+        }
+    }
+
+    #[test]
+    fn summarize_rating_history_measures_from_the_baseline_snapshot() {
+        let history = vec![
+            snapshot(50, 15_000, 100),
+            snapshot(150, 15_020, 104),
+            snapshot(250, 15_041, 110),
+        ];
+
+        let summary = summarize_rating_history(&history, 100, None).expect("summary");
+
+        assert_eq!(
+            summary,
+            RatingHistorySummary {
+                start_rating: 15_000,
+                end_rating: 15_041,
+                changes: 2,
+                plays: 10,
+            }
+        );
+        assert_eq!(summary.delta(), 41);
+        assert_eq!(summarize_rating_history(&[], 100, None), None);
+
+        let quiet = summarize_rating_history(&history[..1], 100, None).expect("summary");
+        assert_eq!((quiet.delta(), quiet.changes), (0, 0));
+    }
+
+    #[test]
+    fn summarize_rating_history_counts_plays_after_the_last_rating_change() {
+        let history = vec![
+            snapshot(50, 15_000, 100),
+            snapshot(150, 15_020, 104),
+            snapshot(250, 15_041, 110),
+        ];
+
+        // 15 plays after the last change that moved no rating.
+        let summary = summarize_rating_history(&history, 100, Some(125)).expect("summary");
+        assert_eq!(summary.plays, 25);
+
+        // The baseline is the latest snapshot at or before `since`, not the first row.
+        let summary = summarize_rating_history(&history, 200, Some(125)).expect("summary");
+        assert_eq!(summary.plays, 21);
+
+        // Without any change in the window, plays still come from the live profile.
+        let quiet = summarize_rating_history(&history, 300, Some(118)).expect("summary");
+        assert_eq!((quiet.changes, quiet.plays), (0, 8));
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+
+use eyre::WrapErr;
+use models::{ChartType, DifficultyCategory, ScoreApiResponse, StoredScoreEntry};
+use poise::serenity_prelude as serenity;
+use serde::Deserialize;
+use serenity::builder::CreateEmbed;
+
+use crate::embeds::embed_base;
+
+/// Largest `db export` file the bot will download.
+pub(crate) const MAX_SCORE_DUMP_BYTES: u32 = 16 * 1024 * 1024;
+const MAX_LINES_PER_SIDE: usize = 10;
+/// Discord's limit on an embed field value.
+const MAX_FIELD_CHARS: usize = 1024;
+
+/// The part of a record collector `db export` document this command reads.
+#[derive(Debug, Deserialize)]
+struct ScoreDump {
+    scores: Vec<StoredScoreEntry>,
+}
+
+type ChartKey = (String, ChartType, DifficultyCategory);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ChartDelta {
+    pub(crate) title: String,
+    pub(crate) chart_type: ChartType,
+    pub(crate) diff_category: DifficultyCategory,
+    pub(crate) mine_x10000: i64,
+    pub(crate) theirs_x10000: i64,
+}
+
+impl ChartDelta {
+    fn delta_x10000(&self) -> i64 {
+        self.theirs_x10000 - self.mine_x10000
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ScoreComparison {
+    /// Charts where the other player is ahead, largest gap first.
+    pub(crate) they_lead: Vec<ChartDelta>,
+    /// Charts where you are ahead, largest gap first.
+    pub(crate) you_lead: Vec<ChartDelta>,
+    pub(crate) tied: usize,
+    /// Charts only one of the two players has an achievement on.
+    pub(crate) skipped: usize,
+}
+
+/// Played charts from a `db export` JSON document, keyed by `(title, chart_type, diff)`.
+/// Rows with an unknown chart type or difficulty are ignored.
+pub(crate) fn parse_score_dump(bytes: &[u8]) -> eyre::Result<HashMap<ChartKey, i64>> {
+    let dump: ScoreDump = serde_json::from_slice(bytes).wrap_err("parse score export")?;
+    let mut scores = HashMap::new();
+    for entry in dump.scores {
+        let (Ok(chart_type), Ok(diff_category), Some(achievement_x10000)) = (
+            entry.chart_type.parse::<ChartType>(),
+            entry.diff_category.parse::<DifficultyCategory>(),
+            entry.achievement_x10000,
+        ) else {
+            continue;
+        };
+        keep_best(
+            &mut scores,
+            (entry.title, chart_type, diff_category),
+            achievement_x10000,
+        );
+    }
+    Ok(scores)
+}
+
+/// Charts that share a title across genres collapse onto one key; the best score wins.
+fn keep_best(scores: &mut HashMap<ChartKey, i64>, key: ChartKey, achievement_x10000: i64) {
+    scores
+        .entry(key)
+        .and_modify(|best| *best = (*best).max(achievement_x10000))
+        .or_insert(achievement_x10000);
+}
+
+pub(crate) fn compare_scores(
+    mine: &[ScoreApiResponse],
+    theirs: &HashMap<ChartKey, i64>,
+) -> ScoreComparison {
+    let mut my_scores = HashMap::new();
+    for score in mine {
+        if let Some(achievement_x10000) = score.achievement_x10000 {
+            keep_best(
+                &mut my_scores,
+                (score.title.clone(), score.chart_type, score.diff_category),
+                achievement_x10000,
+            );
+        }
+    }
+
+    let mut comparison = ScoreComparison::default();
+    for ((title, chart_type, diff_category), mine_x10000) in &my_scores {
+        let Some(theirs_x10000) = theirs.get(&(title.clone(), *chart_type, *diff_category)) else {
+            comparison.skipped += 1;
+            continue;
+        };
+        let delta = ChartDelta {
+            title: title.clone(),
+            chart_type: *chart_type,
+            diff_category: *diff_category,
+            mine_x10000: *mine_x10000,
+            theirs_x10000: *theirs_x10000,
+        };
+        match delta.delta_x10000().signum() {
+            1 => comparison.they_lead.push(delta),
+            -1 => comparison.you_lead.push(delta),
+            _ => comparison.tied += 1,
+        }
+    }
+    comparison.skipped += theirs
+        .keys()
+        .filter(|key| !my_scores.contains_key(*key))
+        .count();
+
+    let by_gap = |a: &ChartDelta, b: &ChartDelta| {
+        b.delta_x10000()
+            .abs()
+            .cmp(&a.delta_x10000().abs())
+            .then_with(|| a.title.cmp(&b.title))
+            .then_with(|| (a.chart_type, a.diff_category).cmp(&(b.chart_type, b.diff_category)))
+    };
+    comparison.they_lead.sort_by(by_gap);
+    comparison.you_lead.sort_by(by_gap);
+    comparison
+}
+
+fn format_delta_lines(deltas: &[ChartDelta]) -> String {
+    if deltas.is_empty() {
+        return "None".to_string();
+    }
+    let mut value = String::new();
+    for delta in deltas.iter().take(MAX_LINES_PER_SIDE) {
+        let line = format!(
+            "**{}** [{}] {} — {:.4}% vs {:.4}% ({:+.4})\n",
+            delta.title,
+            delta.chart_type,
+            delta.diff_category.as_str(),
+            delta.mine_x10000 as f64 / 10000.0,
+            delta.theirs_x10000 as f64 / 10000.0,
+            delta.delta_x10000() as f64 / 10000.0,
+        );
+        if value.len() + line.len() > MAX_FIELD_CHARS {
+            break;
+        }
+        value.push_str(&line);
+    }
+    value
+}
+
+pub(crate) fn build_score_comparison_embed(
+    display_name: &str,
+    other_name: &str,
+    comparison: &ScoreComparison,
+) -> CreateEmbed {
+    embed_base(&format!("{display_name} vs {other_name}"))
+        .description("Achievements are shown as yours vs theirs.")
+        .field(
+            format!("{other_name} is ahead ({})", comparison.they_lead.len()),
+            format_delta_lines(&comparison.they_lead),
+            false,
+        )
+        .field(
+            format!("You are ahead ({})", comparison.you_lead.len()),
+            format_delta_lines(&comparison.you_lead),
+            false,
+        )
+        .footer(serenity::CreateEmbedFooter::new(format!(
+            "Tied: {} • Skipped (played by only one of you): {}",
+            comparison.tied, comparison.skipped
+        )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_scores, parse_score_dump};
+    use models::{ChartType, DifficultyCategory, ScoreApiResponse};
+
+    fn score(title: &str, diff_category: DifficultyCategory, achievement: i64) -> ScoreApiResponse {
+        ScoreApiResponse {
+            title: title.to_string(),
+            genre: "maimai".to_string(),
+            artist: "Artist".to_string(),
+            chart_type: ChartType::Dx,
+            diff_category,
+            achievement_x10000: Some(achievement),
+            rank: None,
+            fc: None,
+            sync: None,
+            dx_score: None,
+            dx_score_max: None,
+            last_played_at: None,
+            play_count: None,
+        }
+    }
+
+    #[test]
+    fn compare_scores_splits_leads_sorted_by_gap_and_counts_skips() {
+        let dump = br#"{
+            "exported_at": 0,
+            "scores": [
+                {"title": "A", "genre": "maimai", "artist": "x", "chart_type": "DX",
+                 "diff_category": "MASTER", "achievement_x10000": 1005000, "rank": null,
+                 "fc": null, "sync": null, "dx_score": null, "dx_score_max": null,
+                 "last_played_at": null, "play_count": null},
+                {"title": "B", "genre": "maimai", "artist": "x", "chart_type": "DX",
+                 "diff_category": "MASTER", "achievement_x10000": 970000, "rank": null,
+                 "fc": null, "sync": null, "dx_score": null, "dx_score_max": null,
+                 "last_played_at": null, "play_count": null},
+                {"title": "C", "genre": "maimai", "artist": "x", "chart_type": "DX",
+                 "diff_category": "MASTER", "achievement_x10000": 1000000, "rank": null,
+                 "fc": null, "sync": null, "dx_score": null, "dx_score_max": null,
+                 "last_played_at": null, "play_count": null},
+                {"title": "D", "genre": "maimai", "artist": "x", "chart_type": "DX",
+                 "diff_category": "MASTER", "achievement_x10000": 1000000, "rank": null,
+                 "fc": null, "sync": null, "dx_score": null, "dx_score_max": null,
+                 "last_played_at": null, "play_count": null},
+                {"title": "E", "genre": "maimai", "artist": "x", "chart_type": "DX",
+                 "diff_category": "MASTER", "achievement_x10000": null, "rank": null,
+                 "fc": null, "sync": null, "dx_score": null, "dx_score_max": null,
+                 "last_played_at": null, "play_count": null}
+            ],
+            "playlogs": []
+        }"#;
+        let theirs = parse_score_dump(dump).expect("parse dump");
+        let mine = vec![
+            score("A", DifficultyCategory::Master, 1_000_000),
+            score("B", DifficultyCategory::Master, 1_001_000),
+            score("C", DifficultyCategory::Master, 1_000_000),
+            score("D", DifficultyCategory::Master, 990_000),
+            score("A", DifficultyCategory::Expert, 1_002_000),
+            score("E", DifficultyCategory::Master, 990_000),
+        ];
+
+        let comparison = compare_scores(&mine, &theirs);
+
+        assert_eq!(
+            comparison
+                .they_lead
+                .iter()
+                .map(|delta| delta.title.as_str())
+                .collect::<Vec<_>>(),
+            vec!["D", "A"]
+        );
+        assert_eq!(comparison.you_lead[0].title, "B");
+        assert_eq!(comparison.you_lead[0].mine_x10000, 1_001_000);
+        assert_eq!(comparison.tied, 1);
+        // A EXPERT is only mine, and E is unplayed on their side.
+        assert_eq!(comparison.skipped, 2);
+    }
+}
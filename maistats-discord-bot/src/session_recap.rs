@@ -0,0 +1,253 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use eyre::WrapErr;
+use maimai_client::RecordCollectorClient;
+use models::{RecapSessionState, RecapStateApiResponse};
+use poise::serenity_prelude as serenity;
+use time::{Duration as TimeDuration, OffsetDateTime};
+use tracing::warn;
+
+use crate::daily_recap::{build_daily_recap, is_recapped, mark_recapped};
+use crate::db;
+use crate::dm;
+use crate::embeds::build_mai_today_embed;
+use crate::play_day::{jst_offset, play_day_window};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Per-player idle detection for one play day, stored in the player's collector between checks
+/// so a bot restart picks the session up where it was.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct SessionState {
+    day: String,
+    /// Newest `played_at_unixtime` seen so far today.
+    latest_play: Option<i64>,
+    /// When `latest_play` last moved; measured on our clock so a slow collector poll does not
+    /// make a fresh session look idle.
+    last_activity: Option<OffsetDateTime>,
+    recapped: bool,
+}
+
+impl SessionState {
+    /// Feed one observation of the play day; `true` means the session just ended and the recap
+    /// should go out now. Fires at most once per play day.
+    pub(crate) fn observe(
+        &mut self,
+        day: &str,
+        latest_play: Option<i64>,
+        now: OffsetDateTime,
+        idle_after: TimeDuration,
+    ) -> bool {
+        if self.day != day {
+            *self = Self {
+                day: day.to_string(),
+                ..Self::default()
+            };
+        }
+        if latest_play.is_some() && latest_play != self.latest_play {
+            self.latest_play = latest_play;
+            self.last_activity = Some(now);
+        }
+        let Some(last_activity) = self.last_activity else {
+            return false;
+        };
+        if self.recapped || now - last_activity < idle_after {
+            return false;
+        }
+        self.recapped = true;
+        true
+    }
+
+    pub(crate) fn from_stored(stored: &RecapSessionState) -> Self {
+        Self {
+            day: stored.day.clone(),
+            latest_play: stored.latest_play,
+            last_activity: stored
+                .last_activity_unixtime
+                .and_then(|unixtime| OffsetDateTime::from_unix_timestamp(unixtime).ok()),
+            recapped: stored.recapped,
+        }
+    }
+
+    pub(crate) fn to_stored(&self) -> RecapSessionState {
+        RecapSessionState {
+            day: self.day.clone(),
+            latest_play: self.latest_play,
+            last_activity_unixtime: self.last_activity.map(OffsetDateTime::unix_timestamp),
+            recapped: self.recapped,
+        }
+    }
+}
+
+/// DM each registered player their `mai-today` summary once they stop playing for
+/// `idle_after`, forever. Skips days the daily recap already covered.
+pub(crate) async fn run_session_recap(
+    db_pool: db::SqlitePool,
+    http: Arc<serenity::Http>,
+    idle_after: TimeDuration,
+    min_achievement_percent: f64,
+) {
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let registrations = match db::list_registrations(&db_pool).await {
+            Ok(registrations) => registrations,
+            Err(err) => {
+                warn!("session recap: failed to list registrations: {err:#}");
+                continue;
+            }
+        };
+
+        for registration in registrations {
+            let user_id = registration.discord_user_id;
+            if let Err(err) =
+                check_registration(&http, &registration, idle_after, min_achievement_percent).await
+            {
+                warn!("session recap failed for {user_id}: {err:#}");
+            }
+        }
+    }
+}
+
+async fn check_registration(
+    http: &serenity::Http,
+    registration: &db::Registration,
+    idle_after: TimeDuration,
+    min_achievement_percent: f64,
+) -> eyre::Result<()> {
+    let now = OffsetDateTime::now_utc();
+    let window = play_day_window(now.to_offset(jst_offset()));
+    let client = RecordCollectorClient::new(registration.record_collector_server_url.clone())?;
+    let plays = client
+        .get_today(&window.day)
+        .await
+        .wrap_err("fetch today's plays")?;
+    let latest_play = plays.iter().map(|play| play.played_at_unixtime).max();
+
+    let recap_state = client
+        .get_recap_state()
+        .await
+        .wrap_err("fetch recap state")?;
+    let (session_ended, session) = observe_stored_session(
+        recap_state.session.as_ref(),
+        &window.day,
+        latest_play,
+        now,
+        idle_after,
+    );
+    if recap_state.session.as_ref() != Some(&session) {
+        client
+            .update_recap_state(&RecapStateApiResponse {
+                session: Some(session),
+                ..RecapStateApiResponse::default()
+            })
+            .await?;
+    }
+    if !session_ended || is_recapped(&recap_state, &window.day) {
+        return Ok(());
+    }
+
+    let profile = client
+        .get_player_profile()
+        .await
+        .wrap_err("fetch player profile")?;
+    let Some(recap) = build_daily_recap(&plays, profile.rating, None, min_achievement_percent)
+    else {
+        return Ok(());
+    };
+    let embed = build_mai_today_embed(&profile.user_name, &window, &recap.counts)
+        .title(format!("{}'s session recap", profile.user_name));
+    dm::send_daily_recap_dm(http, registration.discord_user_id, embed).await?;
+    mark_recapped(&client, &window.day).await?;
+    Ok(())
+}
+
+/// [`SessionState::observe`] over the session stored in the collector; returns whether the
+/// session just ended and the state to store back.
+fn observe_stored_session(
+    stored: Option<&RecapSessionState>,
+    day: &str,
+    latest_play: Option<i64>,
+    now: OffsetDateTime,
+    idle_after: TimeDuration,
+) -> (bool, RecapSessionState) {
+    let mut session = stored.map(SessionState::from_stored).unwrap_or_default();
+    let ended = session.observe(day, latest_play, now, idle_after);
+    (ended, session.to_stored())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SessionState, observe_stored_session};
+    use crate::daily_recap::is_recapped;
+    use models::RecapStateApiResponse;
+    use time::{Duration, OffsetDateTime};
+
+    #[test]
+    fn observe_fires_once_after_the_idle_threshold() {
+        let idle = Duration::minutes(30);
+        let mut state = SessionState::default();
+        let start = OffsetDateTime::from_unix_timestamp(1_792_148_400).expect("valid timestamp");
+
+        // No plays yet today: nothing to recap however long we wait.
+        assert!(!state.observe("2026-10-16", None, start, idle));
+        assert!(!state.observe("2026-10-16", None, start + Duration::hours(2), idle));
+
+        // Activity keeps pushing the deadline out.
+        assert!(!state.observe("2026-10-16", Some(100), start, idle));
+        assert!(!state.observe("2026-10-16", Some(200), start + Duration::minutes(20), idle));
+        assert!(!state.observe("2026-10-16", Some(200), start + Duration::minutes(49), idle));
+
+        assert!(state.observe("2026-10-16", Some(200), start + Duration::minutes(50), idle));
+        assert!(!state.observe("2026-10-16", Some(200), start + Duration::minutes(90), idle));
+        // Playing again the same day does not send a second recap.
+        assert!(!state.observe("2026-10-16", Some(300), start + Duration::minutes(95), idle));
+        assert!(!state.observe("2026-10-16", Some(300), start + Duration::hours(3), idle));
+
+        // A new play day starts a fresh session.
+        let tomorrow = start + Duration::days(1);
+        assert!(!state.observe("2026-10-17", Some(400), tomorrow, idle));
+        assert!(state.observe(
+            "2026-10-17",
+            Some(400),
+            tomorrow + Duration::minutes(30),
+            idle
+        ));
+    }
+
+    #[test]
+    fn restored_state_does_not_send_a_second_recap() {
+        let idle = Duration::minutes(30);
+        let start = OffsetDateTime::from_unix_timestamp(1_792_148_400).expect("valid timestamp");
+
+        let (ended, stored) = observe_stored_session(None, "2026-10-16", Some(100), start, idle);
+        assert!(!ended);
+
+        // Each check starts from what the collector stored, as after a bot restart.
+        let (ended, stored) = observe_stored_session(
+            Some(&stored),
+            "2026-10-16",
+            Some(100),
+            start + Duration::minutes(31),
+            idle,
+        );
+        assert!(ended);
+        let (ended, _) = observe_stored_session(
+            Some(&stored),
+            "2026-10-16",
+            Some(100),
+            start + Duration::minutes(45),
+            idle,
+        );
+        assert!(!ended);
+
+        // A day the daily recap already covered stays covered.
+        let state = RecapStateApiResponse {
+            recapped_day: Some("2026-10-16".to_string()),
+            session: Some(stored),
+        };
+        assert!(is_recapped(&state, "2026-10-16"));
+        assert!(!is_recapped(&state, "2026-10-17"));
+    }
+}
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use eyre::WrapErr;
+use maimai_client::RecordCollectorClient;
+use models::SyncStatusApiResponse;
+use poise::serenity_prelude as serenity;
+use serenity::builder::CreateEmbed;
+use tracing::warn;
+
+use crate::db;
+use crate::dm;
+use crate::embeds::embed_base;
+
+/// Consecutive parse failures before the player is told SEGA's layout may have changed.
+pub(crate) const PARSE_FAILURE_ALERT_THRESHOLD: u32 = 3;
+const CHECK_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+pub(crate) fn build_sync_status_embed(status: &SyncStatusApiResponse) -> CreateEmbed {
+    let summary = if status.parse_error_count == 0 {
+        "The last poll parsed SEGA's pages without errors.".to_string()
+    } else {
+        format!(
+            "The last {} poll(s) failed while parsing SEGA's pages.",
+            status.parse_error_count
+        )
+    };
+    let mut embed = embed_base("Sync status").description(summary).field(
+        "Consecutive parse failures",
+        status.parse_error_count.to_string(),
+        true,
+    );
+    if let Some(last_error) = &status.last_parse_error {
+        embed = embed.field("Last parse error", format!("`{last_error}`"), false);
+    }
+    embed
+}
+
+fn build_parse_failure_alert_embed(status: &SyncStatusApiResponse) -> CreateEmbed {
+    build_sync_status_embed(status)
+        .title("Record sync is failing")
+        .description(format!(
+            "Your record collector failed to parse SEGA's pages {} times in a row. \
+            The maimai DX NET layout may have changed; new plays will not be recorded until \
+            the parser is updated.",
+            status.parse_error_count
+        ))
+}
+
+/// Alert once per failure streak: `true` means a DM should go out now. `alerted` is the
+/// stored flag for the player and is updated to match the streak.
+fn should_alert(alerted: &mut bool, count: u32) -> bool {
+    if count < PARSE_FAILURE_ALERT_THRESHOLD {
+        *alerted = false;
+        return false;
+    }
+    !std::mem::replace(alerted, true)
+}
+
+/// Check every registered collector's parse failure streak and DM the player once it reaches
+/// [`PARSE_FAILURE_ALERT_THRESHOLD`], forever. Who was alerted is stored with the
+/// registration, so a restart mid-streak does not repeat the DM.
+pub(crate) async fn run_parse_failure_alerts(db_pool: db::SqlitePool, http: Arc<serenity::Http>) {
+    loop {
+        tokio::time::sleep(CHECK_INTERVAL).await;
+
+        let registrations = match db::list_registrations(&db_pool).await {
+            Ok(registrations) => registrations,
+            Err(err) => {
+                warn!("parse failure alert: failed to list registrations: {err:#}");
+                continue;
+            }
+        };
+
+        for registration in registrations {
+            let user_id = registration.discord_user_id;
+            if let Err(err) = check_registration(&db_pool, &http, &registration).await {
+                warn!("parse failure alert failed for {user_id}: {err:#}");
+            }
+        }
+    }
+}
+
+async fn check_registration(
+    db_pool: &db::SqlitePool,
+    http: &serenity::Http,
+    registration: &db::Registration,
+) -> eyre::Result<()> {
+    let client = RecordCollectorClient::new(registration.record_collector_server_url.clone())?;
+    let status = client
+        .get_sync_status()
+        .await
+        .wrap_err("fetch sync status")?;
+
+    let user_id = registration.discord_user_id;
+    let was_alerted = db::get_parse_failure_alerted(db_pool, user_id).await?;
+    let mut alerted = was_alerted;
+    if should_alert(&mut alerted, status.parse_error_count) {
+        dm::send_parse_failure_alert_dm(http, user_id, build_parse_failure_alert_embed(&status))
+            .await?;
+    }
+    if alerted != was_alerted {
+        db::set_parse_failure_alerted(db_pool, user_id, alerted).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PARSE_FAILURE_ALERT_THRESHOLD, should_alert};
+
+    #[test]
+    fn should_alert_fires_once_per_failure_streak() {
+        let mut alerted = false;
+
+        assert!(!should_alert(
+            &mut alerted,
+            PARSE_FAILURE_ALERT_THRESHOLD - 1
+        ));
+        assert!(should_alert(&mut alerted, PARSE_FAILURE_ALERT_THRESHOLD));
+        assert!(alerted);
+        assert!(!should_alert(
+            &mut alerted,
+            PARSE_FAILURE_ALERT_THRESHOLD + 4
+        ));
+
+        assert!(!should_alert(&mut alerted, 0));
+        assert!(!alerted);
+        assert!(should_alert(&mut alerted, PARSE_FAILURE_ALERT_THRESHOLD));
+    }
+}
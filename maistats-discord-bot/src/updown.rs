@@ -2,7 +2,7 @@ use crate::BotData;
 use crate::chart_links::linked_chart_label;
 use crate::db;
 use crate::embeds::{embed_base, format_level_with_internal};
-use crate::emoji::{format_fc, format_rank, format_sync};
+use crate::emoji::{format_fc, format_rank, format_sync, tier_prefix};
 use eyre::WrapErr;
 use maimai_client::{RecordCollectorClient, SongCatalogSong};
 use models::{ChartType, DifficultyCategory, ScoreApiResponse};
@@ -466,6 +466,7 @@ fn build_pick_embed(data: &BotData, candidate: &UpdownCandidate) -> serenity::Cr
     let rank = format_rank(&data.status_emojis, score.and_then(|s| s.rank), "-");
     let fc = format_fc(&data.status_emojis, score.and_then(|s| s.fc), "-");
     let sync = format_sync(&data.status_emojis, score.and_then(|s| s.sync), "-");
+    let tier = tier_prefix(score.and_then(|s| s.rank));
     let meta = [
         score
             .and_then(|s| s.last_played_at.as_deref())
@@ -482,7 +483,7 @@ fn build_pick_embed(data: &BotData, candidate: &UpdownCandidate) -> serenity::Cr
     let mut embed = embed_base(&candidate.title).description(format!(
         "**{chart_line}**\n\
          {version_line}\n\
-         {achievement} • {rank} • {fc} • {sync}\n\
+         {tier}{achievement} • {rank} • {fc} • {sync}\n\
          {meta}"
     ));
     if let Some(image_name) = candidate.image_name.as_deref() {
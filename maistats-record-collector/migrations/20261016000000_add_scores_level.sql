@@ -0,0 +1,5 @@
+-- Keep the displayed chart level scraped alongside each score so level-band
+-- queries (e.g. achievement histograms) do not need the song database.
+ALTER TABLE scores ADD COLUMN level TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_scores_level ON scores(level);
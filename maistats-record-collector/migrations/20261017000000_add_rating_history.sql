@@ -0,0 +1,9 @@
+-- Rating time series. `app_state` only keeps the latest rating, so each profile
+-- snapshot whose rating differs from the previous row is appended here.
+CREATE TABLE IF NOT EXISTS rating_history (
+  recorded_at INTEGER NOT NULL,
+  rating INTEGER NOT NULL,
+  total_play_count INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_rating_history_recorded_at ON rating_history(recorded_at);
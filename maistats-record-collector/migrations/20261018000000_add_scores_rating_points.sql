@@ -0,0 +1,6 @@
+-- Rating contribution of each played chart, so history features can read it
+-- without re-resolving internal levels. The internal levels live in the song
+-- database, not here, so the collector fills (and backfills) these after each
+-- sync; both stay NULL for charts without a known internal level.
+ALTER TABLE scores ADD COLUMN rating_points INTEGER;
+ALTER TABLE scores ADD COLUMN bucket TEXT;
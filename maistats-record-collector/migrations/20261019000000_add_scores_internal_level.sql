@@ -0,0 +1,4 @@
+-- Internal level each stored rating was computed from, so the rating routes can
+-- list charts without re-resolving the song database. NULL exactly when
+-- rating_points is.
+ALTER TABLE scores ADD COLUMN internal_level REAL;
@@ -0,0 +1,3 @@
+-- Buddy (2P) plays, marked on the recent or playlogDetail page. Rows stored before
+-- this column existed default to solo.
+ALTER TABLE playlogs ADD COLUMN is_buddy_play INTEGER NOT NULL DEFAULT 0;
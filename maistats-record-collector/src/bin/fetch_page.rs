@@ -2,6 +2,9 @@ use eyre::WrapErr;
 use models::config::AppConfig;
 use reqwest::Url;
 
+#[allow(dead_code)]
+#[path = "../config.rs"]
+mod config;
 #[allow(dead_code)]
 #[path = "../http_client.rs"]
 mod http_client;
@@ -12,12 +15,14 @@ use http_client::MaimaiClient;
 async fn main() -> eyre::Result<()> {
     dotenvy::dotenv().ok();
 
-    let url = std::env::args()
-        .nth(1)
-        .ok_or_else(|| eyre::eyre!("usage: fetch_page <url> <out_path>"))?;
-    let out_path = std::env::args()
-        .nth(2)
-        .ok_or_else(|| eyre::eyre!("usage: fetch_page <url> <out_path>"))?;
+    // `--json` prints the fetch summary as one JSON object; errors stay plain text on stderr.
+    const USAGE: &str = "usage: fetch_page [--json] <url> <out_path>";
+
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let json_output = args.iter().any(|arg| arg == "--json");
+    let mut positional = args.into_iter().filter(|arg| arg != "--json");
+    let url = positional.next().ok_or_else(|| eyre::eyre!(USAGE))?;
+    let out_path = positional.next().ok_or_else(|| eyre::eyre!(USAGE))?;
 
     let sega_id = std::env::var("SEGA_ID").wrap_err("missing SEGA_ID")?;
     let sega_password = std::env::var("SEGA_PASSWORD").wrap_err("missing SEGA_PASSWORD")?;
@@ -34,9 +39,10 @@ async fn main() -> eyre::Result<()> {
         cookie_path,
         discord_bot_token: None,
         discord_user_id: None,
+        user_agents: Vec::new(),
     };
 
-    let mut client = MaimaiClient::new(&app_config).wrap_err("create maimai client")?;
+    let client = MaimaiClient::new(&app_config).wrap_err("create maimai client")?;
     client
         .ensure_logged_in()
         .await
@@ -49,11 +55,22 @@ async fn main() -> eyre::Result<()> {
         .wrap_err("fetch response")?;
     std::fs::write(&out_path, &response.body).wrap_err("write output file")?;
 
-    println!(
-        "wrote {} bytes from {} to {}",
-        response.body.len(),
-        response.final_url,
-        out_path
-    );
+    if json_output {
+        println!(
+            "{}",
+            serde_json::json!({
+                "bytes": response.body.len(),
+                "final_url": response.final_url.as_str(),
+                "out_path": out_path,
+            })
+        );
+    } else {
+        println!(
+            "wrote {} bytes from {} to {}",
+            response.body.len(),
+            response.final_url,
+            out_path
+        );
+    }
     Ok(())
 }
@@ -1,4 +1,5 @@
 use eyre::WrapErr;
+use time::{OffsetDateTime, UtcOffset};
 
 #[derive(Debug, Clone)]
 pub(crate) struct RecordCollectorConfig {
@@ -7,6 +8,43 @@ pub(crate) struct RecordCollectorConfig {
     pub(crate) port: u16,
     pub(crate) database_url: String,
     pub(crate) data_dir: String,
+    pub(crate) db_query_timeout_secs: u64,
+    pub(crate) song_database_url: String,
+    /// Shares `INCLUDE_ESTIMATED_INTERNAL_LEVELS` with the Discord bot; the collector's rating
+    /// routes and stored rating points only see estimates when this is on.
+    pub(crate) include_estimated_internal_levels: bool,
+    pub(crate) slow_request_warn_secs: u64,
+    pub(crate) user_agents: Vec<String>,
+    pub(crate) maintenance_window: MaintenanceWindow,
+}
+
+/// The song catalog settings, readable without the SEGA credentials so `db import` can rate
+/// the rows it restores.
+#[derive(Debug, Clone)]
+pub(crate) struct SongCatalogEnv {
+    pub(crate) data_dir: String,
+    pub(crate) song_database_url: String,
+    pub(crate) include_estimated_internal_levels: bool,
+}
+
+impl SongCatalogEnv {
+    pub(crate) fn from_env() -> Self {
+        let data_dir = std::env::var("DATA_DIR").unwrap_or_else(|_| "data".to_string());
+        let song_database_url = std::env::var("SONG_DATABASE_URL")
+            .unwrap_or_else(|_| "https://maimai-charts.muhwan.dev".to_string());
+        let include_estimated_internal_levels = std::env::var("INCLUDE_ESTIMATED_INTERNAL_LEVELS")
+            .is_ok_and(|value| {
+                matches!(
+                    value.trim().to_ascii_lowercase().as_str(),
+                    "1" | "true" | "yes" | "on"
+                )
+            });
+        Self {
+            data_dir,
+            song_database_url,
+            include_estimated_internal_levels,
+        }
+    }
 }
 
 impl RecordCollectorConfig {
@@ -20,7 +58,28 @@ impl RecordCollectorConfig {
             .wrap_err("RECORD_COLLECTOR_PORT must be a valid u16")?;
         let database_url =
             std::env::var("DATABASE_URL").wrap_err("missing env var: DATABASE_URL")?;
-        let data_dir = std::env::var("DATA_DIR").unwrap_or_else(|_| "data".to_string());
+        let db_query_timeout_secs = std::env::var("DB_QUERY_TIMEOUT_SECS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse::<u64>()
+            .wrap_err("DB_QUERY_TIMEOUT_SECS must be a valid u64")?;
+        // A zero timeout would fail every bounded write immediately.
+        eyre::ensure!(
+            db_query_timeout_secs > 0,
+            "DB_QUERY_TIMEOUT_SECS must be at least 1"
+        );
+        let SongCatalogEnv {
+            data_dir,
+            song_database_url,
+            include_estimated_internal_levels,
+        } = SongCatalogEnv::from_env();
+        let slow_request_warn_secs = std::env::var("SLOW_REQUEST_WARN_SECS")
+            .unwrap_or_else(|_| "5".to_string())
+            .parse::<u64>()
+            .wrap_err("SLOW_REQUEST_WARN_SECS must be a valid u64")?;
+        let user_agents = std::env::var("MAIMAI_USER_AGENTS")
+            .map(|value| parse_user_agents(&value))
+            .unwrap_or_default();
+        let maintenance_window = MaintenanceWindow::from_env()?;
 
         Ok(Self {
             sega_id,
@@ -28,6 +87,132 @@ impl RecordCollectorConfig {
             port,
             database_url,
             data_dir,
+            db_query_timeout_secs,
+            song_database_url,
+            include_estimated_internal_levels,
+            slow_request_warn_secs,
+            user_agents,
+            maintenance_window,
         })
     }
 }
+
+/// Daily maimai DX NET maintenance, in whole hours at a fixed UTC offset (JST by default) so
+/// the host's own time zone does not matter. `start_hour > end_hour` wraps past midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct MaintenanceWindow {
+    pub(crate) start_hour: u8,
+    pub(crate) end_hour: u8,
+    pub(crate) offset: UtcOffset,
+}
+
+impl Default for MaintenanceWindow {
+    fn default() -> Self {
+        Self {
+            start_hour: 4,
+            end_hour: 7,
+            offset: UtcOffset::from_hms(9, 0, 0).unwrap_or(UtcOffset::UTC),
+        }
+    }
+}
+
+impl MaintenanceWindow {
+    fn from_env() -> eyre::Result<Self> {
+        let default = Self::default();
+        let start_hour = parse_hour_env("MAINTENANCE_START_HOUR", default.start_hour)?;
+        let end_hour = parse_hour_env("MAINTENANCE_END_HOUR", default.end_hour)?;
+        let offset = match std::env::var("MAINTENANCE_TZ_OFFSET_MINUTES") {
+            Ok(value) => {
+                let minutes = value
+                    .trim()
+                    .parse::<i32>()
+                    .wrap_err("MAINTENANCE_TZ_OFFSET_MINUTES must be a valid i32")?;
+                UtcOffset::from_whole_seconds(minutes * 60)
+                    .wrap_err("MAINTENANCE_TZ_OFFSET_MINUTES is out of range")?
+            }
+            Err(_) => default.offset,
+        };
+        Ok(Self {
+            start_hour,
+            end_hour,
+            offset,
+        })
+    }
+
+    pub(crate) fn contains(&self, now: OffsetDateTime) -> bool {
+        let hour = now.to_offset(self.offset).hour();
+        if self.start_hour <= self.end_hour {
+            (self.start_hour..self.end_hour).contains(&hour)
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+/// `|`-separated, since User-Agent strings themselves contain commas.
+fn parse_user_agents(raw: &str) -> Vec<String> {
+    raw.split('|')
+        .map(str::trim)
+        .filter(|user_agent| !user_agent.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_hour_env(name: &str, default: u8) -> eyre::Result<u8> {
+    let Ok(value) = std::env::var(name) else {
+        return Ok(default);
+    };
+    let hour = value
+        .trim()
+        .parse::<u8>()
+        .wrap_err_with(|| format!("{name} must be a valid hour"))?;
+    eyre::ensure!(hour < 24, "{name} must be between 0 and 23");
+    Ok(hour)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MaintenanceWindow, parse_user_agents};
+    use time::UtcOffset;
+    use time::macros::datetime;
+
+    #[test]
+    fn default_window_is_four_to_seven_jst_on_any_host() {
+        let window = MaintenanceWindow::default();
+
+        assert!(window.contains(datetime!(2026-10-16 04:00 +9)));
+        assert!(window.contains(datetime!(2026-10-16 06:59 +9)));
+        assert!(!window.contains(datetime!(2026-10-16 07:00 +9)));
+        assert!(!window.contains(datetime!(2026-10-16 03:59 +9)));
+        // 19:30 UTC is 04:30 JST the next morning.
+        assert!(window.contains(datetime!(2026-10-16 19:30 UTC)));
+        assert!(!window.contains(datetime!(2026-10-16 04:30 UTC)));
+    }
+
+    #[test]
+    fn window_wraps_past_midnight_when_start_is_after_end() {
+        let window = MaintenanceWindow {
+            start_hour: 23,
+            end_hour: 2,
+            offset: UtcOffset::UTC,
+        };
+
+        assert!(window.contains(datetime!(2026-10-16 23:00 UTC)));
+        assert!(window.contains(datetime!(2026-10-17 00:30 UTC)));
+        assert!(window.contains(datetime!(2026-10-17 01:59 UTC)));
+        assert!(!window.contains(datetime!(2026-10-17 02:00 UTC)));
+        assert!(!window.contains(datetime!(2026-10-16 22:59 UTC)));
+    }
+
+    #[test]
+    fn user_agents_split_on_pipes_and_skip_blanks() {
+        assert_eq!(
+            parse_user_agents("Mozilla/5.0 (iPhone; like Gecko) | Mozilla/5.0 (Linux, Android)||"),
+            vec![
+                "Mozilla/5.0 (iPhone; like Gecko)".to_string(),
+                "Mozilla/5.0 (Linux, Android)".to_string(),
+            ]
+        );
+        assert!(parse_user_agents("  ").is_empty());
+    }
+}
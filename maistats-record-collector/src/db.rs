@@ -1,18 +1,67 @@
+use std::future::Future;
 use std::str::FromStr;
+use std::time::Duration;
 
 use eyre::WrapErr;
 use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous};
 use sqlx::{Pool, Sqlite};
-use tracing::info;
+use tracing::{info, warn};
 
+use crate::song_catalog::{CatalogIndex, catalog_rating_fingerprint};
 use crate::tasks::utils::player::{
-    STATE_KEY_CURRENT_VERSION_PLAY_COUNT, STATE_KEY_RATING, STATE_KEY_TOTAL_PLAY_COUNT,
-    STATE_KEY_USER_NAME,
+    STATE_KEY_AREA_RANK, STATE_KEY_CLASS_RANK, STATE_KEY_COURSE_RANK,
+    STATE_KEY_CURRENT_VERSION_PLAY_COUNT, STATE_KEY_RATING, STATE_KEY_TARGET_RATING,
+    STATE_KEY_TOTAL_PLAY_COUNT, STATE_KEY_USER_NAME,
+};
+use crate::tasks::utils::recent::unix_timestamp;
+use maimai_client::SongCatalogSong;
+use models::rating::{MAX_ACHIEVEMENT_PERCENT, RatingBucket, chart_rating_points, is_ap_like};
+use models::{
+    ChartType, DifficultyCategory, FcStatus, ParsedPlayRecord, ParsedPlayerProfile,
+    ParsedScoreEntry, RatingSnapshotApiResponse, RecapSessionState, RecapStateApiResponse,
+    StoredPlayRecord, StoredScoreEntry, SyncStatusApiResponse,
 };
-use models::{ChartType, ParsedPlayRecord, ParsedPlayerProfile, ParsedScoreEntry};
 
 pub type SqlitePool = Pool<Sqlite>;
 
+/// Bound on the write/aggregate paths below when `DB_QUERY_TIMEOUT_SECS` is unset.
+pub const DEFAULT_QUERY_TIMEOUT: Duration = Duration::from_secs(30);
+
+const STATE_KEY_PARSE_ERROR_COUNT: &str = "poll.parse_error_count";
+const STATE_KEY_LAST_PARSE_ERROR: &str = "poll.last_parse_error";
+const STATE_KEY_LAST_SYNCED_AT: &str = "poll.last_synced_at";
+/// Present while startup score seeding is writing rows, so an interrupted seed resumes.
+const STATE_KEY_SCORE_SEED_IN_PROGRESS: &str = "scores.seed_in_progress";
+/// Recap bookkeeping of the Discord bot, see [`RecapStateApiResponse`].
+const STATE_KEY_RECAPPED_DAY: &str = "recap.recapped_day";
+const STATE_KEY_RECAP_SESSION: &str = "recap.session";
+/// [`catalog_rating_fingerprint`] of the catalog the stored rating columns were last refreshed
+/// against, so a restart with an unchanged catalog skips the backfill.
+const STATE_KEY_RATING_FINGERPRINT: &str = "scores.rating_fingerprint";
+
+/// `scores` columns read into [`StoredScoreEntry`]; every typed score query selects these.
+pub(crate) const SCORE_COLUMNS: &str = "title, genre, artist, chart_type, diff_category, \
+     achievement_x10000, rank, fc, sync, dx_score, dx_score_max, last_played_at, play_count, level";
+/// `playlogs` columns read into [`StoredPlayRecord`].
+pub(crate) const PLAYLOG_COLUMNS: &str = "played_at_unixtime, played_at, track, title, genre, \
+     artist, chart_type, diff_category, achievement_x10000, score_rank, fc, sync, dx_score, \
+     dx_score_max, credit_id, achievement_new_record, is_buddy_play";
+/// Rating columns written next to every score; all NULL without a known internal level.
+const RATING_COLUMNS: &str = "internal_level, rating_points, bucket";
+const SCORE_ORDER: &str = "ORDER BY title, genre, artist, chart_type, diff_category";
+
+/// Bounds a query (or a whole transaction) so a locked database fails the background task
+/// instead of holding a pool connection forever. Dropping a timed-out transaction rolls it back.
+async fn with_timeout<T>(
+    timeout: Duration,
+    label: &str,
+    query: impl Future<Output = eyre::Result<T>>,
+) -> eyre::Result<T> {
+    tokio::time::timeout(timeout, query)
+        .await
+        .map_err(|_| eyre::eyre!("{label} timed out after {}ms", timeout.as_millis()))?
+}
+
 pub async fn connect(database_url: &str) -> eyre::Result<SqlitePool> {
     let options = SqliteConnectOptions::from_str(database_url)
         .wrap_err("parse database url")?
@@ -36,87 +85,562 @@ pub async fn migrate(pool: &SqlitePool) -> eyre::Result<()> {
     Ok(())
 }
 
+/// Store score rows, with their rating columns computed against `catalog`.
 pub(crate) async fn upsert_scores(
     pool: &SqlitePool,
+    query_timeout: Duration,
     entries: &[ParsedScoreEntry],
+    catalog: &CatalogIndex<'_>,
 ) -> eyre::Result<()> {
-    let mut tx = pool.begin().await.wrap_err("begin transaction")?;
+    with_timeout(query_timeout, "upsert scores", async {
+        let mut tx = pool.begin().await.wrap_err("begin transaction")?;
 
-    for entry in entries {
-        upsert_score(&mut tx, entry).await?;
-    }
+        for entry in entries {
+            upsert_score(&mut tx, entry, catalog).await?;
+        }
 
-    tx.commit().await.wrap_err("commit transaction")?;
-    Ok(())
+        tx.commit().await.wrap_err("commit transaction")?;
+        Ok(())
+    })
+    .await
+}
+
+/// Every score row, played or not.
+pub(crate) async fn query_all_scores(pool: &SqlitePool) -> eyre::Result<Vec<StoredScoreEntry>> {
+    sqlx::query_as::<_, StoredScoreEntry>(&format!(
+        "SELECT {SCORE_COLUMNS} FROM scores {SCORE_ORDER}"
+    ))
+    .fetch_all(pool)
+    .await
+    .wrap_err("query scores")
 }
 
-pub(crate) async fn replace_scores(
+/// A score row with the rating columns stored alongside it.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub(crate) struct StoredRatedScore {
+    #[sqlx(flatten)]
+    pub(crate) score: StoredScoreEntry,
+    pub(crate) internal_level: Option<f64>,
+    pub(crate) rating_points: Option<i64>,
+    pub(crate) bucket: Option<String>,
+}
+
+/// Played charts with their stored ratings, optionally narrowed to one difficulty and/or
+/// chart type.
+pub(crate) async fn query_played_scores(
     pool: &SqlitePool,
-    entries: &[ParsedScoreEntry],
-) -> eyre::Result<()> {
-    let mut tx = pool.begin().await.wrap_err("begin transaction")?;
+    diff: Option<DifficultyCategory>,
+    chart_type: Option<ChartType>,
+) -> eyre::Result<Vec<StoredRatedScore>> {
+    sqlx::query_as::<_, StoredRatedScore>(&format!(
+        "SELECT {SCORE_COLUMNS}, {RATING_COLUMNS} FROM scores
+         WHERE achievement_x10000 IS NOT NULL
+           AND (?1 IS NULL OR diff_category = ?1)
+           AND (?2 IS NULL OR chart_type = ?2)
+         {SCORE_ORDER}"
+    ))
+    .bind(diff.map(DifficultyCategory::as_str))
+    .bind(chart_type.map(ChartType::as_str))
+    .fetch_all(pool)
+    .await
+    .wrap_err("query played scores")
+}
+
+/// Played charts of one song.
+pub(crate) async fn query_song_scores(
+    pool: &SqlitePool,
+    title: &str,
+    genre: &str,
+    artist: &str,
+) -> eyre::Result<Vec<StoredScoreEntry>> {
+    sqlx::query_as::<_, StoredScoreEntry>(&format!(
+        "SELECT {SCORE_COLUMNS} FROM scores
+         WHERE title = ? AND genre = ? AND artist = ? AND achievement_x10000 IS NOT NULL"
+    ))
+    .bind(title)
+    .bind(genre)
+    .bind(artist)
+    .fetch_all(pool)
+    .await
+    .wrap_err("query song scores")
+}
 
-    sqlx::query("DELETE FROM scores")
-        .execute(&mut *tx)
+/// Every playlog, oldest first.
+pub(crate) async fn query_all_playlogs(pool: &SqlitePool) -> eyre::Result<Vec<StoredPlayRecord>> {
+    sqlx::query_as::<_, StoredPlayRecord>(&format!(
+        "SELECT {PLAYLOG_COLUMNS} FROM playlogs ORDER BY played_at_unixtime"
+    ))
+    .fetch_all(pool)
+    .await
+    .wrap_err("query playlogs")
+}
+
+/// Playlogs with `start <= played_at < end` (`YYYY/MM/DD HH:MM` strings), oldest first.
+pub(crate) async fn query_playlogs_between(
+    pool: &SqlitePool,
+    start: &str,
+    end: &str,
+) -> eyre::Result<Vec<StoredPlayRecord>> {
+    sqlx::query_as::<_, StoredPlayRecord>(&format!(
+        "SELECT {PLAYLOG_COLUMNS} FROM playlogs
+         WHERE played_at >= ? AND played_at < ?
+         ORDER BY played_at_unixtime ASC"
+    ))
+    .bind(start)
+    .bind(end)
+    .fetch_all(pool)
+    .await
+    .wrap_err("query playlogs by time range")
+}
+
+/// Rating inputs and the stored rating columns of one score row.
+#[derive(sqlx::FromRow)]
+struct ScoreRatingRow {
+    title: String,
+    genre: String,
+    artist: String,
+    chart_type: String,
+    diff_category: String,
+    achievement_x10000: Option<i64>,
+    fc: Option<String>,
+    internal_level: Option<f64>,
+    rating_points: Option<i64>,
+    bucket: Option<String>,
+}
+
+/// Recompute the stored rating columns of every score row against `catalog` and return how
+/// many rows changed. Rows without an achievement or a known internal level are cleared to
+/// NULL. Writes already rate their own rows, so this only needs to run when the catalog
+/// itself changes; the catalog's fingerprint is stored with the result.
+pub(crate) async fn refresh_score_ratings(
+    pool: &SqlitePool,
+    query_timeout: Duration,
+    catalog: &[SongCatalogSong],
+) -> eyre::Result<u64> {
+    with_timeout(query_timeout, "refresh score ratings", async {
+        let rows = sqlx::query_as::<_, ScoreRatingRow>(
+            "SELECT title, genre, artist, chart_type, diff_category, achievement_x10000, fc, \
+             internal_level, rating_points, bucket FROM scores",
+        )
+        .fetch_all(pool)
         .await
-        .wrap_err("clear scores before replace")?;
+        .wrap_err("query score ratings")?;
 
-    for entry in entries {
-        upsert_score(&mut tx, entry).await?;
-    }
+        let index = CatalogIndex::new(catalog);
+        let mut tx = pool.begin().await.wrap_err("begin transaction")?;
+        let mut changed = 0;
+        for row in &rows {
+            let (Ok(chart_type), Ok(diff_category)) = (
+                row.chart_type.parse::<ChartType>(),
+                row.diff_category.parse::<DifficultyCategory>(),
+            ) else {
+                continue;
+            };
+            let fc = row.fc.as_deref().and_then(|fc| fc.parse::<FcStatus>().ok());
+            let rating = score_rating(
+                &index,
+                &row.title,
+                &row.artist,
+                chart_type,
+                diff_category,
+                row.achievement_x10000,
+                fc,
+            );
+            let internal_level = rating.map(|rating| f64::from(rating.internal_level));
+            let rating_points = rating.map(|rating| i64::from(rating.rating_points));
+            let bucket = rating.map(|rating| rating.bucket.as_str());
+            if internal_level == row.internal_level
+                && rating_points == row.rating_points
+                && bucket == row.bucket.as_deref()
+            {
+                continue;
+            }
 
-    tx.commit().await.wrap_err("commit transaction")?;
-    Ok(())
+            sqlx::query(
+                r#"
+		UPDATE scores SET internal_level = ?6, rating_points = ?7, bucket = ?8
+		WHERE title = ?1 AND genre = ?2 AND artist = ?3 AND chart_type = ?4 AND diff_category = ?5
+		"#,
+            )
+            .bind(&row.title)
+            .bind(&row.genre)
+            .bind(&row.artist)
+            .bind(&row.chart_type)
+            .bind(&row.diff_category)
+            .bind(internal_level)
+            .bind(rating_points)
+            .bind(bucket)
+            .execute(&mut *tx)
+            .await
+            .wrap_err("update score rating")?;
+            changed += 1;
+        }
+        set_app_state_string_in_tx(
+            &mut tx,
+            STATE_KEY_RATING_FINGERPRINT,
+            &catalog_rating_fingerprint(catalog).to_string(),
+            unix_timestamp(),
+        )
+        .await
+        .wrap_err("store rating fingerprint")?;
+        tx.commit().await.wrap_err("commit transaction")?;
+        Ok(changed)
+    })
+    .await
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ScoreRating {
+    internal_level: f32,
+    rating_points: u32,
+    bucket: RatingBucket,
+}
+
+/// The rating columns of one score, or `None` without an achievement or a known internal
+/// level.
+fn score_rating(
+    catalog: &CatalogIndex<'_>,
+    title: &str,
+    artist: &str,
+    chart_type: ChartType,
+    diff_category: DifficultyCategory,
+    achievement_x10000: Option<i64>,
+    fc: Option<FcStatus>,
+) -> Option<ScoreRating> {
+    let achievement_x10000 = achievement_x10000?;
+    let sheet = catalog.find_sheet(title, artist, chart_type, diff_category)?;
+    let internal_level = sheet.internal_level?;
+    let rating_points = chart_rating_points(
+        f64::from(internal_level),
+        achievement_x10000 as f64 / 10000.0,
+        is_ap_like(fc.as_ref()),
+    );
+    Some(ScoreRating {
+        internal_level,
+        rating_points,
+        bucket: sheet.rating_bucket(),
+    })
 }
 
-pub(crate) async fn count_scores_rows(pool: &SqlitePool) -> eyre::Result<i64> {
-    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM scores")
-        .fetch_one(pool)
+/// Fingerprint of the catalog the stored rating columns were last refreshed against.
+pub(crate) async fn get_rating_fingerprint(pool: &SqlitePool) -> eyre::Result<Option<u64>> {
+    let value = sqlx::query_scalar::<_, String>("SELECT value FROM app_state WHERE key = ?1")
+        .bind(STATE_KEY_RATING_FINGERPRINT)
+        .fetch_optional(pool)
         .await
-        .wrap_err("count scores rows")
+        .wrap_err("load rating fingerprint")?;
+    value
+        .map(|value| {
+            value.parse::<u64>().wrap_err_with(|| {
+                format!("parse app_state key '{STATE_KEY_RATING_FINGERPRINT}' as u64")
+            })
+        })
+        .transpose()
+}
+
+/// Forget the rating fingerprint, so the next refresh backfills whatever catalog it sees.
+/// Needed once rows were written without a catalog.
+pub(crate) async fn clear_rating_fingerprint(pool: &SqlitePool) -> eyre::Result<()> {
+    sqlx::query("DELETE FROM app_state WHERE key = ?1")
+        .bind(STATE_KEY_RATING_FINGERPRINT)
+        .execute(pool)
+        .await
+        .wrap_err("clear rating fingerprint")?;
+    Ok(())
+}
+
+pub(crate) async fn count_scores_rows(
+    pool: &SqlitePool,
+    query_timeout: Duration,
+) -> eyre::Result<i64> {
+    with_timeout(query_timeout, "count scores rows", async {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM scores")
+            .fetch_one(pool)
+            .await
+            .wrap_err("count scores rows")
+    })
+    .await
+}
+
+pub(crate) async fn count_playlogs_rows(
+    pool: &SqlitePool,
+    query_timeout: Duration,
+) -> eyre::Result<i64> {
+    with_timeout(query_timeout, "count playlogs rows", async {
+        sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM playlogs")
+            .fetch_one(pool)
+            .await
+            .wrap_err("count playlogs rows")
+    })
+    .await
 }
 
 pub(crate) async fn apply_recent_sync_atomic(
     pool: &SqlitePool,
+    query_timeout: Duration,
     score_updates: &[ParsedScoreEntry],
+    catalog: &CatalogIndex<'_>,
     playlogs: &[ParsedPlayRecord],
     player_data: &ParsedPlayerProfile,
     updated_at: i64,
 ) -> eyre::Result<()> {
-    let mut tx = pool.begin().await.wrap_err("begin transaction")?;
+    with_timeout(query_timeout, "apply recent sync", async {
+        let mut tx = pool.begin().await.wrap_err("begin transaction")?;
 
-    for entry in score_updates {
-        upsert_score(&mut tx, entry).await?;
-    }
+        for entry in score_updates {
+            upsert_score(&mut tx, entry, catalog).await?;
+        }
 
-    for entry in playlogs {
-        let Some(played_at_unixtime) = entry.played_at_unixtime else {
-            continue;
-        };
-        insert_playlog(&mut tx, played_at_unixtime, entry).await?;
+        for entry in playlogs {
+            let Some(played_at_unixtime) = entry.played_at_unixtime else {
+                continue;
+            };
+            insert_playlog(&mut tx, played_at_unixtime, entry).await?;
+        }
+
+        upsert_player_profile_snapshot_in_tx(&mut tx, player_data, updated_at)
+            .await
+            .wrap_err("store player profile snapshot")?;
+
+        tx.commit().await.wrap_err("commit transaction")?;
+        Ok(())
+    })
+    .await
+}
+
+pub(crate) async fn store_player_profile_snapshot(
+    pool: &SqlitePool,
+    query_timeout: Duration,
+    player_data: &ParsedPlayerProfile,
+    updated_at: i64,
+) -> eyre::Result<()> {
+    with_timeout(query_timeout, "store player profile snapshot", async {
+        let mut tx = pool.begin().await.wrap_err("begin transaction")?;
+        upsert_player_profile_snapshot_in_tx(&mut tx, player_data, updated_at)
+            .await
+            .wrap_err("store player profile snapshot")?;
+        tx.commit().await.wrap_err("commit transaction")?;
+        Ok(())
+    })
+    .await
+}
+
+/// The user's rating goal set through `PUT /api/player/target`, if any.
+pub(crate) async fn get_target_rating(pool: &SqlitePool) -> eyre::Result<Option<u32>> {
+    let value = sqlx::query_scalar::<_, String>("SELECT value FROM app_state WHERE key = ?1")
+        .bind(STATE_KEY_TARGET_RATING)
+        .fetch_optional(pool)
+        .await
+        .wrap_err("load target rating")?;
+    value
+        .map(|value| {
+            value
+                .parse::<u32>()
+                .wrap_err_with(|| format!("parse app_state key '{STATE_KEY_TARGET_RATING}' as u32"))
+        })
+        .transpose()
+}
+
+/// Store the rating goal, or clear it when `target_rating` is `None`.
+pub(crate) async fn set_target_rating(
+    pool: &SqlitePool,
+    target_rating: Option<u32>,
+    updated_at: i64,
+) -> eyre::Result<()> {
+    let mut tx = pool.begin().await.wrap_err("begin transaction")?;
+    match target_rating {
+        Some(target_rating) => {
+            set_app_state_string_in_tx(
+                &mut tx,
+                STATE_KEY_TARGET_RATING,
+                &target_rating.to_string(),
+                updated_at,
+            )
+            .await
+            .wrap_err("store target rating")?;
+        }
+        None => {
+            sqlx::query("DELETE FROM app_state WHERE key = ?1")
+                .bind(STATE_KEY_TARGET_RATING)
+                .execute(&mut *tx)
+                .await
+                .wrap_err("clear target rating")?;
+        }
     }
+    tx.commit().await.wrap_err("commit transaction")?;
+    Ok(())
+}
 
-    upsert_player_profile_snapshot_in_tx(&mut tx, player_data, updated_at)
+pub(crate) async fn get_recap_state(pool: &SqlitePool) -> eyre::Result<RecapStateApiResponse> {
+    let load = |key| {
+        sqlx::query_scalar::<_, String>("SELECT value FROM app_state WHERE key = ?1")
+            .bind(key)
+            .fetch_optional(pool)
+    };
+    let recapped_day = load(STATE_KEY_RECAPPED_DAY)
+        .await
+        .wrap_err("load recapped day")?;
+    let session = load(STATE_KEY_RECAP_SESSION)
         .await
-        .wrap_err("store player profile snapshot")?;
+        .wrap_err("load recap session")?
+        .map(|value| {
+            serde_json::from_str::<RecapSessionState>(&value)
+                .wrap_err_with(|| format!("parse app_state key '{STATE_KEY_RECAP_SESSION}'"))
+        })
+        .transpose()?;
+    Ok(RecapStateApiResponse {
+        recapped_day,
+        session,
+    })
+}
 
+/// Store the fields `update` carries and leave the others as they are.
+pub(crate) async fn update_recap_state(
+    pool: &SqlitePool,
+    update: &RecapStateApiResponse,
+    updated_at: i64,
+) -> eyre::Result<()> {
+    let mut tx = pool.begin().await.wrap_err("begin transaction")?;
+    if let Some(recapped_day) = &update.recapped_day {
+        set_app_state_string_in_tx(&mut tx, STATE_KEY_RECAPPED_DAY, recapped_day, updated_at)
+            .await
+            .wrap_err("store recapped day")?;
+    }
+    if let Some(session) = &update.session {
+        let value = serde_json::to_string(session).wrap_err("serialize recap session")?;
+        set_app_state_string_in_tx(&mut tx, STATE_KEY_RECAP_SESSION, &value, updated_at)
+            .await
+            .wrap_err("store recap session")?;
+    }
     tx.commit().await.wrap_err("commit transaction")?;
     Ok(())
 }
 
-pub(crate) async fn store_player_profile_snapshot(
+pub(crate) async fn is_score_seed_in_progress(pool: &SqlitePool) -> eyre::Result<bool> {
+    let value = sqlx::query_scalar::<_, String>("SELECT value FROM app_state WHERE key = ?1")
+        .bind(STATE_KEY_SCORE_SEED_IN_PROGRESS)
+        .fetch_optional(pool)
+        .await
+        .wrap_err("load score seed state")?;
+    Ok(value.is_some())
+}
+
+pub(crate) async fn set_score_seed_in_progress(
     pool: &SqlitePool,
-    player_data: &ParsedPlayerProfile,
+    in_progress: bool,
     updated_at: i64,
 ) -> eyre::Result<()> {
     let mut tx = pool.begin().await.wrap_err("begin transaction")?;
-    upsert_player_profile_snapshot_in_tx(&mut tx, player_data, updated_at)
+    if in_progress {
+        set_app_state_string_in_tx(&mut tx, STATE_KEY_SCORE_SEED_IN_PROGRESS, "1", updated_at)
+            .await
+            .wrap_err("mark score seed in progress")?;
+    } else {
+        sqlx::query("DELETE FROM app_state WHERE key = ?1")
+            .bind(STATE_KEY_SCORE_SEED_IN_PROGRESS)
+            .execute(&mut *tx)
+            .await
+            .wrap_err("clear score seed state")?;
+    }
+    tx.commit().await.wrap_err("commit transaction")?;
+    Ok(())
+}
+
+/// Count one more poll cycle in a row that failed inside a parser and remember its error.
+/// Returns the new consecutive count.
+pub(crate) async fn record_parse_failure(
+    pool: &SqlitePool,
+    message: &str,
+    updated_at: i64,
+) -> eyre::Result<u32> {
+    let mut tx = pool.begin().await.wrap_err("begin transaction")?;
+    let previous = load_parse_error_count(&mut *tx).await?;
+    let count = previous.saturating_add(1);
+    set_app_state_string_in_tx(
+        &mut tx,
+        STATE_KEY_PARSE_ERROR_COUNT,
+        &count.to_string(),
+        updated_at,
+    )
+    .await
+    .wrap_err("store parse error count")?;
+    set_app_state_string_in_tx(&mut tx, STATE_KEY_LAST_PARSE_ERROR, message, updated_at)
         .await
-        .wrap_err("store player profile snapshot")?;
+        .wrap_err("store last parse error")?;
     tx.commit().await.wrap_err("commit transaction")?;
+    Ok(count)
+}
+
+/// End a parse failure streak. The last error message is kept for `GET /api/sync/status`.
+pub(crate) async fn reset_parse_failure_count(pool: &SqlitePool) -> eyre::Result<()> {
+    sqlx::query("DELETE FROM app_state WHERE key = ?1")
+        .bind(STATE_KEY_PARSE_ERROR_COUNT)
+        .execute(pool)
+        .await
+        .wrap_err("clear parse error count")?;
     Ok(())
 }
 
+/// Remember when a poll cycle last finished without failing, for `GET /api/sync/status`.
+pub(crate) async fn record_sync_success(pool: &SqlitePool, synced_at: i64) -> eyre::Result<()> {
+    let mut tx = pool.begin().await.wrap_err("begin transaction")?;
+    set_app_state_string_in_tx(
+        &mut tx,
+        STATE_KEY_LAST_SYNCED_AT,
+        &synced_at.to_string(),
+        synced_at,
+    )
+    .await
+    .wrap_err("store last synced at")?;
+    tx.commit().await.wrap_err("commit transaction")?;
+    Ok(())
+}
+
+pub(crate) async fn get_sync_status(pool: &SqlitePool) -> eyre::Result<SyncStatusApiResponse> {
+    let parse_error_count = load_parse_error_count(pool).await?;
+    let last_parse_error =
+        sqlx::query_scalar::<_, String>("SELECT value FROM app_state WHERE key = ?1")
+            .bind(STATE_KEY_LAST_PARSE_ERROR)
+            .fetch_optional(pool)
+            .await
+            .wrap_err("load last parse error")?;
+    let last_synced_at_unixtime =
+        sqlx::query_scalar::<_, String>("SELECT value FROM app_state WHERE key = ?1")
+            .bind(STATE_KEY_LAST_SYNCED_AT)
+            .fetch_optional(pool)
+            .await
+            .wrap_err("load last synced at")?
+            .map(|value| {
+                value.parse::<i64>().wrap_err_with(|| {
+                    format!("parse app_state key '{STATE_KEY_LAST_SYNCED_AT}' as i64")
+                })
+            })
+            .transpose()?;
+    Ok(SyncStatusApiResponse {
+        parse_error_count,
+        last_parse_error,
+        last_synced_at_unixtime,
+    })
+}
+
+async fn load_parse_error_count(
+    executor: impl sqlx::Executor<'_, Database = Sqlite>,
+) -> eyre::Result<u32> {
+    let value = sqlx::query_scalar::<_, String>("SELECT value FROM app_state WHERE key = ?1")
+        .bind(STATE_KEY_PARSE_ERROR_COUNT)
+        .fetch_optional(executor)
+        .await
+        .wrap_err("load parse error count")?;
+    value
+        .map(|value| {
+            value.parse::<u32>().wrap_err_with(|| {
+                format!("parse app_state key '{STATE_KEY_PARSE_ERROR_COUNT}' as u32")
+            })
+        })
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
 async fn set_app_state_string_in_tx(
     tx: &mut sqlx::Transaction<'_, Sqlite>,
     key: &str,
@@ -173,22 +697,137 @@ async fn upsert_player_profile_snapshot_in_tx(
             .wrap_err(context)?;
     }
 
+    insert_rating_snapshot(
+        tx,
+        player_data.rating,
+        player_data.total_play_count,
+        updated_at,
+    )
+    .await
+    .wrap_err("store rating snapshot")?;
+
+    for (key, value, context) in [
+        (
+            STATE_KEY_AREA_RANK,
+            player_data.area_rank.map(|area_rank| area_rank.to_string()),
+            "area rank",
+        ),
+        (
+            STATE_KEY_COURSE_RANK,
+            player_data.course_rank.clone(),
+            "course rank",
+        ),
+        (
+            STATE_KEY_CLASS_RANK,
+            player_data.class_rank.clone(),
+            "class rank",
+        ),
+    ] {
+        match value {
+            Some(value) => {
+                set_app_state_string_in_tx(tx, key, &value, updated_at)
+                    .await
+                    .wrap_err_with(|| format!("store {context}"))?;
+            }
+            None => {
+                sqlx::query("DELETE FROM app_state WHERE key = ?1")
+                    .bind(key)
+                    .execute(&mut **tx)
+                    .await
+                    .wrap_err_with(|| format!("clear {context}"))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Append a `rating_history` row unless the latest row already has this rating.
+async fn insert_rating_snapshot(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    rating: u32,
+    total_play_count: u32,
+    recorded_at: i64,
+) -> eyre::Result<()> {
+    sqlx::query(
+        r#"
+INSERT INTO rating_history (recorded_at, rating, total_play_count)
+SELECT ?1, ?2, ?3
+WHERE (
+  SELECT rating FROM rating_history ORDER BY recorded_at DESC, rowid DESC LIMIT 1
+) IS NOT ?2
+"#,
+    )
+    .bind(recorded_at)
+    .bind(i64::from(rating))
+    .bind(i64::from(total_play_count))
+    .execute(&mut **tx)
+    .await
+    .wrap_err("insert rating snapshot")?;
     Ok(())
 }
 
+/// Rating changes recorded at or after `since_unixtime`, oldest first. The snapshot in
+/// effect at `since_unixtime` is included too, so a quiet window still has a baseline.
+pub(crate) async fn query_rating_history(
+    pool: &SqlitePool,
+    since_unixtime: i64,
+) -> eyre::Result<Vec<RatingSnapshotApiResponse>> {
+    let rows = sqlx::query_as::<_, (i64, i64, i64)>(
+        r#"
+SELECT recorded_at, rating, total_play_count
+FROM rating_history
+WHERE recorded_at >= ?1
+   OR rowid = (
+     SELECT rowid FROM rating_history
+     WHERE recorded_at < ?1
+     ORDER BY recorded_at DESC, rowid DESC
+     LIMIT 1
+   )
+ORDER BY recorded_at ASC, rowid ASC
+"#,
+    )
+    .bind(since_unixtime)
+    .fetch_all(pool)
+    .await
+    .wrap_err("query rating history")?;
+
+    rows.into_iter()
+        .map(|(recorded_at, rating, total_play_count)| {
+            Ok(RatingSnapshotApiResponse {
+                recorded_at,
+                rating: u32::try_from(rating).wrap_err("rating out of range")?,
+                total_play_count: u32::try_from(total_play_count)
+                    .wrap_err("total play count out of range")?,
+            })
+        })
+        .collect()
+}
+
 async fn upsert_score(
     tx: &mut sqlx::Transaction<'_, Sqlite>,
     entry: &ParsedScoreEntry,
+    catalog: &CatalogIndex<'_>,
 ) -> eyre::Result<bool> {
     let achievement_x10000 = percent_to_x10000(entry.achievement_percent);
+    let rating = score_rating(
+        catalog,
+        &entry.title,
+        &entry.artist,
+        entry.chart_type,
+        entry.diff_category,
+        achievement_x10000,
+        entry.fc,
+    );
     let result = sqlx::query(
         r#"
 		INSERT INTO scores (
 		  title, genre, artist, chart_type, diff_category,
 		  achievement_x10000, rank, fc, sync,
-		  dx_score, dx_score_max, last_played_at, play_count
+		  dx_score, dx_score_max, last_played_at, play_count, level,
+		  internal_level, rating_points, bucket
 		)
-		VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+		VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
 		ON CONFLICT(title, chart_type, diff_category, genre, artist) DO UPDATE SET
 		  achievement_x10000 = excluded.achievement_x10000,
 		  rank = excluded.rank,
@@ -197,7 +836,11 @@ async fn upsert_score(
 		  dx_score = excluded.dx_score,
 		  dx_score_max = excluded.dx_score_max,
 		  last_played_at = excluded.last_played_at,
-		  play_count = excluded.play_count
+		  play_count = excluded.play_count,
+		  level = excluded.level,
+		  internal_level = excluded.internal_level,
+		  rating_points = excluded.rating_points,
+		  bucket = excluded.bucket
         WHERE scores.achievement_x10000 IS NOT excluded.achievement_x10000
            OR scores.rank IS NOT excluded.rank
            OR scores.fc IS NOT excluded.fc
@@ -206,6 +849,10 @@ async fn upsert_score(
            OR scores.dx_score_max IS NOT excluded.dx_score_max
            OR scores.last_played_at IS NOT excluded.last_played_at
            OR scores.play_count IS NOT excluded.play_count
+           OR scores.level IS NOT excluded.level
+           OR scores.internal_level IS NOT excluded.internal_level
+           OR scores.rating_points IS NOT excluded.rating_points
+           OR scores.bucket IS NOT excluded.bucket
 		"#,
     )
     .bind(&entry.title)
@@ -221,6 +868,10 @@ async fn upsert_score(
     .bind(entry.dx_score_max)
     .bind(entry.last_played_at.as_deref())
     .bind(entry.play_count.map(i64::from))
+    .bind(normalized_level(&entry.level))
+    .bind(rating.map(|rating| f64::from(rating.internal_level)))
+    .bind(rating.map(|rating| i64::from(rating.rating_points)))
+    .bind(rating.map(|rating| rating.bucket.as_str()))
     .execute(&mut **tx)
     .await
     .wrap_err("upsert scores")?;
@@ -251,9 +902,9 @@ async fn insert_playlog(
 	  title, genre, artist, chart_type, diff_category,
 	  achievement_x10000, achievement_new_record,
 	  score_rank, fc, sync,
-	  dx_score, dx_score_max
+	  dx_score, dx_score_max, is_buddy_play
 	)
-	VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
+	VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
 	ON CONFLICT(played_at_unixtime) DO NOTHING
 	"#,
     )
@@ -273,6 +924,7 @@ async fn insert_playlog(
     .bind(entry.sync.map(|v| v.as_str()))
     .bind(entry.dx_score)
     .bind(entry.dx_score_max)
+    .bind(entry.is_buddy_play)
     .execute(&mut **tx)
     .await
     .wrap_err("insert playlogs")?;
@@ -291,11 +943,22 @@ fn chart_type_str(t: ChartType) -> &'static str {
     match t {
         ChartType::Std => "STD",
         ChartType::Dx => "DX",
+        ChartType::Utage => "UTAGE",
     }
 }
 
+fn normalized_level(level: &str) -> Option<&str> {
+    let level = level.trim();
+    (!level.is_empty()).then_some(level)
+}
+
 fn percent_to_x10000(percent: Option<f32>) -> Option<i64> {
-    percent.map(|p| (p as f64 * 10000.0).round() as i64)
+    let percent = percent?;
+    if percent > MAX_ACHIEVEMENT_PERCENT {
+        warn!("refusing to store impossible achievement {percent}%");
+        return None;
+    }
+    Some((percent as f64 * 10000.0).round() as i64)
 }
 
 #[cfg(test)]
@@ -343,9 +1006,188 @@ mod tests {
             sync: Some("FS".parse().unwrap()),
             dx_score: Some(1000),
             dx_score_max: Some(2000),
+            is_buddy_play: false,
         }
     }
 
+    #[tokio::test]
+    async fn with_timeout_fails_slow_queries_and_rolls_back() -> eyre::Result<()> {
+        let pool = connect("sqlite::memory:").await?;
+        migrate(&pool).await?;
+
+        let result = with_timeout(Duration::from_millis(20), "slow upsert", async {
+            let mut tx = pool.begin().await.wrap_err("begin transaction")?;
+            upsert_score(&mut tx, &sample_score_entry(), &CatalogIndex::default()).await?;
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            tx.commit().await.wrap_err("commit transaction")?;
+            Ok(())
+        })
+        .await;
+
+        let err = result.expect_err("slow query should time out");
+        assert!(err.to_string().contains("slow upsert timed out"));
+        assert_eq!(count_scores_rows(&pool, DEFAULT_QUERY_TIMEOUT).await?, 0);
+
+        let rows = with_timeout(
+            Duration::from_secs(5),
+            "fast count",
+            count_scores_rows(&pool, DEFAULT_QUERY_TIMEOUT),
+        )
+        .await?;
+        assert_eq!(rows, 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rating_history_skips_unchanged_consecutive_ratings() -> eyre::Result<()> {
+        let pool = connect("sqlite::memory:").await?;
+        migrate(&pool).await?;
+
+        let profile = |rating, total_play_count| ParsedPlayerProfile {
+            user_name: "Player".to_string(),
+            rating,
+            current_version_play_count: 1,
+            total_play_count,
+            area_rank: None,
+            course_rank: None,
+            class_rank: None,
+        };
+        store_player_profile_snapshot(&pool, DEFAULT_QUERY_TIMEOUT, &profile(15_000, 10), 100)
+            .await?;
+        store_player_profile_snapshot(&pool, DEFAULT_QUERY_TIMEOUT, &profile(15_000, 11), 200)
+            .await?;
+        store_player_profile_snapshot(&pool, DEFAULT_QUERY_TIMEOUT, &profile(15_020, 12), 300)
+            .await?;
+        store_player_profile_snapshot(&pool, DEFAULT_QUERY_TIMEOUT, &profile(15_020, 13), 400)
+            .await?;
+        store_player_profile_snapshot(&pool, DEFAULT_QUERY_TIMEOUT, &profile(15_041, 14), 500)
+            .await?;
+
+        let ratings = |history: Vec<RatingSnapshotApiResponse>| {
+            history
+                .into_iter()
+                .map(|snapshot| (snapshot.recorded_at, snapshot.rating))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ratings(query_rating_history(&pool, 0).await?),
+            vec![(100, 15_000), (300, 15_020), (500, 15_041)]
+        );
+        // The snapshot in effect at `since` is kept as the baseline.
+        assert_eq!(
+            ratings(query_rating_history(&pool, 400).await?),
+            vec![(300, 15_020), (500, 15_041)]
+        );
+        Ok(())
+    }
+
+    fn catalog_with_level(internal_level: Option<f32>) -> Vec<SongCatalogSong> {
+        vec![SongCatalogSong {
+            title: "Song A".to_string(),
+            genre: "Genre A".to_string(),
+            artist: "Artist A".to_string(),
+            image_name: None,
+            aliases: models::SongAliases::default(),
+            sheets: vec![maimai_client::SongCatalogSheet {
+                chart_type: ChartType::Dx,
+                diff_category: DifficultyCategory::Master,
+                level: "12+".to_string(),
+                version: Some("CiRCLE".to_string()),
+                internal_level,
+                region: models::SongChartRegion {
+                    jp: true,
+                    intl: true,
+                },
+            }],
+        }]
+    }
+
+    #[tokio::test]
+    async fn refresh_score_ratings_fills_and_clears_rating_columns() -> eyre::Result<()> {
+        let pool = connect("sqlite::memory:").await?;
+        migrate(&pool).await?;
+        let unknown = ParsedScoreEntry {
+            title: "Not In Catalog".to_string(),
+            ..sample_score_entry()
+        };
+        upsert_scores(
+            &pool,
+            DEFAULT_QUERY_TIMEOUT,
+            &[sample_score_entry(), unknown],
+            &CatalogIndex::default(),
+        )
+        .await?;
+        let stored = || {
+            sqlx::query_as::<_, (String, Option<i64>, Option<String>)>(
+                "SELECT title, rating_points, bucket FROM scores ORDER BY title",
+            )
+            .fetch_all(&pool)
+        };
+
+        assert_eq!(
+            refresh_score_ratings(
+                &pool,
+                DEFAULT_QUERY_TIMEOUT,
+                &catalog_with_level(Some(12.7))
+            )
+            .await?,
+            1
+        );
+        assert_eq!(
+            stored().await?,
+            vec![
+                ("Not In Catalog".to_string(), None, None),
+                ("Song A".to_string(), Some(261), Some("NEW".to_string())),
+            ]
+        );
+        // Unchanged inputs rewrite nothing.
+        assert_eq!(
+            refresh_score_ratings(
+                &pool,
+                DEFAULT_QUERY_TIMEOUT,
+                &catalog_with_level(Some(12.7))
+            )
+            .await?,
+            0
+        );
+
+        // The fingerprint outlives the process, so a restart can skip the backfill.
+        assert_eq!(
+            get_rating_fingerprint(&pool).await?,
+            Some(catalog_rating_fingerprint(&catalog_with_level(Some(12.7))))
+        );
+        clear_rating_fingerprint(&pool).await?;
+        assert_eq!(get_rating_fingerprint(&pool).await?, None);
+
+        refresh_score_ratings(&pool, DEFAULT_QUERY_TIMEOUT, &catalog_with_level(None)).await?;
+        assert_eq!(stored().await?[1], ("Song A".to_string(), None, None));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn upsert_scores_rates_rows_against_catalog() -> eyre::Result<()> {
+        let pool = connect("sqlite::memory:").await?;
+        migrate(&pool).await?;
+        let catalog = catalog_with_level(Some(12.7));
+        upsert_scores(
+            &pool,
+            DEFAULT_QUERY_TIMEOUT,
+            &[sample_score_entry()],
+            &CatalogIndex::new(&catalog),
+        )
+        .await?;
+
+        let stored = query_played_scores(&pool, None, None).await?;
+        assert_eq!(stored[0].internal_level, Some(f64::from(12.7_f32)));
+        assert_eq!(stored[0].rating_points, Some(261));
+        assert_eq!(stored[0].bucket.as_deref(), Some("NEW"));
+        assert_eq!(
+            refresh_score_ratings(&pool, DEFAULT_QUERY_TIMEOUT, &catalog).await?,
+            0
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn upsert_scores_overwrites_detail_fields() -> eyre::Result<()> {
         let pool = connect("sqlite::memory:").await?;
@@ -368,7 +1210,13 @@ mod tests {
             play_count: Some(3),
             source_idx: None,
         };
-        upsert_scores(&pool, &[first]).await?;
+        upsert_scores(
+            &pool,
+            DEFAULT_QUERY_TIMEOUT,
+            &[first],
+            &CatalogIndex::default(),
+        )
+        .await?;
 
         let second = ParsedScoreEntry {
             title: "Song A".to_string(),
@@ -387,7 +1235,13 @@ mod tests {
             play_count: Some(7),
             source_idx: None,
         };
-        upsert_scores(&pool, &[second]).await?;
+        upsert_scores(
+            &pool,
+            DEFAULT_QUERY_TIMEOUT,
+            &[second],
+            &CatalogIndex::default(),
+        )
+        .await?;
 
         #[expect(clippy::type_complexity)]
         let row: (
@@ -415,67 +1269,18 @@ mod tests {
         Ok(())
     }
 
-    #[tokio::test]
-    async fn replace_scores_replaces_existing_rows_transactionally() -> eyre::Result<()> {
-        let pool = connect("sqlite::memory:").await?;
-        migrate(&pool).await?;
-
-        let initial = ParsedScoreEntry {
-            title: "Song A".to_string(),
-            genre: "Genre A".to_string(),
-            artist: "Artist A".to_string(),
-            chart_type: ChartType::Dx,
-            diff_category: DifficultyCategory::Master,
-            level: "12+".to_string(),
-            achievement_percent: Some(99.1234),
-            rank: None,
-            fc: None,
-            sync: None,
-            dx_score: Some(1000),
-            dx_score_max: Some(2000),
-            last_played_at: Some("2026/01/20 00:00".to_string()),
-            play_count: Some(3),
-            source_idx: None,
-        };
-        upsert_scores(&pool, &[initial]).await?;
-
-        let replacement = ParsedScoreEntry {
-            title: "Song B".to_string(),
-            genre: "Genre B".to_string(),
-            artist: "Artist B".to_string(),
-            chart_type: ChartType::Std,
-            diff_category: DifficultyCategory::Expert,
-            level: "11+".to_string(),
-            achievement_percent: Some(98.0),
-            rank: None,
-            fc: None,
-            sync: None,
-            dx_score: Some(900),
-            dx_score_max: Some(1900),
-            last_played_at: None,
-            play_count: None,
-            source_idx: None,
-        };
-        replace_scores(&pool, &[replacement]).await?;
-
-        let titles: Vec<String> = sqlx::query_scalar("SELECT title FROM scores ORDER BY title")
-            .fetch_all(&pool)
-            .await?;
-        assert_eq!(titles, vec!["Song B".to_string()]);
-
-        Ok(())
-    }
-
     #[tokio::test]
     async fn upsert_score_returns_true_for_insert_and_update() -> eyre::Result<()> {
         let pool = connect("sqlite::memory:").await?;
         migrate(&pool).await?;
         let mut tx = pool.begin().await?;
 
-        let inserted = upsert_score(&mut tx, &sample_score_entry()).await?;
+        let inserted =
+            upsert_score(&mut tx, &sample_score_entry(), &CatalogIndex::default()).await?;
         assert!(inserted);
 
-        let unchanged = upsert_score(&mut tx, &sample_score_entry()).await?;
+        let unchanged =
+            upsert_score(&mut tx, &sample_score_entry(), &CatalogIndex::default()).await?;
         assert!(!unchanged);
 
         let mut updated_score = sample_score_entry();
@@ -486,7 +1291,7 @@ mod tests {
         updated_score.last_played_at = Some("2026/01/23 01:14".to_string());
         updated_score.play_count = Some(7);
 
-        let updated = upsert_score(&mut tx, &updated_score).await?;
+        let updated = upsert_score(&mut tx, &updated_score, &CatalogIndex::default()).await?;
         assert!(updated);
         tx.commit().await?;
 
@@ -522,4 +1327,171 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn insert_playlog_stores_the_buddy_flag() -> eyre::Result<()> {
+        let pool = connect("sqlite::memory:").await?;
+        migrate(&pool).await?;
+        let buddy = ParsedPlayRecord {
+            is_buddy_play: true,
+            ..sample_playlog()
+        };
+        let mut tx = pool.begin().await?;
+        insert_playlog(&mut tx, 1, &sample_playlog()).await?;
+        insert_playlog(&mut tx, 2, &buddy).await?;
+        tx.commit().await?;
+
+        let stored = query_all_playlogs(&pool)
+            .await?
+            .into_iter()
+            .map(|row| (row.played_at_unixtime, row.is_buddy_play))
+            .collect::<Vec<_>>();
+        assert_eq!(stored, vec![(1, false), (2, true)]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn count_helpers_match_seeded_rows() -> eyre::Result<()> {
+        let pool = connect("sqlite::memory:").await?;
+        migrate(&pool).await?;
+        assert_eq!(count_scores_rows(&pool, DEFAULT_QUERY_TIMEOUT).await?, 0);
+        assert_eq!(count_playlogs_rows(&pool, DEFAULT_QUERY_TIMEOUT).await?, 0);
+
+        let second_score = ParsedScoreEntry {
+            diff_category: DifficultyCategory::Expert,
+            ..sample_score_entry()
+        };
+        upsert_scores(
+            &pool,
+            DEFAULT_QUERY_TIMEOUT,
+            &[sample_score_entry(), second_score],
+            &CatalogIndex::default(),
+        )
+        .await?;
+        let mut tx = pool.begin().await?;
+        insert_playlog(&mut tx, 123_456, &sample_playlog()).await?;
+        tx.commit().await?;
+
+        assert_eq!(count_scores_rows(&pool, DEFAULT_QUERY_TIMEOUT).await?, 2);
+        assert_eq!(count_playlogs_rows(&pool, DEFAULT_QUERY_TIMEOUT).await?, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn parse_failure_counter_increments_and_resets() -> eyre::Result<()> {
+        let pool = connect("sqlite::memory:").await?;
+        migrate(&pool).await?;
+        assert_eq!(
+            get_sync_status(&pool).await?,
+            SyncStatusApiResponse::default()
+        );
+
+        assert_eq!(
+            record_parse_failure(&pool, "parse recent html: a", 1).await?,
+            1
+        );
+        assert_eq!(
+            record_parse_failure(&pool, "parse recent html: b", 2).await?,
+            2
+        );
+        assert_eq!(
+            get_sync_status(&pool).await?,
+            SyncStatusApiResponse {
+                parse_error_count: 2,
+                last_parse_error: Some("parse recent html: b".to_string()),
+                last_synced_at_unixtime: None,
+            }
+        );
+
+        reset_parse_failure_count(&pool).await?;
+        let status = get_sync_status(&pool).await?;
+        assert_eq!(status.parse_error_count, 0);
+        assert_eq!(
+            status.last_parse_error.as_deref(),
+            Some("parse recent html: b")
+        );
+        assert_eq!(
+            record_parse_failure(&pool, "parse scores html", 3).await?,
+            1
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sync_status_reports_the_last_clean_cycle() -> eyre::Result<()> {
+        let pool = connect("sqlite::memory:").await?;
+        migrate(&pool).await?;
+
+        record_sync_success(&pool, 1_700_000_000).await?;
+        record_sync_success(&pool, 1_700_000_600).await?;
+
+        assert_eq!(
+            get_sync_status(&pool).await?.last_synced_at_unixtime,
+            Some(1_700_000_600)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn target_rating_round_trips_and_clears() -> eyre::Result<()> {
+        let pool = connect("sqlite::memory:").await?;
+        migrate(&pool).await?;
+
+        assert_eq!(get_target_rating(&pool).await?, None);
+
+        set_target_rating(&pool, Some(13_000), 1).await?;
+        assert_eq!(get_target_rating(&pool).await?, Some(13_000));
+
+        set_target_rating(&pool, Some(14_500), 2).await?;
+        assert_eq!(get_target_rating(&pool).await?, Some(14_500));
+
+        set_target_rating(&pool, None, 3).await?;
+        assert_eq!(get_target_rating(&pool).await?, None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn recap_state_updates_only_the_fields_sent() -> eyre::Result<()> {
+        let pool = connect("sqlite::memory:").await?;
+        migrate(&pool).await?;
+        assert_eq!(
+            get_recap_state(&pool).await?,
+            RecapStateApiResponse::default()
+        );
+
+        let session = RecapSessionState {
+            day: "2026-10-16".to_string(),
+            latest_play: Some(100),
+            last_activity_unixtime: Some(1_792_148_400),
+            recapped: true,
+        };
+        update_recap_state(
+            &pool,
+            &RecapStateApiResponse {
+                recapped_day: None,
+                session: Some(session.clone()),
+            },
+            1,
+        )
+        .await?;
+        update_recap_state(
+            &pool,
+            &RecapStateApiResponse {
+                recapped_day: Some("2026-10-16".to_string()),
+                session: None,
+            },
+            2,
+        )
+        .await?;
+
+        assert_eq!(
+            get_recap_state(&pool).await?,
+            RecapStateApiResponse {
+                recapped_day: Some("2026-10-16".to_string()),
+                session: Some(session),
+            }
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,334 @@
+//! `maistats-record-collector db export|import`: move the `scores` and `playlogs` tables
+//! through a single JSON document for backups.
+
+use std::path::Path;
+use std::time::Duration;
+
+use eyre::WrapErr;
+use maimai_client::SongCatalogSong;
+use models::{StoredPlayRecord, StoredScoreEntry};
+use serde::{Deserialize, Serialize};
+
+use crate::config::SongCatalogEnv;
+use crate::db::{self, SqlitePool};
+use crate::song_catalog::SongCatalogSource;
+use crate::tasks::utils::recent::unix_timestamp;
+use crate::tasks::utils::scores::load_rating_catalog;
+
+const USAGE: &str = "usage: maistats-record-collector db [--json] export --out <path> | \
+    db [--json] import --in <path>";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseDump {
+    pub exported_at: i64,
+    pub scores: Vec<StoredScoreEntry>,
+    pub playlogs: Vec<StoredPlayRecord>,
+}
+
+/// Entry point for the `db` subcommand; `args` are the arguments after `db`. `--json` prints
+/// the summary as one JSON object instead of a sentence.
+pub async fn run_db_command(args: &[String]) -> eyre::Result<()> {
+    let json_output = args.iter().any(|arg| arg == "--json");
+    let args = args
+        .iter()
+        .map(String::as_str)
+        .filter(|arg| *arg != "--json")
+        .collect::<Vec<_>>();
+    let (command, flag) = match args.first().copied() {
+        Some("export") => ("export", "--out"),
+        Some("import") => ("import", "--in"),
+        _ => return Err(eyre::eyre!(USAGE)),
+    };
+    let path = match &args[1..] {
+        [name, path] if *name == flag => Path::new(path),
+        _ => return Err(eyre::eyre!(USAGE)),
+    };
+
+    let database_url = std::env::var("DATABASE_URL").wrap_err("missing env var: DATABASE_URL")?;
+    let pool = db::connect(&database_url)
+        .await
+        .wrap_err("connect to database")?;
+    db::migrate(&pool)
+        .await
+        .wrap_err("run database migrations")?;
+
+    if command == "export" {
+        let dump = export_database(&pool).await?;
+        let json = serde_json::to_vec_pretty(&dump).wrap_err("serialize database dump")?;
+        std::fs::write(path, json)
+            .wrap_err_with(|| format!("write database dump to {}", path.display()))?;
+        print_summary(json_output, command, &dump, path);
+    } else {
+        let json = std::fs::read(path)
+            .wrap_err_with(|| format!("read database dump from {}", path.display()))?;
+        let dump: DatabaseDump = serde_json::from_slice(&json).wrap_err("parse database dump")?;
+        let settings = SongCatalogEnv::from_env();
+        let song_catalog = SongCatalogSource::from_settings(
+            &settings.song_database_url,
+            settings.include_estimated_internal_levels,
+            Path::new(&settings.data_dir),
+        )?;
+        let catalog = load_rating_catalog(&pool, &song_catalog).await;
+        import_database(&pool, db::DEFAULT_QUERY_TIMEOUT, &dump, &catalog).await?;
+        print_summary(json_output, command, &dump, path);
+    }
+    Ok(())
+}
+
+fn print_summary(json_output: bool, command: &str, dump: &DatabaseDump, path: &Path) {
+    if json_output {
+        println!(
+            "{}",
+            serde_json::json!({
+                "command": command,
+                "score_rows": dump.scores.len(),
+                "playlog_rows": dump.playlogs.len(),
+                "path": path.display().to_string(),
+            })
+        );
+        return;
+    }
+    let (verb, preposition) = if command == "export" {
+        ("exported", "to")
+    } else {
+        ("imported", "from")
+    };
+    println!(
+        "{verb} {} score rows and {} playlog rows {preposition} {}",
+        dump.scores.len(),
+        dump.playlogs.len(),
+        path.display()
+    );
+}
+
+pub async fn export_database(pool: &SqlitePool) -> eyre::Result<DatabaseDump> {
+    let scores = db::query_all_scores(pool).await.wrap_err("export scores")?;
+    let playlogs = db::query_all_playlogs(pool)
+        .await
+        .wrap_err("export playlogs")?;
+
+    Ok(DatabaseDump {
+        exported_at: unix_timestamp(),
+        scores,
+        playlogs,
+    })
+}
+
+/// Upsert every row of `dump` in one transaction, then rate the scores against `catalog`.
+/// Rows already in the database but absent from the dump are left alone.
+pub async fn import_database(
+    pool: &SqlitePool,
+    query_timeout: Duration,
+    dump: &DatabaseDump,
+    catalog: &[SongCatalogSong],
+) -> eyre::Result<()> {
+    let mut tx = pool.begin().await.wrap_err("begin transaction")?;
+
+    for score in &dump.scores {
+        sqlx::query(
+            r#"
+INSERT INTO scores (
+  title, genre, artist, chart_type, diff_category, achievement_x10000, rank, fc, sync,
+  dx_score, dx_score_max, last_played_at, play_count, level
+)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
+ON CONFLICT(title, chart_type, diff_category, genre, artist) DO UPDATE SET
+  achievement_x10000 = excluded.achievement_x10000,
+  rank = excluded.rank,
+  fc = excluded.fc,
+  sync = excluded.sync,
+  dx_score = excluded.dx_score,
+  dx_score_max = excluded.dx_score_max,
+  last_played_at = excluded.last_played_at,
+  play_count = excluded.play_count,
+  level = excluded.level
+"#,
+        )
+        .bind(&score.title)
+        .bind(&score.genre)
+        .bind(&score.artist)
+        .bind(&score.chart_type)
+        .bind(&score.diff_category)
+        .bind(score.achievement_x10000)
+        .bind(&score.rank)
+        .bind(&score.fc)
+        .bind(&score.sync)
+        .bind(score.dx_score)
+        .bind(score.dx_score_max)
+        .bind(&score.last_played_at)
+        .bind(score.play_count)
+        .bind(&score.level)
+        .execute(&mut *tx)
+        .await
+        .wrap_err_with(|| format!("import score row for '{}'", score.title))?;
+    }
+
+    for playlog in &dump.playlogs {
+        sqlx::query(
+            r#"
+INSERT INTO playlogs (
+  played_at_unixtime, played_at, track, title, genre, artist, chart_type, diff_category,
+  achievement_x10000, score_rank, fc, sync, dx_score, dx_score_max, credit_id,
+  achievement_new_record, is_buddy_play
+)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+ON CONFLICT(played_at_unixtime) DO UPDATE SET
+  played_at = excluded.played_at,
+  track = excluded.track,
+  title = excluded.title,
+  genre = excluded.genre,
+  artist = excluded.artist,
+  chart_type = excluded.chart_type,
+  diff_category = excluded.diff_category,
+  achievement_x10000 = excluded.achievement_x10000,
+  score_rank = excluded.score_rank,
+  fc = excluded.fc,
+  sync = excluded.sync,
+  dx_score = excluded.dx_score,
+  dx_score_max = excluded.dx_score_max,
+  credit_id = excluded.credit_id,
+  achievement_new_record = excluded.achievement_new_record,
+  is_buddy_play = excluded.is_buddy_play
+"#,
+        )
+        .bind(playlog.played_at_unixtime)
+        .bind(&playlog.played_at)
+        .bind(playlog.track)
+        .bind(&playlog.title)
+        .bind(&playlog.genre)
+        .bind(&playlog.artist)
+        .bind(&playlog.chart_type)
+        .bind(&playlog.diff_category)
+        .bind(playlog.achievement_x10000)
+        .bind(&playlog.score_rank)
+        .bind(&playlog.fc)
+        .bind(&playlog.sync)
+        .bind(playlog.dx_score)
+        .bind(playlog.dx_score_max)
+        .bind(playlog.credit_id)
+        .bind(playlog.achievement_new_record.unwrap_or(0))
+        .bind(playlog.is_buddy_play)
+        .execute(&mut *tx)
+        .await
+        .wrap_err_with(|| {
+            format!(
+                "import playlog row played at {}",
+                playlog.played_at_unixtime
+            )
+        })?;
+    }
+
+    tx.commit().await.wrap_err("commit transaction")?;
+    // The dump carries no rating columns; rate the restored rows like a catalog refresh does.
+    db::refresh_score_ratings(pool, query_timeout, catalog)
+        .await
+        .wrap_err("rate imported scores")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use maimai_client::{SongCatalogSheet, SongCatalogSong};
+    use models::{ChartType, DifficultyCategory, SongAliases, SongChartRegion};
+
+    use super::{DatabaseDump, export_database, import_database};
+    use crate::db::{DEFAULT_QUERY_TIMEOUT, connect, migrate};
+
+    fn catalog() -> Vec<SongCatalogSong> {
+        vec![SongCatalogSong {
+            title: "Played".to_string(),
+            genre: "maimai".to_string(),
+            artist: "A".to_string(),
+            image_name: None,
+            aliases: SongAliases::default(),
+            sheets: vec![SongCatalogSheet {
+                chart_type: ChartType::Dx,
+                diff_category: DifficultyCategory::Master,
+                level: "14".to_string(),
+                version: Some("CiRCLE".to_string()),
+                internal_level: Some(14.0),
+                region: SongChartRegion {
+                    jp: true,
+                    intl: true,
+                },
+            }],
+        }]
+    }
+
+    async fn seed(pool: &crate::db::SqlitePool) -> eyre::Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO scores (title, genre, artist, chart_type, diff_category, achievement_x10000,
+                    rank, fc, sync, dx_score, dx_score_max, last_played_at, play_count, level)
+VALUES ('Played', 'maimai', 'A', 'DX', 'MASTER', 1005000, 'SSS+', 'AP', 'FDX', 2500, 2700,
+        '2026/10/01 12:00', 4, '14'),
+       ('Unplayed', 'maimai', 'B', 'STD', 'EXPERT', NULL, NULL, NULL, NULL, NULL, NULL,
+        NULL, NULL, NULL)
+"#,
+        )
+        .execute(pool)
+        .await?;
+        sqlx::query(
+            r#"
+INSERT INTO playlogs (played_at_unixtime, played_at, track, title, genre, artist, chart_type,
+                      diff_category, achievement_x10000, score_rank, fc, sync, dx_score,
+                      dx_score_max, credit_id, achievement_new_record)
+VALUES (100, '2026/10/01 12:00', 1, 'Played', 'maimai', 'A', 'DX', 'MASTER', 1005000,
+        'SSS+', 'AP', 'FDX', 2500, 2700, 7, 1),
+       (200, NULL, NULL, 'Utage', NULL, NULL, 'DX', NULL, NULL, NULL, NULL, NULL, NULL,
+        NULL, NULL, 0)
+"#,
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn export_then_import_round_trips_rows_including_nulls() -> eyre::Result<()> {
+        let source = connect("sqlite::memory:").await?;
+        migrate(&source).await?;
+        seed(&source).await?;
+
+        let exported = export_database(&source).await?;
+        let json = serde_json::to_string(&exported)?;
+        let parsed: DatabaseDump = serde_json::from_str(&json)?;
+
+        let target = connect("sqlite::memory:").await?;
+        migrate(&target).await?;
+        import_database(&target, DEFAULT_QUERY_TIMEOUT, &parsed, &catalog()).await?;
+        let reimported = export_database(&target).await?;
+
+        assert_eq!(reimported.scores.len(), 2);
+        assert_eq!(reimported.playlogs.len(), 2);
+        assert_eq!(
+            serde_json::to_value(&reimported.scores)?,
+            serde_json::to_value(&exported.scores)?
+        );
+        assert_eq!(
+            serde_json::to_value(&reimported.playlogs)?,
+            serde_json::to_value(&exported.playlogs)?
+        );
+        let unplayed = &reimported.scores[1];
+        assert_eq!(unplayed.title, "Unplayed");
+        assert_eq!(unplayed.achievement_x10000, None);
+
+        let ratings = sqlx::query_as::<_, (String, Option<i64>, Option<String>)>(
+            "SELECT title, rating_points, bucket FROM scores ORDER BY title",
+        )
+        .fetch_all(&target)
+        .await?;
+        assert_eq!(
+            ratings,
+            vec![
+                ("Played".to_string(), Some(316), Some("NEW".to_string())),
+                ("Unplayed".to_string(), None, None),
+            ]
+        );
+
+        // Importing again is an upsert, not a duplicate insert.
+        import_database(&target, DEFAULT_QUERY_TIMEOUT, &parsed, &catalog()).await?;
+        assert_eq!(export_database(&target).await?.scores.len(), 2);
+        Ok(())
+    }
+}
@@ -7,6 +7,11 @@ use crate::http_client::{MAIMAI_UNAVAILABLE_MESSAGE, is_maintenance_error};
 pub(crate) enum AppError {
     DatabaseError(String),
     NotFound(String),
+    /// A 404 that also lists close matches the client can retry with.
+    NotFoundWithSuggestions {
+        message: String,
+        suggestions: Vec<String>,
+    },
     InternalError(String),
     BadRequest(String),
     Maintenance(String),
@@ -18,10 +23,13 @@ struct ErrorResponse {
     code: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     maintenance: Option<bool>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    suggestions: Vec<String>,
 }
 
 impl IntoResponse for AppError {
     fn into_response(self) -> axum::response::Response {
+        let mut suggestions = Vec::new();
         let (status, message, code, maintenance) = match self {
             AppError::DatabaseError(msg) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -30,6 +38,13 @@ impl IntoResponse for AppError {
                 None,
             ),
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg, "NOT_FOUND", None),
+            AppError::NotFoundWithSuggestions {
+                message,
+                suggestions: close_matches,
+            } => {
+                suggestions = close_matches;
+                (StatusCode::NOT_FOUND, message, "NOT_FOUND", None)
+            }
             AppError::InternalError(msg) => (
                 StatusCode::INTERNAL_SERVER_ERROR,
                 msg,
@@ -50,6 +65,7 @@ impl IntoResponse for AppError {
                 message,
                 code: code.to_string(),
                 maintenance,
+                suggestions,
             }),
         )
             .into_response()
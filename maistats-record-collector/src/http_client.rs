@@ -2,6 +2,7 @@ use std::fs::File;
 use std::io::{BufReader, BufWriter};
 use std::sync::Arc;
 use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 
 use eyre::WrapErr;
@@ -12,6 +13,7 @@ use tokio::sync::Mutex;
 use tokio::time::{Instant, sleep, sleep_until};
 use tracing::warn;
 
+use crate::config::MaintenanceWindow;
 use maimai_auth::intl;
 use models::config::AppConfig;
 
@@ -26,6 +28,17 @@ pub(crate) struct MaimaiClient {
     config: AppConfig,
     cookie_store: Arc<CookieStoreMutex>,
     client: Arc<reqwest::Client>,
+    /// Shared by every clone: set once the session has been verified, cleared when a page
+    /// comes back as the login/expired screen.
+    session_verified: Arc<AtomicBool>,
+    slow_request_threshold: Duration,
+    /// Extra attempts for a GET answered with 502/503, which SEGA returns briefly outside
+    /// maintenance too.
+    transient_retries: u32,
+    /// Delay before the first retry; doubled for each one after.
+    transient_retry_base_delay: Duration,
+    /// A 503 inside this window is real maintenance, so it fails without retrying.
+    maintenance_window: Option<MaintenanceWindow>,
 }
 
 #[derive(Debug)]
@@ -41,6 +54,13 @@ const LOGIN_RETRY_BACKOFFS: [Duration; 3] = [
 ];
 pub(crate) const MAIMAI_UNAVAILABLE_MESSAGE: &str =
     "maimai DX NET is unavailable or under maintenance";
+/// A sync hits one host sequentially, so a couple of idle connections is plenty.
+const POOL_MAX_IDLE_PER_HOST: usize = 2;
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const TCP_KEEPALIVE: Duration = Duration::from_secs(60);
+const DEFAULT_SLOW_REQUEST_THRESHOLD: Duration = Duration::from_secs(5);
+const DEFAULT_TRANSIENT_RETRIES: u32 = 3;
+const DEFAULT_TRANSIENT_RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
 
 impl MaimaiClient {
     pub(crate) fn new(config: &AppConfig) -> eyre::Result<Self> {
@@ -48,8 +68,11 @@ impl MaimaiClient {
         let cookie_store = Arc::new(CookieStoreMutex::new(cookie_store));
 
         let client = Arc::new(
-            reqwest::Client::builder()
-                .default_headers(intl::default_mobile_headers()?)
+            http_client_builder()
+                .default_headers(intl::default_mobile_headers(choose_user_agent(
+                    &config.user_agents,
+                    std::process::id(),
+                ))?)
                 .redirect(reqwest::redirect::Policy::limited(10))
                 .cookie_provider(cookie_store.clone())
                 .build()
@@ -60,14 +83,44 @@ impl MaimaiClient {
             config: config.clone(),
             cookie_store,
             client,
+            session_verified: Arc::new(AtomicBool::new(false)),
+            slow_request_threshold: DEFAULT_SLOW_REQUEST_THRESHOLD,
+            transient_retries: DEFAULT_TRANSIENT_RETRIES,
+            transient_retry_base_delay: DEFAULT_TRANSIENT_RETRY_BASE_DELAY,
+            maintenance_window: None,
         })
     }
 
-    pub(crate) async fn check_logged_in(&mut self) -> eyre::Result<bool> {
-        intl::check_logged_in(self.client.as_ref()).await
+    pub(crate) fn with_slow_request_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_request_threshold = threshold;
+        self
     }
 
-    pub(crate) async fn ensure_logged_in(&mut self) -> eyre::Result<()> {
+    pub(crate) fn with_maintenance_window(mut self, window: MaintenanceWindow) -> Self {
+        self.maintenance_window = Some(window);
+        self
+    }
+
+    /// Production clients keep the defaults; tests shrink the backoff to zero.
+    #[cfg(test)]
+    pub(crate) fn with_transient_retry(mut self, retries: u32, base_delay: Duration) -> Self {
+        self.transient_retries = retries;
+        self.transient_retry_base_delay = base_delay;
+        self
+    }
+
+    pub(crate) async fn check_logged_in(&self) -> eyre::Result<bool> {
+        let logged_in = intl::check_logged_in(self.client.as_ref()).await?;
+        self.session_verified.store(logged_in, Ordering::Release);
+        Ok(logged_in)
+    }
+
+    /// Skips the record page round-trip when a previous step already verified the session;
+    /// expiry is detected per page by `fetch_html_with_auth_recovery` instead.
+    pub(crate) async fn ensure_logged_in(&self) -> eyre::Result<()> {
+        if self.session_verified.load(Ordering::Acquire) {
+            return Ok(());
+        }
         if self.check_logged_in().await? {
             return Ok(());
         }
@@ -95,7 +148,11 @@ impl MaimaiClient {
         }
     }
 
-    pub(crate) async fn login(&mut self) -> eyre::Result<()> {
+    pub(crate) fn mark_session_expired(&self) {
+        self.session_verified.store(false, Ordering::Release);
+    }
+
+    pub(crate) async fn login(&self) -> eyre::Result<()> {
         intl::login(
             self.client.as_ref(),
             &self.config.sega_id,
@@ -108,7 +165,7 @@ impl MaimaiClient {
         Ok(())
     }
 
-    async fn login_and_verify(&mut self) -> eyre::Result<()> {
+    async fn login_and_verify(&self) -> eyre::Result<()> {
         self.login().await?;
         if !self.check_logged_in().await? {
             return Err(eyre::eyre!("login attempted but still not authenticated"));
@@ -119,7 +176,49 @@ impl MaimaiClient {
     }
 
     pub(crate) async fn get_response(&self, url: &Url) -> eyre::Result<HttpResponse> {
+        let mut delay = self.transient_retry_base_delay;
+        for attempt in 1..=self.transient_retries {
+            match self.get_response_once(url).await? {
+                Ok(response) => return Ok(response),
+                Err((status, final_url)) if self.is_maintenance_status(status) => {
+                    return Err(transient_status_error(status, &final_url));
+                }
+                Err((status, _)) => {
+                    warn!(
+                        "transient maimai response, retrying: status={} url={} attempt={}/{} backoff_ms={}",
+                        status,
+                        url,
+                        attempt,
+                        self.transient_retries,
+                        delay.as_millis()
+                    );
+                }
+            }
+            sleep(delay).await;
+            delay = delay.saturating_mul(2);
+        }
+
+        self.get_response_once(url)
+            .await?
+            .map_err(|(status, final_url)| transient_status_error(status, &final_url))
+    }
+
+    /// A 503 while the configured maintenance window is open.
+    fn is_maintenance_status(&self, status: reqwest::StatusCode) -> bool {
+        status == reqwest::StatusCode::SERVICE_UNAVAILABLE
+            && self
+                .maintenance_window
+                .is_some_and(|window| window.contains(time::OffsetDateTime::now_utc()))
+    }
+
+    /// The inner `Err` carries a 502/503 status worth retrying, with the URL that answered
+    /// it; other failures are final.
+    async fn get_response_once(
+        &self,
+        url: &Url,
+    ) -> eyre::Result<Result<HttpResponse, (reqwest::StatusCode, Url)>> {
         wait_for_request_slot().await;
+        let started_at = Instant::now();
         let resp = self
             .client
             .as_ref()
@@ -130,19 +229,54 @@ impl MaimaiClient {
         let status = resp.status();
         let final_url = resp.url().clone();
         let bytes = resp.bytes().await.wrap_err("read response bytes")?;
+        warn_if_slow(url, started_at.elapsed(), self.slow_request_threshold);
+        if matches!(
+            status,
+            reqwest::StatusCode::BAD_GATEWAY | reqwest::StatusCode::SERVICE_UNAVAILABLE
+        ) {
+            return Ok(Err((status, final_url)));
+        }
         if !status.is_success() {
-            if status == reqwest::StatusCode::SERVICE_UNAVAILABLE {
-                return Err(eyre::eyre!(
-                    "site unavailable (503). maimai DX NET may be under maintenance. url={final_url}"
-                ));
-            }
             return Err(eyre::eyre!("non-success status: {status} url={final_url}"));
         }
-        Ok(HttpResponse {
+        Ok(Ok(HttpResponse {
             final_url,
             body: bytes.to_vec(),
-        })
+        }))
+    }
+}
+
+fn transient_status_error(status: reqwest::StatusCode, final_url: &Url) -> eyre::Report {
+    if status == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+        eyre::eyre!(
+            "site unavailable (503). maimai DX NET may be under maintenance. url={final_url}"
+        )
+    } else {
+        eyre::eyre!("non-success status: {status} url={final_url}")
+    }
+}
+
+/// Connection reuse settings shared by every crawl client: the score list, recent and
+/// player pages are fetched back to back, so keeping the TLS connection warm avoids a
+/// handshake per page.
+fn http_client_builder() -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .tcp_keepalive(TCP_KEEPALIVE)
+}
+
+/// Slow responses usually precede SEGA-side timeouts or maintenance, so surface them early.
+fn warn_if_slow(url: &Url, elapsed: Duration, threshold: Duration) {
+    if elapsed < threshold {
+        return;
     }
+    warn!(
+        "slow maimai request: url={} elapsed_ms={} threshold_ms={}",
+        url,
+        elapsed.as_millis(),
+        threshold.as_millis()
+    );
 }
 
 async fn wait_for_request_slot() {
@@ -180,6 +314,15 @@ pub(crate) fn is_maintenance_error(err: &eyre::Error) -> bool {
     })
 }
 
+/// One of the configured User-Agents, fixed for the life of the process so every client
+/// sharing the session cookies presents the same browser.
+fn choose_user_agent(user_agents: &[String], seed: u32) -> &str {
+    if user_agents.is_empty() {
+        return intl::DEFAULT_USER_AGENT;
+    }
+    &user_agents[seed as usize % user_agents.len()]
+}
+
 fn load_cookie_store(path: &std::path::Path) -> eyre::Result<CookieStore> {
     if !path.exists() {
         return Ok(CookieStore::default());
@@ -209,7 +352,199 @@ fn save_cookie_store(
 
 #[cfg(test)]
 mod tests {
-    use super::{is_maintenance_error, next_request_interval_ms};
+    use models::config::AppConfig;
+    use std::sync::Arc;
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+    use test_http_server::{Reply, TestServer};
+
+    use super::{
+        MaimaiClient, MaintenanceWindow, choose_user_agent, http_client_builder,
+        is_maintenance_error, next_request_interval_ms,
+    };
+
+    /// Collects formatted log output so tests can assert on warnings.
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl CapturedLogs {
+        fn text(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock().expect("logs lock")).into_owned()
+        }
+    }
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().expect("logs lock").extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Answers the first `failures` requests with `status_line`, then 200.
+    async fn spawn_flaky_server(failures: usize, status_line: &'static str) -> TestServer {
+        TestServer::spawn(move |request| {
+            if request.index < failures {
+                Reply::status(status_line)
+            } else {
+                Reply::ok("ok")
+            }
+        })
+        .await
+    }
+
+    fn test_config() -> AppConfig {
+        AppConfig {
+            sega_id: "id".to_string(),
+            sega_password: "password".to_string(),
+            data_dir: std::env::temp_dir(),
+            cookie_path: std::env::temp_dir().join("maistats-test-missing-cookies.json"),
+            discord_bot_token: None,
+            discord_user_id: None,
+            user_agents: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn http_client_reuses_connection_across_sequential_requests() {
+        let server = TestServer::spawn(|_| Reply::ok("ok")).await;
+        let client = http_client_builder().build().expect("build client");
+
+        for _ in 0..5 {
+            let body = client
+                .get(server.url("/"))
+                .send()
+                .await
+                .expect("send")
+                .text()
+                .await
+                .expect("body");
+            assert_eq!(body, "ok");
+        }
+
+        assert_eq!(server.connections(), 1);
+    }
+
+    #[tokio::test]
+    async fn slow_response_logs_a_warning() {
+        let server = TestServer::spawn(|request| {
+            let reply = Reply::ok("ok");
+            if request.path == "/slow" {
+                reply.with_delay(Duration::from_millis(300))
+            } else {
+                reply
+            }
+        })
+        .await;
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+        let client = MaimaiClient::new(&test_config())
+            .expect("build client")
+            .with_slow_request_threshold(Duration::from_millis(150));
+
+        for path in ["/fast", "/slow"] {
+            let url = reqwest::Url::parse(&server.url(path)).expect("url");
+            client.get_response(&url).await.expect("response");
+        }
+
+        let warnings = logs
+            .text()
+            .lines()
+            .filter(|line| line.contains("slow maimai request"))
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        assert!(warnings[0].contains("/slow"));
+    }
+
+    #[tokio::test]
+    async fn clones_share_verified_session_state() {
+        let client = MaimaiClient::new(&test_config()).expect("build client");
+        let shared = client.clone();
+
+        client.session_verified.store(true, Ordering::SeqCst);
+        // Returns without touching the network because the session is already verified.
+        shared.ensure_logged_in().await.expect("verified session");
+
+        shared.mark_session_expired();
+        assert!(!client.session_verified.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn get_response_retries_transient_gateway_errors() {
+        let server = spawn_flaky_server(2, "502 Bad Gateway").await;
+        let url = reqwest::Url::parse(&server.url("/")).expect("url");
+        let client = MaimaiClient::new(&test_config())
+            .expect("build client")
+            .with_transient_retry(3, Duration::ZERO);
+
+        let response = client.get_response(&url).await.expect("recovered response");
+
+        assert_eq!(response.body, b"ok");
+        assert_eq!(server.requests(), 3);
+    }
+
+    #[tokio::test]
+    async fn get_response_reports_maintenance_after_exhausting_retries() {
+        let server = spawn_flaky_server(usize::MAX, "503 Service Unavailable").await;
+        let url = reqwest::Url::parse(&server.url("/")).expect("url");
+        let client = MaimaiClient::new(&test_config())
+            .expect("build client")
+            .with_transient_retry(1, Duration::ZERO);
+
+        let err = client
+            .get_response(&url)
+            .await
+            .expect_err("still unavailable");
+
+        assert!(is_maintenance_error(&err));
+        assert_eq!(server.requests(), 2);
+    }
+
+    #[tokio::test]
+    async fn get_response_skips_retries_for_503_inside_maintenance_window() {
+        let server = spawn_flaky_server(usize::MAX, "503 Service Unavailable").await;
+        let url = reqwest::Url::parse(&server.url("/")).expect("url");
+        let always = MaintenanceWindow {
+            start_hour: 0,
+            end_hour: 24,
+            offset: time::UtcOffset::UTC,
+        };
+        let client = MaimaiClient::new(&test_config())
+            .expect("build client")
+            .with_transient_retry(3, Duration::ZERO)
+            .with_maintenance_window(always);
+
+        let err = client
+            .get_response(&url)
+            .await
+            .expect_err("under maintenance");
+
+        assert!(is_maintenance_error(&err));
+        assert!(err.to_string().contains(url.as_str()));
+        assert_eq!(server.requests(), 1);
+    }
+
+    #[test]
+    fn user_agent_falls_back_to_default_and_is_stable_per_seed() {
+        assert_eq!(
+            choose_user_agent(&[], 7),
+            maimai_auth::intl::DEFAULT_USER_AGENT
+        );
+
+        let pool = vec!["ua-a".to_string(), "ua-b".to_string(), "ua-c".to_string()];
+        assert_eq!(choose_user_agent(&pool, 4), "ua-b");
+        assert_eq!(choose_user_agent(&pool, 4), choose_user_agent(&pool, 4));
+        assert_eq!(choose_user_agent(&pool[..1], 4), "ua-a");
+    }
 
     #[test]
     fn request_interval_is_within_expected_range() {
@@ -1,9 +1,11 @@
 pub(crate) mod config;
 pub mod db;
+pub mod dump;
 pub(crate) mod error;
 pub(crate) mod http_client;
 pub mod logging;
 pub(crate) mod routes;
+pub(crate) mod song_catalog;
 pub(crate) mod state;
 pub mod tasks;
 
@@ -20,6 +22,8 @@ pub async fn run_server(log_buffer: Arc<logging::LogBuffer>) -> eyre::Result<()>
 
     std::fs::create_dir_all(&config.data_dir).wrap_err("Failed to create data directory")?;
 
+    let db_query_timeout = std::time::Duration::from_secs(config.db_query_timeout_secs);
+
     let db_pool = db::connect(&config.database_url)
         .await
         .wrap_err("Failed to connect to database")?;
@@ -31,8 +35,37 @@ pub async fn run_server(log_buffer: Arc<logging::LogBuffer>) -> eyre::Result<()>
         .await
         .wrap_err("Failed to run database migrations")?;
     tracing::info!("Database migrations completed successfully");
+    match (
+        db::count_scores_rows(&db_pool, db_query_timeout).await,
+        db::count_playlogs_rows(&db_pool, db_query_timeout).await,
+    ) {
+        (Ok(scores), Ok(playlogs)) => {
+            tracing::info!("Database contains {scores} score rows and {playlogs} playlog rows")
+        }
+        (Err(e), _) | (_, Err(e)) => tracing::warn!("Failed to count database rows: {e:#}"),
+    }
 
-    match tasks::startup::startup_sync(&db_pool, &config).await {
+    let maimai_client = tasks::utils::auth::build_client(&config)?;
+    let song_catalog = song_catalog::SongCatalogSource::from_settings(
+        &config.song_database_url,
+        config.include_estimated_internal_levels,
+        std::path::Path::new(&config.data_dir),
+    )?;
+
+    let startup_sync = if config
+        .maintenance_window
+        .contains(time::OffsetDateTime::now_utc())
+    {
+        tracing::info!("Skipping startup sync during the configured maintenance window");
+        Ok(tasks::utils::reporting::SyncCycleReport {
+            skipped_for_maintenance: true,
+            ..Default::default()
+        })
+    } else {
+        let catalog = tasks::utils::scores::load_rating_catalog(&db_pool, &song_catalog).await;
+        tasks::startup::startup_sync(&db_pool, db_query_timeout, &maimai_client, &catalog).await
+    };
+    match startup_sync {
         Ok(report) => tracing::info!(
             "Startup sync completed: maintenance_skip={} seeded={} seeded_rows={} recent_present={}",
             report.skipped_for_maintenance,
@@ -42,13 +75,18 @@ pub async fn run_server(log_buffer: Arc<logging::LogBuffer>) -> eyre::Result<()>
         ),
         Err(e) => tracing::warn!("Startup sync failed (server will still start): {e:#}"),
     }
+    // First backfill of the process; also covers rows stored before the rating columns existed.
+    tasks::utils::scores::refresh_stored_ratings(&db_pool, db_query_timeout, &song_catalog).await;
 
     let app_state = state::AppState {
         db_pool,
-        config: config.clone(),
+        db_query_timeout,
         log_buffer,
+        maimai_client,
+        song_catalog,
         cycle_lock: Arc::new(tokio::sync::Mutex::new(())),
         timer_reset_notify: Arc::new(tokio::sync::Notify::new()),
+        maintenance_window: config.maintenance_window,
     };
 
     tasks::polling::start_background_polling(app_state.clone());
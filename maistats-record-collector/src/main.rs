@@ -1,10 +1,15 @@
 use eyre::WrapErr;
-use maistats_record_collector::{logging::init_tracing, run_server};
+use maistats_record_collector::{dump::run_db_command, logging::init_tracing, run_server};
 
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
     dotenvy::dotenv().ok();
 
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.first().map(String::as_str) == Some("db") {
+        return run_db_command(&args[1..]).await;
+    }
+
     let log_buffer = init_tracing().wrap_err("initialize tracing")?;
 
     run_server(log_buffer)
@@ -0,0 +1,42 @@
+use axum::{Json, extract::State};
+use tracing::info;
+
+use maimai_client::is_valid_internal_level;
+use models::ChartInternalLevelPatch;
+
+use crate::error::{AppError, Result};
+use crate::state::AppState;
+use crate::tasks::utils::scores::refresh_stored_ratings;
+
+/// PUT /api/admin/internal-level
+/// Overrides one chart's internal level until restart and recomputes the stored ratings.
+pub(crate) async fn put_internal_level(
+    State(state): State<AppState>,
+    Json(patch): Json<ChartInternalLevelPatch>,
+) -> Result<Json<ChartInternalLevelPatch>> {
+    if !is_valid_internal_level(patch.internal_level) {
+        return Err(AppError::BadRequest(format!(
+            "internal_level must be between 1.0 and 15.0, got {}",
+            patch.internal_level
+        )));
+    }
+
+    let patched = state
+        .song_catalog
+        .upsert_chart_internal_level(&patch)
+        .await
+        .map_err(AppError::from)?;
+    if !patched {
+        return Err(AppError::NotFound(format!(
+            "No chart in song database: {} / {} [{} {}]",
+            patch.title, patch.artist, patch.chart_type, patch.diff_category
+        )));
+    }
+
+    info!(
+        "patched internal level: title={} artist={} chart={} diff={} internal_level={}",
+        patch.title, patch.artist, patch.chart_type, patch.diff_category, patch.internal_level
+    );
+    refresh_stored_ratings(&state.db_pool, state.db_query_timeout, &state.song_catalog).await;
+    Ok(Json(patch))
+}
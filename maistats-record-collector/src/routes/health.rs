@@ -1,6 +1,7 @@
 use axum::{Json, extract::State, http::StatusCode, response::IntoResponse};
 use serde::Serialize;
 
+use crate::db::{count_playlogs_rows, count_scores_rows, get_sync_status};
 use crate::state::AppState;
 
 #[derive(Serialize)]
@@ -12,6 +13,16 @@ struct HealthResponse {
 struct ReadyResponse {
     status: String,
     database: String,
+    /// Row counts, so an empty never-synced database is distinguishable from a synced one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    scores: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    playlogs: Option<i64>,
+    /// Consecutive poll cycles that failed inside an HTML parser.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parse_error_count: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_parse_error: Option<String>,
 }
 
 /// GET /health - Simple health check
@@ -21,27 +32,40 @@ pub(crate) async fn health() -> impl IntoResponse {
     })
 }
 
-/// GET /health/ready - Readiness check with database connectivity
+/// GET /health/ready - Readiness check with database connectivity and row counts
 pub(crate) async fn ready(State(state): State<AppState>) -> impl IntoResponse {
-    // Try a simple SELECT 1 query to verify database connectivity
-    match sqlx::query_scalar::<_, i32>("SELECT 1")
-        .fetch_one(&state.db_pool)
-        .await
-    {
-        Ok(_) => (
+    // Counting both tables doubles as the database connectivity check
+    let counts = async {
+        let scores = count_scores_rows(&state.db_pool, state.db_query_timeout).await?;
+        let playlogs = count_playlogs_rows(&state.db_pool, state.db_query_timeout).await?;
+        let sync_status = get_sync_status(&state.db_pool).await?;
+        Ok::<_, eyre::Error>((scores, playlogs, sync_status))
+    }
+    .await;
+
+    match counts {
+        Ok((scores, playlogs, sync_status)) => (
             StatusCode::OK,
             Json(ReadyResponse {
                 status: "ready".to_string(),
                 database: "ok".to_string(),
+                scores: Some(scores),
+                playlogs: Some(playlogs),
+                parse_error_count: Some(sync_status.parse_error_count),
+                last_parse_error: sync_status.last_parse_error,
             }),
         ),
         Err(e) => {
-            tracing::error!("Database health check failed: {}", e);
+            tracing::error!("Database health check failed: {e:#}");
             (
                 StatusCode::SERVICE_UNAVAILABLE,
                 Json(ReadyResponse {
                     status: "not_ready".to_string(),
                     database: "error".to_string(),
+                    scores: None,
+                    playlogs: None,
+                    parse_error_count: None,
+                    last_parse_error: None,
                 }),
             )
         }
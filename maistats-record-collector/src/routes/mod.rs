@@ -1,3 +1,4 @@
+mod admin;
 mod health;
 mod logs;
 mod player;
@@ -11,7 +12,8 @@ mod version;
 
 use axum::{
     Router,
-    routing::{get, post},
+    extract::DefaultBodyLimit,
+    routing::{get, post, put},
 };
 use tower_http::LatencyUnit;
 use tower_http::cors::CorsLayer;
@@ -22,12 +24,35 @@ use crate::state::AppState;
 pub(crate) fn create_routes(state: AppState) -> Router {
     let api_routes = Router::new()
         .route("/api/scores/rated", get(scores::get_all_rated_scores))
+        .route("/api/scores/search", get(scores::search_scores))
+        .route("/api/scores/histogram", get(scores::get_score_histogram))
         .route("/api/scores/refresh", post(scores::refresh_song_scores))
         .route("/api/songs/scores", get(scores::get_song_detail_scores))
         .route("/api/player", get(player::get_player))
+        .route("/api/admin/internal-level", put(admin::put_internal_level))
+        .route(
+            "/api/player/target",
+            get(player::get_target).put(player::put_target),
+        )
+        .route(
+            "/api/player/recap-state",
+            get(player::get_recap).put(player::put_recap),
+        )
+        .route(
+            "/api/player/rating-history",
+            get(player::get_rating_history),
+        )
+        .route("/api/rating", get(rating::get_rating))
         .route("/api/rating/targets", get(rating::get_rating_targets))
+        .route("/api/rating/csv", get(rating::get_rating_csv))
+        .route(
+            "/api/rating/compute",
+            post(rating::compute_rating)
+                .layer(DefaultBodyLimit::max(rating::MAX_COMPUTE_BODY_BYTES)),
+        )
         .route("/api/recent", get(recent::get_recent))
         .route("/api/poll", post(poll::trigger_poll))
+        .route("/api/sync/status", get(poll::sync_status))
         .route("/api/logs", get(logs::get_logs))
         .route("/api/today", get(today::get_today))
         .route("/api/version", get(version::get_version))
@@ -1,11 +1,22 @@
-use axum::{Json, extract::State, http::StatusCode};
+use axum::{
+    Json,
+    extract::{Query, State},
+    http::StatusCode,
+};
+use serde::Deserialize;
 use tracing::debug;
 
-use models::ParsedPlayerProfile;
+use models::{
+    ParsedPlayerProfile, RatingSnapshotApiResponse, RecapStateApiResponse, TargetRatingApiResponse,
+};
 
+use crate::db::{
+    get_recap_state, get_target_rating, query_rating_history, set_target_rating, update_recap_state,
+};
 use crate::error::{AppError, Result};
 use crate::state::AppState;
 use crate::tasks::utils::player::load_stored_player_profile;
+use crate::tasks::utils::recent::unix_timestamp;
 
 /// GET /api/player
 /// Returns the latest stored player profile snapshot from SQLite.
@@ -25,3 +36,81 @@ pub(crate) async fn get_player(
 
     Ok((StatusCode::OK, Json(player_profile)))
 }
+
+/// GET /api/player/target
+/// Returns the stored rating goal (`null` when unset).
+pub(crate) async fn get_target(
+    State(state): State<AppState>,
+) -> Result<Json<TargetRatingApiResponse>> {
+    let target_rating = get_target_rating(&state.db_pool)
+        .await
+        .map_err(AppError::from)?;
+    Ok(Json(TargetRatingApiResponse { target_rating }))
+}
+
+/// PUT /api/player/target
+/// Stores the rating goal; `{"target_rating": null}` clears it.
+pub(crate) async fn put_target(
+    State(state): State<AppState>,
+    Json(payload): Json<TargetRatingApiResponse>,
+) -> Result<Json<TargetRatingApiResponse>> {
+    debug!(
+        "PUT /api/player/target: target_rating={:?}",
+        payload.target_rating
+    );
+
+    let updated_at = unix_timestamp();
+    set_target_rating(&state.db_pool, payload.target_rating, updated_at)
+        .await
+        .map_err(AppError::from)?;
+    Ok(Json(payload))
+}
+
+/// GET /api/player/recap-state
+/// Returns what the Discord bot's recap DMs already covered.
+pub(crate) async fn get_recap(
+    State(state): State<AppState>,
+) -> Result<Json<RecapStateApiResponse>> {
+    let recap_state = get_recap_state(&state.db_pool)
+        .await
+        .map_err(AppError::from)?;
+    Ok(Json(recap_state))
+}
+
+/// PUT /api/player/recap-state
+/// Stores the fields present in the body and returns the merged state.
+pub(crate) async fn put_recap(
+    State(state): State<AppState>,
+    Json(payload): Json<RecapStateApiResponse>,
+) -> Result<Json<RecapStateApiResponse>> {
+    debug!(
+        "PUT /api/player/recap-state: recapped_day={:?}",
+        payload.recapped_day
+    );
+
+    update_recap_state(&state.db_pool, &payload, unix_timestamp())
+        .await
+        .map_err(AppError::from)?;
+    let recap_state = get_recap_state(&state.db_pool)
+        .await
+        .map_err(AppError::from)?;
+    Ok(Json(recap_state))
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RatingHistoryQuery {
+    /// Unix seconds; defaults to the whole history.
+    since: Option<i64>,
+}
+
+/// GET /api/player/rating-history
+/// Returns rating changes since `since`, oldest first, led by the snapshot in effect then.
+pub(crate) async fn get_rating_history(
+    State(state): State<AppState>,
+    Query(params): Query<RatingHistoryQuery>,
+) -> Result<Json<Vec<RatingSnapshotApiResponse>>> {
+    let history = query_rating_history(&state.db_pool, params.since.unwrap_or(0))
+        .await
+        .map_err(AppError::from)?;
+    Ok(Json(history))
+}
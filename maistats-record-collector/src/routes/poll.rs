@@ -1,8 +1,11 @@
+use axum::Json;
 use axum::extract::State;
 use axum::http::StatusCode;
+use models::SyncStatusApiResponse;
 use tracing::error;
 
-use crate::error::{Result, app_error_from_maimai};
+use crate::db::get_sync_status;
+use crate::error::{AppError, Result, app_error_from_maimai};
 use crate::state::AppState;
 use crate::tasks::polling::cycle::run_cycle;
 
@@ -17,3 +20,14 @@ pub(crate) async fn trigger_poll(State(state): State<AppState>) -> Result<Status
     state.timer_reset_notify.notify_one();
     Ok(StatusCode::OK)
 }
+
+/// GET /api/sync/status
+/// Returns the background poll's consecutive parse failure count and last parse error.
+pub(crate) async fn sync_status(
+    State(state): State<AppState>,
+) -> Result<Json<SyncStatusApiResponse>> {
+    let status = get_sync_status(&state.db_pool)
+        .await
+        .map_err(AppError::from)?;
+    Ok(Json(status))
+}
@@ -1,18 +1,38 @@
-use axum::{Json, extract::State};
+use std::collections::HashSet;
+
+use axum::{
+    Json,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+};
 use eyre::WrapErr;
+use maimai_client::SongCatalogSong;
 
-use crate::error::{Result, app_error_from_maimai};
+use crate::db::{StoredRatedScore, query_played_scores};
+use crate::error::{AppError, Result, app_error_from_maimai};
+use crate::routes::responses::score_response_from_entry;
+use crate::song_catalog::find_catalog_sheet;
 use crate::state::AppState;
+use crate::tasks::utils::auth::fetch_html_with_auth_recovery;
+use crate::tasks::utils::source::ExpectedPage;
 use maimai_parsers::parse_rating_target_music_html;
-use models::ParsedRatingTargets;
+use models::rating::{
+    RatedChart, RatingBreakdown, RatingBucket, RatingSelection, is_ap_like, select_rating_set,
+};
+use models::{ParsedRatingTargets, ParsedScoreEntry};
+
+/// Upper bound on uploaded score entries; a full score list is a few thousand charts.
+pub(crate) const MAX_COMPUTE_ENTRIES: usize = 20_000;
+/// Request body cap for `POST /api/rating/compute`.
+pub(crate) const MAX_COMPUTE_BODY_BYTES: usize = 8 * 1024 * 1024;
+const RATING_CSV_HEADER: &str =
+    "title,chart_type,diff,internal_level,achievement,ap,bucket,rating_points,in_new15,in_old35";
 
 pub(crate) async fn get_rating_targets(
     State(state): State<AppState>,
 ) -> Result<Json<ParsedRatingTargets>> {
-    let mut client = state
-        .maimai_client()
-        .wrap_err("create HTTP client")
-        .map_err(app_error_from_maimai)?;
+    let client = &state.maimai_client;
 
     client
         .ensure_logged_in()
@@ -24,14 +44,10 @@ pub(crate) async fn get_rating_targets(
         .wrap_err("parse ratingTargetMusic url")
         .map_err(app_error_from_maimai)?;
 
-    let bytes = client
-        .get_response(&url)
+    // The shared client skips the login check once a session was verified, so an expired
+    // session only shows up as the login page here.
+    let html = fetch_html_with_auth_recovery(client, &url, ExpectedPage::RatingTargets)
         .await
-        .wrap_err("fetch ratingTargetMusic url")
-        .map_err(app_error_from_maimai)?
-        .body;
-    let html = String::from_utf8(bytes)
-        .wrap_err("ratingTargetMusic response is not utf-8")
         .map_err(app_error_from_maimai)?;
 
     let parsed = parse_rating_target_music_html(&html)
@@ -40,3 +56,452 @@ pub(crate) async fn get_rating_targets(
 
     Ok(Json(parsed))
 }
+
+/// Compute NEW 15 / OLD 35 for an uploaded score list (the `ParsedScoreEntry` crawl format)
+/// without reading or writing the collector database.
+pub(crate) async fn compute_rating(
+    State(state): State<AppState>,
+    Json(entries): Json<Vec<ParsedScoreEntry>>,
+) -> Result<Json<RatingBreakdown>> {
+    validate_compute_entries(&entries)?;
+
+    let catalog = state
+        .song_catalog
+        .list_song_catalog()
+        .await
+        .map_err(|err| AppError::InternalError(format!("load song database: {err:#}")))?;
+
+    Ok(Json(compute_rating_response(&entries, &catalog)))
+}
+
+fn validate_compute_entries(entries: &[ParsedScoreEntry]) -> Result<()> {
+    if entries.len() > MAX_COMPUTE_ENTRIES {
+        return Err(AppError::BadRequest(format!(
+            "too many score entries: {} (max {MAX_COMPUTE_ENTRIES})",
+            entries.len()
+        )));
+    }
+
+    let mut seen = HashSet::new();
+    for entry in entries {
+        if entry.title.trim().is_empty() {
+            return Err(AppError::BadRequest(
+                "score entry title must not be empty".to_string(),
+            ));
+        }
+        if let Some(achievement) = entry.achievement_percent
+            && !(0.0..=101.0).contains(&achievement)
+        {
+            return Err(AppError::BadRequest(format!(
+                "achievement {achievement} out of range for '{}'",
+                entry.title
+            )));
+        }
+        if !seen.insert((
+            entry.title.as_str(),
+            entry.artist.as_str(),
+            entry.chart_type,
+            entry.diff_category,
+        )) {
+            return Err(AppError::BadRequest(format!(
+                "duplicate score entry: {} [{} {}]",
+                entry.title, entry.chart_type, entry.diff_category
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn compute_rating_response(
+    entries: &[ParsedScoreEntry],
+    catalog: &[SongCatalogSong],
+) -> RatingBreakdown {
+    let mut missing = 0;
+    let mut charts = Vec::new();
+
+    for entry in entries {
+        let Some(achievement_percent) = entry.achievement_percent else {
+            continue;
+        };
+        let sheet = find_catalog_sheet(
+            catalog,
+            &entry.title,
+            &entry.artist,
+            entry.chart_type,
+            entry.diff_category,
+        );
+        let Some((sheet, internal_level)) =
+            sheet.and_then(|sheet| sheet.internal_level.map(|level| (sheet, level)))
+        else {
+            missing += 1;
+            continue;
+        };
+
+        charts.push(
+            RatedChart::new(
+                entry.title.clone(),
+                entry.chart_type,
+                entry.diff_category,
+                entry.level.clone(),
+                internal_level,
+                f64::from(achievement_percent),
+                entry.fc,
+                sheet.rating_bucket(),
+            )
+            .with_song_identity(entry.artist.clone(), entry.genre.clone()),
+        );
+    }
+
+    RatingBreakdown::from_selection(select_rating_set(charts), missing)
+}
+
+/// NEW 15 / OLD 35 over stored scores, as served by `GET /api/rating`.
+pub(crate) fn rating_breakdown_from_rows(rows: &[StoredRatedScore]) -> Result<RatingBreakdown> {
+    let rated = rate_rows(rows)?;
+    Ok(RatingBreakdown::from_selection(
+        rated.selection,
+        rated.missing,
+    ))
+}
+
+/// Every rated chart of some rows with the NEW 15 / OLD 35 picked from them. `GET /api/rating`
+/// and `GET /api/rating/csv` both go through here, so they never disagree on membership.
+struct RatedRows {
+    charts: Vec<RatedChart>,
+    selection: RatingSelection,
+    missing: usize,
+}
+
+fn rate_rows(rows: &[StoredRatedScore]) -> Result<RatedRows> {
+    let (charts, missing) = rated_charts_from_rows(rows)?;
+    let selection = select_rating_set(charts.clone());
+    Ok(RatedRows {
+        charts,
+        selection,
+        missing,
+    })
+}
+
+/// Played rows as rated charts from their stored rating columns, plus how many had none.
+fn rated_charts_from_rows(rows: &[StoredRatedScore]) -> Result<(Vec<RatedChart>, usize)> {
+    let mut missing = 0;
+    let mut charts = Vec::new();
+    for row in rows {
+        let Some(achievement_x10000) = row.score.achievement_x10000 else {
+            continue;
+        };
+        let (Some(internal_level), Some(rating_points), Some(bucket)) =
+            (row.internal_level, row.rating_points, row.bucket.as_deref())
+        else {
+            missing += 1;
+            continue;
+        };
+        let bucket = bucket
+            .parse::<RatingBucket>()
+            .map_err(|_| AppError::InternalError(format!("invalid bucket '{bucket}'")))?;
+        let rating_points = u32::try_from(rating_points).map_err(|_| {
+            AppError::InternalError(format!("invalid rating_points {rating_points}"))
+        })?;
+        let score = score_response_from_entry(row.score.clone())?;
+        charts.push(RatedChart {
+            title: score.title,
+            chart_type: score.chart_type,
+            diff_category: score.diff_category,
+            level: row.score.level.clone().unwrap_or_default(),
+            internal_level: internal_level as f32,
+            achievement_percent: achievement_x10000 as f64 / 10000.0,
+            fc: score.fc,
+            bucket,
+            rating_points,
+            artist: score.artist,
+            genre: score.genre,
+        });
+    }
+    Ok((charts, missing))
+}
+
+/// NEW 15 / OLD 35 over every stored score: the single place the bot's `/mai-rating` gets its
+/// numbers from.
+pub(crate) async fn get_rating(State(state): State<AppState>) -> Result<Json<RatingBreakdown>> {
+    let rows = query_played_scores(&state.db_pool, None, None).await?;
+    Ok(Json(rating_breakdown_from_rows(&rows)?))
+}
+
+/// Every stored score that can be rated, one CSV row per chart, with NEW 15 / OLD 35
+/// membership flags from the same selection `GET /api/rating` serves.
+pub(crate) async fn get_rating_csv(State(state): State<AppState>) -> Result<Response> {
+    let rows = query_played_scores(&state.db_pool, None, None).await?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+        rating_csv(&rows)?,
+    )
+        .into_response())
+}
+
+fn rating_csv(rows: &[StoredRatedScore]) -> Result<String> {
+    let RatedRows {
+        mut charts,
+        selection,
+        ..
+    } = rate_rows(rows)?;
+    charts.sort_by(|a, b| {
+        b.rating_points
+            .cmp(&a.rating_points)
+            .then_with(|| b.achievement_percent.total_cmp(&a.achievement_percent))
+    });
+
+    let mut csv = String::from(RATING_CSV_HEADER);
+    csv.push('\n');
+    for chart in &charts {
+        csv.push_str(&format!(
+            "{},{},{},{:.1},{:.4},{},{},{},{},{}\n",
+            csv_field(&chart.title),
+            chart.chart_type,
+            chart.diff_category,
+            chart.internal_level,
+            chart.achievement_percent,
+            is_ap_like(chart.fc.as_ref()),
+            chart.bucket.as_str(),
+            chart.rating_points,
+            selection.new.contains(chart),
+            selection.old.contains(chart),
+        ));
+    }
+    Ok(csv)
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        RATING_CSV_HEADER, compute_rating_response, rating_breakdown_from_rows, rating_csv,
+        validate_compute_entries,
+    };
+    use crate::db::StoredRatedScore;
+    use maimai_client::{SongCatalogSheet, SongCatalogSong};
+    use models::rating::{RatedChart, RatingBucket};
+    use models::{
+        ChartType, DifficultyCategory, FcStatus, ParsedScoreEntry, SongAliases, SongChartRegion,
+        StoredScoreEntry,
+    };
+
+    fn song(title: &str, version: &str, internal_level: Option<f32>) -> SongCatalogSong {
+        SongCatalogSong {
+            title: title.to_string(),
+            genre: "maimai".to_string(),
+            artist: "Artist".to_string(),
+            image_name: None,
+            aliases: SongAliases::default(),
+            sheets: vec![SongCatalogSheet {
+                chart_type: ChartType::Dx,
+                diff_category: DifficultyCategory::Master,
+                level: "13+".to_string(),
+                version: Some(version.to_string()),
+                internal_level,
+                region: SongChartRegion {
+                    jp: true,
+                    intl: true,
+                },
+            }],
+        }
+    }
+
+    fn entry(title: &str, achievement_percent: Option<f32>) -> ParsedScoreEntry {
+        ParsedScoreEntry {
+            title: title.to_string(),
+            genre: "maimai".to_string(),
+            artist: "Artist".to_string(),
+            chart_type: ChartType::Dx,
+            diff_category: DifficultyCategory::Master,
+            level: "13+".to_string(),
+            achievement_percent,
+            rank: None,
+            fc: None,
+            sync: None,
+            dx_score: None,
+            dx_score_max: None,
+            last_played_at: None,
+            play_count: None,
+            source_idx: None,
+        }
+    }
+
+    #[test]
+    fn compute_rating_response_buckets_and_counts_missing() {
+        let catalog = vec![
+            song("New Song", "CiRCLE", Some(13.7)),
+            song("Old Song", "FESTiVAL", Some(13.7)),
+            song("Unknown Level", "FESTiVAL", None),
+        ];
+        let mut ap = entry("Old Song", Some(100.5));
+        ap.fc = Some(FcStatus::Ap);
+        let entries = vec![
+            entry("New Song", Some(100.5)),
+            ap,
+            entry("Unknown Level", Some(99.0)),
+            entry("Not In Catalog", Some(99.0)),
+            entry("Unplayed", None),
+        ];
+
+        let response = compute_rating_response(&entries, &catalog);
+
+        assert_eq!(response.new.len(), 1);
+        assert_eq!(response.new[0].bucket, RatingBucket::New);
+        assert_eq!(response.new_sum, 308);
+        assert_eq!(response.old_sum, 309);
+        assert_eq!(response.total, 617);
+        assert_eq!(response.missing, 2);
+    }
+
+    #[test]
+    fn compute_rating_response_buckets_std_and_dx_sheets_separately() {
+        let mut split = song("Split Song", "FESTiVAL", Some(13.7));
+        let mut dx_sheet = split.sheets[0].clone();
+        split.sheets[0].chart_type = ChartType::Std;
+        dx_sheet.version = Some("CiRCLE".to_string());
+        split.sheets.push(dx_sheet);
+        let mut std_entry = entry("Split Song", Some(100.5));
+        std_entry.chart_type = ChartType::Std;
+
+        let response =
+            compute_rating_response(&[entry("Split Song", Some(100.5)), std_entry], &[split]);
+
+        assert_eq!(response.new.len(), 1);
+        assert_eq!(response.new[0].chart_type, ChartType::Dx);
+        assert_eq!(response.old.len(), 1);
+        assert_eq!(response.old[0].chart_type, ChartType::Std);
+    }
+
+    #[test]
+    fn validate_compute_entries_rejects_bad_payloads() {
+        assert!(validate_compute_entries(&[entry("Song", Some(100.0))]).is_ok());
+        assert!(validate_compute_entries(&[entry("Song", Some(101.5))]).is_err());
+        assert!(validate_compute_entries(&[entry(" ", Some(100.0))]).is_err());
+        assert!(
+            validate_compute_entries(&[entry("Song", Some(100.0)), entry("Song", Some(99.0))])
+                .is_err()
+        );
+    }
+
+    fn row(
+        title: &str,
+        achievement_x10000: i64,
+        rating: Option<(f64, i64, &str)>,
+    ) -> StoredRatedScore {
+        StoredRatedScore {
+            score: StoredScoreEntry {
+                title: title.to_string(),
+                genre: "maimai".to_string(),
+                artist: "Artist".to_string(),
+                chart_type: "DX".to_string(),
+                diff_category: "MASTER".to_string(),
+                achievement_x10000: Some(achievement_x10000),
+                rank: None,
+                fc: None,
+                sync: None,
+                dx_score: None,
+                dx_score_max: None,
+                last_played_at: None,
+                play_count: None,
+                level: Some("13+".to_string()),
+            },
+            internal_level: rating.map(|(internal_level, _, _)| internal_level),
+            rating_points: rating.map(|(_, rating_points, _)| rating_points),
+            bucket: rating.map(|(_, _, bucket)| bucket.to_string()),
+        }
+    }
+
+    #[test]
+    fn rating_csv_lists_columns_and_membership() {
+        let mut rows = vec![row("New, \"Quoted\"", 1_005_000, Some((13.7, 308, "NEW")))];
+        rows.extend((0..36).map(|i| {
+            row(
+                &format!("Old {i:02}"),
+                1_000_000 - i * 100,
+                Some((13.0, 280, "OLD")),
+            )
+        }));
+        rows.push(row("Unrated", 1_000_000, None));
+
+        let csv = rating_csv(&rows).expect("csv");
+        let lines = csv.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[0], RATING_CSV_HEADER);
+        assert_eq!(lines.len(), 1 + 37);
+        assert_eq!(
+            lines[1],
+            "\"New, \"\"Quoted\"\"\",DX,MASTER,13.7,100.5000,false,NEW,308,true,false"
+        );
+        assert_eq!(
+            lines[2],
+            "Old 00,DX,MASTER,13.0,100.0000,false,OLD,280,false,true"
+        );
+        assert!(lines[37].starts_with("Old 35,"));
+        assert!(lines[37].ends_with(",false,false"));
+    }
+
+    #[test]
+    fn rating_csv_membership_matches_the_rating_breakdown() {
+        // Ties on rating points at both cutoffs, so the selection's tie-break decides.
+        let mut rows = (0..20)
+            .map(|i| row(&format!("New {i:02}"), 1_000_000, Some((13.0, 280, "NEW"))))
+            .collect::<Vec<_>>();
+        rows.extend(
+            (0..40).map(|i| row(&format!("Old {i:02}"), 990_000, Some((12.5, 250, "OLD")))),
+        );
+
+        let breakdown = rating_breakdown_from_rows(&rows).expect("breakdown");
+        let csv = rating_csv(&rows).expect("csv");
+        let members = |column: usize| {
+            let mut titles = csv
+                .lines()
+                .skip(1)
+                .map(|line| line.split(',').collect::<Vec<_>>())
+                .filter(|fields| fields[column] == "true")
+                .map(|fields| fields[0].to_string())
+                .collect::<Vec<_>>();
+            titles.sort();
+            titles
+        };
+        let titles = |charts: &[RatedChart]| {
+            let mut titles = charts
+                .iter()
+                .map(|chart| chart.title.clone())
+                .collect::<Vec<_>>();
+            titles.sort();
+            titles
+        };
+
+        assert_eq!(members(8), titles(&breakdown.new));
+        assert_eq!(members(9), titles(&breakdown.old));
+        assert_eq!(breakdown.new.len(), 15);
+        assert_eq!(breakdown.old.len(), 35);
+    }
+
+    #[test]
+    fn rating_breakdown_from_rows_uses_stored_ratings() {
+        let rows = vec![
+            row("New Song", 1_005_000, Some((13.7, 308, "NEW"))),
+            row("Old Song", 1_005_000, Some((13.7, 309, "OLD"))),
+            row("Unrated", 1_005_000, None),
+        ];
+
+        let breakdown = rating_breakdown_from_rows(&rows).expect("breakdown");
+
+        assert_eq!(breakdown.new[0].title, "New Song");
+        assert_eq!(breakdown.new[0].level, "13+");
+        assert_eq!(breakdown.new[0].genre, "maimai");
+        assert_eq!(breakdown.old[0].rating_points, 309);
+        assert_eq!(breakdown.total, 617);
+        assert_eq!(breakdown.missing, 1);
+    }
+}
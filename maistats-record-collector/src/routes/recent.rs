@@ -5,16 +5,28 @@ use axum::{
 use serde::Deserialize;
 
 use crate::{
-    error::Result,
+    db::{PLAYLOG_COLUMNS, SqlitePool},
+    error::{AppError, Result},
     routes::responses::{PlayRecordApiResponse, play_record_response_from_record},
     state::AppState,
 };
-use models::StoredPlayRecord;
+use models::{ChartType, DifficultyCategory, StoredPlayRecord};
 
 #[derive(Deserialize)]
 pub(crate) struct RecentQuery {
     #[serde(default = "default_limit")]
     limit: i64,
+    diff: Option<String>,
+    min_achievement: Option<String>,
+    chart_type: Option<String>,
+}
+
+/// Optional filters applied in SQL before `LIMIT`; all `None` returns the plain recent list.
+#[derive(Debug, Default, PartialEq)]
+struct RecentFilter {
+    diff_category: Option<DifficultyCategory>,
+    chart_type: Option<ChartType>,
+    min_achievement_x10000: Option<i64>,
 }
 
 fn default_limit() -> i64 {
@@ -25,23 +37,52 @@ fn requested_limit(limit: i64) -> i64 {
     limit.max(1)
 }
 
+fn parse_recent_filter(query: &RecentQuery) -> Result<RecentFilter> {
+    let diff_category = query
+        .diff
+        .as_deref()
+        .map(|raw| {
+            raw.parse::<DifficultyCategory>()
+                .map_err(|_| AppError::BadRequest(format!("invalid diff '{raw}'")))
+        })
+        .transpose()?;
+    let chart_type = query
+        .chart_type
+        .as_deref()
+        .map(|raw| match raw.parse::<ChartType>() {
+            // Play records are only ever STD or DX.
+            Ok(chart_type @ (ChartType::Std | ChartType::Dx)) => Ok(chart_type),
+            _ => Err(AppError::BadRequest(format!("invalid chart_type '{raw}'"))),
+        })
+        .transpose()?;
+    let min_achievement_x10000 = query
+        .min_achievement
+        .as_deref()
+        .map(|raw| match raw.trim().parse::<f64>() {
+            Ok(percent) if percent.is_finite() && (0.0..=101.0).contains(&percent) => {
+                Ok((percent * 10000.0).round() as i64)
+            }
+            _ => Err(AppError::BadRequest(format!(
+                "invalid min_achievement '{raw}': expected a percent between 0 and 101"
+            ))),
+        })
+        .transpose()?;
+
+    Ok(RecentFilter {
+        diff_category,
+        chart_type,
+        min_achievement_x10000,
+    })
+}
+
 pub(crate) async fn get_recent(
     State(state): State<AppState>,
     Query(params): Query<RecentQuery>,
 ) -> Result<Json<Vec<PlayRecordApiResponse>>> {
     let limit = requested_limit(params.limit);
+    let filter = parse_recent_filter(&params)?;
 
-    let rows = sqlx::query_as::<_, StoredPlayRecord>(
-        "SELECT played_at_unixtime, played_at, track, title, genre, artist, chart_type, diff_category, 
-                achievement_x10000, score_rank, fc, sync, dx_score, dx_score_max, 
-                credit_id, achievement_new_record
-         FROM playlogs
-         ORDER BY played_at_unixtime DESC
-         LIMIT ?",
-    )
-    .bind(limit)
-    .fetch_all(&state.db_pool)
-    .await?;
+    let rows = fetch_recent(&state.db_pool, limit, &filter).await?;
 
     let mut responses = Vec::with_capacity(rows.len());
     for record in rows {
@@ -51,9 +92,150 @@ pub(crate) async fn get_recent(
     Ok(Json(responses))
 }
 
+async fn fetch_recent(
+    pool: &SqlitePool,
+    limit: i64,
+    filter: &RecentFilter,
+) -> sqlx::Result<Vec<StoredPlayRecord>> {
+    sqlx::query_as::<_, StoredPlayRecord>(&format!(
+        "SELECT {PLAYLOG_COLUMNS}
+         FROM playlogs
+         WHERE (?1 IS NULL OR diff_category = ?1)
+           AND (?2 IS NULL OR chart_type = ?2)
+           AND (?3 IS NULL OR achievement_x10000 >= ?3)
+         ORDER BY played_at_unixtime DESC
+         LIMIT ?4"
+    ))
+    .bind(filter.diff_category.map(|d| d.as_str()))
+    .bind(filter.chart_type.map(|t| t.as_str()))
+    .bind(filter.min_achievement_x10000)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
 #[cfg(test)]
 mod tests {
-    use super::requested_limit;
+    use super::{RecentFilter, RecentQuery, fetch_recent, parse_recent_filter, requested_limit};
+    use crate::db;
+    use models::{ChartType, DifficultyCategory};
+
+    fn query(
+        diff: Option<&str>,
+        min_achievement: Option<&str>,
+        chart_type: Option<&str>,
+    ) -> RecentQuery {
+        RecentQuery {
+            limit: 50,
+            diff: diff.map(str::to_string),
+            min_achievement: min_achievement.map(str::to_string),
+            chart_type: chart_type.map(str::to_string),
+        }
+    }
+
+    async fn seeded_pool() -> eyre::Result<db::SqlitePool> {
+        let pool = db::connect("sqlite::memory:").await?;
+        db::migrate(&pool).await?;
+        let rows = [
+            (1, "DX", "MASTER", 995_000),
+            (2, "STD", "MASTER", 1_005_000),
+            (3, "DX", "EXPERT", 1_000_500),
+            (4, "DX", "MASTER", 980_000),
+            (5, "STD", "Re:MASTER", 990_000),
+        ];
+        for (played_at_unixtime, chart_type, diff_category, achievement_x10000) in rows {
+            sqlx::query(
+                "INSERT INTO playlogs (played_at_unixtime, title, chart_type, diff_category, achievement_x10000)
+                 VALUES (?1, 'Song', ?2, ?3, ?4)",
+            )
+            .bind(played_at_unixtime)
+            .bind(chart_type)
+            .bind(diff_category)
+            .bind(achievement_x10000)
+            .execute(&pool)
+            .await?;
+        }
+        Ok(pool)
+    }
+
+    async fn played_at_for(
+        pool: &db::SqlitePool,
+        limit: i64,
+        filter: &RecentFilter,
+    ) -> eyre::Result<Vec<i64>> {
+        Ok(fetch_recent(pool, limit, filter)
+            .await?
+            .into_iter()
+            .map(|row| row.played_at_unixtime)
+            .collect())
+    }
+
+    #[tokio::test]
+    async fn fetch_recent_without_filters_returns_latest_rows() -> eyre::Result<()> {
+        let pool = seeded_pool().await?;
+        let filter = parse_recent_filter(&query(None, None, None)).expect("valid filter");
+
+        assert_eq!(filter, RecentFilter::default());
+        assert_eq!(
+            played_at_for(&pool, 50, &filter).await?,
+            vec![5, 4, 3, 2, 1]
+        );
+        assert_eq!(played_at_for(&pool, 2, &filter).await?, vec![5, 4]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn fetch_recent_applies_each_filter_before_limit() -> eyre::Result<()> {
+        let pool = seeded_pool().await?;
+        let cases = [
+            (query(Some("master"), None, None), vec![4, 2, 1]),
+            (query(Some("Re:MASTER"), None, None), vec![5]),
+            (query(None, Some("99.5"), None), vec![3, 2, 1]),
+            (query(None, None, Some("std")), vec![5, 2]),
+            (query(Some("MASTER"), Some("99"), None), vec![2, 1]),
+            (query(Some("MASTER"), None, Some("DX")), vec![4, 1]),
+            (query(None, Some("100"), Some("DX")), vec![3]),
+            (query(Some("MASTER"), Some("99"), Some("STD")), vec![2]),
+            (query(Some("BASIC"), None, None), vec![]),
+        ];
+
+        for (query, expected) in cases {
+            let filter = parse_recent_filter(&query).expect("valid filter");
+            assert_eq!(played_at_for(&pool, 50, &filter).await?, expected);
+        }
+
+        let filter = parse_recent_filter(&query(Some("MASTER"), None, None)).expect("valid filter");
+        assert_eq!(played_at_for(&pool, 1, &filter).await?, vec![4]);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_recent_filter_normalizes_values() {
+        let filter = parse_recent_filter(&query(Some("remas"), Some(" 99.25 "), Some("dx")))
+            .expect("valid filter");
+        assert_eq!(
+            filter,
+            RecentFilter {
+                diff_category: Some(DifficultyCategory::ReMaster),
+                chart_type: Some(ChartType::Dx),
+                min_achievement_x10000: Some(992_500),
+            }
+        );
+    }
+
+    #[test]
+    fn parse_recent_filter_rejects_invalid_values() {
+        for query in [
+            query(Some("legendary"), None, None),
+            query(None, Some("abc"), None),
+            query(None, Some("-1"), None),
+            query(None, Some("101.5"), None),
+            query(None, Some("NaN"), None),
+            query(None, None, Some("utage")),
+        ] {
+            assert!(parse_recent_filter(&query).is_err());
+        }
+    }
 
     #[test]
     fn requested_limit_keeps_large_values() {
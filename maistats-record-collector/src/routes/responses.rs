@@ -109,6 +109,7 @@ mod tests {
             dx_score_max: Some(1500),
             last_played_at: None,
             play_count: Some(3),
+            level: None,
         })
         .expect("score response should parse");
 
@@ -137,6 +138,7 @@ mod tests {
             dx_score_max: Some(1500),
             credit_id: Some(7),
             achievement_new_record: Some(1),
+            is_buddy_play: false,
         })
         .expect("play record response should parse");
 
@@ -5,18 +5,24 @@ use axum::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    db::{StoredRatedScore, query_played_scores, query_song_scores},
     error::{AppError, Result, app_error_from_maimai},
     routes::responses::{ScoreApiResponse, score_response_from_entry},
+    song_catalog::CatalogIndex,
     state::AppState,
     tasks::utils::{
         auth::ensure_session,
         scores::{
-            RefreshSongScoresOutcome, RefreshSongScoresTarget,
-            refresh_song_scores as refresh_song_scores_task,
+            RefreshSongScoresOutcome, RefreshSongScoresTarget, load_rating_catalog,
+            refresh_song_scores as refresh_song_scores_task, refresh_stored_ratings,
         },
     },
 };
-use models::{SongDetailScoreApiResponse, StoredScoreEntry};
+use models::{
+    ChartType, DifficultyCategory, SongDetailScoreApiResponse, cmp_chart_order, normalize_title,
+};
+use sqlx::SqlitePool;
+use tracing::warn;
 
 #[derive(Deserialize)]
 pub(crate) struct SongScoresQuery {
@@ -32,53 +38,150 @@ pub(crate) struct RefreshSongScoresRequest {
     artist: String,
 }
 
+#[derive(Deserialize)]
+pub(crate) struct RatedScoresQuery {
+    #[serde(alias = "diff_category")]
+    diff: Option<String>,
+    chart_type: Option<String>,
+    min_internal: Option<f32>,
+    max_internal: Option<f32>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct SearchScoresQuery {
+    q: String,
+    /// Rank distinct titles by similarity instead of matching substrings.
+    #[serde(default)]
+    fuzzy: bool,
+    /// How many titles fuzzy mode keeps; ignored otherwise.
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct ScoreHistogramQuery {
+    level: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct ScoreHistogramBucket {
+    /// Inclusive lower bound (percent * 10000).
+    min_x10000: i64,
+    /// Exclusive upper bound (percent * 10000); the last bucket also includes it.
+    max_x10000: i64,
+    count: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct ScoreHistogramResponse {
+    level: String,
+    total: usize,
+    below_min_count: usize,
+    buckets: Vec<ScoreHistogramBucket>,
+}
+
+const HISTOGRAM_MIN_X10000: i64 = 970_000;
+const HISTOGRAM_MAX_X10000: i64 = 1_010_000;
+const HISTOGRAM_BUCKET_WIDTH_X10000: i64 = 5_000;
+
 #[derive(Serialize)]
 pub(crate) struct RefreshSongScoresResponse {
     detail_pages_refreshed: usize,
     rows_written: usize,
 }
 
+/// Every played chart, narrowed by the optional `diff` (e.g. `MASTER`), `chart_type`
+/// (`STD`/`DX`) and `min_internal`/`max_internal` filters. Internal levels are the ones stored
+/// with each score's rating; charts without one are dropped once a level bound is given.
 pub(crate) async fn get_all_rated_scores(
     State(state): State<AppState>,
+    Query(params): Query<RatedScoresQuery>,
 ) -> Result<Json<Vec<ScoreApiResponse>>> {
-    let rows = sqlx::query_as::<_, StoredScoreEntry>(
-        "SELECT title, genre, artist, chart_type, diff_category, achievement_x10000, rank, fc, sync, dx_score, dx_score_max, last_played_at, play_count
-         FROM scores
-         WHERE achievement_x10000 IS NOT NULL
-         ORDER BY title, genre, artist, chart_type, diff_category"
-    )
-    .fetch_all(&state.db_pool)
-    .await?;
+    let diff = params.diff.as_deref().map(parse_diff_filter).transpose()?;
+    let chart_type = params
+        .chart_type
+        .as_deref()
+        .map(parse_chart_type_filter)
+        .transpose()?;
+    let internal_range = parse_internal_range(params.min_internal, params.max_internal)?;
 
-    let mut responses = Vec::with_capacity(rows.len());
-    for entry in rows {
-        responses.push(score_response_from_entry(entry)?);
+    let mut rows = query_played_scores(&state.db_pool, diff, chart_type).await?;
+    if let Some((min_internal, max_internal)) = internal_range {
+        rows = filter_by_internal_level(rows, min_internal, max_internal);
     }
+    let scores = rows
+        .into_iter()
+        .map(|row| score_response_from_entry(row.score))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Json(scores))
+}
 
-    Ok(Json(responses))
+fn parse_chart_type_filter(raw: &str) -> Result<ChartType> {
+    raw.parse::<ChartType>().map_err(|_| {
+        AppError::BadRequest(format!(
+            "invalid chart_type '{raw}': expected STD, DX or UTAGE"
+        ))
+    })
+}
+
+/// `None` when neither bound is given; a missing bound is open-ended.
+fn parse_internal_range(min: Option<f32>, max: Option<f32>) -> Result<Option<(f32, f32)>> {
+    if min.is_none() && max.is_none() {
+        return Ok(None);
+    }
+    let min = min.unwrap_or(f32::MIN);
+    let max = max.unwrap_or(f32::MAX);
+    if min > max {
+        return Err(AppError::BadRequest(format!(
+            "min_internal ({min}) must not exceed max_internal ({max})"
+        )));
+    }
+    Ok(Some((min, max)))
+}
+
+fn filter_by_internal_level(
+    rows: Vec<StoredRatedScore>,
+    min_internal: f32,
+    max_internal: f32,
+) -> Vec<StoredRatedScore> {
+    rows.into_iter()
+        .filter(|row| {
+            row.internal_level
+                .is_some_and(|level| (min_internal..=max_internal).contains(&(level as f32)))
+        })
+        .collect()
+}
+
+fn parse_diff_filter(raw: &str) -> Result<DifficultyCategory> {
+    raw.parse::<DifficultyCategory>().map_err(|_| {
+        AppError::BadRequest(format!(
+            "invalid diff '{raw}': expected BASIC, ADVANCED, EXPERT, MASTER or Re:MASTER"
+        ))
+    })
+}
+
+async fn query_rated_scores(
+    pool: &SqlitePool,
+    diff: Option<DifficultyCategory>,
+    chart_type: Option<ChartType>,
+) -> Result<Vec<ScoreApiResponse>> {
+    let rows = query_played_scores(pool, diff, chart_type).await?;
+
+    let mut responses = Vec::with_capacity(rows.len());
+    for row in rows {
+        responses.push(score_response_from_entry(row.score)?);
+    }
+    Ok(responses)
 }
 
 pub(crate) async fn get_song_detail_scores(
     State(state): State<AppState>,
     Query(params): Query<SongScoresQuery>,
 ) -> Result<Json<Vec<SongDetailScoreApiResponse>>> {
-    let rows = sqlx::query_as::<_, StoredScoreEntry>(
-        "SELECT title, genre, artist, chart_type, diff_category, achievement_x10000, rank, fc, sync, dx_score, dx_score_max, last_played_at, play_count
-         FROM scores
-         WHERE title = ? AND genre = ? AND artist = ? AND achievement_x10000 IS NOT NULL
-         ORDER BY chart_type, diff_category"
-    )
-    .bind(&params.title)
-    .bind(&params.genre)
-    .bind(&params.artist)
-    .fetch_all(&state.db_pool)
-    .await?;
+    let rows =
+        query_song_scores(&state.db_pool, &params.title, &params.genre, &params.artist).await?;
 
     if rows.is_empty() {
-        return Err(AppError::NotFound(format!(
-            "No played scores found for title='{}', genre='{}', artist='{}'",
-            params.title, params.genre, params.artist
-        )));
+        return Err(song_scores_not_found(&state.db_pool, &params).await);
     }
 
     let mut responses = Vec::with_capacity(rows.len());
@@ -100,10 +203,202 @@ pub(crate) async fn get_song_detail_scores(
             play_count: score.play_count,
         });
     }
+    responses.sort_by(|a, b| {
+        cmp_chart_order(
+            (a.chart_type, a.diff_category),
+            (b.chart_type, b.diff_category),
+        )
+    });
 
     Ok(Json(responses))
 }
 
+/// 404 for `/api/songs/scores`, carrying the played titles closest to the requested one so a
+/// client that spelled the title differently can retry.
+async fn song_scores_not_found(pool: &SqlitePool, params: &SongScoresQuery) -> AppError {
+    let suggestions = match sqlx::query_scalar::<_, String>(
+        "SELECT DISTINCT title FROM scores WHERE achievement_x10000 IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await
+    {
+        Ok(titles) => suggest_titles(&params.title, &titles),
+        Err(err) => {
+            warn!("failed to load titles for suggestions: {err}");
+            Vec::new()
+        }
+    };
+    AppError::NotFoundWithSuggestions {
+        message: format!(
+            "No played scores found for title='{}', genre='{}', artist='{}'",
+            params.title, params.genre, params.artist
+        ),
+        suggestions,
+    }
+}
+
+/// Most suggestions a 404 carries.
+const MAX_TITLE_SUGGESTIONS: usize = 5;
+/// Jaro-Winkler similarity (on [`normalize_title`] keys, case-insensitive) a title needs.
+const MIN_TITLE_SIMILARITY: f64 = 0.8;
+/// Titles `/api/scores/search?fuzzy=true` keeps when `limit` is omitted.
+const DEFAULT_FUZZY_SEARCH_LIMIT: usize = 5;
+const MAX_FUZZY_SEARCH_LIMIT: usize = 25;
+
+fn suggest_titles(query: &str, titles: &[String]) -> Vec<String> {
+    top_title_matches(query, titles, MAX_TITLE_SUGGESTIONS)
+}
+
+/// Up to `limit` of `titles` clearing [`MIN_TITLE_SIMILARITY`], most similar first.
+fn top_title_matches(query: &str, titles: &[String], limit: usize) -> Vec<String> {
+    let query = normalize_title(query).to_lowercase();
+    let mut scored = titles
+        .iter()
+        .map(|title| {
+            let key = normalize_title(title).to_lowercase();
+            (strsim::jaro_winkler(&query, &key), title)
+        })
+        .filter(|(similarity, _)| *similarity >= MIN_TITLE_SIMILARITY)
+        .collect::<Vec<_>>();
+    scored.sort_by(|(a_score, a_title), (b_score, b_title)| {
+        b_score
+            .total_cmp(a_score)
+            .then_with(|| a_title.cmp(b_title))
+    });
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, title)| title.clone())
+        .collect()
+}
+
+/// Played charts whose title contains `q` (case-insensitive, on [`normalize_title`] keys).
+/// With `fuzzy=true` the distinct played titles are ranked by Jaro-Winkler against `q`
+/// instead and only the charts of the best `limit` titles come back, best title first.
+pub(crate) async fn search_scores(
+    State(state): State<AppState>,
+    Query(params): Query<SearchScoresQuery>,
+) -> Result<Json<Vec<ScoreApiResponse>>> {
+    if params.q.trim().is_empty() {
+        return Err(AppError::BadRequest("q must not be empty".to_string()));
+    }
+    let fuzzy_limit = if params.fuzzy {
+        Some(parse_fuzzy_limit(params.limit)?)
+    } else {
+        None
+    };
+    Ok(Json(
+        query_search_scores(&state.db_pool, &params.q, fuzzy_limit).await?,
+    ))
+}
+
+fn parse_fuzzy_limit(limit: Option<usize>) -> Result<usize> {
+    match limit.unwrap_or(DEFAULT_FUZZY_SEARCH_LIMIT) {
+        limit @ 1..=MAX_FUZZY_SEARCH_LIMIT => Ok(limit),
+        limit => Err(AppError::BadRequest(format!(
+            "invalid limit {limit}: expected 1 to {MAX_FUZZY_SEARCH_LIMIT}"
+        ))),
+    }
+}
+
+async fn query_search_scores(
+    pool: &SqlitePool,
+    query: &str,
+    fuzzy_limit: Option<usize>,
+) -> Result<Vec<ScoreApiResponse>> {
+    let scores = query_rated_scores(pool, None, None).await?;
+    let Some(limit) = fuzzy_limit else {
+        let key = normalize_title(query).to_lowercase();
+        return Ok(scores
+            .into_iter()
+            .filter(|score| normalize_title(&score.title).to_lowercase().contains(&key))
+            .collect());
+    };
+
+    let mut titles = scores
+        .iter()
+        .map(|score| score.title.clone())
+        .collect::<Vec<_>>();
+    titles.dedup();
+    let ranked = top_title_matches(query, &titles, limit);
+    let mut matches = scores
+        .into_iter()
+        .filter_map(|score| {
+            let rank = ranked.iter().position(|title| *title == score.title)?;
+            Some((rank, score))
+        })
+        .collect::<Vec<_>>();
+    // Stable, so each title keeps the chart order of `query_rated_scores`.
+    matches.sort_by_key(|(rank, _)| *rank);
+    Ok(matches.into_iter().map(|(_, score)| score).collect())
+}
+
+pub(crate) async fn get_score_histogram(
+    State(state): State<AppState>,
+    Query(params): Query<ScoreHistogramQuery>,
+) -> Result<Json<ScoreHistogramResponse>> {
+    let level = parse_histogram_level(&params.level)?;
+
+    let achievements = sqlx::query_scalar::<_, i64>(
+        "SELECT achievement_x10000
+         FROM scores
+         WHERE level = ? AND achievement_x10000 IS NOT NULL",
+    )
+    .bind(&level)
+    .fetch_all(&state.db_pool)
+    .await?;
+
+    Ok(Json(build_score_histogram(level, &achievements)))
+}
+
+fn parse_histogram_level(raw: &str) -> Result<String> {
+    let level = raw.trim();
+    let base = level.strip_suffix('+').unwrap_or(level);
+    let valid = !base.is_empty()
+        && base.len() <= 2
+        && base.bytes().all(|b| b.is_ascii_digit())
+        && base.parse::<u8>().is_ok_and(|v| (1..=15).contains(&v));
+    if !valid {
+        return Err(AppError::BadRequest(format!(
+            "invalid level '{raw}': expected a level like 14 or 14+"
+        )));
+    }
+    Ok(level.to_string())
+}
+
+fn build_score_histogram(level: String, achievements: &[i64]) -> ScoreHistogramResponse {
+    let bucket_count =
+        ((HISTOGRAM_MAX_X10000 - HISTOGRAM_MIN_X10000) / HISTOGRAM_BUCKET_WIDTH_X10000) as usize;
+    let mut buckets = (0..bucket_count)
+        .map(|idx| {
+            let min_x10000 = HISTOGRAM_MIN_X10000 + idx as i64 * HISTOGRAM_BUCKET_WIDTH_X10000;
+            ScoreHistogramBucket {
+                min_x10000,
+                max_x10000: min_x10000 + HISTOGRAM_BUCKET_WIDTH_X10000,
+                count: 0,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut below_min_count = 0;
+    for &achievement_x10000 in achievements {
+        if achievement_x10000 < HISTOGRAM_MIN_X10000 {
+            below_min_count += 1;
+            continue;
+        }
+        let idx =
+            ((achievement_x10000 - HISTOGRAM_MIN_X10000) / HISTOGRAM_BUCKET_WIDTH_X10000) as usize;
+        buckets[idx.min(bucket_count - 1)].count += 1;
+    }
+
+    ScoreHistogramResponse {
+        level,
+        total: achievements.len(),
+        below_min_count,
+        buckets,
+    }
+}
+
 pub(crate) async fn refresh_song_scores(
     State(state): State<AppState>,
     Json(payload): Json<RefreshSongScoresRequest>,
@@ -114,18 +409,307 @@ pub(crate) async fn refresh_song_scores(
         artist: payload.artist.trim().to_string(),
     };
 
-    let mut client = state.maimai_client().map_err(app_error_from_maimai)?;
-    ensure_session(&mut client)
+    let mut client = state.maimai_client.clone();
+    ensure_session(&client)
         .await
         .map_err(app_error_from_maimai)?;
 
-    let outcome: RefreshSongScoresOutcome =
-        refresh_song_scores_task(&state.db_pool, &mut client, &target)
-            .await
-            .map_err(app_error_from_maimai)?;
+    let catalog = load_rating_catalog(&state.db_pool, &state.song_catalog).await;
+    let outcome: RefreshSongScoresOutcome = refresh_song_scores_task(
+        &state.db_pool,
+        state.db_query_timeout,
+        &mut client,
+        &target,
+        &CatalogIndex::new(&catalog),
+    )
+    .await
+    .map_err(app_error_from_maimai)?;
+    refresh_stored_ratings(&state.db_pool, state.db_query_timeout, &state.song_catalog).await;
 
     Ok(Json(RefreshSongScoresResponse {
         detail_pages_refreshed: outcome.detail_pages_refreshed,
         rows_written: outcome.rows_written,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        SongScoresQuery, build_score_histogram, filter_by_internal_level, parse_diff_filter,
+        parse_fuzzy_limit, parse_histogram_level, parse_internal_range, query_rated_scores,
+        query_search_scores, song_scores_not_found,
+    };
+    use crate::db::{DEFAULT_QUERY_TIMEOUT, connect, migrate, query_played_scores, upsert_scores};
+    use crate::song_catalog::CatalogIndex;
+    use axum::body::to_bytes;
+    use axum::http::StatusCode;
+    use axum::response::IntoResponse;
+    use maimai_client::{SongCatalogSheet, SongCatalogSong};
+    use models::{ChartType, DifficultyCategory, ParsedScoreEntry, SongAliases, SongChartRegion};
+
+    #[test]
+    fn parse_histogram_level_accepts_plain_and_plus_levels() {
+        assert_eq!(parse_histogram_level("14").unwrap(), "14");
+        assert_eq!(parse_histogram_level(" 13+ ").unwrap(), "13+");
+        assert_eq!(parse_histogram_level("1").unwrap(), "1");
+    }
+
+    #[test]
+    fn parse_histogram_level_rejects_invalid_values() {
+        for raw in ["", "+", "0", "16", "14.5", "abc", "014"] {
+            assert!(parse_histogram_level(raw).is_err(), "expected error: {raw}");
+        }
+    }
+
+    #[test]
+    fn build_score_histogram_buckets_half_percent_bins() {
+        let histogram = build_score_histogram(
+            "14".to_string(),
+            &[950_000, 970_000, 974_999, 975_000, 1_005_000, 1_010_000],
+        );
+
+        assert_eq!(histogram.total, 6);
+        assert_eq!(histogram.below_min_count, 1);
+        assert_eq!(histogram.buckets.len(), 8);
+        assert_eq!(histogram.buckets[0].min_x10000, 970_000);
+        assert_eq!(histogram.buckets[0].count, 2);
+        assert_eq!(histogram.buckets[1].count, 1);
+        assert_eq!(histogram.buckets[7].min_x10000, 1_005_000);
+        assert_eq!(histogram.buckets[7].max_x10000, 1_010_000);
+        assert_eq!(histogram.buckets[7].count, 2);
+    }
+
+    #[test]
+    fn build_score_histogram_is_empty_without_matches() {
+        let histogram = build_score_histogram("15".to_string(), &[]);
+
+        assert_eq!(histogram.total, 0);
+        assert_eq!(histogram.below_min_count, 0);
+        assert!(histogram.buckets.iter().all(|bucket| bucket.count == 0));
+    }
+
+    fn played(title: &str) -> ParsedScoreEntry {
+        played_diff(title, DifficultyCategory::Master)
+    }
+
+    fn played_diff(title: &str, diff_category: DifficultyCategory) -> ParsedScoreEntry {
+        ParsedScoreEntry {
+            title: title.to_string(),
+            genre: "東方Project".to_string(),
+            artist: "Artist".to_string(),
+            chart_type: ChartType::Std,
+            diff_category,
+            level: "13".to_string(),
+            achievement_percent: Some(99.5),
+            rank: None,
+            fc: None,
+            sync: None,
+            dx_score: None,
+            dx_score_max: None,
+            last_played_at: None,
+            play_count: None,
+            source_idx: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn near_miss_title_returns_404_with_suggestions() -> eyre::Result<()> {
+        let pool = connect("sqlite::memory:").await?;
+        migrate(&pool).await?;
+        upsert_scores(
+            &pool,
+            DEFAULT_QUERY_TIMEOUT,
+            &[
+                played("Night of Nights"),
+                played("Bad Apple!! feat.nomico"),
+                played("ガラテアの螺旋"),
+            ],
+            &CatalogIndex::default(),
+        )
+        .await?;
+
+        let params = SongScoresQuery {
+            title: "Night of Knights".to_string(),
+            genre: "東方Project".to_string(),
+            artist: "Artist".to_string(),
+        };
+        let response = song_scores_not_found(&pool, &params).await.into_response();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let body: serde_json::Value =
+            serde_json::from_slice(&to_bytes(response.into_body(), usize::MAX).await?)?;
+        assert_eq!(body["code"], "NOT_FOUND");
+        assert_eq!(body["suggestions"], serde_json::json!(["Night of Nights"]));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn search_scores_fuzzy_mode_ranks_titles_and_plain_mode_matches_substrings()
+    -> eyre::Result<()> {
+        let pool = connect("sqlite::memory:").await?;
+        migrate(&pool).await?;
+        upsert_scores(
+            &pool,
+            DEFAULT_QUERY_TIMEOUT,
+            &[
+                played("Night of Nights"),
+                played_diff("Night of Nights", DifficultyCategory::Expert),
+                played("Night of Knights"),
+                played("Bad Apple!! feat.nomico"),
+            ],
+            &CatalogIndex::default(),
+        )
+        .await?;
+
+        let titles = |scores: Vec<super::ScoreApiResponse>| {
+            scores
+                .into_iter()
+                .map(|score| (score.title, score.diff_category))
+                .collect::<Vec<_>>()
+        };
+
+        let fuzzy = query_search_scores(&pool, "night of nights", Some(1))
+            .await
+            .expect("fuzzy search");
+        assert_eq!(
+            titles(fuzzy),
+            vec![
+                ("Night of Nights".to_string(), DifficultyCategory::Expert),
+                ("Night of Nights".to_string(), DifficultyCategory::Master),
+            ]
+        );
+        let fuzzy = query_search_scores(&pool, "Night of Nights", Some(5))
+            .await
+            .expect("fuzzy search");
+        assert_eq!(fuzzy.len(), 3);
+        assert_eq!(fuzzy[2].title, "Night of Knights");
+
+        let plain = query_search_scores(&pool, "ＡＰＰＬＥ", None)
+            .await
+            .expect("substring search");
+        assert_eq!(
+            titles(plain),
+            vec![(
+                "Bad Apple!! feat.nomico".to_string(),
+                DifficultyCategory::Master
+            )]
+        );
+
+        assert_eq!(parse_fuzzy_limit(None).expect("default limit"), 5);
+        assert!(parse_fuzzy_limit(Some(0)).is_err());
+        assert!(parse_fuzzy_limit(Some(26)).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn rated_scores_diff_filter_keeps_only_that_difficulty() -> eyre::Result<()> {
+        let pool = connect("sqlite::memory:").await?;
+        migrate(&pool).await?;
+        upsert_scores(
+            &pool,
+            DEFAULT_QUERY_TIMEOUT,
+            &[
+                played_diff("A", DifficultyCategory::Expert),
+                played_diff("A", DifficultyCategory::Master),
+                played_diff("B", DifficultyCategory::Master),
+                played_diff("B", DifficultyCategory::ReMaster),
+            ],
+            &CatalogIndex::default(),
+        )
+        .await?;
+
+        let all = query_rated_scores(&pool, None, None)
+            .await
+            .expect("all rated scores");
+        let diff = parse_diff_filter("MASTER").expect("valid diff");
+        let master = query_rated_scores(&pool, Some(diff), None)
+            .await
+            .expect("master rated scores");
+
+        assert_eq!(all.len(), 4);
+        assert_eq!(
+            master
+                .iter()
+                .map(|score| score.title.as_str())
+                .collect::<Vec<_>>(),
+            vec!["A", "B"]
+        );
+        let expected_master = all
+            .iter()
+            .filter(|score| score.diff_category == DifficultyCategory::Master)
+            .count();
+        assert_eq!(master.len(), expected_master);
+        assert!(parse_diff_filter("LUNATIC").is_err());
+        Ok(())
+    }
+
+    fn catalog_song(title: &str, internal_level: Option<f32>) -> SongCatalogSong {
+        SongCatalogSong {
+            title: title.to_string(),
+            genre: "東方Project".to_string(),
+            artist: "Artist".to_string(),
+            image_name: None,
+            aliases: SongAliases::default(),
+            sheets: vec![SongCatalogSheet {
+                chart_type: ChartType::Std,
+                diff_category: DifficultyCategory::Master,
+                level: "13".to_string(),
+                version: None,
+                internal_level,
+                region: SongChartRegion {
+                    jp: true,
+                    intl: true,
+                },
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn rated_scores_internal_level_filter_uses_stored_levels() -> eyre::Result<()> {
+        let pool = connect("sqlite::memory:").await?;
+        migrate(&pool).await?;
+        let catalog = vec![
+            catalog_song("Low", Some(12.9)),
+            catalog_song("Mid", Some(13.4)),
+            catalog_song("High", Some(14.0)),
+            catalog_song("Unknown", None),
+        ];
+        upsert_scores(
+            &pool,
+            DEFAULT_QUERY_TIMEOUT,
+            &[
+                played("Low"),
+                played("Mid"),
+                played("High"),
+                played("Unknown"),
+            ],
+            &CatalogIndex::new(&catalog),
+        )
+        .await?;
+
+        let rows = query_played_scores(&pool, None, Some(ChartType::Std)).await?;
+        let (min, max) = parse_internal_range(Some(13.0), Some(14.0))
+            .expect("valid range")
+            .expect("bounded");
+        let mut titles = filter_by_internal_level(rows, min, max)
+            .into_iter()
+            .map(|row| row.score.title)
+            .collect::<Vec<_>>();
+        titles.sort();
+
+        assert_eq!(titles, vec!["High", "Mid"]);
+        assert!(
+            query_rated_scores(&pool, None, Some(ChartType::Dx))
+                .await
+                .expect("rated scores")
+                .is_empty()
+        );
+        assert!(
+            parse_internal_range(None, None)
+                .expect("no bounds")
+                .is_none()
+        );
+        assert!(parse_internal_range(Some(14.0), Some(13.0)).is_err());
+        Ok(())
+    }
+}
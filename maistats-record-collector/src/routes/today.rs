@@ -6,11 +6,11 @@ use serde::Deserialize;
 use time::{Date, Duration as TimeDuration, Month, OffsetDateTime, UtcOffset};
 
 use crate::{
+    db::query_playlogs_between,
     error::Result,
     routes::responses::{PlayRecordApiResponse, play_record_response_from_record},
     state::AppState,
 };
-use models::StoredPlayRecord;
 
 #[derive(Deserialize)]
 pub(crate) struct TodayQuery {
@@ -70,32 +70,7 @@ pub(crate) async fn get_today(
         end_date.day()
     );
 
-    let rows = sqlx::query_as::<_, StoredPlayRecord>(
-        "SELECT 
-            played_at_unixtime,
-            played_at,
-            track,
-            title,
-            genre,
-            artist,
-            chart_type,
-            diff_category,
-            achievement_x10000,
-            score_rank,
-            fc,
-            sync,
-            dx_score,
-            dx_score_max,
-            credit_id,
-            achievement_new_record
-         FROM playlogs
-         WHERE played_at >= ? AND played_at < ?
-         ORDER BY played_at_unixtime ASC",
-    )
-    .bind(&start)
-    .bind(&end)
-    .fetch_all(&state.db_pool)
-    .await?;
+    let rows = query_playlogs_between(&state.db_pool, &start, &end).await?;
 
     let mut responses = Vec::with_capacity(rows.len());
     for record in rows {
@@ -0,0 +1,368 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use eyre::WrapErr;
+use maimai_client::{SongCatalogSheet, SongCatalogSong, SongDatabaseClient};
+use models::{ChartInternalLevelPatch, ChartType, DifficultyCategory, normalize_title};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::warn;
+
+pub(crate) const SONG_CATALOG_CACHE_FILE: &str = "song_catalog_cache.json";
+/// The song database only changes once a day; rewriting the snapshot more often is wasted IO.
+const CACHE_PERSIST_INTERVAL: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SongCatalogSnapshot {
+    saved_at_unix: u64,
+    songs: Vec<SongCatalogSong>,
+}
+
+/// Song database access with a last-known-good snapshot in the data dir, so rating routes
+/// keep working while the song database host is unreachable.
+#[derive(Clone)]
+pub(crate) struct SongCatalogSource {
+    client: SongDatabaseClient,
+    cache_path: PathBuf,
+    persisted_at: Arc<Mutex<Option<Instant>>>,
+}
+
+impl SongCatalogSource {
+    pub(crate) fn new(client: SongDatabaseClient, data_dir: &Path) -> Self {
+        Self {
+            client,
+            cache_path: data_dir.join(SONG_CATALOG_CACHE_FILE),
+            persisted_at: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub(crate) fn from_settings(
+        song_database_url: &str,
+        include_estimated_internal_levels: bool,
+        data_dir: &Path,
+    ) -> eyre::Result<Self> {
+        let client = SongDatabaseClient::new(song_database_url.to_string())
+            .wrap_err("Failed to create song database client")?
+            .with_estimated_internal_levels(include_estimated_internal_levels);
+        Ok(Self::new(client, data_dir))
+    }
+
+    pub(crate) async fn list_song_catalog(&self) -> eyre::Result<Vec<SongCatalogSong>> {
+        match self.client.list_song_catalog().await {
+            Ok(songs) => {
+                self.persist_if_due(&songs).await;
+                Ok(songs)
+            }
+            Err(err) => {
+                let Some(snapshot) = self.load_snapshot() else {
+                    return Err(err);
+                };
+                warn!(
+                    "song database unavailable; using cached catalog: age_secs={} songs={} cause={:#}",
+                    unix_now().saturating_sub(snapshot.saved_at_unix),
+                    snapshot.songs.len(),
+                    err
+                );
+                Ok(snapshot.songs)
+            }
+        }
+    }
+
+    /// Patch one chart's internal level in the in-memory catalog; `Ok(false)` when the
+    /// catalog has no such chart.
+    pub(crate) async fn upsert_chart_internal_level(
+        &self,
+        patch: &ChartInternalLevelPatch,
+    ) -> eyre::Result<bool> {
+        let patched = self.client.upsert_chart_internal_level(patch).await?;
+        if patched {
+            // Force the next fetch to rewrite the snapshot so the patch survives an outage.
+            *self.persisted_at.lock().await = None;
+        }
+        Ok(patched)
+    }
+
+    async fn persist_if_due(&self, songs: &[SongCatalogSong]) {
+        let mut persisted_at = self.persisted_at.lock().await;
+        if persisted_at.is_some_and(|at| at.elapsed() < CACHE_PERSIST_INTERVAL) {
+            return;
+        }
+        // Serializing and syncing the whole catalog is blocking work; keep it off the runtime.
+        let path = self.cache_path.clone();
+        let songs = songs.to_vec();
+        let written = tokio::task::spawn_blocking(move || write_snapshot(&path, &songs))
+            .await
+            .wrap_err("join song catalog cache writer")
+            .and_then(|result| result);
+        match written {
+            Ok(()) => *persisted_at = Some(Instant::now()),
+            Err(err) => warn!("failed to persist song catalog cache: {err:#}"),
+        }
+    }
+
+    fn load_snapshot(&self) -> Option<SongCatalogSnapshot> {
+        let json = std::fs::read_to_string(&self.cache_path).ok()?;
+        match serde_json::from_str(&json) {
+            Ok(snapshot) => Some(snapshot),
+            Err(err) => {
+                warn!(
+                    "ignoring unreadable song catalog cache {}: {err}",
+                    self.cache_path.display()
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Hash of everything the stored rating columns depend on: song identity, internal levels
+/// and the versions that pick the NEW/OLD bucket. Equal fingerprints mean a backfill would
+/// change nothing.
+pub(crate) fn catalog_rating_fingerprint(catalog: &[SongCatalogSong]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for song in catalog {
+        song.title.hash(&mut hasher);
+        song.artist.hash(&mut hasher);
+        for sheet in &song.sheets {
+            sheet.chart_type.hash(&mut hasher);
+            sheet.diff_category.hash(&mut hasher);
+            sheet.version.hash(&mut hasher);
+            sheet.internal_level.map(f32::to_bits).hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+/// [`find_catalog_sheet`] over a title index built once, for passes over many scores.
+#[derive(Default)]
+pub(crate) struct CatalogIndex<'a> {
+    by_title: HashMap<String, Vec<&'a SongCatalogSong>>,
+}
+
+impl<'a> CatalogIndex<'a> {
+    pub(crate) fn new(catalog: &'a [SongCatalogSong]) -> Self {
+        let mut by_title = HashMap::<String, Vec<&'a SongCatalogSong>>::new();
+        for song in catalog {
+            by_title
+                .entry(normalize_title(&song.title))
+                .or_default()
+                .push(song);
+        }
+        Self { by_title }
+    }
+
+    pub(crate) fn find_sheet(
+        &self,
+        title: &str,
+        artist: &str,
+        chart_type: ChartType,
+        diff_category: DifficultyCategory,
+    ) -> Option<&'a SongCatalogSheet> {
+        let candidates = self.by_title.get(&normalize_title(title))?;
+        pick_by_artist(candidates.iter().copied(), artist)?
+            .sheets
+            .iter()
+            .find(|sheet| sheet.chart_type == chart_type && sheet.diff_category == diff_category)
+    }
+}
+
+pub(crate) fn find_catalog_sheet<'a>(
+    catalog: &'a [SongCatalogSong],
+    title: &str,
+    artist: &str,
+    chart_type: ChartType,
+    diff_category: DifficultyCategory,
+) -> Option<&'a SongCatalogSheet> {
+    find_catalog_song(catalog, title, artist)?
+        .sheets
+        .iter()
+        .find(|sheet| sheet.chart_type == chart_type && sheet.diff_category == diff_category)
+}
+
+/// Title match, disambiguated by artist when several songs share a title; `None` when they
+/// do and none is by `artist`.
+fn find_catalog_song<'a>(
+    catalog: &'a [SongCatalogSong],
+    title: &str,
+    artist: &str,
+) -> Option<&'a SongCatalogSong> {
+    let title = normalize_title(title);
+    pick_by_artist(
+        catalog
+            .iter()
+            .filter(|song| normalize_title(&song.title) == title),
+        artist,
+    )
+}
+
+/// The first candidate by `artist`. Without one, the only candidate when the title is
+/// unique; another song's internal level must never stand in for the requested one.
+fn pick_by_artist<'a>(
+    mut candidates: impl Iterator<Item = &'a SongCatalogSong>,
+    artist: &str,
+) -> Option<&'a SongCatalogSong> {
+    let first = candidates.next()?;
+    if first.artist == artist {
+        return Some(first);
+    }
+    let mut others = candidates.peekable();
+    if others.peek().is_none() {
+        return Some(first);
+    }
+    others.find(|song| song.artist == artist)
+}
+
+fn write_snapshot(path: &Path, songs: &[SongCatalogSong]) -> eyre::Result<()> {
+    let snapshot = SongCatalogSnapshot {
+        saved_at_unix: unix_now(),
+        songs: songs.to_vec(),
+    };
+    let json = serde_json::to_vec(&snapshot).wrap_err("serialize song catalog cache")?;
+    let tmp_path = path.with_extension("json.tmp");
+    let mut file = std::fs::File::create(&tmp_path).wrap_err("create song catalog cache")?;
+    file.write_all(&json).wrap_err("write song catalog cache")?;
+    // Flush before the rename so a crash never leaves a renamed but empty snapshot.
+    file.sync_all().wrap_err("sync song catalog cache")?;
+    std::fs::rename(&tmp_path, path).wrap_err("replace song catalog cache")?;
+    Ok(())
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        CatalogIndex, SongCatalogSource, catalog_rating_fingerprint, find_catalog_sheet,
+        write_snapshot,
+    };
+    use maimai_client::{SongCatalogSheet, SongCatalogSong, SongDatabaseClient};
+    use models::{ChartType, DifficultyCategory, SongAliases, SongChartRegion};
+
+    fn source(dir: &std::path::Path) -> SongCatalogSource {
+        // Nothing listens on port 9 locally, so every fetch fails like an unreachable host.
+        let client = SongDatabaseClient::new("http://127.0.0.1:9".to_string()).expect("client");
+        SongCatalogSource::new(client, dir)
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_cached_catalog_when_song_database_is_down() {
+        let dir = std::env::temp_dir().join(format!(
+            "maistats-song-catalog-cache-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let source = source(&dir);
+
+        assert!(source.list_song_catalog().await.is_err());
+
+        let song = SongCatalogSong {
+            title: "Cached Song".to_string(),
+            genre: "maimai".to_string(),
+            artist: "Artist".to_string(),
+            image_name: None,
+            aliases: SongAliases::default(),
+            sheets: Vec::new(),
+        };
+        write_snapshot(&source.cache_path, &[song]).expect("warm cache");
+
+        let songs = source.list_song_catalog().await.expect("cached catalog");
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(songs.len(), 1);
+        assert_eq!(songs[0].title, "Cached Song");
+    }
+
+    fn link(artist: &str, internal_level: f32) -> SongCatalogSong {
+        SongCatalogSong {
+            title: "Link".to_string(),
+            genre: "maimai".to_string(),
+            artist: artist.to_string(),
+            image_name: None,
+            aliases: SongAliases::default(),
+            sheets: vec![SongCatalogSheet {
+                chart_type: ChartType::Std,
+                diff_category: DifficultyCategory::Master,
+                level: "12".to_string(),
+                version: None,
+                internal_level: Some(internal_level),
+                region: SongChartRegion {
+                    jp: true,
+                    intl: true,
+                },
+            }],
+        }
+    }
+
+    #[test]
+    fn catalog_index_picks_same_title_songs_by_artist() {
+        let catalog = vec![link("Artist A", 12.3), link("Artist B", 12.6)];
+        let index = CatalogIndex::new(&catalog);
+        let level = |artist: &str| {
+            index
+                .find_sheet("Link", artist, ChartType::Std, DifficultyCategory::Master)
+                .and_then(|sheet| sheet.internal_level)
+        };
+
+        assert_eq!(level("Artist A"), Some(12.3));
+        assert_eq!(level("Artist B"), Some(12.6));
+        assert_eq!(level("Unknown"), None);
+        assert!(
+            find_catalog_sheet(
+                &catalog,
+                "Link",
+                "Unknown",
+                ChartType::Std,
+                DifficultyCategory::Master
+            )
+            .is_none()
+        );
+
+        // A unique title still matches when the stored artist spelling differs.
+        let unique = vec![link("Artist A", 12.3)];
+        assert_eq!(
+            find_catalog_sheet(
+                &unique,
+                "Link",
+                "Artist a",
+                ChartType::Std,
+                DifficultyCategory::Master
+            )
+            .and_then(|sheet| sheet.internal_level),
+            Some(12.3)
+        );
+        assert!(
+            index
+                .find_sheet(
+                    "Link",
+                    "Artist A",
+                    ChartType::Dx,
+                    DifficultyCategory::Master
+                )
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn catalog_rating_fingerprint_tracks_internal_levels() {
+        let catalog = vec![link("Artist A", 12.3)];
+        assert_eq!(
+            catalog_rating_fingerprint(&catalog),
+            catalog_rating_fingerprint(&catalog.clone())
+        );
+        assert_ne!(
+            catalog_rating_fingerprint(&catalog),
+            catalog_rating_fingerprint(&[link("Artist A", 12.4)])
+        );
+    }
+}
@@ -1,36 +1,28 @@
-use crate::config::RecordCollectorConfig;
+use crate::config::MaintenanceWindow;
 use crate::http_client::MaimaiClient;
 use crate::logging::LogBuffer;
+use crate::song_catalog::SongCatalogSource;
 use sqlx::SqlitePool;
-use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::{Mutex, Notify};
 
 #[derive(Clone)]
 pub(crate) struct AppState {
     pub(crate) db_pool: SqlitePool,
-    pub(crate) config: RecordCollectorConfig,
+    /// Bound on the database write/aggregate paths (`DB_QUERY_TIMEOUT_SECS`).
+    pub(crate) db_query_timeout: Duration,
     pub(crate) log_buffer: Arc<LogBuffer>,
+    /// Single crawl client shared by startup, polling and routes. Clones share the cookie
+    /// store, connection pool and session state.
+    pub(crate) maimai_client: MaimaiClient,
+    /// Song catalog (internal levels, versions) used by the rating routes; falls back to the
+    /// last snapshot in the data dir when the song database is unreachable.
+    pub(crate) song_catalog: SongCatalogSource,
     /// Held while a polling cycle is running; prevents concurrent cycles.
     pub(crate) cycle_lock: Arc<Mutex<()>>,
     /// Signalled after a cycle completes via /api/poll so the scheduler resets its timer.
     pub(crate) timer_reset_notify: Arc<Notify>,
-}
-
-impl AppState {
-    pub(crate) fn maimai_client(&self) -> eyre::Result<MaimaiClient> {
-        let data_dir = PathBuf::from(&self.config.data_dir);
-        let cookie_path =
-            std::env::temp_dir().join(format!("maistats-cookies-{}.json", std::process::id()));
-
-        let app_config = models::config::AppConfig {
-            sega_id: self.config.sega_id.clone(),
-            sega_password: self.config.sega_password.clone(),
-            data_dir,
-            cookie_path,
-            discord_bot_token: None,
-            discord_user_id: None,
-        };
-        MaimaiClient::new(&app_config)
-    }
+    /// Cycles starting inside this window skip maimai DX NET entirely.
+    pub(crate) maintenance_window: MaintenanceWindow,
 }
@@ -1,26 +1,111 @@
+use std::time::Duration;
+
 use eyre::Result;
+use maimai_client::SongCatalogSong;
 use sqlx::SqlitePool;
-use tracing::info;
+use tracing::{info, warn};
 
+use crate::db::{record_parse_failure, record_sync_success, reset_parse_failure_count};
 use crate::http_client::is_maintenance_error;
+use crate::song_catalog::CatalogIndex;
 use crate::state::AppState;
-use crate::tasks::utils::auth::build_client;
-use crate::tasks::utils::recent::sync_recent_if_play_count_changed;
+use crate::tasks::utils::parse_failure::is_parse_failure;
+use crate::tasks::utils::recent::{
+    RecentSyncOutcome, sync_recent_if_play_count_changed, unix_timestamp,
+};
 use crate::tasks::utils::reporting::{SyncCycleReport, log_recent_outcome};
-use crate::tasks::utils::scores::SeedScoresOutcome;
+use crate::tasks::utils::scores::{SeedScoresOutcome, load_rating_catalog, refresh_stored_ratings};
 use crate::tasks::utils::source::CollectorSource;
 
 pub type PollingCycleReport = SyncCycleReport;
 
 pub(crate) async fn run_cycle(app_state: &AppState) -> Result<PollingCycleReport> {
-    let mut client = build_client(&app_state.config)?;
-    run_cycle_with_source(&app_state.db_pool, &mut client).await
+    if app_state
+        .maintenance_window
+        .contains(time::OffsetDateTime::now_utc())
+    {
+        info!("Skipping periodic poll during the configured maintenance window");
+        return Ok(PollingCycleReport {
+            skipped_for_maintenance: true,
+            ..PollingCycleReport::default()
+        });
+    }
+
+    let catalog = load_rating_catalog(&app_state.db_pool, &app_state.song_catalog).await;
+    let mut client = app_state.maimai_client.clone();
+    let result = run_cycle_with_source(
+        &app_state.db_pool,
+        app_state.db_query_timeout,
+        &mut client,
+        &catalog,
+    )
+    .await;
+    if result
+        .as_ref()
+        .is_ok_and(|report| !report.skipped_for_maintenance)
+    {
+        refresh_stored_ratings(
+            &app_state.db_pool,
+            app_state.db_query_timeout,
+            &app_state.song_catalog,
+        )
+        .await;
+    }
+    if let Err(err) = track_parse_failures(&app_state.db_pool, &result).await {
+        warn!("Failed to update parse failure counter: {err:#}");
+    }
+    if result.as_ref().is_ok_and(cycle_synced)
+        && let Err(err) = record_sync_success(&app_state.db_pool, unix_timestamp()).await
+    {
+        warn!("Failed to record the last sync time: {err:#}");
+    }
+    result
+}
+
+/// Whether a finished cycle checked maimai DX NET without its recent sync failing.
+fn cycle_synced(report: &PollingCycleReport) -> bool {
+    !report.skipped_for_maintenance
+        && !matches!(
+            report.recent_outcome,
+            Some(
+                RecentSyncOutcome::FailedValidation(_)
+                    | RecentSyncOutcome::FailedParse(_)
+                    | RecentSyncOutcome::FailedRequest(_)
+            )
+        )
+}
+
+/// Keep `GET /api/sync/status` current: a cycle that broke inside an HTML parser extends the
+/// failure streak, a clean cycle ends it, and anything else (network, maintenance) leaves it.
+async fn track_parse_failures(
+    db_pool: &SqlitePool,
+    result: &Result<PollingCycleReport>,
+) -> Result<()> {
+    let parse_failure = match result {
+        Err(err) if is_parse_failure(err) => format!("{err:#}"),
+        Ok(report) => match &report.recent_outcome {
+            Some(RecentSyncOutcome::FailedParse(message)) => message.clone(),
+            Some(RecentSyncOutcome::FailedRequest(_))
+            | Some(RecentSyncOutcome::FailedValidation(_)) => return Ok(()),
+            Some(_) => return reset_parse_failure_count(db_pool).await,
+            None => return Ok(()),
+        },
+        Err(_) => return Ok(()),
+    };
+
+    let count = record_parse_failure(db_pool, &parse_failure, unix_timestamp()).await?;
+    warn!("Poll cycle failed while parsing ({count} in a row): {parse_failure}");
+    Ok(())
 }
 
+/// One poll, rating the written rows against `catalog`.
 pub async fn run_cycle_with_source(
     db_pool: &SqlitePool,
+    query_timeout: Duration,
     source: &mut impl CollectorSource,
+    catalog: &[SongCatalogSong],
 ) -> Result<PollingCycleReport> {
+    let catalog = CatalogIndex::new(catalog);
     if let Err(err) = source.ensure_session().await {
         if is_maintenance_error(&err) {
             info!(
@@ -48,7 +133,9 @@ pub async fn run_cycle_with_source(
         }
         Err(err) => return Err(err),
     };
-    let recent_outcome = sync_recent_if_play_count_changed(db_pool, source, &player_data).await;
+    let recent_outcome =
+        sync_recent_if_play_count_changed(db_pool, query_timeout, source, &player_data, &catalog)
+            .await;
 
     log_recent_outcome("polling", &recent_outcome);
     info!(
@@ -1,10 +1,12 @@
+use std::time::Duration;
+
 use eyre::Result;
+use maimai_client::SongCatalogSong;
 use sqlx::SqlitePool;
 use tracing::info;
 
-use crate::config::RecordCollectorConfig;
-use crate::http_client::is_maintenance_error;
-use crate::tasks::utils::auth::build_client;
+use crate::http_client::{MaimaiClient, is_maintenance_error};
+use crate::song_catalog::CatalogIndex;
 use crate::tasks::utils::recent::sync_recent_if_play_count_changed;
 use crate::tasks::utils::reporting::{SyncCycleReport, log_recent_outcome};
 use crate::tasks::utils::scores::ensure_scores_seeded;
@@ -14,18 +16,24 @@ pub type StartupSyncReport = SyncCycleReport;
 
 pub(crate) async fn startup_sync(
     db_pool: &SqlitePool,
-    config: &RecordCollectorConfig,
+    query_timeout: Duration,
+    client: &MaimaiClient,
+    catalog: &[SongCatalogSong],
 ) -> Result<StartupSyncReport> {
     info!("Starting startup sync...");
 
-    let mut client = build_client(config)?;
-    startup_sync_with_source(db_pool, &mut client).await
+    let mut client = client.clone();
+    startup_sync_with_source(db_pool, query_timeout, &mut client, catalog).await
 }
 
+/// Seed and sync scores, rating the written rows against `catalog`.
 pub async fn startup_sync_with_source(
     db_pool: &SqlitePool,
+    query_timeout: Duration,
     source: &mut impl CollectorSource,
+    catalog: &[SongCatalogSong],
 ) -> Result<StartupSyncReport> {
+    let catalog = CatalogIndex::new(catalog);
     if let Err(err) = source.ensure_session().await {
         if is_maintenance_error(&err) {
             info!(
@@ -39,7 +47,7 @@ pub async fn startup_sync_with_source(
         return Err(err);
     }
 
-    let seeded_scores = ensure_scores_seeded(db_pool, source).await?;
+    let seeded_scores = ensure_scores_seeded(db_pool, query_timeout, source, &catalog).await?;
     let player_data = match source.fetch_player_data().await {
         Ok(player_data) => player_data,
         Err(err) if is_maintenance_error(&err) => {
@@ -53,7 +61,9 @@ pub async fn startup_sync_with_source(
         }
         Err(err) => return Err(err),
     };
-    let recent_outcome = sync_recent_if_play_count_changed(db_pool, source, &player_data).await;
+    let recent_outcome =
+        sync_recent_if_play_count_changed(db_pool, query_timeout, source, &player_data, &catalog)
+            .await;
 
     log_recent_outcome("startup", &recent_outcome);
     info!(
@@ -21,20 +21,26 @@ pub(crate) fn to_app_config(config: &RecordCollectorConfig) -> AppConfig {
         cookie_path,
         discord_bot_token: None,
         discord_user_id: None,
+        user_agents: config.user_agents.clone(),
     }
 }
 
 pub(crate) fn build_client(config: &RecordCollectorConfig) -> Result<MaimaiClient> {
     let app_config = to_app_config(config);
-    MaimaiClient::new(&app_config).wrap_err("create HTTP client")
+    let client = MaimaiClient::new(&app_config).wrap_err("create HTTP client")?;
+    Ok(client
+        .with_slow_request_threshold(std::time::Duration::from_secs(
+            config.slow_request_warn_secs,
+        ))
+        .with_maintenance_window(config.maintenance_window))
 }
 
-pub(crate) async fn ensure_session(client: &mut MaimaiClient) -> Result<()> {
+pub(crate) async fn ensure_session(client: &MaimaiClient) -> Result<()> {
     client.ensure_logged_in().await.wrap_err("ensure logged in")
 }
 
 pub(crate) async fn fetch_html_with_auth_recovery(
-    client: &mut MaimaiClient,
+    client: &MaimaiClient,
     url: &Url,
     expected_page: ExpectedPage,
 ) -> Result<String> {
@@ -47,6 +53,7 @@ pub(crate) async fn fetch_html_with_auth_recovery(
         return Ok(first_html);
     }
 
+    client.mark_session_expired();
     client
         .login()
         .await
@@ -75,5 +82,6 @@ fn expected_page_label(expected_page: &ExpectedPage) -> String {
         ExpectedPage::ScoresList { diff } => format!("scores list page (diff={diff})"),
         ExpectedPage::PlaylogDetail { idx } => format!("playlogDetail page (idx={idx})"),
         ExpectedPage::MusicDetail { idx } => format!("musicDetail page (idx={idx})"),
+        ExpectedPage::RatingTargets => "ratingTargetMusic page".to_string(),
     }
 }
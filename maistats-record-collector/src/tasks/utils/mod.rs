@@ -1,4 +1,5 @@
 pub(crate) mod auth;
+pub(crate) mod parse_failure;
 pub(crate) mod player;
 pub(crate) mod playlog_detail;
 pub mod recent;
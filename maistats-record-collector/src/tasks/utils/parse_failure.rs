@@ -0,0 +1,42 @@
+use std::fmt;
+
+/// Context attached where SEGA's HTML fails to parse, e.g. `ParseFailure("recent")`. The poll
+/// finds it with `downcast_ref` to tell a layout change from network or maintenance errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ParseFailure(pub(crate) &'static str);
+
+impl fmt::Display for ParseFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "parse {} html", self.0)
+    }
+}
+
+/// Whether `err` broke inside an HTML parser, however many contexts were added on top.
+pub(crate) fn is_parse_failure(err: &eyre::Report) -> bool {
+    err.downcast_ref::<ParseFailure>().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use eyre::WrapErr;
+
+    use super::{ParseFailure, is_parse_failure};
+
+    #[test]
+    fn is_parse_failure_finds_the_marker_under_outer_contexts() {
+        let parsed: eyre::Result<()> = Err(eyre::eyre!("missing rating"));
+        let err = parsed
+            .wrap_err(ParseFailure("playerData"))
+            .wrap_err("fetch player data")
+            .unwrap_err();
+
+        assert!(is_parse_failure(&err));
+        assert_eq!(
+            format!("{err:#}"),
+            "fetch player data: parse playerData html: missing rating"
+        );
+        assert!(!is_parse_failure(&eyre::eyre!(
+            "parse recent html: looks like one, but is only a message"
+        )));
+    }
+}
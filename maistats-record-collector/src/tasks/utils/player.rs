@@ -3,6 +3,7 @@ use sqlx::SqlitePool;
 
 use crate::http_client::MaimaiClient;
 use crate::tasks::utils::auth::fetch_html_with_auth_recovery;
+use crate::tasks::utils::parse_failure::ParseFailure;
 use crate::tasks::utils::source::ExpectedPage;
 use maimai_parsers::parse_player_data_html;
 use models::ParsedPlayerProfile;
@@ -11,6 +12,10 @@ pub(crate) const STATE_KEY_USER_NAME: &str = "player.user_name";
 pub(crate) const STATE_KEY_TOTAL_PLAY_COUNT: &str = "player.total_play_count";
 pub(crate) const STATE_KEY_RATING: &str = "player.rating";
 pub(crate) const STATE_KEY_CURRENT_VERSION_PLAY_COUNT: &str = "player.current_version_play_count";
+pub(crate) const STATE_KEY_TARGET_RATING: &str = "player.target_rating";
+pub(crate) const STATE_KEY_AREA_RANK: &str = "player.area_rank";
+pub(crate) const STATE_KEY_COURSE_RANK: &str = "player.course_rank";
+pub(crate) const STATE_KEY_CLASS_RANK: &str = "player.class_rank";
 
 #[derive(Debug, Clone, Default)]
 pub(crate) struct StoredPlayerProfileState {
@@ -18,6 +23,10 @@ pub(crate) struct StoredPlayerProfileState {
     rating: Option<u32>,
     current_version_play_count: Option<u32>,
     total_play_count: Option<u32>,
+    /// Optional on the source page, so these do not count towards completeness.
+    area_rank: Option<u32>,
+    course_rank: Option<String>,
+    class_rank: Option<String>,
 }
 
 impl StoredPlayerProfileState {
@@ -43,6 +52,9 @@ impl StoredPlayerProfileState {
             rating,
             current_version_play_count,
             total_play_count,
+            area_rank,
+            course_rank,
+            class_rank,
         } = self;
 
         let (
@@ -65,6 +77,9 @@ impl StoredPlayerProfileState {
             rating,
             current_version_play_count,
             total_play_count,
+            area_rank,
+            course_rank,
+            class_rank,
         })
     }
 }
@@ -75,7 +90,7 @@ pub(crate) async fn fetch_player_data_logged_in(
     let url = reqwest::Url::parse("https://maimaidx-eng.com/maimai-mobile/playerData/")
         .wrap_err("parse playerData url")?;
     let html = fetch_html_with_auth_recovery(client, &url, ExpectedPage::PlayerData).await?;
-    parse_player_data_html(&html).wrap_err("parse playerData html")
+    parse_player_data_html(&html).wrap_err(ParseFailure("playerData"))
 }
 
 pub(crate) async fn load_stored_player_profile_state(
@@ -85,13 +100,16 @@ pub(crate) async fn load_stored_player_profile_state(
         r#"
         SELECT key, value
         FROM app_state
-        WHERE key IN (?1, ?2, ?3, ?4)
+        WHERE key IN (?1, ?2, ?3, ?4, ?5, ?6, ?7)
         "#,
     )
     .bind(STATE_KEY_USER_NAME)
     .bind(STATE_KEY_RATING)
     .bind(STATE_KEY_CURRENT_VERSION_PLAY_COUNT)
     .bind(STATE_KEY_TOTAL_PLAY_COUNT)
+    .bind(STATE_KEY_AREA_RANK)
+    .bind(STATE_KEY_COURSE_RANK)
+    .bind(STATE_KEY_CLASS_RANK)
     .fetch_all(pool)
     .await
     .wrap_err("load stored player profile state")?;
@@ -121,6 +139,15 @@ pub(crate) async fn load_stored_player_profile_state(
                         .wrap_err_with(|| format!("parse app_state key '{key}' as u32"))?,
                 );
             }
+            STATE_KEY_AREA_RANK => {
+                state.area_rank = Some(
+                    value
+                        .parse::<u32>()
+                        .wrap_err_with(|| format!("parse app_state key '{key}' as u32"))?,
+                );
+            }
+            STATE_KEY_COURSE_RANK => state.course_rank = Some(value),
+            STATE_KEY_CLASS_RANK => state.class_rank = Some(value),
             _ => {}
         }
     }
@@ -172,9 +199,13 @@ mod tests {
             rating: 12_345,
             current_version_play_count: 50,
             total_play_count: 200,
+            area_rank: Some(42),
+            course_rank: Some("真二段".to_string()),
+            class_rank: Some("S5".to_string()),
         };
 
-        store_player_profile_snapshot(&pool, &expected, 1).await?;
+        store_player_profile_snapshot(&pool, crate::db::DEFAULT_QUERY_TIMEOUT, &expected, 1)
+            .await?;
 
         let state = load_stored_player_profile_state(&pool).await?;
         assert_eq!(state.total_play_count(), Some(expected.total_play_count));
@@ -189,6 +220,9 @@ mod tests {
             expected.current_version_play_count
         );
         assert_eq!(profile.total_play_count, expected.total_play_count);
+        assert_eq!(profile.area_rank, expected.area_rank);
+        assert_eq!(profile.course_rank, expected.course_rank);
+        assert_eq!(profile.class_rank, expected.class_rank);
 
         Ok(())
     }
@@ -3,6 +3,7 @@ use reqwest::Url;
 
 use crate::http_client::MaimaiClient;
 use crate::tasks::utils::auth::fetch_html_with_auth_recovery;
+use crate::tasks::utils::parse_failure::ParseFailure;
 use crate::tasks::utils::source::ExpectedPage;
 use maimai_parsers::parse_playlog_detail_html;
 use models::ParsedPlaylogDetail;
@@ -25,7 +26,7 @@ pub(crate) async fn fetch_playlog_detail(
     )
     .await
     .wrap_err("fetch playlogDetail html")?;
-    parse_playlog_detail_html(&html).wrap_err("parse playlogDetail html")
+    parse_playlog_detail_html(&html).wrap_err(ParseFailure("playlogDetail"))
 }
 
 #[cfg(test)]
@@ -1,4 +1,5 @@
 use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 use eyre::{Result, WrapErr};
 use sqlx::SqlitePool;
@@ -6,7 +7,9 @@ use tracing::warn;
 
 use crate::db::{apply_recent_sync_atomic, store_player_profile_snapshot};
 use crate::http_client::MaimaiClient;
+use crate::song_catalog::CatalogIndex;
 use crate::tasks::utils::auth::fetch_html_with_auth_recovery;
+use crate::tasks::utils::parse_failure::{ParseFailure, is_parse_failure};
 use crate::tasks::utils::player::load_stored_player_profile_state;
 use crate::tasks::utils::scores::score_entries_from_song_detail;
 use crate::tasks::utils::song_detail::SongDetailCache;
@@ -27,6 +30,8 @@ pub enum RecentSyncOutcome {
         failed_targets: usize,
     },
     FailedValidation(String),
+    /// The recent page was fetched but did not parse, likely a SEGA layout change.
+    FailedParse(String),
     FailedRequest(String),
 }
 
@@ -36,13 +41,15 @@ pub(crate) async fn fetch_recent_entries_logged_in(
     let url = reqwest::Url::parse("https://maimaidx-eng.com/maimai-mobile/record/")
         .wrap_err("parse record url")?;
     let html = fetch_html_with_auth_recovery(client, &url, ExpectedPage::Recent).await?;
-    parse_recent_html(&html).wrap_err("parse recent html")
+    parse_recent_html(&html).wrap_err(ParseFailure("recent"))
 }
 
 pub(crate) async fn sync_recent_if_play_count_changed(
     pool: &SqlitePool,
+    query_timeout: Duration,
     source: &mut impl CollectorSource,
     player_data: &ParsedPlayerProfile,
+    catalog: &CatalogIndex<'_>,
 ) -> RecentSyncOutcome {
     let stored_player_state = match load_stored_player_profile_state(pool).await {
         Ok(value) => value,
@@ -55,7 +62,8 @@ pub(crate) async fn sync_recent_if_play_count_changed(
     {
         if stored_player_state.has_incomplete_fields() {
             // Upgrade/backfill path for older DBs that predate newer player snapshot fields.
-            return backfill_incomplete_player_snapshot_or_fail(pool, player_data).await;
+            return backfill_incomplete_player_snapshot_or_fail(pool, query_timeout, player_data)
+                .await;
         }
 
         return RecentSyncOutcome::SkippedUnchanged;
@@ -63,6 +71,9 @@ pub(crate) async fn sync_recent_if_play_count_changed(
 
     let entries = match source.fetch_recent_entries().await {
         Ok(entries) => entries,
+        Err(err) if is_parse_failure(&err) => {
+            return RecentSyncOutcome::FailedParse(format!("{err:#}"));
+        }
         Err(err) => return RecentSyncOutcome::FailedRequest(format!("{err:#}")),
     };
     let entries =
@@ -78,7 +89,7 @@ pub(crate) async fn sync_recent_if_play_count_changed(
 
     if new_entries.is_empty() {
         let now = unix_timestamp();
-        return match store_player_profile_snapshot(pool, player_data, now).await {
+        return match store_player_profile_snapshot(pool, query_timeout, player_data, now).await {
             Ok(()) => RecentSyncOutcome::SkippedUnchanged,
             Err(err) => RecentSyncOutcome::FailedRequest(format!("{err:#}")),
         };
@@ -90,7 +101,9 @@ pub(crate) async fn sync_recent_if_play_count_changed(
     let now = unix_timestamp();
     if let Err(err) = apply_recent_sync_atomic(
         pool,
+        query_timeout,
         &resolved.score_updates,
+        catalog,
         &resolved.entries,
         player_data,
         now,
@@ -218,6 +231,7 @@ async fn resolve_recent_entries_and_collect_score_updates(
         let mut resolved = entry.clone();
         resolved.genre = detail.genre.clone();
         resolved.artist = Some(detail.artist.clone());
+        resolved.is_buddy_play |= playlog_detail.is_buddy_play;
 
         songs_to_refresh
             .entry((
@@ -259,7 +273,7 @@ fn titles_mismatch_when_present(left: &str, right: &str) -> bool {
     !left.is_empty() && !right.is_empty() && left != right
 }
 
-fn unix_timestamp() -> i64 {
+pub(crate) fn unix_timestamp() -> i64 {
     use std::time::{SystemTime, UNIX_EPOCH};
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -277,10 +291,11 @@ fn count_distinct_credit_ids(entries: &[ParsedPlayRecord]) -> usize {
 
 async fn backfill_incomplete_player_snapshot_or_fail(
     pool: &SqlitePool,
+    query_timeout: Duration,
     player_data: &ParsedPlayerProfile,
 ) -> RecentSyncOutcome {
     let now = unix_timestamp();
-    match store_player_profile_snapshot(pool, player_data, now).await {
+    match store_player_profile_snapshot(pool, query_timeout, player_data, now).await {
         Ok(()) => RecentSyncOutcome::SkippedUnchanged,
         Err(err) => RecentSyncOutcome::FailedRequest(format!("{err:#}")),
     }
@@ -361,6 +376,7 @@ mod tests {
             sync: None,
             dx_score: None,
             dx_score_max: None,
+            is_buddy_play: false,
         }];
 
         assert!(annotate_recent_entries_with_credit_id(entries, 100).is_err());
@@ -373,6 +389,7 @@ mod tests {
         crate::db::migrate(&pool).await?;
         crate::db::upsert_scores(
             &pool,
+            crate::db::DEFAULT_QUERY_TIMEOUT,
             &[models::ParsedScoreEntry {
                 title: "Song A".to_string(),
                 genre: "Genre A".to_string(),
@@ -390,6 +407,7 @@ mod tests {
                 play_count: Some(10),
                 source_idx: None,
             }],
+            &CatalogIndex::default(),
         )
         .await?;
 
@@ -412,6 +430,7 @@ mod tests {
             sync: None,
             dx_score: None,
             dx_score_max: None,
+            is_buddy_play: false,
         };
         let detail = ParsedSongDetail {
             title: "Song A".to_string(),
@@ -423,6 +442,7 @@ mod tests {
                 level: "12+".to_string(),
                 chart_type: ChartType::Dx,
                 achievement_percent: Some(100.2),
+                played: true,
                 rank: Some("SSS".parse().unwrap()),
                 fc: Some("AP".parse().unwrap()),
                 sync: Some("FDX+".parse().unwrap()),
@@ -460,7 +480,9 @@ mod tests {
         crate::db::migrate(&pool).await?;
         crate::db::apply_recent_sync_atomic(
             &pool,
+            crate::db::DEFAULT_QUERY_TIMEOUT,
             &[],
+            &CatalogIndex::default(),
             &[ParsedPlayRecord {
                 played_at_unixtime: Some(100),
                 playlog_detail_idx: Some("old-song::100".to_string()),
@@ -480,12 +502,16 @@ mod tests {
                 sync: None,
                 dx_score: Some(900),
                 dx_score_max: Some(1000),
+                is_buddy_play: false,
             }],
             &ParsedPlayerProfile {
                 user_name: "fixture-user".to_string(),
                 rating: 10_000,
                 current_version_play_count: 10,
                 total_play_count: 10,
+                area_rank: None,
+                course_rank: None,
+                class_rank: None,
             },
             1,
         )
@@ -510,6 +536,7 @@ mod tests {
             sync: None,
             dx_score: Some(900),
             dx_score_max: Some(1000),
+            is_buddy_play: false,
         };
         let new_entry = ParsedPlayRecord {
             played_at_unixtime: Some(200),
@@ -530,6 +557,7 @@ mod tests {
             sync: Some("FS".parse().unwrap()),
             dx_score: Some(950),
             dx_score_max: Some(1000),
+            is_buddy_play: false,
         };
 
         let filtered =
@@ -555,6 +583,7 @@ mod tests {
                         level: "4".to_string(),
                         chart_type: ChartType::Std,
                         achievement_percent: Some(95.0),
+                        played: true,
                         rank: Some("AAA".parse().unwrap()),
                         fc: Some("FC".parse().unwrap()),
                         sync: Some("FS".parse().unwrap()),
@@ -596,6 +625,7 @@ mod tests {
             sync: Some("FDX+".parse().unwrap()),
             dx_score: Some(1999),
             dx_score_max: Some(2000),
+            is_buddy_play: false,
         };
         let mut source = FixtureCollectorSource::from_data(FixtureCollectorData {
             player_data: None,
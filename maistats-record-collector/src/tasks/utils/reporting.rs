@@ -41,6 +41,9 @@ pub(crate) fn log_recent_outcome(scope: &str, outcome: &RecentSyncOutcome) {
         RecentSyncOutcome::FailedValidation(message) => {
             warn!("{scope} recent sync validation failed: {message}");
         }
+        RecentSyncOutcome::FailedParse(message) => {
+            warn!("{scope} recent sync parse failed: {message}");
+        }
         RecentSyncOutcome::FailedRequest(message) => {
             warn!("{scope} recent sync request failed: {message}");
         }
@@ -1,16 +1,24 @@
 use std::collections::HashSet;
+use std::time::Duration;
 
 use eyre::{Result, WrapErr};
 use reqwest::Url;
 use sqlx::SqlitePool;
 use tracing::{info, warn};
 
-use crate::db::{count_scores_rows, replace_scores, upsert_scores};
+use crate::db::{
+    clear_rating_fingerprint, count_scores_rows, get_rating_fingerprint, is_score_seed_in_progress,
+    refresh_score_ratings, set_score_seed_in_progress, upsert_scores,
+};
 use crate::http_client::MaimaiClient;
+use crate::song_catalog::{CatalogIndex, SongCatalogSource, catalog_rating_fingerprint};
 use crate::tasks::utils::auth::fetch_html_with_auth_recovery;
+use crate::tasks::utils::parse_failure::ParseFailure;
+use crate::tasks::utils::recent::unix_timestamp;
 use crate::tasks::utils::song_detail::SongDetailCache;
 use crate::tasks::utils::source::CollectorSource;
 use crate::tasks::utils::source::ExpectedPage;
+use maimai_client::SongCatalogSong;
 use maimai_parsers::parse_scores_html;
 use models::{ChartType, ParsedScoreEntry, ParsedSongDetail};
 
@@ -56,54 +64,62 @@ struct SeedSongIndexEntry {
 
 pub(crate) async fn ensure_scores_seeded(
     pool: &SqlitePool,
+    query_timeout: Duration,
     source: &mut impl CollectorSource,
+    catalog: &CatalogIndex<'_>,
 ) -> Result<SeedScoresOutcome> {
-    let existing_rows = count_scores_rows(pool)
+    let existing_rows = count_scores_rows(pool, query_timeout)
         .await
         .wrap_err("count scores rows")?;
-    if existing_rows > 0 {
+    let resuming = is_score_seed_in_progress(pool).await?;
+    if existing_rows > 0 && !resuming {
         return Ok(SeedScoresOutcome::default());
     }
+    if resuming {
+        info!("resuming interrupted score seeding: rows_present={existing_rows}");
+    }
 
     let seed_targets = fetch_seed_song_index_entries(source)
         .await
         .wrap_err("fetch diff=0 song index")?;
     let mut detail_cache = SongDetailCache::default();
-    let mut entries = Vec::new();
+    let mut rows_written = 0;
     let total_songs = seed_targets.len();
     let started_at = std::time::Instant::now();
 
     info!("startup score seeding started: songs={total_songs}");
+    set_score_seed_in_progress(pool, true, unix_timestamp()).await?;
 
+    // Each song is written as soon as it is fetched, so a failure part-way keeps the songs
+    // before it; the in-progress marker makes the next startup run the seed again.
     for (idx, target) in seed_targets.iter().enumerate() {
         let detail = fetch_seed_song_detail(source, target, &mut detail_cache)
             .await
             .wrap_err_with(|| format!("fetch seed song detail for '{}'", target.title))?;
-        entries.extend(score_entries_from_song_detail(detail));
+        let rows = score_entries_from_song_detail(detail);
+        upsert_scores(pool, query_timeout, &rows, catalog)
+            .await
+            .wrap_err_with(|| format!("store seeded scores for '{}'", target.title))?;
+        rows_written += rows.len();
 
         let processed = idx + 1;
         if should_log_seed_progress(processed, total_songs) {
             let percent = (processed as f64 / total_songs as f64) * 100.0;
             info!(
-                "startup score seeding progress: songs={processed}/{total_songs} ({percent:.1}%) rows_collected={}",
-                entries.len()
+                "startup score seeding progress: songs={processed}/{total_songs} ({percent:.1}%) rows_written={rows_written}"
             );
         }
     }
 
-    replace_scores(pool, &entries)
-        .await
-        .wrap_err("replace seeded scores rows")?;
-
+    set_score_seed_in_progress(pool, false, unix_timestamp()).await?;
     info!(
-        "startup score seeding completed: songs={total_songs} rows_written={} elapsed_sec={:.1}",
-        entries.len(),
+        "startup score seeding completed: songs={total_songs} rows_written={rows_written} elapsed_sec={:.1}",
         started_at.elapsed().as_secs_f64()
     );
 
     Ok(SeedScoresOutcome {
         seeded: true,
-        rows_written: entries.len(),
+        rows_written,
     })
 }
 
@@ -150,7 +166,7 @@ pub(crate) async fn fetch_score_entries_logged_in(
     let html = fetch_html_with_auth_recovery(client, &url, ExpectedPage::ScoresList { diff })
         .await
         .wrap_err("fetch scores html with auth recovery")?;
-    parse_scores_html(&html, diff).wrap_err("parse scores html")
+    parse_scores_html(&html, diff).wrap_err(ParseFailure("scores"))
 }
 
 async fn fetch_score_list_snapshot(
@@ -287,6 +303,9 @@ fn seed_target_matches(expected: &SeedSongIndexEntry, actual: &SeedSongIndexEntr
         && expected.basic_level == actual.basic_level
 }
 
+/// Score rows for the played difficulties of `detail`. A difficulty whose score block shows no
+/// readable achievement is skipped with a warning rather than written, so a markup change
+/// cannot blank the stored score.
 pub(crate) fn score_entries_from_song_detail(
     detail: models::ParsedSongDetail,
 ) -> Vec<ParsedScoreEntry> {
@@ -297,6 +316,15 @@ pub(crate) fn score_entries_from_song_detail(
     detail
         .difficulties
         .into_iter()
+        .filter(|difficulty| {
+            if difficulty.played && difficulty.achievement_percent.is_none() {
+                warn!(
+                    "musicDetail for '{title}' shows a score block without an achievement: chart={} diff={}",
+                    difficulty.chart_type, difficulty.diff_category
+                );
+            }
+            difficulty.played && difficulty.achievement_percent.is_some()
+        })
         .map(|difficulty| ParsedScoreEntry {
             title: title.clone(),
             genre: genre.clone(),
@@ -317,14 +345,64 @@ pub(crate) fn score_entries_from_song_detail(
         .collect()
 }
 
+/// Backfill the stored rating columns when the song catalog changed since the last backfill;
+/// score writes rate their own rows, so an unchanged catalog costs one hash and one
+/// `app_state` read, across restarts too. Failures are logged: the columns are derived data,
+/// and the next sync retries.
+pub(crate) async fn refresh_stored_ratings(
+    pool: &SqlitePool,
+    query_timeout: Duration,
+    song_catalog: &SongCatalogSource,
+) {
+    let catalog = match song_catalog.list_song_catalog().await {
+        Ok(catalog) => catalog,
+        Err(err) => {
+            warn!("Skipping score rating refresh; song database unavailable: {err:#}");
+            return;
+        }
+    };
+    match get_rating_fingerprint(pool).await {
+        Ok(stored) if stored == Some(catalog_rating_fingerprint(&catalog)) => return,
+        Ok(_) => {}
+        Err(err) => warn!("Failed to load rating fingerprint; refreshing anyway: {err:#}"),
+    }
+    match refresh_score_ratings(pool, query_timeout, &catalog).await {
+        Ok(changed) if changed > 0 => {
+            info!("Refreshed stored rating points for {changed} score rows");
+        }
+        Ok(_) => {}
+        Err(err) => warn!("Failed to refresh stored rating points: {err:#}"),
+    }
+}
+
+/// The catalog that score writes rate their rows against. Without a song database or a
+/// snapshot the rows are stored unrated, and the next [`refresh_stored_ratings`] backfills.
+pub(crate) async fn load_rating_catalog(
+    pool: &SqlitePool,
+    song_catalog: &SongCatalogSource,
+) -> Vec<SongCatalogSong> {
+    match song_catalog.list_song_catalog().await {
+        Ok(catalog) => catalog,
+        Err(err) => {
+            warn!("Song database unavailable; storing scores without rating points: {err:#}");
+            if let Err(err) = clear_rating_fingerprint(pool).await {
+                warn!("Failed to clear rating fingerprint: {err:#}");
+            }
+            Vec::new()
+        }
+    }
+}
+
 pub(crate) fn canonical_title_for_detail(detail: &models::ParsedSongDetail) -> String {
     detail.title.trim().to_string()
 }
 
 pub(crate) async fn refresh_song_scores(
     pool: &SqlitePool,
+    query_timeout: Duration,
     source: &mut impl CollectorSource,
     target: &RefreshSongScoresTarget,
+    catalog: &CatalogIndex<'_>,
 ) -> Result<RefreshSongScoresOutcome> {
     let details = fetch_matching_song_details(source, target)
         .await
@@ -344,7 +422,7 @@ pub(crate) async fn refresh_song_scores(
         .flat_map(score_entries_from_song_detail)
         .collect::<Vec<_>>();
 
-    upsert_scores(pool, &rows)
+    upsert_scores(pool, query_timeout, &rows, catalog)
         .await
         .wrap_err("upsert manually refreshed song scores")?;
 
@@ -441,6 +519,7 @@ mod tests {
                 level: "12+".to_string(),
                 chart_type: ChartType::Dx,
                 achievement_percent: Some(100.5),
+                played: true,
                 rank: None,
                 fc: None,
                 sync: None,
@@ -458,6 +537,40 @@ mod tests {
         assert_eq!(entries[0].artist, "Artist A");
     }
 
+    #[test]
+    fn score_entries_from_song_detail_skips_unplayed_difficulties() {
+        let difficulty = |diff_category, played, achievement_percent| ParsedSongChartDetail {
+            diff_category,
+            level: "12".to_string(),
+            chart_type: ChartType::Dx,
+            achievement_percent,
+            played,
+            rank: None,
+            fc: None,
+            sync: None,
+            dx_score: None,
+            dx_score_max: None,
+            last_played_at: None,
+            play_count: None,
+        };
+        let detail = ParsedSongDetail {
+            title: "Song A".to_string(),
+            genre: Some("Genre A".to_string()),
+            artist: "Artist A".to_string(),
+            chart_type: ChartType::Dx,
+            difficulties: vec![
+                difficulty(DifficultyCategory::Expert, true, Some(99.5)),
+                difficulty(DifficultyCategory::Master, false, None),
+                // Played, but the achievement did not parse: keep whatever is stored.
+                difficulty(DifficultyCategory::ReMaster, true, None),
+            ],
+        };
+
+        let entries = score_entries_from_song_detail(detail);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].diff_category, DifficultyCategory::Expert);
+    }
+
     #[test]
     fn seed_target_match_requires_same_position_key() {
         let expected = SeedSongIndexEntry {
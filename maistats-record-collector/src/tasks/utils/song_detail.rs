@@ -5,6 +5,7 @@ use reqwest::Url;
 
 use crate::http_client::MaimaiClient;
 use crate::tasks::utils::auth::fetch_html_with_auth_recovery;
+use crate::tasks::utils::parse_failure::ParseFailure;
 use crate::tasks::utils::source::ExpectedPage;
 use maimai_parsers::parse_song_detail_html;
 use models::ParsedSongDetail;
@@ -42,5 +43,5 @@ pub(crate) async fn fetch_song_detail_by_idx(
     )
     .await
     .wrap_err("fetch musicDetail html")?;
-    parse_song_detail_html(&html).wrap_err("parse musicDetail html")
+    parse_song_detail_html(&html).wrap_err(ParseFailure("musicDetail"))
 }
@@ -70,26 +70,28 @@ async fn test_db() -> eyre::Result<db::SqlitePool> {
 }
 
 async fn snapshot_scores(pool: &db::SqlitePool) -> eyre::Result<Vec<ScoreSnapshot>> {
-    sqlx::query_as::<_, ScoreSnapshot>(
+    let mut rows = sqlx::query_as::<_, ScoreSnapshot>(
         r#"
         SELECT title, genre, artist, chart_type, diff_category,
                achievement_x10000, rank, fc, sync,
                dx_score, dx_score_max, last_played_at, play_count
         FROM scores
-        ORDER BY title, genre, artist, chart_type,
-                 CASE diff_category
-                   WHEN 'BASIC' THEN 0
-                   WHEN 'ADVANCED' THEN 1
-                   WHEN 'EXPERT' THEN 2
-                   WHEN 'MASTER' THEN 3
-                   WHEN 'Re:MASTER' THEN 4
-                   ELSE 99
-                 END
         "#,
     )
     .fetch_all(pool)
-    .await
-    .map_err(Into::into)
+    .await?;
+    rows.sort_by_cached_key(|row| {
+        (
+            row.title.clone(),
+            row.genre.clone(),
+            row.artist.clone(),
+            row.chart_type.clone(),
+            row.diff_category
+                .parse::<DifficultyCategory>()
+                .map_or(u8::MAX, DifficultyCategory::as_u8),
+        )
+    });
+    Ok(rows)
 }
 
 async fn snapshot_playlogs(pool: &db::SqlitePool) -> eyre::Result<Vec<PlaylogSnapshot>> {
@@ -149,6 +151,9 @@ fn build_full_recent_50_source() -> FixtureCollectorSource {
         rating: 14_000,
         current_version_play_count: 120,
         total_play_count: 350,
+        area_rank: None,
+        course_rank: None,
+        class_rank: None,
     };
 
     let mut recent_entries = Vec::new();
@@ -260,6 +265,7 @@ fn build_full_recent_50_source() -> FixtureCollectorSource {
                 sync: sync.and_then(|value| value.parse().ok()),
                 dx_score: Some(dx_score),
                 dx_score_max: Some(dx_score_max),
+                is_buddy_play: false,
             });
             played_at_unixtime -= 1;
         }
@@ -296,6 +302,7 @@ fn build_full_recent_50_source() -> FixtureCollectorSource {
             sync: None,
             dx_score: Some(if title == "Song Golf" { 970 } else { 790 }),
             dx_score_max: Some(if title == "Song Golf" { 1200 } else { 1000 }),
+            is_buddy_play: false,
         });
         played_at_unixtime -= 1;
     }
@@ -314,6 +321,7 @@ fn build_full_recent_50_source() -> FixtureCollectorSource {
                         level: "5".to_string(),
                         chart_type: ChartType::Dx,
                         achievement_percent: Some(95.0),
+                        played: true,
                         rank: Some("AAA".parse().unwrap()),
                         fc: Some("FC".parse().unwrap()),
                         sync: Some("FS".parse().unwrap()),
@@ -327,6 +335,7 @@ fn build_full_recent_50_source() -> FixtureCollectorSource {
                         level: "13".to_string(),
                         chart_type: ChartType::Dx,
                         achievement_percent: Some(99.7),
+                        played: true,
                         rank: Some("SSS".parse().unwrap()),
                         fc: Some("AP".parse().unwrap()),
                         sync: Some("FDX+".parse().unwrap()),
@@ -350,6 +359,7 @@ fn build_full_recent_50_source() -> FixtureCollectorSource {
                     level: "8".to_string(),
                     chart_type: ChartType::Std,
                     achievement_percent: Some(97.0),
+                    played: true,
                     rank: Some("S".parse().unwrap()),
                     fc: Some("FC".parse().unwrap()),
                     sync: Some("FS".parse().unwrap()),
@@ -372,6 +382,7 @@ fn build_full_recent_50_source() -> FixtureCollectorSource {
                     level: "4".to_string(),
                     chart_type: ChartType::Std,
                     achievement_percent: Some(88.8),
+                    played: true,
                     rank: Some("A".parse().unwrap()),
                     fc: None,
                     sync: None,
@@ -398,11 +409,12 @@ async fn seed_small_startup_matches_expected_snapshots() -> eyre::Result<()> {
     let pool = test_db().await?;
     let mut source = load_fixture_source("seed_small_startup");
 
-    let report = startup_sync_with_source(&pool, &mut source).await?;
+    let report =
+        startup_sync_with_source(&pool, db::DEFAULT_QUERY_TIMEOUT, &mut source, &[]).await?;
 
     assert!(!report.skipped_for_maintenance);
     assert!(report.seeded);
-    assert_eq!(report.seeded_rows_written, 9);
+    assert_eq!(report.seeded_rows_written, 8);
     assert_recent_outcome(&report.recent_outcome, 1, 3, 6, 0);
 
     let expected_scores: Vec<ScoreSnapshot> =
@@ -419,14 +431,61 @@ async fn seed_small_startup_matches_expected_snapshots() -> eyre::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn interrupted_seed_keeps_written_songs_and_resumes_next_startup() -> eyre::Result<()> {
+    let pool = test_db().await?;
+    let data: FixtureCollectorData =
+        read_json(&fixture_dir("seed_small_startup").join("source.json"));
+    let mut interrupted = data.clone();
+    interrupted.song_details.remove("song-charlie");
+    let mut source = FixtureCollectorSource::from_data(interrupted);
+
+    assert!(
+        startup_sync_with_source(&pool, db::DEFAULT_QUERY_TIMEOUT, &mut source, &[])
+            .await
+            .is_err()
+    );
+
+    let partial_scores = snapshot_scores(&pool).await?;
+    assert!(!partial_scores.is_empty());
+    assert!(
+        partial_scores
+            .iter()
+            .all(|score| score.title != "Song Charlie")
+    );
+    assert_eq!(
+        snapshot_app_state(&pool)
+            .await?
+            .get("scores.seed_in_progress")
+            .map(String::as_str),
+        Some("1")
+    );
+
+    let mut source = FixtureCollectorSource::from_data(data);
+    let report =
+        startup_sync_with_source(&pool, db::DEFAULT_QUERY_TIMEOUT, &mut source, &[]).await?;
+
+    assert!(report.seeded);
+    assert_eq!(report.seeded_rows_written, 8);
+    let expected_scores: Vec<ScoreSnapshot> =
+        read_json(&fixture_dir("seed_small_startup").join("expected_scores.json"));
+    let expected_app_state: BTreeMap<String, String> =
+        read_json(&fixture_dir("seed_small_startup").join("expected_app_state.json"));
+    assert_eq!(snapshot_scores(&pool).await?, expected_scores);
+    assert_eq!(snapshot_app_state(&pool).await?, expected_app_state);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn polling_update_small_updates_scores_and_playlogs() -> eyre::Result<()> {
     let pool = test_db().await?;
     let mut seed_source = load_fixture_source("seed_small_startup");
-    startup_sync_with_source(&pool, &mut seed_source).await?;
+    startup_sync_with_source(&pool, db::DEFAULT_QUERY_TIMEOUT, &mut seed_source, &[]).await?;
 
     let mut update_source = load_fixture_source("polling_update_small");
-    let report = run_cycle_with_source(&pool, &mut update_source).await?;
+    let report =
+        run_cycle_with_source(&pool, db::DEFAULT_QUERY_TIMEOUT, &mut update_source, &[]).await?;
 
     assert!(!report.skipped_for_maintenance);
     assert!(!report.seeded);
@@ -450,10 +509,11 @@ async fn polling_update_small_updates_scores_and_playlogs() -> eyre::Result<()>
 async fn polling_partial_resolve_keeps_playlogs() -> eyre::Result<()> {
     let pool = test_db().await?;
     let mut seed_source = load_fixture_source("seed_small_startup");
-    startup_sync_with_source(&pool, &mut seed_source).await?;
+    startup_sync_with_source(&pool, db::DEFAULT_QUERY_TIMEOUT, &mut seed_source, &[]).await?;
 
     let mut update_source = load_fixture_source("polling_partial_resolve_keeps_playlogs");
-    let report = run_cycle_with_source(&pool, &mut update_source).await?;
+    let report =
+        run_cycle_with_source(&pool, db::DEFAULT_QUERY_TIMEOUT, &mut update_source, &[]).await?;
 
     assert!(!report.skipped_for_maintenance);
     assert!(!report.seeded);
@@ -480,10 +540,11 @@ async fn polling_partial_resolve_keeps_playlogs() -> eyre::Result<()> {
 async fn polling_unresolved_only_keeps_playlogs() -> eyre::Result<()> {
     let pool = test_db().await?;
     let mut seed_source = load_fixture_source("seed_small_startup");
-    startup_sync_with_source(&pool, &mut seed_source).await?;
+    startup_sync_with_source(&pool, db::DEFAULT_QUERY_TIMEOUT, &mut seed_source, &[]).await?;
 
     let mut update_source = load_fixture_source("polling_unresolved_only_keeps_playlogs");
-    let report = run_cycle_with_source(&pool, &mut update_source).await?;
+    let report =
+        run_cycle_with_source(&pool, db::DEFAULT_QUERY_TIMEOUT, &mut update_source, &[]).await?;
 
     assert!(!report.skipped_for_maintenance);
     assert!(!report.seeded);
@@ -510,13 +571,14 @@ async fn polling_unresolved_only_keeps_playlogs() -> eyre::Result<()> {
 async fn polling_unchanged_skips_recent_fetches() -> eyre::Result<()> {
     let pool = test_db().await?;
     let mut seed_source = load_fixture_source("seed_small_startup");
-    startup_sync_with_source(&pool, &mut seed_source).await?;
+    startup_sync_with_source(&pool, db::DEFAULT_QUERY_TIMEOUT, &mut seed_source, &[]).await?;
 
     let initial_playlog_count = snapshot_playlogs(&pool).await?.len();
     let initial_score_count = snapshot_scores(&pool).await?.len();
 
     let mut unchanged_source = load_fixture_source("polling_unchanged_skips_recent");
-    let report = run_cycle_with_source(&pool, &mut unchanged_source).await?;
+    let report =
+        run_cycle_with_source(&pool, db::DEFAULT_QUERY_TIMEOUT, &mut unchanged_source, &[]).await?;
 
     assert!(!report.skipped_for_maintenance);
     assert!(matches!(
@@ -534,7 +596,7 @@ async fn polling_unchanged_skips_recent_fetches() -> eyre::Result<()> {
 async fn polling_unchanged_backfills_incomplete_player_snapshot_only() -> eyre::Result<()> {
     let pool = test_db().await?;
     let mut seed_source = load_fixture_source("seed_small_startup");
-    startup_sync_with_source(&pool, &mut seed_source).await?;
+    startup_sync_with_source(&pool, db::DEFAULT_QUERY_TIMEOUT, &mut seed_source, &[]).await?;
 
     sqlx::query("DELETE FROM app_state WHERE key = ?")
         .bind("player.current_version_play_count")
@@ -545,7 +607,8 @@ async fn polling_unchanged_backfills_incomplete_player_snapshot_only() -> eyre::
     let initial_score_count = snapshot_scores(&pool).await?.len();
 
     let mut unchanged_source = load_fixture_source("polling_unchanged_skips_recent");
-    let report = run_cycle_with_source(&pool, &mut unchanged_source).await?;
+    let report =
+        run_cycle_with_source(&pool, db::DEFAULT_QUERY_TIMEOUT, &mut unchanged_source, &[]).await?;
 
     assert!(!report.skipped_for_maintenance);
     assert!(matches!(
@@ -572,7 +635,7 @@ async fn polling_full_recent_truncates_oldest_partial_credit() -> eyre::Result<(
     let pool = test_db().await?;
     let mut source = build_full_recent_50_source();
 
-    let report = run_cycle_with_source(&pool, &mut source).await?;
+    let report = run_cycle_with_source(&pool, db::DEFAULT_QUERY_TIMEOUT, &mut source, &[]).await?;
 
     assert!(!report.skipped_for_maintenance);
     assert_recent_outcome(&report.recent_outcome, 12, 48, 4, 0);
@@ -613,7 +676,8 @@ async fn polling_full_recent_truncates_oldest_partial_credit() -> eyre::Result<(
     }));
 
     let mut rerun_source = build_full_recent_50_source();
-    let rerun = run_cycle_with_source(&pool, &mut rerun_source).await?;
+    let rerun =
+        run_cycle_with_source(&pool, db::DEFAULT_QUERY_TIMEOUT, &mut rerun_source, &[]).await?;
     assert!(matches!(
         rerun.recent_outcome,
         Some(RecentSyncOutcome::SkippedUnchanged)
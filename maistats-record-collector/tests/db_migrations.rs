@@ -22,6 +22,7 @@ async fn migrations_create_rebuilt_scores_schema() -> eyre::Result<()> {
     assert!(columns.contains(&"dx_score_max".to_string()));
     assert!(columns.contains(&"last_played_at".to_string()));
     assert!(columns.contains(&"play_count".to_string()));
+    assert!(columns.contains(&"level".to_string()));
     assert!(!columns.contains(&"source_idx".to_string()));
 
     Ok(())
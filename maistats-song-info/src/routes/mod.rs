@@ -16,6 +16,7 @@ pub(crate) fn create_router(state: AppState) -> Router {
     let api_routes = Router::new()
         .route("/api/songs", get(songs::list_song_info))
         .route("/api/songs/versions", get(songs::list_versions))
+        .route("/api/songs/recent", get(songs::list_recent_songs))
         .route("/api/songs/metadata", post(songs::search_song_metadata))
         .route("/api/cover/{image_name}", get(cover::get_cover))
         .layer(
@@ -1,4 +1,7 @@
-use axum::{Json, extract::State};
+use axum::{
+    Json,
+    extract::{Query, State},
+};
 use models::{
     ChartType, DifficultyCategory, MaimaiVersion, SongAliases, SongChartRegion, SongGenre,
 };
@@ -41,6 +44,7 @@ pub(crate) struct SongInfoResponse {
     genre: String,
     artist: String,
     image_name: Option<String>,
+    release_date: Option<String>,
     aliases: SongAliases,
     sheets: Vec<SongSheetResponse>,
 }
@@ -78,6 +82,15 @@ pub(crate) struct SongMetadataSearchRequest {
     limits: Option<usize>,
 }
 
+#[derive(Debug, Deserialize)]
+pub(crate) struct RecentSongsQuery {
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+const DEFAULT_RECENT_SONGS_LIMIT: usize = 20;
+const MAX_RECENT_SONGS_LIMIT: usize = 200;
+
 #[derive(Serialize)]
 pub(crate) struct SongMetadataSearchResponse {
     total: usize,
@@ -183,6 +196,7 @@ fn build_song_info_response(song: &models::SongCatalogSong) -> Result<SongInfoRe
         genre: song.genre.to_string(),
         artist: song.artist.clone(),
         image_name: song.image_name.clone(),
+        release_date: song.release_date.clone(),
         aliases: song.aliases.clone(),
         sheets,
     })
@@ -374,6 +388,47 @@ pub(crate) async fn list_song_info(
     Ok(Json(SongCatalogResponse { songs }))
 }
 
+/// Newest releases first, for a "what's new" list.
+pub(crate) async fn list_recent_songs(
+    State(state): State<AppState>,
+    Query(params): Query<RecentSongsQuery>,
+) -> Result<Json<SongCatalogResponse>> {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_RECENT_SONGS_LIMIT)
+        .clamp(1, MAX_RECENT_SONGS_LIMIT);
+    let song_data_root = state
+        .song_data_root
+        .read()
+        .map_err(|_| AppError::IoError("Failed to read song data".to_string()))?;
+
+    let songs = songs_by_release_date(song_data_root.as_slice())
+        .into_iter()
+        .take(limit)
+        .map(build_song_info_response)
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(Json(SongCatalogResponse { songs }))
+}
+
+/// Newest first; songs without a parseable `release_date` go last in catalog order.
+fn songs_by_release_date(songs: &[models::SongCatalogSong]) -> Vec<&models::SongCatalogSong> {
+    let mut sorted = songs.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|song| {
+        std::cmp::Reverse(song.release_date.as_deref().and_then(parse_release_date))
+    });
+    sorted
+}
+
+/// `YYYY-MM-DD` as a sortable `(year, month, day)`.
+fn parse_release_date(value: &str) -> Option<(u16, u8, u8)> {
+    let mut parts = value.trim().splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts.next()?.parse().ok()?;
+    let day = parts.next()?.parse().ok()?;
+    Some((year, month, day))
+}
+
 fn parse_sheet_chart_type(sheet_type: &str) -> Option<ChartType> {
     sheet_type.parse::<ChartType>().ok()
 }
@@ -418,7 +473,7 @@ mod tests {
     use super::{
         SongMetadataSearchRequest, SongVersionResponse, build_song_info_response,
         build_song_version_responses, is_intl_sheet, parse_intl_sheet_version,
-        search_song_metadata_items,
+        search_song_metadata_items, songs_by_release_date,
     };
     use models::{
         DifficultyCategory, MaimaiVersion, SongAliases, SongCatalogChart, SongCatalogSong,
@@ -494,6 +549,7 @@ mod tests {
                 genre: SongGenre::Maimai,
                 artist: "".to_string(),
                 image_name: None,
+                release_date: None,
                 aliases: SongAliases::default(),
                 sheets: vec![SongCatalogChart {
                     chart_type: "std".to_string(),
@@ -512,6 +568,7 @@ mod tests {
                 genre: SongGenre::Maimai,
                 artist: "".to_string(),
                 image_name: None,
+                release_date: None,
                 aliases: SongAliases::default(),
                 sheets: vec![SongCatalogChart {
                     chart_type: "std".to_string(),
@@ -537,6 +594,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn songs_by_release_date_puts_newest_first_and_undated_last() {
+        let song = |title: &str, release_date: Option<&str>| SongCatalogSong {
+            title: title.to_string(),
+            genre: SongGenre::Maimai,
+            artist: "Artist".to_string(),
+            image_name: None,
+            release_date: release_date.map(str::to_string),
+            aliases: SongAliases::default(),
+            sheets: Vec::new(),
+        };
+        let songs = vec![
+            song("Undated", None),
+            song("Old", Some("2019-07-11")),
+            song("Newest", Some("2026-10-09")),
+            song("Garbled", Some("soon")),
+            song("Newer", Some("2025-12-31")),
+        ];
+
+        let titles = songs_by_release_date(&songs)
+            .into_iter()
+            .map(|song| song.title.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(titles, vec!["Newest", "Newer", "Old", "Undated", "Garbled"]);
+    }
+
     #[test]
     fn build_song_info_response_normalizes_sheet_fields() {
         let song = SongCatalogSong {
@@ -544,6 +628,7 @@ mod tests {
             genre: SongGenre::Maimai,
             artist: "".to_string(),
             image_name: Some("cover.png".to_string()),
+            release_date: None,
             aliases: SongAliases {
                 en: vec!["Alias".to_string()],
                 ko: vec!["별칭".to_string()],
@@ -588,6 +673,7 @@ mod tests {
                 genre: SongGenre::Maimai,
                 artist: "Artist A".to_string(),
                 image_name: Some("a.png".to_string()),
+                release_date: None,
                 aliases: SongAliases::default(),
                 sheets: vec![SongCatalogChart {
                     chart_type: "std".to_string(),
@@ -606,6 +692,7 @@ mod tests {
                 genre: SongGenre::NiconicoVocaloid,
                 artist: "Artist B".to_string(),
                 image_name: Some("b.png".to_string()),
+                release_date: None,
                 aliases: SongAliases::default(),
                 sheets: vec![SongCatalogChart {
                     chart_type: "std".to_string(),
@@ -645,6 +732,7 @@ mod tests {
             genre: SongGenre::Maimai,
             artist: "".to_string(),
             image_name: None,
+            release_date: None,
             aliases: SongAliases::default(),
             sheets: vec![SongCatalogChart {
                 chart_type: "std".to_string(),
@@ -683,6 +771,7 @@ mod tests {
             genre: SongGenre::Maimai,
             artist: "Artist A".to_string(),
             image_name: Some("a.png".to_string()),
+            release_date: None,
             aliases: SongAliases::default(),
             sheets: vec![SongCatalogChart {
                 chart_type: "std".to_string(),
@@ -721,6 +810,7 @@ mod tests {
             genre: SongGenre::Maimai,
             artist: "Artist A".to_string(),
             image_name: Some("a.png".to_string()),
+            release_date: None,
             aliases: SongAliases {
                 en: vec!["Test Alias".to_string()],
                 ko: vec!["테스트 별칭".to_string()],
@@ -767,6 +857,7 @@ mod tests {
                 genre: SongGenre::Maimai,
                 artist: "Artist A".to_string(),
                 image_name: Some("a.png".to_string()),
+                release_date: None,
                 aliases: SongAliases::default(),
                 sheets: vec![SongCatalogChart {
                     chart_type: "std".to_string(),
@@ -785,6 +876,7 @@ mod tests {
                 genre: SongGenre::Maimai,
                 artist: "Artist B".to_string(),
                 image_name: Some("b.png".to_string()),
+                release_date: None,
                 aliases: SongAliases::default(),
                 sheets: vec![SongCatalogChart {
                     chart_type: "std".to_string(),
@@ -825,6 +917,7 @@ mod tests {
             genre: SongGenre::Maimai,
             artist: "Artist A".to_string(),
             image_name: Some("a.png".to_string()),
+            release_date: None,
             aliases: SongAliases::default(),
             sheets: vec![SongCatalogChart {
                 chart_type: "std".to_string(),
@@ -0,0 +1,95 @@
+use std::io::Cursor;
+
+use eyre::WrapErr;
+use image::ImageFormat;
+
+/// File format covers are stored in (and served as) under `cover/`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CoverFormat {
+    /// The official PNG bytes, stored untouched.
+    #[default]
+    Png,
+    /// Lossless WebP transcoded from the official PNGs.
+    Webp,
+}
+
+impl CoverFormat {
+    /// `COVER_FORMAT` values: `png` (default) or `webp`.
+    pub(crate) fn parse(value: &str) -> eyre::Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "png" => Ok(Self::Png),
+            "webp" => Ok(Self::Webp),
+            other => Err(eyre::eyre!("unsupported cover format '{other}'")),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Webp => "webp",
+        }
+    }
+
+    /// Keep the hashed base of `image_name` and swap in this format's extension.
+    pub(crate) fn image_name(self, image_name: &str) -> String {
+        let stem = image_name
+            .rsplit_once('.')
+            .map_or(image_name, |(stem, _)| stem);
+        format!("{stem}.{}", self.extension())
+    }
+
+    /// Convert downloaded cover bytes into this format.
+    pub(crate) fn encode(self, downloaded: Vec<u8>) -> eyre::Result<Vec<u8>> {
+        match self {
+            Self::Png => Ok(downloaded),
+            Self::Webp => {
+                let cover = image::load_from_memory(&downloaded).wrap_err("decode cover image")?;
+                let mut encoded = Cursor::new(Vec::new());
+                cover
+                    .write_to(&mut encoded, ImageFormat::WebP)
+                    .wrap_err("encode cover as WebP")?;
+                Ok(encoded.into_inner())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CoverFormat;
+    use image::{ImageFormat, Rgba, RgbaImage};
+    use std::io::Cursor;
+
+    #[test]
+    fn webp_cover_format_transcodes_png_into_decodable_webp() {
+        let source = RgbaImage::from_fn(8, 4, |x, y| Rgba([x as u8 * 30, y as u8 * 60, 200, 255]));
+        let mut png = Cursor::new(Vec::new());
+        source
+            .write_to(&mut png, ImageFormat::Png)
+            .expect("encode png");
+        let png = png.into_inner();
+
+        assert_eq!(CoverFormat::Png.encode(png.clone()).expect("png"), png);
+
+        let webp = CoverFormat::Webp.encode(png).expect("webp");
+        assert_eq!(
+            image::guess_format(&webp).expect("format"),
+            ImageFormat::WebP
+        );
+        let decoded = image::load_from_memory_with_format(&webp, ImageFormat::WebP)
+            .expect("decode webp")
+            .to_rgba8();
+        assert_eq!(decoded, source);
+    }
+
+    #[test]
+    fn cover_format_swaps_only_the_extension() {
+        assert_eq!(CoverFormat::Webp.image_name("abc123.png"), "abc123.webp");
+        assert_eq!(CoverFormat::Png.image_name("abc123.png"), "abc123.png");
+        assert_eq!(
+            CoverFormat::parse("WebP").expect("parse"),
+            CoverFormat::Webp
+        );
+        assert!(CoverFormat::parse("jpeg").is_err());
+    }
+}
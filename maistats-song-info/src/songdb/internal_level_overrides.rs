@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use eyre::WrapErr;
+use models::{ChartType, DifficultyCategory, SongGenre};
+use serde::Deserialize;
+
+use super::internal_levels::{InternalLevelKey, InternalLevelRow};
+use super::{SongIdentity, canonical_song_title, normalize_identity_component};
+
+pub(crate) const INTERNAL_LEVEL_OVERRIDES_FILE: &str = "internal_level_overrides.json";
+
+#[derive(Debug, Deserialize)]
+struct InternalLevelOverrideData {
+    #[serde(default)]
+    charts: Vec<InternalLevelOverrideChart>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InternalLevelOverrideChart {
+    title: String,
+    genre: SongGenre,
+    artist: String,
+    #[serde(rename = "type")]
+    chart_type: ChartType,
+    difficulty: DifficultyCategory,
+    internal_level: f32,
+}
+
+/// Loads the optional lowest-priority internal level source from the song data dir.
+/// A missing file is not an error; the build just has no fallback values.
+pub(crate) fn load_internal_level_overrides(
+    song_data_dir: &Path,
+) -> eyre::Result<HashMap<InternalLevelKey, InternalLevelRow>> {
+    let path = song_data_dir.join(INTERNAL_LEVEL_OVERRIDES_FILE);
+    let json = match std::fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(err) => {
+            return Err(err).wrap_err_with(|| format!("read {}", path.display()));
+        }
+    };
+    parse_internal_level_overrides(&json).wrap_err_with(|| format!("parse {}", path.display()))
+}
+
+fn parse_internal_level_overrides(
+    json: &str,
+) -> eyre::Result<HashMap<InternalLevelKey, InternalLevelRow>> {
+    let parsed: InternalLevelOverrideData = serde_json::from_str(json)?;
+    let mut rows = HashMap::new();
+
+    for chart in parsed.charts {
+        let title = canonical_song_title(&chart.title);
+        let artist = normalize_identity_component(&chart.artist);
+        let identity = SongIdentity::new(&title, chart.genre, &artist);
+        let key = (identity.clone(), chart.chart_type, chart.difficulty);
+        let row = InternalLevelRow {
+            song_identity: identity,
+            sheet_type: chart.chart_type,
+            difficulty: chart.difficulty,
+            internal_level: format!("{:.1}", chart.internal_level),
+        };
+        if rows.insert(key, row).is_some() {
+            return Err(eyre::eyre!(
+                "duplicate internal level override for '{}' / {} / {}",
+                title,
+                chart.chart_type.as_str(),
+                chart.difficulty.as_str()
+            ));
+        }
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_internal_level_overrides_keys_by_song_identity() {
+        let json = r#"{
+            "charts": [
+                {
+                    "title": "Gap Song",
+                    "genre": "maimai",
+                    "artist": "Artist",
+                    "type": "dx",
+                    "difficulty": "master",
+                    "internal_level": 13.7
+                }
+            ]
+        }"#;
+
+        let rows = parse_internal_level_overrides(json).expect("parse overrides");
+        let identity = SongIdentity::new("Gap Song", SongGenre::Maimai, "Artist");
+        let row = rows
+            .get(&(identity, ChartType::Dx, DifficultyCategory::Master))
+            .expect("override row exists");
+        assert_eq!(row.internal_level, "13.7");
+    }
+
+    #[test]
+    fn parse_internal_level_overrides_rejects_duplicates() {
+        let chart = r#"{
+            "title": "Gap Song",
+            "genre": "maimai",
+            "artist": "Artist",
+            "type": "dx",
+            "difficulty": "master",
+            "internal_level": 13.7
+        }"#;
+        let json = format!(r#"{{ "charts": [{chart}, {chart}] }}"#);
+
+        assert!(parse_internal_level_overrides(&json).is_err());
+    }
+
+    #[test]
+    fn load_internal_level_overrides_treats_missing_file_as_empty() {
+        let dir = std::env::temp_dir().join(format!(
+            "maistats-internal-level-overrides-missing-{}",
+            std::process::id()
+        ));
+        let rows = load_internal_level_overrides(&dir).expect("missing file is fine");
+        assert!(rows.is_empty());
+    }
+}
@@ -7,6 +7,7 @@ use maimai_parsers::parse_internal_level_page_html;
 use models::{ChartType, DifficultyCategory, SongGenre};
 use serde::{Deserialize, Serialize};
 
+use super::title_aliases::TitleAliases;
 use super::{SheetRow, SongIdentity, SongRow, normalize_song_title_value};
 
 const INTL_LEVEL_SEARCH_URL: &str =
@@ -94,7 +95,7 @@ fn displayed_level_for_param(level_param: u8) -> eyre::Result<String> {
     }
 }
 
-fn level_param_for_displayed_level(displayed_level: &str) -> Option<u8> {
+pub(crate) fn level_param_for_displayed_level(displayed_level: &str) -> Option<u8> {
     let (base_level, is_plus) = parse_displayed_level(displayed_level)?;
     let offset = (base_level - MIN_SUPPORTED_BASE_LEVEL) * 2 + u8::from(is_plus);
     Some(MIN_SUPPORTED_BASE_LEVEL + offset)
@@ -126,7 +127,7 @@ fn format_internal_level_tenths(internal_level_tenths: u16) -> String {
     )
 }
 
-fn supported_level_params() -> impl Iterator<Item = u8> {
+pub(crate) fn supported_level_params() -> impl Iterator<Item = u8> {
     MIN_SUPPORTED_BASE_LEVEL..=23
 }
 
@@ -202,6 +203,7 @@ fn resolve_level_page_entries(
     html: &str,
     lookup: &HashMap<LookupKey, LookupEntry>,
     ignored_titles: &HashSet<String>,
+    title_aliases: &TitleAliases,
 ) -> eyre::Result<Vec<ParsedLevelPageEntry>> {
     let parsed_entries = parse_internal_level_page_html(html)?;
     let mut entries = Vec::with_capacity(parsed_entries.len());
@@ -212,11 +214,16 @@ fn resolve_level_page_entries(
         let chart_type = entry.chart_type;
         let difficulty = entry.difficulty;
 
-        let key = (normalize_song_title_value(&title), chart_type, difficulty);
+        let canonical_title = title_aliases.canonical_title(&title);
+        let key = (
+            models::normalize_title(&canonical_title),
+            chart_type,
+            difficulty,
+        );
         if ignored_titles.contains(&key.0) {
             tracing::info!(
                 "internal levels: skipping manual override title='{}' chart_type='{}' difficulty='{}' level='{}'",
-                key.0,
+                canonical_title,
                 chart_type.as_str(),
                 difficulty.as_str(),
                 displayed_level
@@ -226,7 +233,7 @@ fn resolve_level_page_entries(
         let resolved = lookup.get(&key).cloned().ok_or_else(|| {
             eyre::eyre!(
                 "no song candidate for level page row: title='{}', chart_type='{}', difficulty='{}', level='{}'",
-                key.0,
+                canonical_title,
                 chart_type.as_str(),
                 difficulty.as_str(),
                 displayed_level
@@ -365,9 +372,16 @@ pub(crate) async fn fetch_internal_levels(
     sega_password: &str,
     songs: &[SongRow],
     sheets: &[SheetRow],
+    title_aliases: &TitleAliases,
+    level_params: &[u8],
 ) -> eyre::Result<HashMap<InternalLevelKey, InternalLevelRow>> {
+    if level_params.is_empty() {
+        tracing::info!("Internal levels: no level pages to fetch");
+        return Ok(HashMap::new());
+    }
+
     let client = reqwest::Client::builder()
-        .default_headers(intl::default_mobile_headers()?)
+        .default_headers(intl::default_mobile_headers(intl::DEFAULT_USER_AGENT)?)
         .redirect(reqwest::redirect::Policy::limited(10))
         .cookie_store(true)
         .build()
@@ -380,15 +394,15 @@ pub(crate) async fn fetch_internal_levels(
     let lookup = build_lookup(songs, sheets)?;
     let ignored_titles = collect_manual_override_titles(sheets);
     let mut result: HashMap<InternalLevelKey, InternalLevelRow> = HashMap::new();
-    let level_params = supported_level_params().collect::<Vec<_>>();
 
     for (index, level_param) in level_params.iter().copied().enumerate() {
         let displayed_level = displayed_level_for_param(level_param)?;
         let html =
             fetch_level_page_html_with_auth_recovery(&client, sega_id, sega_password, level_param)
                 .await?;
-        let parsed_entries = resolve_level_page_entries(&html, &lookup, &ignored_titles)
-            .wrap_err_with(|| format!("parse INTL level page {displayed_level}"))?;
+        let parsed_entries =
+            resolve_level_page_entries(&html, &lookup, &ignored_titles, title_aliases)
+                .wrap_err_with(|| format!("parse INTL level page {displayed_level}"))?;
         let (assigned_entries, check) = assign_internal_levels(parsed_entries, level_param)
             .wrap_err_with(|| format!("assign INTL level page {displayed_level}"))?;
 
@@ -478,6 +492,7 @@ mod tests {
             difficulty,
             level: level.to_string(),
             source: super::super::SheetSource::Official,
+            utage: None,
         }
     }
 
@@ -535,7 +550,9 @@ mod tests {
         let html = html_for_rows(&[("expert", "dx", "12", "Song A")]);
         let ignored_titles = HashSet::new();
 
-        let rows = resolve_level_page_entries(&html, &lookup, &ignored_titles).expect("parse rows");
+        let rows =
+            resolve_level_page_entries(&html, &lookup, &ignored_titles, &TitleAliases::default())
+                .expect("parse rows");
         assert_eq!(rows.len(), 1);
         assert_eq!(rows[0].title, "Song A");
         assert_eq!(rows[0].chart_type, ChartType::Dx);
@@ -586,6 +603,36 @@ mod tests {
         assert_eq!(check.observed_bucket_count, 2);
     }
 
+    #[test]
+    fn assign_internal_levels_covers_14_plus_page() {
+        let entry = |title: &str, genre: SongGenre| ParsedLevelPageEntry {
+            title: title.to_string(),
+            chart_type: ChartType::Dx,
+            difficulty: DifficultyCategory::Master,
+            resolved: LookupEntry {
+                song_identity: SongIdentity::new(title, genre.clone(), ""),
+                genre,
+            },
+        };
+        let entries = vec![
+            entry("A", SongGenre::PopsAnime),
+            entry("B", SongGenre::NiconicoVocaloid),
+            entry("C", SongGenre::PopsAnime),
+        ];
+
+        let level_param = level_param_for_displayed_level("14+").expect("14+ is supported");
+        assert_eq!(level_param, 22);
+        let (assigned, check) =
+            assign_internal_levels(entries, level_param).expect("assign levels");
+        let inferred = assigned
+            .iter()
+            .map(|entry| format_internal_level_tenths(entry.inferred_internal_level_tenths))
+            .collect::<Vec<_>>();
+        assert_eq!(inferred, vec!["14.6", "14.6", "14.7"]);
+        assert_eq!(assigned[2].parsed.difficulty, DifficultyCategory::Master);
+        assert_eq!(check.expected_bucket_count, 4);
+    }
+
     #[test]
     fn build_lookup_errors_on_duplicate_official_key() {
         let songs = vec![
@@ -622,7 +669,9 @@ mod tests {
             ("expert", "dx", "12", "Song A"),
         ]);
 
-        let rows = resolve_level_page_entries(&html, &lookup, &ignored_titles).expect("parse rows");
+        let rows =
+            resolve_level_page_entries(&html, &lookup, &ignored_titles, &TitleAliases::default())
+                .expect("parse rows");
         assert_eq!(rows.len(), 1);
         assert_eq!(rows[0].title, "Song A");
     }
@@ -640,7 +689,9 @@ mod tests {
         let ignored_titles = HashSet::new();
         let html = html_for_rows(&[("expert", "dx", "10", "ハオ")]);
 
-        let rows = resolve_level_page_entries(&html, &lookup, &ignored_titles).expect("parse rows");
+        let rows =
+            resolve_level_page_entries(&html, &lookup, &ignored_titles, &TitleAliases::default())
+                .expect("parse rows");
         assert_eq!(rows.len(), 1);
         assert_eq!(rows[0].title, "ハオ");
         assert_eq!(rows[0].resolved.song_identity.title, "ハオ");
@@ -695,8 +746,9 @@ mod tests {
 
         let lookup = build_lookup(&songs, &sheets).expect("build lookup");
         let ignored_titles = collect_manual_override_titles(&sheets);
-        let resolved_entries = resolve_level_page_entries(&html, &lookup, &ignored_titles)
-            .expect("resolve level page entries");
+        let resolved_entries =
+            resolve_level_page_entries(&html, &lookup, &ignored_titles, &TitleAliases::default())
+                .expect("resolve level page entries");
         let (assigned_entries, check) =
             assign_internal_levels(resolved_entries, level_param).expect("assign internal levels");
 
@@ -0,0 +1,99 @@
+use eyre::WrapErr;
+use maimai_auth::intl;
+use tokio::sync::OnceCell;
+
+const INTL_IMAGE_BASE_URL: &str = "https://maimaidx-eng.com/maimai-mobile/img/Music/";
+
+/// Fetches covers from the logged-in INTL mobile site for images the public JP host does not
+/// serve. Logs in lazily on first use, so builds where every public download succeeds never
+/// touch the INTL site. A failed login is remembered for the rest of the run instead of being
+/// retried for every missing cover.
+pub(crate) struct IntlCoverClient {
+    sega_id: String,
+    sega_password: String,
+    base_url: String,
+    client: OnceCell<Result<reqwest::Client, String>>,
+}
+
+impl IntlCoverClient {
+    /// `None` when no INTL credentials are configured.
+    pub(crate) fn new(sega_id: &str, sega_password: &str) -> Option<Self> {
+        if sega_id.trim().is_empty() || sega_password.trim().is_empty() {
+            return None;
+        }
+        Some(Self {
+            sega_id: sega_id.to_string(),
+            sega_password: sega_password.to_string(),
+            base_url: INTL_IMAGE_BASE_URL.to_string(),
+            client: OnceCell::new(),
+        })
+    }
+
+    /// Download a cover by its file name under `img/Music/`, e.g. `0a1b2c3d.png`.
+    pub(crate) async fn download(&self, file_name: &str) -> eyre::Result<Vec<u8>> {
+        let url = format!("{}{}", self.base_url, file_name);
+
+        let client = self
+            .client
+            .get_or_init(|| async { self.login().await.map_err(|err| format!("{err:#}")) })
+            .await
+            .as_ref()
+            .map_err(|err| eyre::eyre!("INTL cover login failed for this run: {err}"))?;
+        let resp = client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()
+            .wrap_err_with(|| format!("fetch INTL cover {url}"))?;
+        // A lapsed session answers 200 with the login page instead of the image.
+        let content_type = resp
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        if !content_type.starts_with("image/") {
+            return Err(eyre::eyre!(
+                "INTL cover {url} is not an image (content-type: {content_type:?})"
+            ));
+        }
+        let bytes = resp.bytes().await?;
+        if bytes.is_empty() {
+            return Err(eyre::eyre!("INTL cover {url} is empty"));
+        }
+        Ok(bytes.to_vec())
+    }
+
+    async fn login(&self) -> eyre::Result<reqwest::Client> {
+        let client = reqwest::Client::builder()
+            .default_headers(intl::default_mobile_headers(intl::DEFAULT_USER_AGENT)?)
+            .redirect(reqwest::redirect::Policy::limited(10))
+            .cookie_store(true)
+            .build()
+            .wrap_err("build INTL cover client")?;
+        intl::ensure_logged_in(&client, &self.sega_id, &self.sega_password)
+            .await
+            .wrap_err("ensure INTL login")?;
+        Ok(client)
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_client(base_url: &str, client: reqwest::Client) -> Self {
+        Self {
+            sega_id: "id".to_string(),
+            sega_password: "password".to_string(),
+            base_url: base_url.to_string(),
+            client: OnceCell::new_with(Some(Ok(client))),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn with_login_failure(base_url: &str, message: &str) -> Self {
+        Self {
+            sega_id: "id".to_string(),
+            sega_password: "password".to_string(),
+            base_url: base_url.to_string(),
+            client: OnceCell::new_with(Some(Err(message.to_string()))),
+        }
+    }
+}
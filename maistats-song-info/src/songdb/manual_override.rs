@@ -7,8 +7,8 @@ use models::{
 use serde::Deserialize;
 
 use super::{
-    SheetRow, SheetSource, SongIdentity, SongRow, normalize_identity_component,
-    normalize_song_title_value, sha256_hex,
+    SheetRow, SheetSource, SongIdentity, SongRow, canonical_song_title,
+    normalize_identity_component, normalize_song_title_value, sha256_hex,
 };
 
 const MANUAL_OVERRIDE_DATA_JSON: &str = include_str!("data/manual_override.json");
@@ -64,7 +64,7 @@ fn map_to_rows(parsed: ManualOverrideData) -> eyre::Result<ManualOverrideRows> {
     let mut overridden_titles = HashSet::new();
 
     for song in parsed.songs {
-        let title = normalize_song_title_value(&song.title);
+        let title = canonical_song_title(&song.title);
         let artist = normalize_identity_component(&song.artist);
         let identity = SongIdentity::new(&title, song.genre, &artist);
         let image_url = song.image_url.trim().to_string();
@@ -108,6 +108,7 @@ fn map_to_rows(parsed: ManualOverrideData) -> eyre::Result<ManualOverrideRows> {
                     internal_level: sheet.internal_level.map(|value| format!("{value:.1}")),
                     region: sheet.region,
                 },
+                utage: None,
             });
         }
     }
@@ -1,24 +1,33 @@
 #![allow(dead_code)]
 
 use eyre::{ContextCompat, WrapErr};
+use futures::StreamExt;
 use models::{
-    ChartType, DifficultyCategory, SongAliases, SongCatalog, SongCatalogChart, SongCatalogSong,
-    SongChartRegion, SongGenre,
+    ChartType, DifficultyCategory, InternalLevelSource, MaimaiVersion, SongAliases, SongCatalog,
+    SongCatalogChart, SongCatalogSong, SongChartRegion, SongGenre, UtageChartInfo,
 };
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fmt;
 use std::path::Path;
 
 mod aliases;
+mod cover_format;
+mod internal_level_overrides;
 mod internal_levels;
+mod intl_covers;
 mod manual_override;
 mod sheet_versions;
+mod title_aliases;
 
+pub use cover_format::CoverFormat;
+use internal_level_overrides::{INTERNAL_LEVEL_OVERRIDES_FILE, load_internal_level_overrides};
 use internal_levels::{InternalLevelKey, InternalLevelRow};
+use intl_covers::IntlCoverClient;
 use manual_override::load_manual_override_rows;
 use sheet_versions::SheetVersionMap;
+use title_aliases::{TITLE_ALIASES_FILE, builtin_canonical_title, load_title_aliases};
 
 pub const SONG_DATA_SUBDIR: &str = "song_data";
 const MAIMAI_SONGS_URL: &str = "https://maimai.sega.jp/data/maimai_songs.json";
@@ -108,6 +117,8 @@ pub(crate) struct SheetRow {
     pub(crate) difficulty: DifficultyCategory,
     pub(crate) level: String,
     pub(crate) source: SheetSource,
+    /// Set only for `ChartType::Utage` sheets.
+    pub(crate) utage: Option<UtageChartInfo>,
 }
 
 #[derive(Debug, Clone)]
@@ -139,6 +150,14 @@ pub struct SongDbConfig {
     pub intl_sega_password: String,
     pub user_agent: String,
     pub skip_cover_download: bool,
+    pub cover_format: CoverFormat,
+    /// How many covers to download at once; maimaidx.jp throttles aggressive clients.
+    pub cover_download_concurrency: usize,
+    pub skip_internal_levels: bool,
+    pub estimate_missing_internal_levels: bool,
+    pub incremental_internal_levels: bool,
+    /// Development-only: build from these versions' charts instead of every version.
+    pub limit_versions: Option<Vec<MaimaiVersion>>,
 }
 
 impl fmt::Debug for SongDbConfig {
@@ -148,6 +167,21 @@ impl fmt::Debug for SongDbConfig {
             .field("intl_sega_password", &"<redacted>")
             .field("user_agent", &self.user_agent)
             .field("skip_cover_download", &self.skip_cover_download)
+            .field("cover_format", &self.cover_format)
+            .field(
+                "cover_download_concurrency",
+                &self.cover_download_concurrency,
+            )
+            .field("skip_internal_levels", &self.skip_internal_levels)
+            .field(
+                "estimate_missing_internal_levels",
+                &self.estimate_missing_internal_levels,
+            )
+            .field(
+                "incremental_internal_levels",
+                &self.incremental_internal_levels,
+            )
+            .field("limit_versions", &self.limit_versions)
             .finish()
     }
 }
@@ -162,27 +196,108 @@ impl SongDbConfig {
             .wrap_err("missing env var: MAIMAI_INTL_SEGA_PASSWORD or SEGA_PASSWORD")?;
         let user_agent = std::env::var("USER_AGENT").wrap_err("missing env var: USER_AGENT")?;
         let skip_cover_download = parse_env_flag("SKIP_COVER_DOWNLOAD");
+        let cover_format = CoverFormat::parse(&std::env::var("COVER_FORMAT").unwrap_or_default())
+            .wrap_err("parse COVER_FORMAT")?;
+        let cover_download_concurrency = parse_cover_download_concurrency(
+            &std::env::var("COVER_DOWNLOAD_CONCURRENCY").unwrap_or_default(),
+        )
+        .wrap_err("parse COVER_DOWNLOAD_CONCURRENCY")?;
+        let skip_internal_levels = parse_env_flag("SKIP_INTERNAL_LEVELS");
+        let estimate_missing_internal_levels = parse_env_flag("ESTIMATE_MISSING_INTERNAL_LEVELS");
+        let incremental_internal_levels = parse_env_flag("INCREMENTAL_INTERNAL_LEVELS");
+        let limit_versions = std::env::var("LIMIT_VERSIONS")
+            .ok()
+            .map(|value| parse_limit_versions(&value))
+            .transpose()
+            .wrap_err("parse LIMIT_VERSIONS")?
+            .flatten();
 
         Ok(Self {
             intl_sega_id,
             intl_sega_password,
             user_agent,
             skip_cover_download,
+            cover_format,
+            cover_download_concurrency,
+            skip_internal_levels,
+            estimate_missing_internal_levels,
+            incremental_internal_levels,
+            limit_versions,
         })
     }
 }
 
+const DEFAULT_COVER_DOWNLOAD_CONCURRENCY: usize = 8;
+
+/// Blank means [`DEFAULT_COVER_DOWNLOAD_CONCURRENCY`]; zero is rejected.
+fn parse_cover_download_concurrency(value: &str) -> eyre::Result<usize> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Ok(DEFAULT_COVER_DOWNLOAD_CONCURRENCY);
+    }
+    match value.parse::<usize>() {
+        Ok(0) | Err(_) => Err(eyre::eyre!("expected a positive integer, got '{value}'")),
+        Ok(concurrency) => Ok(concurrency),
+    }
+}
+
+/// Comma-separated version names such as `CiRCLE,PRiSM PLUS`; blank means every version.
+fn parse_limit_versions(value: &str) -> eyre::Result<Option<Vec<MaimaiVersion>>> {
+    let versions = value
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            name.parse::<MaimaiVersion>()
+                .map_err(|_| eyre::eyre!("unknown maimai version '{name}'"))
+        })
+        .collect::<eyre::Result<Vec<_>>>()?;
+    Ok((!versions.is_empty()).then_some(versions))
+}
+
 #[derive(Debug, Clone)]
 pub struct SongDatabase {
     songs: Vec<SongRow>,
     sheets: Vec<SheetRow>,
     sheet_versions: SheetVersionMap,
     internal_levels: HashMap<InternalLevelKey, InternalLevelRow>,
+    fallback_internal_levels: HashMap<InternalLevelKey, InternalLevelRow>,
     aliases: HashMap<SongIdentity, SongAliases>,
+    estimate_missing_internal_levels: bool,
+}
+
+/// Song-level changes between the previous `data.json` and the current official list.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct IncrementalSummary {
+    pub added: usize,
+    pub removed: usize,
+    /// Songs present in both whose displayed chart levels differ.
+    pub changed: usize,
 }
 
 impl SongDatabase {
     pub async fn fetch(config: &SongDbConfig, song_data_dir: &Path) -> eyre::Result<Self> {
+        let (database, _) = Self::fetch_with_previous(config, song_data_dir, None).await?;
+        Ok(database)
+    }
+
+    /// Like [`SongDatabase::fetch`], but only re-reads the INTL level pages whose displayed
+    /// level gained, lost, or changed a chart since `previous`; every other chart keeps its
+    /// previous level-page internal level. Re-rates that leave the displayed level untouched
+    /// are not picked up, so a full [`SongDatabase::fetch`] is still needed now and then.
+    pub async fn fetch_incremental(
+        config: &SongDbConfig,
+        song_data_dir: &Path,
+        previous: &models::SongDatabase,
+    ) -> eyre::Result<(Self, IncrementalSummary)> {
+        Self::fetch_with_previous(config, song_data_dir, Some(&previous.songs)).await
+    }
+
+    async fn fetch_with_previous(
+        config: &SongDbConfig,
+        song_data_dir: &Path,
+        previous: Option<&[SongCatalogSong]>,
+    ) -> eyre::Result<(Self, IncrementalSummary)> {
         // NOTE: maimaidx.jp sometimes has SSL certificate issues ("unable to get local issuer certificate").
         // We bypass verification here since we're only fetching public cover images.
         let client = reqwest::Client::builder()
@@ -207,6 +322,9 @@ impl SongDatabase {
         let (mut songs, mut sheets) = build_official_rows(raw_songs)?;
         songs.extend(manual_override_rows.songs);
         sheets.extend(manual_override_rows.sheets);
+        for song in &mut songs {
+            song.image_name = config.cover_format.image_name(&song.image_name);
+        }
         ensure_unique_song_row_ids(&songs)?;
         ensure_unique_sheet_keys(&sheets)?;
         tracing::info!(
@@ -214,6 +332,7 @@ impl SongDatabase {
             songs.len(),
             sheets.len()
         );
+        let level_diff = diff_previous_levels(previous.unwrap_or_default(), &sheets);
 
         tracing::info!("Fetching INTL sheet versions...");
         let sheet_versions = sheet_versions::fetch_intl_sheet_versions(
@@ -222,19 +341,87 @@ impl SongDatabase {
             &songs,
             &sheets,
             &overridden_titles,
+            config.limit_versions.as_deref(),
         )
         .await
         .wrap_err("fetch INTL sheet versions")?;
+        if let Some(versions) = &config.limit_versions {
+            retain_limited_versions(&mut songs, &mut sheets, &sheet_versions, versions);
+            tracing::warn!(
+                "LIMIT_VERSIONS is set: this build is PARTIAL and only covers {} songs / {} sheets \
+                 from {:?}; do not upload it",
+                songs.len(),
+                sheets.len(),
+                versions
+                    .iter()
+                    .map(|version| version.as_str())
+                    .collect::<Vec<_>>()
+            );
+        }
 
-        tracing::info!("Fetching internal levels...");
-        let internal_levels = internal_levels::fetch_internal_levels(
-            &config.intl_sega_id,
-            &config.intl_sega_password,
-            &songs,
-            &sheets,
-        )
-        .await
-        .wrap_err("fetch internal levels")?;
+        let internal_levels = if config.skip_internal_levels {
+            tracing::warn!(
+                "Skipping internal level fetch because SKIP_INTERNAL_LEVELS is enabled; \
+                 rating will not be computable except for manual override charts"
+            );
+            HashMap::new()
+        } else {
+            let title_aliases = load_title_aliases(song_data_dir)
+                .wrap_err_with(|| format!("load {TITLE_ALIASES_FILE}"))?;
+            if !title_aliases.is_empty() {
+                tracing::info!(
+                    "Loaded {} title aliases from {TITLE_ALIASES_FILE}",
+                    title_aliases.len()
+                );
+            }
+            let mut level_params = match previous {
+                None => internal_levels::supported_level_params().collect::<Vec<_>>(),
+                Some(_) => {
+                    tracing::info!(
+                        "Reusing {} previous internal levels; re-reading level pages {:?}",
+                        level_diff.reusable.len(),
+                        level_diff.stale_levels
+                    );
+                    level_diff
+                        .stale_levels
+                        .iter()
+                        .filter_map(|level| internal_levels::level_param_for_displayed_level(level))
+                        .collect()
+                }
+            };
+            if config.limit_versions.is_some() {
+                let limited_params = sheets
+                    .iter()
+                    .filter_map(|sheet| {
+                        internal_levels::level_param_for_displayed_level(&sheet.level)
+                    })
+                    .collect::<HashSet<_>>();
+                level_params.retain(|param| limited_params.contains(param));
+            }
+            tracing::info!("Fetching internal levels...");
+            let fetched = internal_levels::fetch_internal_levels(
+                &config.intl_sega_id,
+                &config.intl_sega_password,
+                &songs,
+                &sheets,
+                &title_aliases,
+                &level_params,
+            )
+            .await
+            .wrap_err("fetch internal levels")?;
+            let mut internal_levels = level_diff.reusable;
+            internal_levels.extend(fetched);
+            internal_levels
+        };
+
+        let fallback_internal_levels = load_internal_level_overrides(song_data_dir)
+            .wrap_err_with(|| format!("load {INTERNAL_LEVEL_OVERRIDES_FILE}"))?;
+        if !fallback_internal_levels.is_empty() {
+            tracing::info!(
+                "Loaded {} fallback internal levels from {INTERNAL_LEVEL_OVERRIDES_FILE}",
+                fallback_internal_levels.len()
+            );
+        }
 
         tracing::info!("Fetching song aliases...");
         let aliases = aliases::fetch_song_aliases(&client)
@@ -255,20 +442,43 @@ impl SongDatabase {
         } else {
             tracing::info!("Downloading covers...");
             let cover_dir = song_data_dir.join("cover");
-            if let Err(err) = download_cover_images(&client, &songs, &cover_dir).await {
+            let intl_fallback =
+                IntlCoverClient::new(&config.intl_sega_id, &config.intl_sega_password);
+            if let Err(err) = download_cover_images(
+                &client,
+                intl_fallback.as_ref(),
+                &songs,
+                &cover_dir,
+                config.cover_format,
+                config.cover_download_concurrency,
+            )
+            .await
+            {
                 tracing::warn!(
                     "cover download step failed; continuing song database build without complete covers: {err:#}"
                 );
             }
         }
 
-        Ok(SongDatabase {
+        let database = SongDatabase {
             songs,
             sheets,
             sheet_versions,
             internal_levels,
+            fallback_internal_levels,
             aliases,
-        })
+            estimate_missing_internal_levels: config.estimate_missing_internal_levels,
+        };
+        Ok((database, level_diff.summary))
+    }
+
+    /// `title / artist` keys of songs the official list still marks as locked.
+    pub fn locked_song_keys(&self) -> BTreeSet<String> {
+        self.songs
+            .iter()
+            .filter(|song| song.is_locked)
+            .map(|song| song_key(&song.identity.title, &song.identity.artist))
+            .collect()
     }
 
     pub fn into_data_root(self) -> eyre::Result<SongCatalog> {
@@ -277,17 +487,136 @@ impl SongDatabase {
             &self.sheets,
             &self.sheet_versions,
             &self.internal_levels,
+            &self.fallback_internal_levels,
             &self.aliases,
+            self.estimate_missing_internal_levels,
         ))
     }
 }
 
+struct LevelDiff {
+    summary: IncrementalSummary,
+    /// Displayed levels whose level page must be re-read: a chart joined or left the page,
+    /// which can shift the genre-wrap buckets of every other chart on it.
+    stale_levels: BTreeSet<String>,
+    /// Previous level-page internal levels for charts on pages that did not go stale.
+    reusable: HashMap<InternalLevelKey, InternalLevelRow>,
+}
+
+type PreviousChart<'a> = (&'a str, Option<&'a str>);
+
+fn diff_previous_levels(previous: &[SongCatalogSong], sheets: &[SheetRow]) -> LevelDiff {
+    let mut previous_charts: HashMap<
+        SongIdentity,
+        HashMap<(ChartType, DifficultyCategory), PreviousChart<'_>>,
+    > = HashMap::new();
+    for song in previous {
+        let identity = SongIdentity::new(&song.title, song.genre.clone(), &song.artist);
+        let charts = previous_charts.entry(identity).or_default();
+        for sheet in &song.sheets {
+            let (Ok(chart_type), Ok(difficulty)) = (
+                sheet.chart_type.parse::<ChartType>(),
+                sheet.difficulty.parse::<DifficultyCategory>(),
+            ) else {
+                continue;
+            };
+            let level_page_internal = sheet
+                .internal_level
+                .as_deref()
+                .filter(|_| sheet.internal_level_source == Some(InternalLevelSource::LevelPage));
+            charts.insert(
+                (chart_type, difficulty),
+                (sheet.level.as_str(), level_page_internal),
+            );
+        }
+    }
+
+    let mut current_charts: HashMap<&SongIdentity, HashMap<(ChartType, DifficultyCategory), &str>> =
+        HashMap::new();
+    for sheet in sheets {
+        current_charts
+            .entry(&sheet.song_identity)
+            .or_default()
+            .insert((sheet.sheet_type, sheet.difficulty), sheet.level.as_str());
+    }
+
+    let mut summary = IncrementalSummary::default();
+    let mut stale_levels = BTreeSet::new();
+    for (identity, charts) in &current_charts {
+        let Some(previous) = previous_charts.get(*identity) else {
+            summary.added += 1;
+            stale_levels.extend(charts.values().map(|level| level.to_string()));
+            continue;
+        };
+        let mut changed = false;
+        for (key, level) in charts {
+            match previous.get(key) {
+                Some((previous_level, _)) if previous_level == level => {}
+                Some((previous_level, _)) => {
+                    changed = true;
+                    stale_levels.insert(previous_level.to_string());
+                    stale_levels.insert(level.to_string());
+                }
+                None => {
+                    changed = true;
+                    stale_levels.insert(level.to_string());
+                }
+            }
+        }
+        for (key, (previous_level, _)) in previous {
+            if !charts.contains_key(key) {
+                changed = true;
+                stale_levels.insert(previous_level.to_string());
+            }
+        }
+        summary.changed += usize::from(changed);
+    }
+    for (identity, charts) in &previous_charts {
+        if !current_charts.contains_key(identity) {
+            summary.removed += 1;
+            stale_levels.extend(charts.values().map(|(level, _)| level.to_string()));
+        }
+    }
+
+    let reusable = sheets
+        .iter()
+        .filter(|sheet| !stale_levels.contains(&sheet.level))
+        .filter_map(|sheet| {
+            let (_, internal_level) = *previous_charts
+                .get(&sheet.song_identity)?
+                .get(&(sheet.sheet_type, sheet.difficulty))?;
+            let row = InternalLevelRow {
+                song_identity: sheet.song_identity.clone(),
+                sheet_type: sheet.sheet_type,
+                difficulty: sheet.difficulty,
+                internal_level: internal_level?.to_string(),
+            };
+            Some((
+                (
+                    sheet.song_identity.clone(),
+                    sheet.sheet_type,
+                    sheet.difficulty,
+                ),
+                row,
+            ))
+        })
+        .collect();
+
+    LevelDiff {
+        summary,
+        stale_levels,
+        reusable,
+    }
+}
+
 fn build_data_root(
     songs: &[SongRow],
     sheets: &[SheetRow],
     sheet_versions: &SheetVersionMap,
     internal_levels: &HashMap<InternalLevelKey, InternalLevelRow>,
+    fallback_internal_levels: &HashMap<InternalLevelKey, InternalLevelRow>,
     aliases: &HashMap<SongIdentity, SongAliases>,
+    estimate_missing_internal_levels: bool,
 ) -> SongCatalog {
     use std::collections::BTreeMap;
 
@@ -301,12 +630,18 @@ fn build_data_root(
                 genre: song.identity.genre.clone(),
                 artist: song.identity.artist.clone(),
                 image_name: Some(song.image_name.clone()),
+                release_date: song.release_date.clone(),
                 aliases: aliases.get(&song.identity).cloned().unwrap_or_default(),
                 sheets: Vec::new(),
             },
         );
     }
 
+    // Official rows follow the upstream JSON order and manual overrides are appended after
+    // them; sort so `data.json` is byte-stable for identical inputs.
+    let mut sheets = sheets.iter().collect::<Vec<_>>();
+    sheets.sort_by_key(|sheet| (sheet.sheet_type, sheet.difficulty));
+
     for sheet in sheets {
         let song = match song_map.get_mut(&sheet.song_identity) {
             Some(song) => song,
@@ -331,7 +666,7 @@ fn build_data_root(
                     .cloned();
                 (
                     version_name.clone(),
-                    internal_level_from_map,
+                    internal_level_from_map.map(|level| (level, InternalLevelSource::LevelPage)),
                     SongChartRegion {
                         jp: true,
                         intl: version_name.is_some(),
@@ -344,10 +679,30 @@ fn build_data_root(
                 region,
             } => (
                 Some(version_name.clone()),
-                internal_level.clone(),
+                merge_manual_override_internal_level(
+                    sheet,
+                    internal_level.as_deref(),
+                    internal_level_from_map,
+                ),
                 region.clone(),
             ),
         };
+        // `internal_level_overrides.json` only fills charts no other source knows about.
+        let internal_level = internal_level.or_else(|| {
+            fallback_internal_levels.get(&il_key).map(|il| {
+                (
+                    il.internal_level.trim().to_string(),
+                    InternalLevelSource::Fallback,
+                )
+            })
+        });
+        let internal_level = internal_level.or_else(|| {
+            estimate_missing_internal_levels
+                .then(|| estimated_internal_level(&sheet.level))
+                .flatten()
+                .map(|level| (level, InternalLevelSource::Estimate))
+        });
+        let (internal_level, internal_level_source) = internal_level.unzip();
 
         song.sheets.push(SongCatalogChart {
             chart_type: sheet.sheet_type.as_lowercase().to_string(),
@@ -355,7 +710,9 @@ fn build_data_root(
             level: sheet.level.clone(),
             version_name,
             internal_level,
+            internal_level_source,
             region,
+            utage: sheet.utage.clone(),
         });
     }
 
@@ -364,6 +721,58 @@ fn build_data_root(
     }
 }
 
+/// Lowest value of a displayed level band (`13` -> 13.0, `13+` -> 13.6), used as a
+/// conservative stand-in when no source knows the chart's exact internal level.
+fn estimated_internal_level(level: &str) -> Option<String> {
+    let level = level.trim();
+    let (base, is_plus) = match level.strip_suffix('+') {
+        Some(base) => (base, true),
+        None => (level, false),
+    };
+    let base = base
+        .parse::<u8>()
+        .ok()
+        .filter(|base| (1..=15).contains(base))?;
+    Some(format!("{base}.{}", if is_plus { 6 } else { 0 }))
+}
+
+/// Manual overrides win over level-page inference; the inferred value only fills
+/// in when the override leaves `internal_level` empty.
+fn merge_manual_override_internal_level(
+    sheet: &SheetRow,
+    override_level: Option<&str>,
+    level_page_level: Option<String>,
+) -> Option<(String, InternalLevelSource)> {
+    let override_level = override_level
+        .map(str::trim)
+        .filter(|level| !level.is_empty());
+
+    match (override_level, level_page_level) {
+        (Some(override_level), Some(level_page_level)) => {
+            if override_level != level_page_level {
+                tracing::warn!(
+                    "internal level mismatch for '{}' / {} / {}: manual override {} vs level page {}; keeping manual override",
+                    sheet.song_identity.title,
+                    sheet.sheet_type.as_str(),
+                    sheet.difficulty.as_str(),
+                    override_level,
+                    level_page_level
+                );
+            }
+            Some((
+                override_level.to_string(),
+                InternalLevelSource::ManualOverride,
+            ))
+        }
+        (Some(override_level), None) => Some((
+            override_level.to_string(),
+            InternalLevelSource::ManualOverride,
+        )),
+        (None, Some(level_page_level)) => Some((level_page_level, InternalLevelSource::LevelPage)),
+        (None, None) => None,
+    }
+}
+
 fn build_song_alias_map(
     songs: &[SongRow],
     fetched_aliases: HashMap<String, SongAliases>,
@@ -409,17 +818,37 @@ async fn fetch_maimai_songs(client: &reqwest::Client) -> eyre::Result<Vec<RawSon
 }
 
 fn parse_maimai_songs_json(json: &str) -> eyre::Result<Vec<RawSong>> {
-    let raw_songs =
+    let mut raw_songs =
         serde_json::from_str::<Vec<RawSong>>(json).wrap_err("parse maimai songs json")?;
-    let (mut filtered, dropped_count) = filter_out_utage_entries(raw_songs);
-    if dropped_count > 0 {
-        tracing::info!(
-            "Skipped {} utage entries from official songs JSON",
-            dropped_count
-        );
-    }
-    apply_jp_song_patches(&mut filtered);
-    Ok(filtered)
+    apply_jp_song_patches(&mut raw_songs);
+    Ok(raw_songs)
+}
+
+/// Drop every sheet outside `versions` (official sheets by their fetched INTL version, manual
+/// overrides by their own version name), then every song left without sheets.
+fn retain_limited_versions(
+    songs: &mut Vec<SongRow>,
+    sheets: &mut Vec<SheetRow>,
+    sheet_versions: &SheetVersionMap,
+    versions: &[MaimaiVersion],
+) {
+    let in_limit = |version_name: &str| {
+        version_name
+            .parse::<MaimaiVersion>()
+            .is_ok_and(|version| versions.contains(&version))
+    };
+    sheets.retain(|sheet| match &sheet.source {
+        SheetSource::Official => sheet_versions
+            .get(&sheet.song_identity)
+            .and_then(|by_type| by_type.get(&sheet.sheet_type))
+            .is_some_and(|version_name| in_limit(version_name)),
+        SheetSource::ManualOverride { version_name, .. } => in_limit(version_name),
+    });
+    let kept_songs = sheets
+        .iter()
+        .map(|sheet| &sheet.song_identity)
+        .collect::<HashSet<_>>();
+    songs.retain(|song| kept_songs.contains(&song.identity));
 }
 
 fn build_official_rows(raw_songs: Vec<RawSong>) -> eyre::Result<(Vec<SongRow>, Vec<SheetRow>)> {
@@ -488,16 +917,6 @@ fn filter_official_songs_by_title(
         .collect()
 }
 
-fn filter_out_utage_entries(raw_songs: Vec<RawSong>) -> (Vec<RawSong>, usize) {
-    let before = raw_songs.len();
-    let filtered = raw_songs
-        .into_iter()
-        .filter(|song| song.lev_utage.is_none())
-        .collect::<Vec<_>>();
-    let dropped_count = before.saturating_sub(filtered.len());
-    (filtered, dropped_count)
-}
-
 fn ensure_unique_song_identities(raw_songs: &[RawSong]) -> eyre::Result<()> {
     let mut seen = HashSet::new();
     let mut duplicates = Vec::new();
@@ -572,6 +991,14 @@ fn extract_song(raw_song: &RawSong) -> eyre::Result<SongRow> {
     let image_name = format!("{}.png", sha256_hex(&image_url));
     let release_date = parse_release_date(raw_song.release.as_deref());
     let sort_order = raw_song.version.parse::<i64>().ok();
+    if let Some(version_id) = sort_order
+        && MaimaiVersion::from_version_id(version_id).is_none()
+    {
+        tracing::warn!(
+            "'{}' has version id {version_id} that MaimaiVersion does not know; add the new version",
+            raw_song.title
+        );
+    }
 
     Ok(SongRow {
         identity,
@@ -586,6 +1013,10 @@ fn extract_song(raw_song: &RawSong) -> eyre::Result<SongRow> {
 }
 
 fn extract_sheets(raw_song: &RawSong) -> eyre::Result<Vec<SheetRow>> {
+    if raw_song.lev_utage.is_some() {
+        return Ok(extract_utage_sheet(raw_song)?.into_iter().collect());
+    }
+
     let song_identity = derive_song_identity(raw_song)?;
 
     let candidates: [(ChartType, DifficultyCategory, Option<&str>); 10] = [
@@ -651,11 +1082,34 @@ fn extract_sheets(raw_song: &RawSong) -> eyre::Result<Vec<SheetRow>> {
                 difficulty,
                 level,
                 source: SheetSource::Official,
+                utage: None,
             })
         })
         .collect())
 }
 
+/// Utage entries carry a single chart in `lev_utage` (levels like `12+?`). It has no
+/// difficulty of its own, so it is keyed as MASTER; each utage entry is its own song, so
+/// this never collides with a regular chart.
+fn extract_utage_sheet(raw_song: &RawSong) -> eyre::Result<Option<SheetRow>> {
+    let song_identity = derive_song_identity(raw_song)?;
+    let Some(level) = normalize_level(raw_song.lev_utage.as_deref()) else {
+        return Ok(None);
+    };
+
+    Ok(Some(SheetRow {
+        song_identity,
+        sheet_type: ChartType::Utage,
+        difficulty: DifficultyCategory::Master,
+        level,
+        source: SheetSource::Official,
+        utage: Some(UtageChartInfo {
+            kanji: non_empty_trimmed(raw_song.kanji.as_deref()),
+            utage_type: non_empty_trimmed(raw_song.utage_type.as_deref()),
+        }),
+    }))
+}
+
 fn derive_song_identity(raw_song: &RawSong) -> eyre::Result<SongIdentity> {
     let genre = raw_song.catcode.parse::<SongGenre>().ok().ok_or_else(|| {
         eyre::eyre!(
@@ -680,21 +1134,28 @@ fn normalized_song_title(raw_song: &RawSong, genre: &SongGenre) -> String {
         }
     }
 
-    let title = normalize_identity_component(&raw_song.title);
-    normalize_song_title_value(&title)
+    canonical_song_title(&raw_song.title)
 }
 
 fn normalized_song_artist(artist: Option<&str>) -> String {
     normalize_identity_component(artist.unwrap_or_default())
 }
 
-pub(crate) fn normalize_song_title_value(title: &str) -> String {
+pub(crate) fn song_key(title: &str, artist: &str) -> String {
+    format!("{title} / {artist}")
+}
+
+/// Trimmed official spelling of `title`, as stored in a song identity.
+pub(crate) fn canonical_song_title(title: &str) -> String {
     let title = normalize_identity_component(title);
-    if title == "Bad Apple!! feat nomico" {
-        "Bad Apple!! feat.nomico".to_string()
-    } else {
-        title
-    }
+    builtin_canonical_title(&title)
+        .map(str::to_string)
+        .unwrap_or(title)
+}
+
+/// Key for joining titles from different sources; see [`models::normalize_title`].
+pub(crate) fn normalize_song_title_value(title: &str) -> String {
+    models::normalize_title(&canonical_song_title(title))
 }
 
 pub(crate) fn normalize_identity_component(value: &str) -> String {
@@ -741,6 +1202,11 @@ fn normalize_level(level: Option<&str>) -> Option<String> {
     }
 }
 
+fn non_empty_trimmed(value: Option<&str>) -> Option<String> {
+    let value = value?.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
 fn is_truthy(value: &Option<String>) -> bool {
     value.as_deref().is_some_and(|text| !text.trim().is_empty())
 }
@@ -781,7 +1247,8 @@ async fn download_image(client: &reqwest::Client, image_url: &str) -> eyre::Resu
 
         match result {
             Ok(data) => return Ok(data),
-            Err(e) if attempt < MAX_RETRIES - 1 => {
+            // A missing cover stays missing; retrying only delays the INTL fallback.
+            Err(e) if attempt < MAX_RETRIES - 1 && !is_not_found(&e) => {
                 let delay_ms = 200 * 2_u64.pow(attempt);
                 tracing::warn!(
                     "Failed to download '{}': {}. Retrying in {}ms (attempt {}/{})",
@@ -799,63 +1266,113 @@ async fn download_image(client: &reqwest::Client, image_url: &str) -> eyre::Resu
     unreachable!()
 }
 
+fn is_not_found(err: &eyre::Error) -> bool {
+    err.downcast_ref::<reqwest::Error>()
+        .and_then(reqwest::Error::status)
+        == Some(reqwest::StatusCode::NOT_FOUND)
+}
+
+/// Download from the public host, falling back to the logged-in INTL site only when the
+/// public host has no such cover.
+async fn download_cover(
+    client: &reqwest::Client,
+    intl_fallback: Option<&IntlCoverClient>,
+    image_url: &str,
+) -> eyre::Result<Vec<u8>> {
+    let err = match download_image(client, image_url).await {
+        Ok(data) => return Ok(data),
+        Err(err) => err,
+    };
+    let Some(intl_fallback) = intl_fallback.filter(|_| is_not_found(&err)) else {
+        return Err(err);
+    };
+    let file_name = image_url.rsplit('/').next().unwrap_or(image_url);
+    tracing::info!("Public cover '{image_url}' not found; trying INTL");
+    intl_fallback
+        .download(file_name)
+        .await
+        .wrap_err_with(|| format!("public cover not found and INTL fallback failed: {err}"))
+}
+
 fn should_download(cover_path: &Path) -> bool {
     !cover_path.exists()
 }
 
-fn write_atomic(path: &Path, contents: &[u8]) -> eyre::Result<()> {
+/// Write to a sibling `.tmp` file, flush it to disk, then rename over `path`, so readers
+/// only ever see the previous or the complete new contents.
+pub(crate) fn write_atomic(path: &Path, contents: &[u8]) -> eyre::Result<()> {
+    use std::io::Write;
+
     let file_name = path
         .file_name()
         .and_then(|name| name.to_str())
         .wrap_err("invalid output filename")?;
     let tmp_path = path.with_file_name(format!("{file_name}.tmp"));
-    std::fs::write(&tmp_path, contents).wrap_err("write temp file")?;
+    let mut file = std::fs::File::create(&tmp_path).wrap_err("create temp file")?;
+    file.write_all(contents).wrap_err("write temp file")?;
+    file.sync_all().wrap_err("sync temp file")?;
+    drop(file);
     std::fs::rename(&tmp_path, path).wrap_err("rename temp file")?;
     Ok(())
 }
 
+/// Transcoding is CPU-bound, so it runs on the blocking pool instead of the download workers.
+async fn encode_cover(cover_format: CoverFormat, downloaded: Vec<u8>) -> eyre::Result<Vec<u8>> {
+    tokio::task::spawn_blocking(move || cover_format.encode(downloaded))
+        .await
+        .wrap_err("join cover encoder")?
+}
+
 async fn download_cover_images(
     client: &reqwest::Client,
+    intl_fallback: Option<&IntlCoverClient>,
     songs: &[SongRow],
     cover_dir: &Path,
+    cover_format: CoverFormat,
+    concurrency: usize,
 ) -> eyre::Result<()> {
     std::fs::create_dir_all(cover_dir).wrap_err("create cover image dir")?;
 
     let total = songs.len();
+    let pending = songs
+        .iter()
+        .map(|song| (song, cover_dir.join(&song.image_name)))
+        .filter(|(_, cover_path)| should_download(cover_path))
+        .collect::<Vec<_>>();
+    let skipped_count = total - pending.len();
+
+    let results = futures::stream::iter(pending)
+        .map(|(song, cover_path)| async move {
+            let result = match download_cover(client, intl_fallback, &song.image_url).await {
+                Ok(downloaded) => encode_cover(cover_format, downloaded).await,
+                Err(err) => Err(err),
+            };
+            let result = result
+                .wrap_err_with(|| format!("Failed to download cover for '{}'", song.identity.title))
+                .and_then(|downloaded| {
+                    write_atomic(&cover_path, &downloaded).wrap_err_with(|| {
+                        format!(
+                            "Failed to write cover '{}' to '{}'",
+                            song.identity.title,
+                            cover_path.display()
+                        )
+                    })
+                });
+            (song, result)
+        })
+        .buffer_unordered(concurrency.max(1))
+        .collect::<Vec<_>>()
+        .await;
+
     let mut downloaded_count = 0;
-    let mut skipped_count = 0;
     let mut failed_downloads = Vec::new();
-
-    for song in songs {
-        let cover_path = cover_dir.join(&song.image_name);
-
-        if should_download(&cover_path) {
-            match download_image(client, &song.image_url).await {
-                Ok(downloaded) => match write_atomic(&cover_path, &downloaded) {
-                    Ok(_) => {
-                        downloaded_count += 1;
-                    }
-                    Err(e) => {
-                        tracing::warn!(
-                            "Failed to write cover '{}' to '{}': {:#}",
-                            song.identity.title,
-                            cover_path.display(),
-                            e
-                        );
-                        failed_downloads.push(song.identity.title.clone());
-                    }
-                },
-                Err(e) => {
-                    tracing::warn!(
-                        "Failed to download cover for '{}': {:#}",
-                        song.identity.title,
-                        e
-                    );
-                    failed_downloads.push(song.identity.title.clone());
-                }
+    for (song, result) in results {
+        match result {
+            Ok(_) => downloaded_count += 1,
+            Err(e) => {
+                tracing::warn!("{:#}", e);
+                failed_downloads.push(song.identity.title.clone());
             }
-        } else {
-            skipped_count += 1;
         }
     }
 
@@ -886,6 +1403,7 @@ async fn download_cover_images(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use test_http_server::{Reply, TestServer};
 
     fn raw_song_stub() -> RawSong {
         RawSong {
@@ -988,12 +1506,26 @@ mod tests {
     }
 
     #[test]
-    fn skips_utage_sheets() {
+    fn extracts_utage_sheet_with_metadata() {
         let mut raw_song = raw_song_stub();
-        raw_song.lev_utage = Some("14".to_string());
-        raw_song.kanji = Some("協奏曲".to_string());
+        raw_song.catcode = "宴会場".to_string();
+        raw_song.title = "[協]Love You".to_string();
+        raw_song.lev_utage = Some("12?".to_string());
+        raw_song.kanji = Some("協".to_string());
+
         let sheets = extract_sheets(&raw_song).expect("extract sheets");
-        assert!(sheets.is_empty());
+
+        assert_eq!(sheets.len(), 1);
+        let sheet = &sheets[0];
+        assert_eq!(sheet.sheet_type, ChartType::Utage);
+        assert_eq!(sheet.level, "12?");
+        assert_eq!(
+            sheet.utage,
+            Some(UtageChartInfo {
+                kanji: Some("協".to_string()),
+                utage_type: None,
+            })
+        );
     }
 
     #[test]
@@ -1007,16 +1539,42 @@ mod tests {
     }
 
     #[test]
-    fn filters_out_utage_entries() {
-        let normal_song = raw_song_stub();
-        let mut utage_song = raw_song_stub();
-        utage_song.title = "Utage Song".to_string();
-        utage_song.lev_utage = Some("14".to_string());
+    fn build_data_root_emits_utage_sheets_without_internal_levels() {
+        let mut raw_song = raw_song_stub();
+        raw_song.catcode = "宴会場".to_string();
+        raw_song.title = "[蛸]Utage Song".to_string();
+        raw_song.lev_utage = Some("12+?".to_string());
+        raw_song.kanji = Some("蛸".to_string());
+        let (songs, sheets) =
+            build_official_rows(vec![raw_song_stub(), raw_song]).expect("build official rows");
 
-        let (filtered, dropped_count) = filter_out_utage_entries(vec![normal_song, utage_song]);
-        assert_eq!(dropped_count, 1);
-        assert_eq!(filtered.len(), 1);
-        assert_eq!(filtered[0].title, "Stub");
+        let catalog = build_data_root(
+            &songs,
+            &sheets,
+            &SheetVersionMap::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            true,
+        );
+
+        let utage = catalog
+            .songs
+            .iter()
+            .find(|song| song.title == "[蛸]Utage Song")
+            .expect("utage song exists");
+        assert_eq!(utage.sheets.len(), 1);
+        let sheet = &utage.sheets[0];
+        assert_eq!(sheet.chart_type, "utage");
+        assert_eq!(sheet.level, "12+?");
+        assert_eq!(sheet.internal_level, None);
+        assert_eq!(
+            sheet
+                .utage
+                .as_ref()
+                .and_then(|utage| utage.kanji.as_deref()),
+            Some("蛸")
+        );
     }
 
     #[test]
@@ -1046,6 +1604,105 @@ mod tests {
         assert_eq!(hash1.len(), 64);
     }
 
+    #[test]
+    fn retain_limited_versions_keeps_only_the_limited_versions_rows() {
+        let song = |title: &str| SongRow {
+            identity: SongIdentity::new(title, SongGenre::Maimai, ""),
+            image_name: format!("{title}.png"),
+            image_url: format!("https://example.com/{title}.png"),
+            release_date: None,
+            sort_order: None,
+            is_new: false,
+            is_locked: false,
+            comment: None,
+        };
+        let sheet = |title: &str, sheet_type: ChartType, source: SheetSource| SheetRow {
+            song_identity: SongIdentity::new(title, SongGenre::Maimai, ""),
+            sheet_type,
+            difficulty: DifficultyCategory::Master,
+            level: "13".to_string(),
+            source,
+            utage: None,
+        };
+        let override_source = |version_name: &str| SheetSource::ManualOverride {
+            version_name: version_name.to_string(),
+            internal_level: None,
+            region: SongChartRegion {
+                jp: true,
+                intl: true,
+            },
+        };
+        let mut songs = vec![song("New"), song("Old"), song("Mixed"), song("Override")];
+        let mut sheets = vec![
+            sheet("New", ChartType::Dx, SheetSource::Official),
+            sheet("Old", ChartType::Dx, SheetSource::Official),
+            sheet("Mixed", ChartType::Std, SheetSource::Official),
+            sheet("Mixed", ChartType::Dx, SheetSource::Official),
+            sheet("Override", ChartType::Dx, override_source("CiRCLE")),
+            sheet("Override", ChartType::Std, override_source("PRiSM")),
+        ];
+        let sheet_versions = SheetVersionMap::from([
+            (
+                SongIdentity::new("New", SongGenre::Maimai, ""),
+                HashMap::from([(ChartType::Dx, "CiRCLE".to_string())]),
+            ),
+            (
+                SongIdentity::new("Old", SongGenre::Maimai, ""),
+                HashMap::from([(ChartType::Dx, "Splash".to_string())]),
+            ),
+            (
+                SongIdentity::new("Mixed", SongGenre::Maimai, ""),
+                HashMap::from([
+                    (ChartType::Std, "FiNALE".to_string()),
+                    (ChartType::Dx, "CiRCLE".to_string()),
+                ]),
+            ),
+        ]);
+
+        retain_limited_versions(
+            &mut songs,
+            &mut sheets,
+            &sheet_versions,
+            &[MaimaiVersion::Circle],
+        );
+
+        assert_eq!(
+            songs
+                .iter()
+                .map(|song| song.identity.title.as_str())
+                .collect::<Vec<_>>(),
+            vec!["New", "Mixed", "Override"]
+        );
+        assert_eq!(
+            sheets
+                .iter()
+                .map(|sheet| (sheet.song_identity.title.as_str(), sheet.sheet_type))
+                .collect::<Vec<_>>(),
+            vec![
+                ("New", ChartType::Dx),
+                ("Mixed", ChartType::Dx),
+                ("Override", ChartType::Dx),
+            ]
+        );
+        assert_eq!(
+            parse_limit_versions(" CiRCLE , PRiSM PLUS ").expect("parse"),
+            Some(vec![MaimaiVersion::Circle, MaimaiVersion::PrismPlus])
+        );
+        assert_eq!(parse_limit_versions("").expect("parse"), None);
+        assert!(parse_limit_versions("CiRCLE,unknown").is_err());
+    }
+
+    #[test]
+    fn parse_cover_download_concurrency_defaults_and_rejects_zero() {
+        assert_eq!(
+            parse_cover_download_concurrency("").expect("parse"),
+            DEFAULT_COVER_DOWNLOAD_CONCURRENCY
+        );
+        assert_eq!(parse_cover_download_concurrency(" 3 ").expect("parse"), 3);
+        assert!(parse_cover_download_concurrency("0").is_err());
+        assert!(parse_cover_download_concurrency("many").is_err());
+    }
+
     #[test]
     fn build_data_root_sets_region_flags_for_official_and_manual_override() {
         let songs = vec![
@@ -1077,6 +1734,7 @@ mod tests {
                 difficulty: DifficultyCategory::Master,
                 level: "12+".to_string(),
                 source: SheetSource::Official,
+                utage: None,
             },
             SheetRow {
                 song_identity: SongIdentity::new("Official Song", SongGenre::Maimai, ""),
@@ -1084,6 +1742,7 @@ mod tests {
                 difficulty: DifficultyCategory::Master,
                 level: "12+".to_string(),
                 source: SheetSource::Official,
+                utage: None,
             },
             SheetRow {
                 song_identity: SongIdentity::new("Intl Song", SongGenre::Maimai, ""),
@@ -1098,6 +1757,7 @@ mod tests {
                         intl: true,
                     },
                 },
+                utage: None,
             },
         ];
         let mut sheet_versions = SheetVersionMap::new();
@@ -1112,6 +1772,8 @@ mod tests {
             &sheet_versions,
             &HashMap::new(),
             &HashMap::new(),
+            &HashMap::new(),
+            false,
         );
         let official = catalog
             .songs
@@ -1143,6 +1805,450 @@ mod tests {
         assert!(intl_sheet.region.intl);
     }
 
+    fn internal_level_source_fixture() -> (Vec<SongRow>, Vec<SheetRow>) {
+        let identity = SongIdentity::new("Source Song", SongGenre::Maimai, "");
+        let song = SongRow {
+            identity: identity.clone(),
+            image_name: "source.png".to_string(),
+            image_url: "https://example.com/source.png".to_string(),
+            release_date: None,
+            sort_order: None,
+            is_new: false,
+            is_locked: false,
+            comment: None,
+        };
+        let manual_override = |internal_level: Option<&str>| SheetSource::ManualOverride {
+            version_name: "PRiSM".to_string(),
+            internal_level: internal_level.map(str::to_string),
+            region: SongChartRegion {
+                jp: true,
+                intl: true,
+            },
+        };
+        let sheet = |sheet_type, difficulty, source| SheetRow {
+            song_identity: identity.clone(),
+            sheet_type,
+            difficulty,
+            level: "13+".to_string(),
+            source,
+            utage: None,
+        };
+        let sheets = vec![
+            sheet(
+                ChartType::Std,
+                DifficultyCategory::Master,
+                SheetSource::Official,
+            ),
+            sheet(
+                ChartType::Std,
+                DifficultyCategory::Expert,
+                SheetSource::Official,
+            ),
+            sheet(
+                ChartType::Dx,
+                DifficultyCategory::Master,
+                manual_override(Some("13.9")),
+            ),
+            sheet(
+                ChartType::Dx,
+                DifficultyCategory::Expert,
+                manual_override(None),
+            ),
+            sheet(
+                ChartType::Dx,
+                DifficultyCategory::Advanced,
+                manual_override(None),
+            ),
+        ];
+        (vec![song], sheets)
+    }
+
+    #[test]
+    fn diff_previous_levels_marks_touched_pages_stale_and_reuses_the_rest() {
+        let official = |title: &str, charts: &[(DifficultyCategory, &str)]| {
+            let identity = SongIdentity::new(title, SongGenre::Maimai, "");
+            let song = SongRow {
+                identity: identity.clone(),
+                image_name: format!("{title}.png"),
+                image_url: format!("https://example.com/{title}.png"),
+                release_date: None,
+                sort_order: None,
+                is_new: false,
+                is_locked: false,
+                comment: None,
+            };
+            let sheets = charts
+                .iter()
+                .map(|(difficulty, level)| SheetRow {
+                    song_identity: identity.clone(),
+                    sheet_type: ChartType::Dx,
+                    difficulty: *difficulty,
+                    level: level.to_string(),
+                    source: SheetSource::Official,
+                    utage: None,
+                })
+                .collect::<Vec<_>>();
+            (song, sheets)
+        };
+        let build = |rows: Vec<(SongRow, Vec<SheetRow>)>| {
+            let (songs, sheets): (Vec<_>, Vec<_>) = rows.into_iter().unzip();
+            (songs, sheets.into_iter().flatten().collect::<Vec<_>>())
+        };
+
+        let (previous_songs, previous_sheets) = build(vec![
+            official(
+                "Kept",
+                &[
+                    (DifficultyCategory::Master, "13+"),
+                    (DifficultyCategory::Expert, "12+"),
+                ],
+            ),
+            official("Neighbour", &[(DifficultyCategory::Master, "13")]),
+            official("Gone", &[(DifficultyCategory::Master, "14")]),
+        ]);
+        let previous_levels = previous_sheets
+            .iter()
+            .zip(["13.7", "12.8", "13.2", "14.1"])
+            .map(|(sheet, internal_level)| {
+                (
+                    (
+                        sheet.song_identity.clone(),
+                        sheet.sheet_type,
+                        sheet.difficulty,
+                    ),
+                    InternalLevelRow {
+                        song_identity: sheet.song_identity.clone(),
+                        sheet_type: sheet.sheet_type,
+                        difficulty: sheet.difficulty,
+                        internal_level: internal_level.to_string(),
+                    },
+                )
+            })
+            .collect::<HashMap<_, _>>();
+        let previous = build_data_root(
+            &previous_songs,
+            &previous_sheets,
+            &SheetVersionMap::new(),
+            &previous_levels,
+            &HashMap::new(),
+            &HashMap::new(),
+            false,
+        );
+
+        let (_, sheets) = build(vec![
+            official(
+                "Kept",
+                &[
+                    (DifficultyCategory::Master, "13+"),
+                    (DifficultyCategory::Expert, "13"),
+                ],
+            ),
+            official("Neighbour", &[(DifficultyCategory::Master, "13")]),
+            official("Fresh", &[(DifficultyCategory::Master, "11")]),
+        ]);
+
+        let diff = diff_previous_levels(&previous.songs, &sheets);
+
+        assert_eq!(
+            diff.summary,
+            IncrementalSummary {
+                added: 1,
+                removed: 1,
+                changed: 1,
+            }
+        );
+        assert_eq!(
+            diff.stale_levels,
+            BTreeSet::from(["11", "12+", "13", "14"].map(str::to_string))
+        );
+        let reused = diff
+            .reusable
+            .values()
+            .map(|row| {
+                (
+                    row.song_identity.title.as_str(),
+                    row.internal_level.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(reused, vec![("Kept", "13.7")]);
+    }
+
+    #[test]
+    fn build_data_root_records_internal_level_source_per_path() {
+        let (songs, sheets) = internal_level_source_fixture();
+        let identity = songs[0].identity.clone();
+        let level_page_row = |sheet_type, difficulty, internal_level: &str| {
+            (
+                (identity.clone(), sheet_type, difficulty),
+                InternalLevelRow {
+                    song_identity: identity.clone(),
+                    sheet_type,
+                    difficulty,
+                    internal_level: internal_level.to_string(),
+                },
+            )
+        };
+        let internal_levels = HashMap::from([
+            level_page_row(ChartType::Std, DifficultyCategory::Master, "13.7"),
+            level_page_row(ChartType::Dx, DifficultyCategory::Master, "13.8"),
+            level_page_row(ChartType::Dx, DifficultyCategory::Expert, "12.4"),
+        ]);
+
+        let catalog = build_data_root(
+            &songs,
+            &sheets,
+            &SheetVersionMap::new(),
+            &internal_levels,
+            &HashMap::new(),
+            &HashMap::new(),
+            false,
+        );
+        let find = |chart_type: &str, difficulty: &str| {
+            catalog.songs[0]
+                .sheets
+                .iter()
+                .find(|sheet| sheet.chart_type == chart_type && sheet.difficulty == difficulty)
+                .map(|sheet| (sheet.internal_level.as_deref(), sheet.internal_level_source))
+                .expect("sheet exists")
+        };
+
+        assert_eq!(
+            find("std", "master"),
+            (Some("13.7"), Some(InternalLevelSource::LevelPage))
+        );
+        assert_eq!(find("std", "expert"), (None, None));
+        assert_eq!(
+            find("dx", "master"),
+            (Some("13.9"), Some(InternalLevelSource::ManualOverride))
+        );
+        assert_eq!(
+            find("dx", "expert"),
+            (Some("12.4"), Some(InternalLevelSource::LevelPage))
+        );
+        assert_eq!(find("dx", "advanced"), (None, None));
+    }
+
+    #[test]
+    fn build_data_root_fallback_fills_gaps_without_overriding_sources() {
+        let (songs, sheets) = internal_level_source_fixture();
+        let identity = songs[0].identity.clone();
+        let row = |sheet_type, difficulty, internal_level: &str| {
+            (
+                (identity.clone(), sheet_type, difficulty),
+                InternalLevelRow {
+                    song_identity: identity.clone(),
+                    sheet_type,
+                    difficulty,
+                    internal_level: internal_level.to_string(),
+                },
+            )
+        };
+        let internal_levels =
+            HashMap::from([row(ChartType::Std, DifficultyCategory::Master, "13.7")]);
+        let fallback_internal_levels = HashMap::from([
+            row(ChartType::Std, DifficultyCategory::Master, "13.0"),
+            row(ChartType::Std, DifficultyCategory::Expert, "12.2"),
+            row(ChartType::Dx, DifficultyCategory::Master, "13.0"),
+        ]);
+
+        let catalog = build_data_root(
+            &songs,
+            &sheets,
+            &SheetVersionMap::new(),
+            &internal_levels,
+            &fallback_internal_levels,
+            &HashMap::new(),
+            false,
+        );
+        let find = |chart_type: &str, difficulty: &str| {
+            catalog.songs[0]
+                .sheets
+                .iter()
+                .find(|sheet| sheet.chart_type == chart_type && sheet.difficulty == difficulty)
+                .map(|sheet| (sheet.internal_level.as_deref(), sheet.internal_level_source))
+                .expect("sheet exists")
+        };
+
+        assert_eq!(
+            find("std", "master"),
+            (Some("13.7"), Some(InternalLevelSource::LevelPage))
+        );
+        assert_eq!(
+            find("std", "expert"),
+            (Some("12.2"), Some(InternalLevelSource::Fallback))
+        );
+        assert_eq!(
+            find("dx", "master"),
+            (Some("13.9"), Some(InternalLevelSource::ManualOverride))
+        );
+        assert_eq!(find("dx", "advanced"), (None, None));
+    }
+
+    #[test]
+    fn estimated_internal_level_uses_band_minimum() {
+        for base in 1..=15 {
+            assert_eq!(
+                estimated_internal_level(&base.to_string()),
+                Some(format!("{base}.0"))
+            );
+            assert_eq!(
+                estimated_internal_level(&format!("{base}+")),
+                Some(format!("{base}.6"))
+            );
+        }
+        assert_eq!(estimated_internal_level(" 13+ "), Some("13.6".to_string()));
+        for level in ["", "+", "0", "16", "13.7", "?", "N/A"] {
+            assert_eq!(estimated_internal_level(level), None, "level: {level}");
+        }
+    }
+
+    #[test]
+    fn build_data_root_estimates_only_charts_without_exact_levels() {
+        let (songs, sheets) = internal_level_source_fixture();
+        let identity = songs[0].identity.clone();
+        let internal_levels = HashMap::from([(
+            (identity.clone(), ChartType::Std, DifficultyCategory::Master),
+            InternalLevelRow {
+                song_identity: identity,
+                sheet_type: ChartType::Std,
+                difficulty: DifficultyCategory::Master,
+                internal_level: "13.8".to_string(),
+            },
+        )]);
+        let build = |estimate| {
+            build_data_root(
+                &songs,
+                &sheets,
+                &SheetVersionMap::new(),
+                &internal_levels,
+                &HashMap::new(),
+                &HashMap::new(),
+                estimate,
+            )
+        };
+        let find = |catalog: &SongCatalog, chart_type: &str, difficulty: &str| {
+            catalog.songs[0]
+                .sheets
+                .iter()
+                .find(|sheet| sheet.chart_type == chart_type && sheet.difficulty == difficulty)
+                .map(|sheet| (sheet.internal_level.clone(), sheet.internal_level_source))
+                .expect("sheet exists")
+        };
+
+        let estimated = build(true);
+        assert_eq!(
+            find(&estimated, "std", "master"),
+            (
+                Some("13.8".to_string()),
+                Some(InternalLevelSource::LevelPage)
+            )
+        );
+        assert_eq!(
+            find(&estimated, "dx", "master"),
+            (
+                Some("13.9".to_string()),
+                Some(InternalLevelSource::ManualOverride)
+            )
+        );
+        assert_eq!(
+            find(&estimated, "std", "expert"),
+            (
+                Some("13.6".to_string()),
+                Some(InternalLevelSource::Estimate)
+            )
+        );
+
+        let exact_only = build(false);
+        assert_eq!(find(&exact_only, "std", "expert"), (None, None));
+    }
+
+    #[test]
+    fn build_data_root_serializes_identically_regardless_of_sheet_order() {
+        let (songs, sheets) = internal_level_source_fixture();
+        let mut reversed_sheets = sheets.clone();
+        reversed_sheets.reverse();
+        let serialize = |sheets: &[SheetRow]| {
+            let catalog = build_data_root(
+                &songs,
+                sheets,
+                &SheetVersionMap::new(),
+                &HashMap::new(),
+                &HashMap::new(),
+                &HashMap::new(),
+                false,
+            );
+            serde_json::to_vec_pretty(&catalog.songs).expect("serialize catalog")
+        };
+
+        let first = serialize(&sheets);
+        assert_eq!(first, serialize(&sheets));
+        assert_eq!(first, serialize(&reversed_sheets));
+
+        let catalog = build_data_root(
+            &songs,
+            &reversed_sheets,
+            &SheetVersionMap::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            false,
+        );
+        let order = catalog.songs[0]
+            .sheets
+            .iter()
+            .map(|sheet| format!("{}/{}", sheet.chart_type, sheet.difficulty))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            order,
+            vec![
+                "std/expert",
+                "std/master",
+                "dx/advanced",
+                "dx/expert",
+                "dx/master"
+            ]
+        );
+    }
+
+    #[test]
+    fn build_data_root_without_internal_levels_keeps_displayed_levels() {
+        let (songs, sheets) = internal_level_source_fixture();
+
+        let catalog = build_data_root(
+            &songs,
+            &sheets,
+            &SheetVersionMap::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            &HashMap::new(),
+            false,
+        );
+
+        let sheets = &catalog.songs[0].sheets;
+        assert!(sheets.iter().all(|sheet| !sheet.level.is_empty()));
+        assert!(
+            sheets
+                .iter()
+                .filter(|sheet| sheet.internal_level.is_some())
+                .all(|sheet| sheet.internal_level_source
+                    == Some(InternalLevelSource::ManualOverride))
+        );
+        assert!(
+            sheets.iter().any(
+                |sheet| sheet.internal_level.is_none() && sheet.internal_level_source.is_none()
+            )
+        );
+
+        let root = models::SongDatabase {
+            generated_at: "2026-01-01T00:00:00+00:00".to_string(),
+            songs: catalog.songs,
+        };
+        let json = serde_json::to_string(&root).expect("serialize data root");
+        let parsed: models::SongDatabase = serde_json::from_str(&json).expect("parse data root");
+        assert_eq!(parsed.songs.len(), 1);
+    }
+
     #[test]
     fn load_manual_override_rows_hashes_cover_url() {
         let rows = load_manual_override_rows().expect("load manual override rows");
@@ -1269,6 +2375,7 @@ mod tests {
                 difficulty: DifficultyCategory::Master,
                 level: "13".to_string(),
                 source: SheetSource::Official,
+                utage: None,
             },
             SheetRow {
                 song_identity: SongIdentity::new("Song A", SongGenre::Maimai, ""),
@@ -1283,6 +2390,7 @@ mod tests {
                         intl: true,
                     },
                 },
+                utage: None,
             },
         ];
 
@@ -1296,4 +2404,103 @@ mod tests {
         let upper = SongIdentity::new("Link", SongGenre::Maimai, "");
         assert_ne!(lower, upper);
     }
+
+    #[test]
+    fn write_atomic_keeps_previous_file_when_write_is_interrupted() {
+        let dir =
+            std::env::temp_dir().join(format!("maistats-write-atomic-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        let path = dir.join("data.json");
+        write_atomic(&path, br#"{"songs":[]}"#).expect("initial write");
+
+        // Occupy the temp path with a directory so the next write fails before the rename,
+        // as a crash mid-write would.
+        let tmp_path = dir.join("data.json.tmp");
+        std::fs::create_dir_all(&tmp_path).expect("block temp path");
+        assert!(write_atomic(&path, br#"{"songs":[tr"#).is_err());
+        assert_eq!(
+            std::fs::read(&path).expect("read previous file"),
+            br#"{"songs":[]}"#
+        );
+
+        std::fs::remove_dir(&tmp_path).expect("unblock temp path");
+        write_atomic(&path, br#"{"songs":[1]}"#).expect("retry write");
+        assert_eq!(
+            std::fs::read(&path).expect("read new file"),
+            br#"{"songs":[1]}"#
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    /// `/public/found.png` and the `/intl/` covers answer 200, everything else 404. The INTL
+    /// host also serves a login page and an empty body the way a lapsed session would.
+    async fn spawn_cover_server() -> TestServer {
+        TestServer::spawn(|request| match request.path.as_str() {
+            "/public/found.png" => Reply::ok("public").with_content_type("image/png"),
+            "/intl/missing.png" => Reply::ok("intl").with_content_type("image/png"),
+            "/intl/login.png" => {
+                Reply::ok("<html></html>").with_content_type("text/html; charset=utf-8")
+            }
+            "/intl/empty.png" => Reply::ok("").with_content_type("image/png"),
+            _ => Reply::status("404 Not Found"),
+        })
+        .await
+    }
+
+    fn intl_hits(server: &TestServer) -> usize {
+        server
+            .paths()
+            .iter()
+            .filter(|path| path.starts_with("/intl/"))
+            .count()
+    }
+
+    #[tokio::test]
+    async fn cover_download_tries_intl_only_when_public_cover_is_missing() {
+        let server = spawn_cover_server().await;
+        let client = reqwest::Client::new();
+        let intl = IntlCoverClient::with_client(&server.url("/intl/"), client.clone());
+
+        let found = download_cover(&client, Some(&intl), &server.url("/public/found.png"))
+            .await
+            .expect("public cover");
+        assert_eq!(found, b"public");
+        assert_eq!(intl_hits(&server), 0);
+
+        let missing_url = server.url("/public/missing.png");
+        let fallback = download_cover(&client, Some(&intl), &missing_url)
+            .await
+            .expect("intl cover");
+        assert_eq!(fallback, b"intl");
+        assert_eq!(intl_hits(&server), 1);
+
+        assert!(download_cover(&client, None, &missing_url).await.is_err());
+        assert_eq!(intl_hits(&server), 1);
+    }
+
+    #[tokio::test]
+    async fn intl_cover_rejects_non_image_and_empty_responses() {
+        let server = spawn_cover_server().await;
+        let intl = IntlCoverClient::with_client(&server.url("/intl/"), reqwest::Client::new());
+
+        let login_page = intl.download("login.png").await.expect_err("html page");
+        assert!(login_page.to_string().contains("not an image"));
+        let empty = intl.download("empty.png").await.expect_err("empty body");
+        assert!(empty.to_string().contains("is empty"));
+    }
+
+    #[tokio::test]
+    async fn intl_cover_does_not_retry_a_failed_login() {
+        let server = spawn_cover_server().await;
+        let intl = IntlCoverClient::with_login_failure(&server.url("/intl/"), "bad password");
+
+        for _ in 0..2 {
+            let err = intl
+                .download("missing.png")
+                .await
+                .expect_err("login failed");
+            assert!(err.to_string().contains("bad password"));
+        }
+        assert_eq!(intl_hits(&server), 0);
+    }
 }
@@ -32,9 +32,10 @@ pub async fn fetch_intl_sheet_versions(
     songs: &[SongRow],
     sheets: &[SheetRow],
     ignored_titles: &HashSet<String>,
+    limit_versions: Option<&[MaimaiVersion]>,
 ) -> eyre::Result<SheetVersionMap> {
     let client = reqwest::Client::builder()
-        .default_headers(intl::default_mobile_headers()?)
+        .default_headers(intl::default_mobile_headers(intl::DEFAULT_USER_AGENT)?)
         .redirect(reqwest::redirect::Policy::limited(10))
         .cookie_store(true)
         .build()
@@ -48,7 +49,7 @@ pub async fn fetch_intl_sheet_versions(
     let mut out: SheetVersionMap = HashMap::new();
     let mut seen = HashSet::new();
 
-    for version in MaimaiVersion::iter().filter(|version| version.is_available_in_intl()) {
+    for version in intl_fetch_versions(limit_versions) {
         let rows =
             fetch_rows_for_version(&client, sega_id, sega_password, version, &resolver).await?;
         for (song_identity, chart_type) in rows {
@@ -72,6 +73,14 @@ pub async fn fetch_intl_sheet_versions(
     Ok(out)
 }
 
+/// INTL version pages to read: every INTL version, or only those in `limit_versions`.
+fn intl_fetch_versions(limit_versions: Option<&[MaimaiVersion]>) -> Vec<MaimaiVersion> {
+    MaimaiVersion::iter()
+        .filter(|version| version.is_available_in_intl())
+        .filter(|version| limit_versions.is_none_or(|limit| limit.contains(version)))
+        .collect()
+}
+
 impl SongVersionResolver {
     fn new(songs: &[SongRow], sheets: &[SheetRow], ignored_titles: &HashSet<String>) -> Self {
         let mut chart_types_by_song_identity: HashMap<&SongIdentity, HashSet<ChartType>> =
@@ -282,6 +291,7 @@ mod tests {
             difficulty: models::DifficultyCategory::Basic,
             level: "6".to_string(),
             source: SheetSource::Official,
+            utage: None,
         }
     }
 
@@ -373,6 +383,7 @@ mod tests {
                     intl: true,
                 },
             },
+            utage: None,
         };
         let ignored_titles =
             HashSet::from([normalize_song_title_value("全世界共通リズム感テスト")]);
@@ -446,11 +457,17 @@ mod tests {
 
     #[test]
     fn intl_fetch_targets_exclude_future_jp_only_versions() {
-        let versions = MaimaiVersion::iter()
-            .filter(|version| version.is_available_in_intl())
-            .collect::<Vec<_>>();
+        let versions = intl_fetch_versions(None);
 
         assert_eq!(versions.last().copied(), Some(MaimaiVersion::Circle));
         assert!(!versions.contains(&MaimaiVersion::CirclePlus));
     }
+
+    #[test]
+    fn intl_fetch_targets_respect_limit_versions() {
+        assert_eq!(
+            intl_fetch_versions(Some(&[MaimaiVersion::Circle, MaimaiVersion::CirclePlus])),
+            vec![MaimaiVersion::Circle]
+        );
+    }
 }
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use eyre::WrapErr;
+
+use super::normalize_identity_component;
+
+pub(crate) const TITLE_ALIASES_FILE: &str = "title_aliases.json";
+
+/// Spellings other sources use for official titles (alias -> canonical). Always applied,
+/// even without a `title_aliases.json`.
+const BUILTIN_TITLE_ALIASES: &[(&str, &str)] =
+    &[("Bad Apple!! feat nomico", "Bad Apple!! feat.nomico")];
+
+pub(crate) fn builtin_canonical_title(title: &str) -> Option<&'static str> {
+    BUILTIN_TITLE_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == title)
+        .map(|(_, canonical)| *canonical)
+}
+
+/// Title corrections applied when matching titles scraped from other sources against the
+/// official song list.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TitleAliases {
+    aliases: HashMap<String, String>,
+}
+
+impl TitleAliases {
+    pub(crate) fn len(&self) -> usize {
+        self.aliases.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    pub(crate) fn canonical_title(&self, title: &str) -> String {
+        let title = normalize_identity_component(title);
+        if let Some(canonical) = self.aliases.get(&title) {
+            return canonical.clone();
+        }
+        builtin_canonical_title(&title)
+            .map(str::to_string)
+            .unwrap_or(title)
+    }
+}
+
+/// Loads operator-provided aliases from the song data dir. Entries take precedence over the
+/// built-ins; a missing file leaves only the built-ins.
+pub(crate) fn load_title_aliases(song_data_dir: &Path) -> eyre::Result<TitleAliases> {
+    let path = song_data_dir.join(TITLE_ALIASES_FILE);
+    let json = match std::fs::read_to_string(&path) {
+        Ok(json) => json,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(TitleAliases::default());
+        }
+        Err(err) => {
+            return Err(err).wrap_err_with(|| format!("read {}", path.display()));
+        }
+    };
+    parse_title_aliases(&json).wrap_err_with(|| format!("parse {}", path.display()))
+}
+
+fn parse_title_aliases(json: &str) -> eyre::Result<TitleAliases> {
+    let parsed: HashMap<String, String> = serde_json::from_str(json)?;
+    let mut aliases = HashMap::with_capacity(parsed.len());
+
+    for (alias, canonical) in parsed {
+        let alias = normalize_identity_component(&alias);
+        let canonical = normalize_identity_component(&canonical);
+        if alias.is_empty() || canonical.is_empty() {
+            return Err(eyre::eyre!(
+                "title alias entries must not be empty: '{alias}' -> '{canonical}'"
+            ));
+        }
+        aliases.insert(alias, canonical);
+    }
+
+    Ok(TitleAliases { aliases })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_title_aliases_merges_file_with_builtins() {
+        let dir =
+            std::env::temp_dir().join(format!("maistats-title-aliases-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("create temp dir");
+        std::fs::write(
+            dir.join(TITLE_ALIASES_FILE),
+            r#"{ " Divergent Spelling ": "Canonical Title" }"#,
+        )
+        .expect("write alias file");
+
+        let aliases = load_title_aliases(&dir).expect("load aliases");
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(aliases.len(), 1);
+        assert_eq!(
+            aliases.canonical_title("Divergent Spelling"),
+            "Canonical Title"
+        );
+        assert_eq!(
+            aliases.canonical_title("Bad Apple!! feat nomico"),
+            "Bad Apple!! feat.nomico"
+        );
+        assert_eq!(aliases.canonical_title(" Other "), "Other");
+    }
+
+    #[test]
+    fn parse_title_aliases_rejects_empty_entries() {
+        assert!(parse_title_aliases(r#"{ "": "Canonical" }"#).is_err());
+    }
+}
@@ -1,9 +1,10 @@
+use std::collections::BTreeSet;
 use std::path::Path;
 
 use chrono::Utc;
 use eyre::WrapErr;
 
-use crate::songdb::{SongDatabase, SongDbConfig};
+use crate::songdb::{SongDatabase, SongDbConfig, song_key, write_atomic};
 
 pub(crate) async fn generate_song_database(song_data_base_path: &Path) -> eyre::Result<()> {
     tracing::info!("songdb: starting generation");
@@ -13,9 +14,34 @@ pub(crate) async fn generate_song_database(song_data_base_path: &Path) -> eyre::
 
     std::fs::create_dir_all(song_data_base_path).wrap_err("create song_data output dir")?;
 
-    let database = SongDatabase::fetch(&config, song_data_base_path)
-        .await
-        .wrap_err("failed to fetch song database")?;
+    let data_json_path = song_data_base_path.join("data.json");
+    let previous_database = load_previous_database(&data_json_path);
+    let previous_charts = previous_database
+        .as_ref()
+        .map(|previous| chart_keys(&previous.songs));
+
+    let database = match previous_database
+        .as_ref()
+        .filter(|_| config.incremental_internal_levels)
+    {
+        Some(previous) => {
+            let (database, summary) =
+                SongDatabase::fetch_incremental(&config, song_data_base_path, previous)
+                    .await
+                    .wrap_err("failed to fetch song database incrementally")?;
+            tracing::info!(
+                "songdb: incremental build: {} song(s) added, {} removed, {} changed",
+                summary.added,
+                summary.removed,
+                summary.changed
+            );
+            database
+        }
+        None => SongDatabase::fetch(&config, song_data_base_path)
+            .await
+            .wrap_err("failed to fetch song database")?,
+    };
+    let locked_songs = database.locked_song_keys();
 
     let catalog = database
         .into_data_root()
@@ -23,12 +49,144 @@ pub(crate) async fn generate_song_database(song_data_base_path: &Path) -> eyre::
     let data_root = build_song_database_root(catalog);
 
     let json_bytes = serde_json::to_vec_pretty(&data_root).wrap_err("serialize data.json")?;
-    std::fs::write(song_data_base_path.join("data.json"), json_bytes)
-        .wrap_err("write data.json")?;
+    write_atomic(&data_json_path, &json_bytes).wrap_err("write data.json")?;
+
+    if let Some(previous_charts) = previous_charts {
+        log_catalog_diff(&diff_chart_keys(
+            &previous_charts,
+            &chart_keys(&data_root.songs),
+        ));
+    }
+
+    let locked_songs_path = song_data_base_path.join("locked_songs.json");
+    let previous_locked_songs = load_previous_locked_songs(&locked_songs_path);
+    let locked_json =
+        serde_json::to_vec_pretty(&locked_songs).wrap_err("serialize locked_songs.json")?;
+    write_atomic(&locked_songs_path, &locked_json).wrap_err("write locked_songs.json")?;
+
+    if let Some(previous_locked_songs) = previous_locked_songs {
+        let unlocked = newly_unlocked_songs(
+            &previous_locked_songs,
+            &locked_songs,
+            &song_keys(&data_root.songs),
+        );
+        if unlocked.is_empty() {
+            tracing::info!("songdb: no newly unlockable songs since previous build");
+        } else {
+            tracing::info!("songdb: {} newly unlockable song(s)", unlocked.len());
+            for song in &unlocked {
+                tracing::info!("songdb: unlocked {song}");
+            }
+        }
+    }
 
     Ok(())
 }
 
+fn load_previous_locked_songs(path: &Path) -> Option<BTreeSet<String>> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(err) => {
+            tracing::warn!("songdb: failed to read previous locked_songs.json: {err}");
+            return None;
+        }
+    };
+    serde_json::from_slice(&bytes)
+        .inspect_err(|err| {
+            tracing::warn!("songdb: failed to parse previous locked_songs.json: {err}")
+        })
+        .ok()
+}
+
+fn song_keys(songs: &[models::SongCatalogSong]) -> BTreeSet<String> {
+    songs
+        .iter()
+        .map(|song| song_key(&song.title, &song.artist))
+        .collect()
+}
+
+/// Songs locked in the previous build that are unlocked now. Songs that vanished from the
+/// catalog are reported by the chart diff instead, so they are excluded here.
+fn newly_unlocked_songs(
+    previous_locked: &BTreeSet<String>,
+    locked: &BTreeSet<String>,
+    current_songs: &BTreeSet<String>,
+) -> Vec<String> {
+    previous_locked
+        .difference(locked)
+        .filter(|song| current_songs.contains(*song))
+        .cloned()
+        .collect()
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CatalogDiff {
+    added: Vec<String>,
+    removed: Vec<String>,
+}
+
+/// Read the `data.json` about to be replaced, so a rebuild can report which charts SEGA
+/// added or removed since the last successful run and reuse its internal levels.
+fn load_previous_database(path: &Path) -> Option<models::SongDatabase> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(err) => {
+            tracing::warn!("songdb: failed to read previous data.json: {err}");
+            return None;
+        }
+    };
+    match serde_json::from_slice::<models::SongDatabase>(&bytes) {
+        Ok(previous) => Some(previous),
+        Err(err) => {
+            tracing::warn!("songdb: failed to parse previous data.json: {err}");
+            None
+        }
+    }
+}
+
+fn chart_keys(songs: &[models::SongCatalogSong]) -> BTreeSet<String> {
+    songs
+        .iter()
+        .flat_map(|song| {
+            song.sheets.iter().map(move |sheet| {
+                format!(
+                    "{} [{} {}]",
+                    song_key(&song.title, &song.artist),
+                    sheet.chart_type,
+                    sheet.difficulty
+                )
+            })
+        })
+        .collect()
+}
+
+fn diff_chart_keys(previous: &BTreeSet<String>, next: &BTreeSet<String>) -> CatalogDiff {
+    CatalogDiff {
+        added: next.difference(previous).cloned().collect(),
+        removed: previous.difference(next).cloned().collect(),
+    }
+}
+
+fn log_catalog_diff(diff: &CatalogDiff) {
+    if diff.added.is_empty() && diff.removed.is_empty() {
+        tracing::info!("songdb: no chart changes since previous data.json");
+        return;
+    }
+    tracing::info!(
+        "songdb: {} chart(s) added, {} chart(s) removed since previous data.json",
+        diff.added.len(),
+        diff.removed.len()
+    );
+    for chart in &diff.added {
+        tracing::info!("songdb: added {chart}");
+    }
+    for chart in &diff.removed {
+        tracing::warn!("songdb: removed {chart}");
+    }
+}
+
 fn build_song_database_root(catalog: models::SongCatalog) -> models::SongDatabase {
     models::SongDatabase {
         generated_at: Utc::now().to_rfc3339(),
@@ -48,4 +206,45 @@ mod tests {
         assert!(chrono::DateTime::parse_from_rfc3339(&root.generated_at).is_ok());
         assert!(root.songs.is_empty());
     }
+
+    #[test]
+    fn diff_chart_keys_reports_added_and_removed_charts() {
+        let previous = BTreeSet::from([
+            "Kept / A [DX MASTER]".to_string(),
+            "Gone / B [STD EXPERT]".to_string(),
+        ]);
+        let next = BTreeSet::from([
+            "Kept / A [DX MASTER]".to_string(),
+            "Fresh / C [DX BASIC]".to_string(),
+        ]);
+
+        assert_eq!(
+            diff_chart_keys(&previous, &next),
+            CatalogDiff {
+                added: vec!["Fresh / C [DX BASIC]".to_string()],
+                removed: vec!["Gone / B [STD EXPERT]".to_string()],
+            }
+        );
+        assert_eq!(diff_chart_keys(&next, &next), CatalogDiff::default());
+    }
+
+    #[test]
+    fn newly_unlocked_songs_skips_still_locked_and_removed_songs() {
+        let previous_locked = BTreeSet::from([
+            "Opened / A".to_string(),
+            "Still Locked / B".to_string(),
+            "Removed / C".to_string(),
+        ]);
+        let locked = BTreeSet::from(["Still Locked / B".to_string(), "New Lock / D".to_string()]);
+        let current_songs = BTreeSet::from([
+            "Opened / A".to_string(),
+            "Still Locked / B".to_string(),
+            "New Lock / D".to_string(),
+        ]);
+
+        assert_eq!(
+            newly_unlocked_songs(&previous_locked, &locked, &current_songs),
+            vec!["Opened / A".to_string()]
+        );
+    }
 }
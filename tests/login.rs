@@ -26,6 +26,7 @@ async fn sega_login_works() -> eyre::Result<()> {
         cookie_path,
         discord_bot_token,
         discord_user_id,
+        user_agents: Vec::new(),
     };
 
     let mut client = MaimaiClient::new(&config)?;
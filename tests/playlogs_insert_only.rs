@@ -32,6 +32,7 @@ async fn insert_playlogs_does_not_overwrite_existing_row() -> eyre::Result<()> {
         sync: Some(SyncStatus::Fs),
         dx_score: None,
         dx_score_max: None,
+        is_buddy_play: false,
     }];
     db::upsert_playlogs(&pool, scraped_at, &entries).await?;
 
@@ -52,6 +53,7 @@ async fn insert_playlogs_does_not_overwrite_existing_row() -> eyre::Result<()> {
         sync: None,
         dx_score: None,
         dx_score_max: None,
+        is_buddy_play: false,
     }];
     db::upsert_playlogs(&pool, scraped_at, &entries).await?;
 